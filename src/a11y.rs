@@ -0,0 +1,39 @@
+//! Screen reader support (AccessKit, with the `accessibility` feature)
+//!
+//! egui already reads labels and buttons out by their text. What it cannot
+//! know is added here: painted widgets (checkboxes, the path strip, charts)
+//! get a name, status badges say which check they belong to, and the status
+//! line is a live region, so NVDA and Narrator announce a finished run or a
+//! changed status without moving focus.
+
+use crate::diagnostics::CheckStatus;
+use eframe::egui;
+
+/// Spoken form of a status ("..." and "--" are not words)
+pub fn status_word(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Ok => "OK",
+        CheckStatus::Warning => "warning",
+        CheckStatus::Error => "error",
+        CheckStatus::Unknown => "unknown",
+        CheckStatus::Inactive => "off",
+    }
+}
+
+/// Name a painted or terse widget for screen readers
+pub fn describe(response: &egui::Response, typ: egui::WidgetType, label: &str) {
+    let enabled = response.enabled();
+    response.widget_info(|| egui::WidgetInfo::labeled(typ, enabled, label));
+}
+
+/// Announce changes of the widget's text as they happen (polite: after
+/// whatever is being read)
+#[cfg(feature = "accessibility")]
+pub fn live(response: &egui::Response) {
+    response
+        .ctx
+        .accesskit_node_builder(response.id, |node| node.set_live(egui::accesskit::Live::Polite));
+}
+
+#[cfg(not(feature = "accessibility"))]
+pub fn live(_response: &egui::Response) {}
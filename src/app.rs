@@ -0,0 +1,6061 @@
+//! Desktop GUI (egui), built with the `gui` feature
+
+use eframe::egui;
+use crate::{a11y, cli, diagnostics, scheduler, server, theme};
+use theme::{Theme, ThemeMode, apply_theme};
+use diagnostics::{DiagnosticReport, ErrorLog, CheckResult, CheckStatus, DiagnosticSettings, History, SharedReport};
+use diagnostics::settings::{CERT_WARN_PRESETS, CONCURRENCY_PRESETS, FRESH_TTL_PRESETS, HARDWARE_INTERVAL_PRESETS, REFRESH_PRESETS, SCALE_PRESETS};
+use diagnostics::profiles::{NetworkProfile, ViewRole, ROLE_PRESETS};
+use diagnostics::wizard::{Wizard, SYMPTOMS};
+use diagnostics::actions::{NetworkAction, NETWORK_ACTIONS};
+use diagnostics::digest::{self, Digest};
+use diagnostics::history::{HISTORY_RETENTION_DAYS, TIMESTAMP_FORMAT};
+use diagnostics::hysteresis::HYSTERESIS_PRESETS;
+use diagnostics::inject;
+use diagnostics::{notify, readiness};
+use diagnostics::netwatch::{self, NetChange, NetWatcher};
+use diagnostics::palette;
+use diagnostics::session::{self, SessionState, SessionWatcher};
+use diagnostics::power::{PowerStatus, BATTERY_INTERVAL_FACTOR, BATTERY_PRESETS};
+use diagnostics::feedback;
+use diagnostics::hardware::{self, HardwareWorker};
+use diagnostics::live::LiveMetrics;
+use diagnostics::monitor::{ResourceMonitor, ResourceSample, MONITOR_INTERVAL, MONITOR_WINDOW};
+use diagnostics::simulate::Simulation;
+use diagnostics::share::{self, SharedSession};
+use diagnostics::tail::{OutputTail, Stream, DEFAULT_TAIL_COMMAND};
+use diagnostics::trace::{Trace, TRACE_INTERVAL};
+use diagnostics::traffic::{format_bytes, CAP_PRESETS};
+use diagnostics::upload::{self, UPLOAD_INTERVAL_PRESETS};
+use diagnostics::policy;
+use diagnostics::signing;
+use diagnostics::reportfile;
+use diagnostics::fallback;
+use diagnostics::support;
+use diagnostics::verdict::{self, Verdict};
+use diagnostics::compare::{self, Comparison, CompareStage, COMPARE_PRESETS, format_cell};
+use server::proxy::{self, CaptureProxy};
+use server::{ServerState, StatusServer};
+use server::discovery::Discovery;
+use scheduler::{Scheduler, Watch};
+use arboard::Clipboard;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Outcome slot filled by a background remediation action
+type ActionResult = Arc<Mutex<Option<Result<String, String>>>>;
+
+const RESTART_LABEL: &str = "RESTART OPENCODE";
+
+/// How long `--run-and-copy` waits for the hardware worker after the run
+const AUTO_COPY_HARDWARE_WAIT: Duration = Duration::from_secs(15);
+
+/// How long the copy confirmation stays on screen
+const COPY_FLASH: Duration = Duration::from_secs(4);
+
+/// How long cards that changed in the latest run pulse
+const CHANGE_HIGHLIGHT: Duration = Duration::from_secs(3);
+
+/// Heatmap cell size and weekday label column
+const HEATMAP_CELL: f32 = 11.0;
+const HEATMAP_LABEL_WIDTH: f32 = 30.0;
+
+/// Width of the latency chart popup
+const LATENCY_CHART_WIDTH: f32 = 300.0;
+
+/// Time ranges of the latency chart (hours)
+const LATENCY_RANGES: &[(i64, &str)] = &[(24, "24H"), (24 * 7, "7D"), (24 * HISTORY_RETENTION_DAYS, "14D")];
+
+/// Progress of `--run-and-copy`
+#[derive(Clone, Copy)]
+enum AutoCopy {
+    Running,
+    /// Run done at this time; waiting for CPU/GPU results from the hardware worker
+    Waiting(Instant),
+}
+
+/// Snooze length offered on a check card (minutes)
+const SNOOZE_MINUTES: i64 = 60;
+
+/// Hover text of settings locked by the managed policy
+const POLICY_HINT: &str = "Managed by your organization's policy";
+
+/// Quick mute picked from a check card's badge menu
+#[derive(Clone, Copy)]
+enum CardMute {
+    Disable,
+    Snooze,
+    Wake,
+}
+
+/// What "Restart as administrator" carries over (settings are saved as usual)
+#[derive(Serialize, Deserialize)]
+struct CarriedState {
+    report: DiagnosticReport,
+    error_log: ErrorLog,
+    dark: bool,
+}
+
+impl CarriedState {
+    /// Load and delete the state file named after `--restore-state`
+    fn take_from_args() -> Option<Self> {
+        let path = cli::args().restore_state.as_ref()?;
+        let text = std::fs::read_to_string(path).ok();
+        let _ = std::fs::remove_file(path);
+        serde_json::from_str(&text?).ok()
+    }
+}
+
+/// A shared session open read-only, with this machine's own state set aside until CLOSE
+struct Viewing {
+    /// "SHARED SESSION 2025-01-01 12:00:00 :: v0.3.0" / "REPORT FILE report.txt"
+    title: String,
+    /// System summary, or the file path
+    hover: String,
+    own_report: SharedReport,
+    own_history: Arc<Mutex<History>>,
+    own_error_log: ErrorLog,
+}
+
+/// "42s ago" / "3m ago"
+fn format_age(secs: u64) -> String {
+    if secs < 60 {
+        format!("{}s ago", secs)
+    } else {
+        format!("{}m ago", secs / 60)
+    }
+}
+
+/// Time from process start to the first frame; anything slower belongs off the UI thread
+const STARTUP_BUDGET: Duration = Duration::from_millis(800);
+
+/// How often the battery state is polled
+const POWER_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Next whole second since `last`; next minute once "ago" shows minutes and nothing counts down
+fn next_tick(last: Instant, countdown: bool) -> Instant {
+    let elapsed = last.elapsed().as_secs();
+    let step = if countdown || elapsed < 60 { 1 } else { 60 };
+    last + Duration::from_secs((elapsed / step + 1) * step)
+}
+
+/// Detect system theme (Windows)
+///
+/// Reads AppsUseLightTheme directly: spawning `reg` cost a visible delay
+/// before the first frame on slow machines.
+#[cfg(target_os = "windows")]
+fn detect_system_theme() -> ThemeMode {
+    use winapi::shared::minwindef::DWORD;
+    use winapi::um::winreg::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+
+    let wide = |s: &str| s.encode_utf16().chain(std::iter::once(0)).collect::<Vec<u16>>();
+    let key = wide(r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize");
+    let value = wide("AppsUseLightTheme");
+    let mut data: DWORD = 0;
+    let mut size = std::mem::size_of::<DWORD>() as DWORD;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            key.as_ptr(),
+            value.as_ptr(),
+            RRF_RT_REG_DWORD,
+            std::ptr::null_mut(),
+            &mut data as *mut DWORD as *mut _,
+            &mut size,
+        )
+    };
+
+    // 0 = Dark, 1 = Light; default to dark
+    if status == 0 && data == 1 {
+        ThemeMode::Light
+    } else {
+        ThemeMode::Dark
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn detect_system_theme() -> ThemeMode {
+    ThemeMode::Dark
+}
+
+/// Open a URL in the default browser
+fn open_url(url: &str) {
+    use diagnostics::command;
+    
+    #[cfg(target_os = "windows")]
+    let result = command("cmd").args(["/C", "start", "", url]).spawn();
+    #[cfg(target_os = "macos")]
+    let result = command("open").arg(url).spawn();
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let result = command("xdg-open").arg(url).spawn();
+    
+    let _ = result;
+}
+
+/// Open the main window
+pub fn run() -> eframe::Result<()> {
+    let started = Instant::now();
+    // Name the settings file in the title when it is not the default one
+    let title = match cli::args().config.as_ref().and_then(|c| c.file_name()) {
+        Some(name) => format!("OpenCode Diagnostics :: {}", name.to_string_lossy()),
+        None => "OpenCode Diagnostics".to_string(),
+    };
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_title(title)
+            .with_inner_size([550.0, 580.0])
+            .with_min_inner_size([450.0, 450.0]),
+        ..Default::default()
+    };
+
+    eframe::run_native(
+        "OpenCode Diagnostics",
+        options,
+        Box::new(move |cc| Ok(Box::new(App::new(cc, started)))),
+    )
+}
+
+pub struct App {
+    theme_mode: ThemeMode,
+    theme: Theme,
+    status: String,
+    report: SharedReport,
+    is_running: Arc<Mutex<bool>>,
+    just_completed: Arc<Mutex<bool>>, // Flag to know when run completed
+    // Last completed run, and the cards that changed since the one before (pulsing)
+    previous_run: Option<Arc<DiagnosticReport>>,
+    changed_cards: Option<(Instant, Vec<String>)>,
+    // "Is it me or them?" for the report with this timestamp
+    verdict: Option<Verdict>,
+    verdict_for: Option<String>,
+    // "Ask for help" prompt closed for the report with this timestamp
+    help_dismissed: Option<String>,
+    // The run in progress is the morning readiness run (notify when done)
+    readiness_running: bool,
+    copied_feedback: Option<Instant>,
+    // Settings
+    settings: DiagnosticSettings,
+    show_settings: bool,
+    // Auto-refresh
+    last_refresh: Option<Instant>,
+    // Pauses auto-refresh while locked / asleep
+    session: SessionState,
+    // Network changes (polled by the scheduler), and the change that starts the next run
+    net_change: NetChange,
+    run_trigger: Option<String>,
+    // Battery state (polled every 30s by the scheduler)
+    power: Arc<Mutex<Option<PowerStatus>>>,
+    // Repaints on demand and runs the session/battery watches
+    scheduler: Scheduler,
+    // Error log (grouped by error type)
+    error_log: ErrorLog,
+    show_history: bool,
+    // Run history (persisted, shared with the status server)
+    history: Arc<Mutex<History>>,
+    // Local status page
+    status_server: Option<StatusServer>,
+    status_server_error: Option<String>,
+    // LAN agent discovery (mDNS)
+    discovery: Option<Discovery>,
+    show_agents: bool,
+    // Troubleshooting wizard
+    wizard: Option<Wizard>,
+    show_wizard: bool,
+    // A/B compare mode
+    compare: Option<Comparison>,
+    show_compare: bool,
+    // Findings and evidence under the diagnosis
+    show_why: bool,
+    // Timestamp of the report voted RIGHT/WRONG on, and why saving the vote failed
+    feedback_voted: Option<String>,
+    feedback_error: Option<String>,
+    // Latency heatmap (weekday x hour)
+    show_heatmap: bool,
+    heatmap_check: &'static str,
+    // Latency chart of one check, opened from its card, and the hours it covers
+    latency_chart: Option<&'static str>,
+    latency_hours: i64,
+    // Heatmap, latency chart and error log in their own window (viewport)
+    show_history_window: bool,
+    // Weekly digest popup, one built by the history load when due, and the SAVE outcome
+    show_digest: bool,
+    digest: Option<Digest>,
+    pending_digest: Arc<Mutex<Option<Digest>>>,
+    digest_status: Option<String>,
+    // Remediation action running in the background, and the last outcome (by label)
+    action: Option<(&'static str, ActionResult)>,
+    action_status: Option<(&'static str, Result<String, String>)>,
+    // Network quick actions popup, and the action waiting for confirmation
+    show_actions: bool,
+    pending_action: Option<(NetworkAction, String)>,
+    // Failure injection popup (provider, minutes, consent), the host blocked
+    // and when it is unblocked ("12:05:00" for display), and the outcome of the
+    // last start / stop
+    show_inject: bool,
+    inject_provider: usize,
+    inject_minutes: u32,
+    inject_consent: bool,
+    injection: Option<(&'static str, Instant, String)>,
+    inject_status: Option<Result<String, String>>,
+    // Traffic capture proxy while it runs, and its popup
+    capture: Option<CaptureProxy>,
+    capture_error: Option<String>,
+    show_capture: bool,
+    // Trace mode while it runs, and the summary of the last one (popup)
+    trace: Option<Trace>,
+    trace_summary: Option<String>,
+    show_trace: bool,
+    // Result of the last CREATE SUPPORT BUNDLE (path or error)
+    bundle_status: Option<String>,
+    // Last EXPORT SESSION: (path, access code) or error
+    share_status: Option<Result<(PathBuf, String), String>>,
+    // Session file waiting for its access code, the code being typed and why opening failed
+    session_file: Option<PathBuf>,
+    session_code: String,
+    session_error: Option<String>,
+    // Shared session being viewed read-only
+    viewing: Option<Viewing>,
+    // OpenCode output tail (launched process or followed log) and why starting it failed
+    tail: Option<OutputTail>,
+    tail_error: Option<String>,
+    tail_errors_only: bool,
+    show_output: bool,
+    // Custom endpoint being typed in settings
+    new_endpoint: String,
+    // Extra request header being typed in settings, and why it was rejected
+    new_header: String,
+    header_error: Option<String>,
+    // Why "Restart as administrator" failed
+    admin_error: Option<String>,
+    // Process start, until the first frame is drawn
+    startup: Option<Instant>,
+    // --run-and-copy in progress, then the confirmation (shown at, copied)
+    auto_copy: Option<AutoCopy>,
+    copy_flash: Option<(Instant, bool)>,
+    // Mock results instead of real checks (--simulate [--seed N])
+    simulation: Option<Simulation>,
+    // CPU/RAM/network samples of the current or last run
+    live: Option<Arc<LiveMetrics>>,
+    // CPU/RAM/GPU sampled every few seconds by the scheduler, independent of runs
+    resources: ResourceMonitor,
+    // LOCAL RESOURCES and GPU checks, off the run thread so a stalled GPU query never delays it
+    hardware: Arc<HardwareWorker>,
+}
+
+impl App {
+    pub fn new(cc: &eframe::CreationContext<'_>, started: Instant) -> Self {
+        let settings = DiagnosticSettings::load();
+
+        let session = SessionState::default();
+        let net_change = NetChange::default();
+        // Battery state and history are filled in off the UI thread so the window shows at once
+        let power = Arc::new(Mutex::new(None));
+        let resources = ResourceMonitor::default();
+        let watches = vec![
+            {
+                let state = session.clone();
+                let mut watcher = SessionWatcher::new();
+                Watch::new(session::POLL_INTERVAL, move || watcher.poll_into(&state))
+            },
+            {
+                let power = Arc::clone(&power);
+                Watch::new(POWER_POLL_INTERVAL, move || {
+                    let status = diagnostics::power::power_status();
+                    let mut current = power.lock().unwrap();
+                    let changed = *current != status;
+                    *current = status;
+                    changed
+                })
+                .immediately()
+            },
+            {
+                let state = net_change.clone();
+                let mut watcher = NetWatcher::new();
+                Watch::new(netwatch::POLL_INTERVAL, move || watcher.poll_into(&state)).immediately()
+            },
+            Watch::new(MONITOR_INTERVAL, resources.sampler()).immediately(),
+        ];
+        let scheduler = Scheduler::start(cc.egui_ctx.clone(), watches);
+        let hardware = Arc::new(HardwareWorker::start(scheduler.waker()));
+        
+        // State carried over from "Restart as administrator"
+        let carried = CarriedState::take_from_args();
+        
+        // Detect system theme
+        let theme_mode = match carried {
+            Some(ref state) if state.dark => ThemeMode::Dark,
+            Some(_) => ThemeMode::Light,
+            None => detect_system_theme(),
+        };
+        let theme = Theme::from_mode(theme_mode).with_status_colors(&settings.status_colors);
+        
+        let mut app = Self {
+            theme_mode,
+            theme,
+            status: "SYS.STATUS: READY".to_string(),
+            report: SharedReport::default(),
+            is_running: Arc::new(Mutex::new(false)),
+            just_completed: Arc::new(Mutex::new(false)),
+            previous_run: None,
+            changed_cards: None,
+            verdict: None,
+            verdict_for: None,
+            help_dismissed: None,
+            readiness_running: false,
+            copied_feedback: None,
+            settings,
+            show_settings: false,
+            // Auto-refresh
+            last_refresh: None,
+            session,
+            net_change,
+            run_trigger: None,
+            power,
+            scheduler,
+            // Error log
+            error_log: ErrorLog::new(),
+            show_history: false,
+            // History
+            history: Arc::new(Mutex::new(History::default())),
+            // Status page
+            status_server: None,
+            status_server_error: None,
+            // Discovery
+            discovery: None,
+            show_agents: false,
+            // Wizard
+            wizard: None,
+            show_wizard: false,
+            // Compare
+            compare: None,
+            show_compare: false,
+            show_why: false,
+            feedback_voted: None,
+            feedback_error: None,
+            show_heatmap: false,
+            heatmap_check: diagnostics::ids::CLAUDE,
+            latency_chart: None,
+            latency_hours: LATENCY_RANGES[0].0,
+            show_history_window: false,
+            show_digest: false,
+            digest: None,
+            pending_digest: Arc::new(Mutex::new(None)),
+            digest_status: None,
+            action: None,
+            action_status: None,
+            show_actions: false,
+            pending_action: None,
+            show_inject: false,
+            inject_provider: 0,
+            inject_minutes: inject::DURATION_PRESETS[0].0,
+            inject_consent: false,
+            injection: None,
+            inject_status: None,
+            capture: None,
+            capture_error: None,
+            show_capture: false,
+            trace: None,
+            trace_summary: None,
+            show_trace: false,
+            bundle_status: None,
+            share_status: None,
+            session_file: share::path_from_args(),
+            session_code: String::new(),
+            session_error: None,
+            viewing: None,
+            tail: None,
+            tail_error: None,
+            tail_errors_only: false,
+            show_output: false,
+            new_endpoint: String::new(),
+            new_header: String::new(),
+            header_error: None,
+            admin_error: None,
+            startup: Some(started),
+            auto_copy: None,
+            copy_flash: None,
+            simulation: Simulation::from_args(),
+            live: None,
+            resources,
+            hardware,
+        };
+        History::load_into(Arc::clone(&app.history), {
+            // Weekly digest on launch, once the history covers a week
+            let pending = Arc::clone(&app.pending_digest);
+            let enabled = app.settings.weekly_digest && app.session_file.is_none();
+            let last_shown = app.settings.digest_shown.clone();
+            let wake = app.scheduler.waker();
+            move |history| {
+                let now = chrono::Local::now().naive_local();
+                if enabled && digest::due(history, &last_shown, now) {
+                    *pending.lock().unwrap() = Digest::build(history, now);
+                    wake();
+                }
+            }
+        });
+        if let Some(state) = carried {
+            app.report.replace(state.report);
+            app.error_log = state.error_log;
+        }
+        app.sync_status_server();
+        app.sync_discovery();
+        // A provider still blocked by an injection that never ended (crash, power loss)
+        if !inject::active().is_empty() {
+            app.inject_status = Some(inject::stop().map(|n| format!("Removed a leftover injection ({} hosts unblocked)", n)));
+        }
+        // Support docs' shortcut: run at once, copy the report when done
+        if cli::args().run_and_copy && app.session_file.is_none() {
+            app.run_diagnostics(&cc.egui_ctx);
+            app.auto_copy = Some(AutoCopy::Running);
+        }
+        app
+    }
+
+    /// Save settings (with the managed policy put back) and apply the ones that run in the background
+    fn apply_settings(&mut self) {
+        if self.settings.enforce_policy() {
+            self.rebuild_theme();
+        }
+        let _ = self.settings.save();
+        self.sync_status_server();
+        self.sync_discovery();
+    }
+
+    /// Start or stop mDNS browsing/advertising to match settings
+    fn sync_discovery(&mut self) {
+        let advertise = self.status_server.as_ref().and_then(|s| s.lan_port());
+        if !self.settings.discover_agents && advertise.is_none() {
+            self.discovery = None;
+            self.show_agents = false;
+            return;
+        }
+        if self.discovery.is_none() {
+            self.discovery = Discovery::start(self.scheduler.waker()).ok();
+        }
+        if let Some(ref mut discovery) = self.discovery {
+            discovery.set_advertised_port(advertise);
+        }
+    }
+
+    /// Start, restart or stop the status page server to match settings
+    fn sync_status_server(&mut self) {
+        if !self.settings.status_page_enabled {
+            self.status_server = None;
+            self.status_server_error = None;
+            return;
+        }
+
+        if self.settings.ensure_api_token() {
+            let _ = self.settings.save();
+        }
+
+        let port = self.settings.status_page_port;
+        let lan = self.settings.status_page_lan;
+        if let Some(ref server) = self.status_server {
+            if server.matches(port, lan, &self.settings.api_token, &self.settings.status_colors) {
+                return;
+            }
+        }
+
+        // Drop the old listener before binding the same port again
+        self.status_server = None;
+        let state = ServerState {
+            report: self.report.clone(),
+            history: Arc::clone(&self.history),
+            api_token: self.settings.api_token.clone(),
+            status_colors: self.settings.status_colors.clone(),
+        };
+        match StatusServer::start(port, lan, state) {
+            Ok(server) => {
+                self.status_server = Some(server);
+                self.status_server_error = None;
+            }
+            Err(e) => self.status_server_error = Some(e),
+        }
+    }
+
+    /// Relaunch elevated with the current report, error log and theme, then close
+    fn restart_as_admin(&mut self, ctx: &egui::Context) {
+        self.apply_settings();
+        let state = CarriedState {
+            report: (*self.report.snapshot()).clone(),
+            error_log: self.error_log.clone(),
+            dark: self.theme_mode == ThemeMode::Dark,
+        };
+        let path = std::env::temp_dir().join(format!("opencode-diag-state-{}.json", std::process::id()));
+        let saved = serde_json::to_string(&state)
+            .map_err(|e| format!("Failed to save state: {}", e))
+            .and_then(|json| std::fs::write(&path, json).map_err(|e| format!("Failed to save state: {}", e)));
+        if let Err(e) = saved {
+            self.admin_error = Some(e);
+            return;
+        }
+
+        // Free the status page port for the new instance
+        self.status_server = None;
+        let mut args = vec![cli::RESTORE_STATE_ARG.to_string(), path.display().to_string()];
+        // The elevated instance starts elsewhere: pass the settings file by absolute path
+        if let Some(config) = cli::args().config.as_ref().and_then(|c| std::path::absolute(c).ok()) {
+            args.extend([cli::CONFIG_ARG.to_string(), config.display().to_string()]);
+        }
+        match diagnostics::elevation::relaunch_elevated(&args) {
+            Ok(()) => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+            Err(e) => {
+                let _ = std::fs::remove_file(&path);
+                self.admin_error = Some(e);
+                self.sync_status_server();
+            }
+        }
+    }
+
+    fn toggle_theme(&mut self) {
+        self.theme_mode = match self.theme_mode {
+            ThemeMode::Light => ThemeMode::Dark,
+            ThemeMode::Dark => ThemeMode::Light,
+        };
+        self.rebuild_theme();
+    }
+
+    /// Theme of the current mode with the status colors from settings (after either changes)
+    fn rebuild_theme(&mut self) {
+        self.theme = Theme::from_mode(self.theme_mode).with_status_colors(&self.settings.status_colors);
+    }
+
+    fn run_diagnostics(&mut self, ctx: &egui::Context) {
+        self.run_diagnostics_with(ctx, self.settings.clone());
+    }
+
+    /// On battery below the threshold with battery saver on
+    fn battery_low(&self) -> bool {
+        self.settings.battery_saver
+            && self.power.lock().unwrap().map(|p| p.is_low(self.settings.battery_threshold)).unwrap_or(false)
+    }
+
+    /// Auto-refresh interval, stretched on low battery
+    fn refresh_interval_secs(&self) -> u32 {
+        if self.battery_low() {
+            self.settings.refresh_interval_secs * BATTERY_INTERVAL_FACTOR
+        } else {
+            self.settings.refresh_interval_secs
+        }
+    }
+
+    /// RUN DIAGNOSTICS: with skip-fresh on, results younger than the TTL are kept
+    fn run_diagnostics_skipping_fresh(&mut self, ctx: &egui::Context) {
+        if !self.settings.skip_fresh {
+            self.run_diagnostics(ctx);
+            return;
+        }
+        let fresh: Vec<&'static str> = self.report.snapshot()
+            .fresh_ids(self.settings.fresh_ttl_secs)
+            .into_iter()
+            .filter(|id| self.settings.is_check_enabled(id))
+            .collect();
+        let mut settings = self.settings.clone();
+        for id in &fresh {
+            settings.set_check_enabled(id, false);
+        }
+        self.spawn_run(ctx, settings, fresh);
+    }
+
+    /// Run with explicit settings (e.g. only the checks a wizard step needs)
+    fn run_diagnostics_with(&mut self, ctx: &egui::Context, settings: DiagnosticSettings) {
+        self.spawn_run(ctx, settings, Vec::new());
+    }
+
+    /// Re-run one check, keeping the other results
+    fn rerun_check(&mut self, ctx: &egui::Context, id: &'static str) {
+        let mut settings = self.settings.clone();
+        let mut reuse = Vec::new();
+        for other in diagnostics::ids::ALL.iter().filter(|other| **other != id) {
+            if settings.is_check_enabled(other) {
+                settings.set_check_enabled(other, false);
+                reuse.push(*other);
+            }
+        }
+        settings.set_check_enabled(id, true);
+        self.spawn_run(ctx, settings, reuse);
+    }
+
+    /// Run in the background, carrying over the `reuse` results from the current report
+    fn spawn_run(&mut self, ctx: &egui::Context, settings: DiagnosticSettings, reuse: Vec<&'static str>) {
+        // Taken before anything can stop the run, so it never labels a later one
+        let trigger = self.run_trigger.take();
+        // A shared session is read-only
+        if self.viewing.is_some() {
+            return;
+        }
+
+        // Check if already running
+        {
+            let mut is_running = self.is_running.lock().unwrap();
+            if *is_running {
+                return;
+            }
+            *is_running = true;
+        }
+
+        self.status = "SYS.STATUS: RUNNING DIAGNOSTICS...".to_string();
+
+        // Skip heavy checks on low battery
+        let settings = if self.battery_low() {
+            diagnostics::power::throttled(&settings)
+        } else {
+            settings
+        };
+
+        let report = self.report.clone();
+        let previous = self.report.snapshot();
+        let history = Arc::clone(&self.history);
+        let simulation = self.simulation;
+        let hardware = Arc::clone(&self.hardware);
+        let requested = Instant::now();
+        if simulation.is_none() {
+            hardware.request(&settings, &reuse);
+        }
+        let live = LiveMetrics::start({
+            let ctx = ctx.clone();
+            move || ctx.request_repaint()
+        });
+        self.live = Some(Arc::clone(&live));
+        let is_running = Arc::clone(&self.is_running);
+        let just_completed = Arc::clone(&self.just_completed);
+        let ctx = ctx.clone();
+
+        thread::spawn(move || {
+            // Run checks based on settings
+            let mut reuse = reuse;
+            let mut hardware_fresh = false;
+            let mut new_report = match simulation {
+                Some(simulation) => simulation.report(&settings),
+                None => {
+                    let mut new_report = DiagnosticReport::new();
+                    // Latest hardware results from the worker, never waited for
+                    new_report.run_with_settings(&hardware::without_hardware(&settings));
+                    hardware_fresh = hardware.apply_to(&mut new_report).is_some_and(|started| started >= requested);
+                    reuse.retain(|id| !hardware::HARDWARE_CHECKS.contains(id));
+                    new_report
+                }
+            };
+
+            new_report.trigger = trigger;
+
+            // Record in history (only what actually ran, never mock data, and
+            // no hardware sample an earlier run already recorded)
+            if simulation.is_none() && !new_report.checks().is_empty() {
+                if hardware_fresh {
+                    history.lock().unwrap().record(&new_report);
+                } else {
+                    history.lock().unwrap().record(&hardware::without_hardware_results(&new_report));
+                }
+            }
+            if !reuse.is_empty() {
+                new_report.adopt(&previous, &reuse);
+            }
+
+            // Update report
+            report.replace(new_report);
+            live.stop();
+
+            // Mark as complete
+            {
+                let mut running = is_running.lock().unwrap();
+                *running = false;
+            }
+            
+            // Signal completion for history
+            {
+                let mut completed = just_completed.lock().unwrap();
+                *completed = true;
+            }
+
+            // Request repaint
+            ctx.request_repaint();
+
+            // History upload, when due (after the run is shown: it can take a while)
+            if simulation.is_none() {
+                upload::upload_if_due(&history, &settings);
+            }
+        });
+    }
+
+    /// Run a remediation action in the background; diagnostics re-run when it finishes
+    fn spawn_action(
+        &mut self,
+        ctx: &egui::Context,
+        label: &'static str,
+        action: impl FnOnce() -> Result<String, String> + Send + 'static,
+    ) {
+        if self.action.is_some() {
+            return;
+        }
+        let result = Arc::new(Mutex::new(None));
+        self.action = Some((label, Arc::clone(&result)));
+        self.action_status = None;
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            *result.lock().unwrap() = Some(action());
+            ctx.request_repaint();
+        });
+    }
+
+    /// Card names from the current report, ids for checks not run this time
+    fn check_names(&self, ids: &[&'static str]) -> Vec<(&'static str, String)> {
+        let report = self.report.snapshot();
+        let checks = report.checks();
+        ids.iter()
+            .map(|id| {
+                let name = checks
+                    .iter()
+                    .find(|(cid, _)| cid == id)
+                    .map(|(_, c)| c.name.clone())
+                    .unwrap_or_else(|| id.to_uppercase());
+                (*id, name)
+            })
+            .collect()
+    }
+
+    /// The text report as COPY REPORT copies it, and why it is unsigned when
+    /// signing was asked for and failed
+    fn report_text(&self, report: &DiagnosticReport) -> (String, Option<String>) {
+        let sections = self.settings.view_role.sections();
+        let mut text = report.to_text_report(sections);
+        if sections.error_log {
+            text.push('\n');
+            text.push_str(&self.error_log.to_text());
+        }
+        if !self.settings.sign_reports {
+            return (text, None);
+        }
+        match signing::sign(&text) {
+            Ok(signed) => (signed, None),
+            Err(e) => (text, Some(e)),
+        }
+    }
+
+    /// Copy the text report to the clipboard; false when the clipboard is unavailable
+    fn copy_report(&mut self) -> bool {
+        let (text, unsigned) = self.report_text(&self.report.snapshot());
+        let copied_status = match unsigned {
+            Some(e) => format!("SYS.STATUS: REPORT COPIED UNSIGNED ({})", e),
+            None => "SYS.STATUS: REPORT COPIED".to_string(),
+        };
+        if let Ok(mut clipboard) = Clipboard::new() {
+            if clipboard.set_text(&text).is_ok() {
+                self.copied_feedback = Some(std::time::Instant::now());
+                self.status = copied_status;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Copy for `--run-and-copy` once the run and the hardware checks are in
+    fn poll_auto_copy(&mut self) {
+        let Some(AutoCopy::Waiting(since)) = self.auto_copy else {
+            return;
+        };
+        let report = self.report.snapshot();
+        let checks = report.checks();
+        let hardware_done = hardware::HARDWARE_CHECKS
+            .iter()
+            .all(|id| !self.settings.is_check_enabled(id) || checks.iter().any(|(done, _)| done == id));
+        if !hardware_done && since.elapsed() < AUTO_COPY_HARDWARE_WAIT {
+            self.scheduler.repaint_at(since + AUTO_COPY_HARDWARE_WAIT);
+            return;
+        }
+        self.auto_copy = None;
+        let copied = self.copy_report();
+        self.copy_flash = Some((Instant::now(), copied));
+    }
+
+    /// Centered confirmation after `--run-and-copy`
+    fn render_copy_flash(&mut self, ctx: &egui::Context) {
+        let Some((shown, copied)) = self.copy_flash else {
+            return;
+        };
+        if shown.elapsed() >= COPY_FLASH {
+            self.copy_flash = None;
+            return;
+        }
+        self.scheduler.repaint_at(shown + COPY_FLASH);
+        let (title, hint, color) = if copied {
+            ("REPORT COPIED TO CLIPBOARD", "Paste it into your support request", self.status_color(CheckStatus::Ok))
+        } else {
+            ("COULD NOT COPY REPORT", "Use COPY REPORT to try again", self.status_color(CheckStatus::Error))
+        };
+        egui::Area::new(egui::Id::new("copy_flash"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(self.theme.panel)
+                    .stroke(egui::Stroke::new(2.0, color))
+                    .rounding(0.0)
+                    .inner_margin(16.0)
+                    .show(ui, |ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.label(
+                                egui::RichText::new(title)
+                                    .size(14.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .strong()
+                                    .color(color),
+                            );
+                            ui.add_space(4.0);
+                            ui.label(
+                                egui::RichText::new(hint)
+                                    .size(9.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.theme.text_dim),
+                            );
+                        });
+                    });
+            });
+    }
+
+    fn status_color(&self, status: CheckStatus) -> egui::Color32 {
+        self.theme.status_color(status)
+    }
+
+    /// Text on a status badge: black or white to read on a remapped color
+    fn badge_text_color(&self, status: CheckStatus) -> egui::Color32 {
+        match self.settings.status_colors.rgb(status) {
+            Some(rgb) => {
+                let [r, g, b] = diagnostics::palette::text_on(rgb);
+                egui::Color32::from_rgb(r, g, b)
+            }
+            None if status == CheckStatus::Ok || status == CheckStatus::Inactive => egui::Color32::WHITE,
+            None => egui::Color32::BLACK,
+        }
+    }
+}
+
+impl eframe::App for App {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Apply UI scale
+        ctx.set_pixels_per_point(self.settings.ui_scale);
+        
+        // Handle Ctrl+scroll for zoom
+        let scroll_delta = ctx.input(|i| i.raw_scroll_delta.y);
+        let ctrl_held = ctx.input(|i| i.modifiers.ctrl);
+        if ctrl_held && scroll_delta != 0.0 && !policy::is_locked("ui_scale") {
+            let delta = if scroll_delta > 0.0 { 0.1 } else { -0.1 };
+            self.settings.adjust_scale(delta);
+        }
+        
+        if let Some(started) = self.startup.take() {
+            let elapsed = started.elapsed();
+            if elapsed > STARTUP_BUDGET {
+                // Shown under LOG and in copied reports (release builds have no console)
+                let time = format!("{} {}ms", chrono::Local::now().format("%H:%M"), elapsed.as_millis());
+                self.error_log.add_error("SLOW STARTUP", &time);
+            }
+        }
+        
+        apply_theme(ctx, &self.theme);
+
+        // Session file dropped on the window: ask for its access code; a report file opens as is
+        let dropped = ctx.input(|i| i.raw.dropped_files.iter().filter_map(|f| f.path.clone()).collect::<Vec<_>>());
+        if let Some(path) = dropped.iter().find(|p| p.extension().is_some_and(|e| e == share::EXTENSION)) {
+            self.session_file = Some(path.clone());
+            self.session_code.clear();
+            self.session_error = None;
+        } else if let Some(path) = dropped.iter().find(|p| reportfile::is_report_file(p)) {
+            self.open_report_file(path);
+        }
+
+        // New hardware results: patch them into the report shown (not into a shared session);
+        // results that arrive during a run wait for it to finish
+        if self.viewing.is_none() && !*self.is_running.lock().unwrap() && self.hardware.take_updated() {
+            let mut report = (*self.report.snapshot()).clone();
+            self.hardware.apply_to(&mut report);
+            self.report.replace(report);
+        }
+
+        // Weekly digest due: open it once, remember when
+        let pending_digest = self.pending_digest.lock().unwrap().take();
+        if let Some(digest) = pending_digest {
+            self.open_digest(Some(digest));
+            self.settings.digest_shown = chrono::Local::now().format(TIMESTAMP_FORMAT).to_string();
+            let _ = self.settings.save();
+        }
+
+        // Handle completed diagnostics - process errors for log
+        {
+            let mut just_completed = self.just_completed.lock().unwrap();
+            if *just_completed {
+                *just_completed = false;
+                self.last_refresh = Some(Instant::now());
+                
+                // Process report for error log (and a waiting wizard step)
+                let report = self.report.snapshot();
+                self.error_log.process_report(&report);
+                if let Some(previous) = self.previous_run.replace(Arc::clone(&report)) {
+                    let changed = compare::changed_checks(&previous, &report);
+                    self.changed_cards = (!changed.is_empty()).then(|| (Instant::now(), changed));
+                }
+                self.status = self.completion_status(&report);
+                if let Some(ref mut wizard) = self.wizard {
+                    wizard.apply_report(&report);
+                }
+                if let Some(ref mut compare) = self.compare {
+                    compare.apply_report(&report);
+                }
+                if std::mem::take(&mut self.readiness_running) {
+                    if let Err(e) = App::notify_readiness(ctx, &report) {
+                        self.status = format!("SYS.STATUS: {}", e.to_uppercase());
+                    }
+                }
+                if let Some(AutoCopy::Running) = self.auto_copy {
+                    self.auto_copy = Some(AutoCopy::Waiting(Instant::now()));
+                }
+            }
+        }
+        self.poll_auto_copy();
+
+        // Failure injection over: unblock, and a run shows the provider is back
+        if let Some((_, until, _)) = self.injection {
+            if Instant::now() >= until {
+                self.stop_injection(ctx);
+            } else {
+                self.scheduler.repaint_at(until);
+            }
+        }
+
+        // Trace over: show the summary (its rounds wake the UI)
+        if self.trace.as_ref().is_some_and(|trace| trace.is_done()) {
+            self.trace_summary = self.trace.take().map(|trace| trace.summary());
+            self.show_trace = true;
+            self.status = "SYS.STATUS: TRACE DONE".to_string();
+        }
+
+        // Cards that changed in the latest run pulse: repaint until they are done
+        if let Some((at, _)) = self.changed_cards {
+            if at.elapsed() >= CHANGE_HIGHLIGHT {
+                self.changed_cards = None;
+            } else {
+                ctx.request_repaint();
+            }
+        }
+
+        // Finished remediation action: show the outcome and verify with a fresh run
+        let finished = self.action.as_ref().and_then(|(label, result)| Some((*label, result.lock().unwrap().take()?)));
+        if let Some(finished) = finished {
+            self.action = None;
+            self.action_status = Some(finished);
+            self.run_diagnostics(ctx);
+        }
+
+        // Network changed: switch the network profile, run on the new network
+        // (not while locked or viewing a shared session)
+        let watch_network = self.settings.run_on_network_change || !self.settings.network_profiles.is_empty();
+        self.net_change.set_enabled(watch_network && self.viewing.is_none());
+        if let Some(network) = self.net_change.network() {
+            if self.settings.apply_network(&network) {
+                self.settings.enforce_policy();
+                let _ = self.settings.save();
+            }
+        }
+        if !self.session.is_locked() && !*self.is_running.lock().unwrap() {
+            if let Some(change) = self.net_change.take().filter(|_| self.settings.run_on_network_change) {
+                let profile = &self.settings.active_network_profile;
+                self.run_trigger = Some(if profile.is_empty() {
+                    format!("network change ({})", change)
+                } else {
+                    format!("network change ({}), profile {}", change, profile)
+                });
+                self.run_diagnostics(ctx);
+            }
+        }
+
+        // Morning readiness: the daily run at the set time (or the first chance after it)
+        if self.settings.readiness_enabled && self.viewing.is_none() {
+            let now = chrono::Local::now().naive_local();
+            if !*self.is_running.lock().unwrap() && readiness::is_due(&self.settings.readiness_time, &self.settings.readiness_last, now) {
+                self.settings.readiness_last = now.format(readiness::DATE_FORMAT).to_string();
+                let _ = self.settings.save();
+                self.readiness_running = true;
+                self.run_trigger = Some(format!("morning readiness ({})", self.settings.readiness_time.trim()));
+                self.run_diagnostics(ctx);
+            }
+            if let Some(wait) = readiness::next_due(&self.settings.readiness_time, now).and_then(|due| (due - now).to_std().ok()) {
+                self.scheduler.repaint_at(Instant::now() + wait);
+            }
+        }
+
+        // Auto-refresh logic (paused while the session is locked, immediate run on unlock/wake)
+        if self.settings.auto_refresh {
+            let resumed = self.session.take_resumed();
+            if !self.session.is_locked() && !*self.is_running.lock().unwrap() {
+                let due = self.last_refresh
+                    .map(|last| last.elapsed().as_secs() as u32 >= self.refresh_interval_secs())
+                    .unwrap_or(false);
+                if resumed || due {
+                    self.run_diagnostics(ctx);
+                }
+            }
+        }
+
+        // Repaint when the header LAST/NEXT text next changes (this also lands on the due time)
+        if let Some(last) = self.last_refresh {
+            let countdown = self.settings.auto_refresh && !self.session.is_locked();
+            self.scheduler.repaint_at(next_tick(last, countdown));
+        }
+
+        // Check if copied feedback should be cleared
+        if let Some(instant) = self.copied_feedback {
+            if instant.elapsed().as_secs() >= 2 {
+                self.copied_feedback = None;
+                self.status = "SYS.STATUS: READY".to_string();
+            } else {
+                self.scheduler.repaint_at(instant + Duration::from_secs(2));
+            }
+        }
+
+        // Update status if running
+        if let Some((label, _)) = self.action {
+            self.status = format!("SYS.STATUS: RUNNING {}...", label);
+        } else if *self.is_running.lock().unwrap() {
+            self.status = "SYS.STATUS: RUNNING DIAGNOSTICS...".to_string();
+        }
+
+        self.render_header(ctx);
+        self.render_footer(ctx);
+
+        // Settings popup
+        if self.show_settings {
+            self.render_settings(ctx);
+        }
+
+        // Error Log popup
+        if self.show_history {
+            self.render_error_log(ctx);
+        }
+
+        // Agents popup (other machines found via mDNS)
+        if self.show_agents {
+            self.render_agents(ctx);
+        }
+
+        // Troubleshooting wizard popup
+        if self.show_wizard {
+            self.render_wizard(ctx);
+        }
+
+        // A/B compare popup
+        if self.show_compare {
+            self.render_compare(ctx);
+        }
+
+        // Latency heatmap popup
+        if self.show_heatmap {
+            self.render_heatmap(ctx);
+        }
+
+        // Latency chart popup
+        if self.latency_chart.is_some() {
+            self.render_latency_chart(ctx);
+        }
+
+        // History window, next to the live dashboard
+        if self.show_history_window {
+            self.render_history_window(ctx);
+        }
+
+        // Weekly digest popup
+        if self.show_digest {
+            self.render_digest(ctx);
+        }
+
+        // Network quick actions popup
+        if self.show_actions {
+            self.render_actions(ctx);
+        }
+
+        // Failure injection popup
+        if self.show_inject {
+            self.render_inject(ctx);
+        }
+
+        // Traffic capture popup
+        if self.show_capture {
+            self.render_capture(ctx);
+        }
+
+        // Trace summary popup
+        if self.show_trace {
+            self.render_trace(ctx);
+        }
+
+        // OpenCode output popup
+        if self.show_output {
+            self.render_output(ctx);
+        }
+
+        // Access code for a shared session
+        if self.session_file.is_some() {
+            self.render_session_prompt(ctx);
+        }
+
+        self.render_copy_flash(ctx);
+
+        self.render_main(ctx);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // Never leave a provider blocked
+        if self.injection.is_some() {
+            let _ = inject::stop();
+        }
+    }
+}
+
+impl App {
+    /// Draw a transparent full-screen layer behind a popup, true when clicked (close)
+    fn overlay_clicked(ctx: &egui::Context, id: &str) -> bool {
+        let screen_rect = ctx.screen_rect();
+        egui::Area::new(egui::Id::new(id))
+            .fixed_pos(screen_rect.min)
+            .order(egui::Order::Background)
+            .show(ctx, |ui| {
+                ui.allocate_response(screen_rect.size(), egui::Sense::click())
+            })
+            .inner
+            .clicked()
+    }
+
+    /// Write the support bundle, returns a status line for the LOG popup
+    fn create_support_bundle(&self) -> String {
+        let report = self.report.snapshot();
+        let history = self.history.lock().unwrap();
+        match diagnostics::bundle::create_support_bundle(&self.settings, &report, &history, &self.error_log.to_text()) {
+            Ok(path) => format!("Saved {}", path.display()),
+            Err(e) => e,
+        }
+    }
+
+    /// Encrypt the report, history and error log for someone helping remotely
+    fn export_session(&self) -> Result<(PathBuf, String), String> {
+        let report = self.report.snapshot();
+        let history = self.history.lock().unwrap();
+        share::export_session(&SharedSession::capture(&report, &history, &self.error_log))
+    }
+
+    /// Decrypt `session_file` and show it in place of this machine's results
+    fn open_session(&mut self) {
+        let Some(path) = self.session_file.clone() else {
+            return;
+        };
+        if *self.is_running.lock().unwrap() {
+            self.session_error = Some("Wait for the current run to finish".to_string());
+            return;
+        }
+        let session = match share::import_session(&path, &self.session_code) {
+            Ok(session) => session,
+            Err(e) => {
+                self.session_error = Some(e);
+                return;
+            }
+        };
+
+        let title = format!("SHARED SESSION {} :: v{}", session.created, session.app_version);
+        self.view(session.report.clone(), session.history(), session.error_log.clone(), title, session.system);
+        self.session_file = None;
+        self.session_code.clear();
+        self.session_error = None;
+    }
+
+    /// Open a dropped `.json` / `.txt` report read-only
+    fn open_report_file(&mut self, path: &Path) {
+        if *self.is_running.lock().unwrap() {
+            self.status = "SYS.STATUS: WAIT FOR THE RUN TO FINISH".to_string();
+            return;
+        }
+        match reportfile::load(path) {
+            Ok(file) => {
+                let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                let title = match file.report.timestamp {
+                    Some(ref time) => format!("REPORT FILE {} :: {}", name, time),
+                    None => format!("REPORT FILE {}", name),
+                };
+                let hover = file.system.unwrap_or_else(|| path.display().to_string());
+                self.view(file.report, History::default(), ErrorLog::new(), title, hover);
+            }
+            Err(e) => self.status = format!("SYS.STATUS: {}", e.to_uppercase()),
+        }
+    }
+
+    /// Show results from elsewhere read-only, keeping this machine's state to return to
+    fn view(&mut self, report: DiagnosticReport, history: History, error_log: ErrorLog, title: String, hover: String) {
+        // Opened over another one: keep the original machine's state
+        let (own_report, own_history, own_error_log) = match self.viewing.take() {
+            Some(viewing) => (viewing.own_report, viewing.own_history, viewing.own_error_log),
+            None => (
+                std::mem::take(&mut self.report),
+                std::mem::replace(&mut self.history, Arc::new(Mutex::new(History::default()))),
+                std::mem::replace(&mut self.error_log, ErrorLog::new()),
+            ),
+        };
+        // Fresh handles: the status page keeps serving this machine's own report and history
+        self.report = SharedReport::default();
+        self.report.replace(report);
+        self.history = Arc::new(Mutex::new(history));
+        self.error_log = error_log;
+        self.viewing = Some(Viewing { title, hover, own_report, own_history, own_error_log });
+        self.show_why = false;
+    }
+
+    /// Leave the shared session and restore this machine's results
+    fn close_session(&mut self) {
+        if let Some(viewing) = self.viewing.take() {
+            self.report = viewing.own_report;
+            self.history = viewing.own_history;
+            self.error_log = viewing.own_error_log;
+        }
+    }
+
+    /// Header: title and the theme toggle
+    fn render_header(&mut self, ctx: &egui::Context) {
+        egui::TopBottomPanel::top("header")
+            .frame(egui::Frame::none().fill(self.theme.header))
+            .show(ctx, |ui| {
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    ui.add_space(20.0);
+
+                    // Square indicator (glows in dark mode)
+                    let indicator_color = if self.theme_mode == ThemeMode::Dark {
+                        self.theme.accent_on
+                    } else {
+                        self.theme.text
+                    };
+                    let (rect, _) = ui.allocate_exact_size(
+                        egui::vec2(10.0, 10.0),
+                        egui::Sense::hover(),
+                    );
+                    ui.painter().rect_filled(rect, 0.0, indicator_color);
+
+                    ui.add_space(10.0);
+
+                    // Title
+                    ui.label(
+                        egui::RichText::new("OPENCODE DIAGNOSTICS")
+                            .size(14.0)
+                            .strong()
+                            .color(self.theme.text),
+                    );
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.add_space(20.0);
+
+                        // Theme toggle button
+                        let btn_text = match self.theme_mode {
+                            ThemeMode::Light => "DARK",
+                            ThemeMode::Dark => "LIGHT",
+                        };
+
+                        if ui.add(
+                            egui::Button::new(
+                                egui::RichText::new(btn_text)
+                                    .size(9.0)
+                                    .strong()
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.theme.text_dim)
+                            )
+                            .fill(self.theme.panel)
+                            .stroke(egui::Stroke::NONE)
+                            .rounding(0.0)
+                            .min_size(egui::vec2(50.0, 24.0))
+                        ).clicked() {
+                            self.toggle_theme();
+                        }
+                    });
+                });
+
+                ui.add_space(12.0);
+            });
+    }
+
+    /// Footer: status line, version, LAST/NEXT run times and what is running in the background
+    fn render_footer(&mut self, ctx: &egui::Context) {
+        egui::TopBottomPanel::bottom("footer")
+            .frame(egui::Frame::none().fill(self.theme.window))
+            .show(ctx, |ui| {
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    ui.add_space(25.0);
+                    let status = ui.label(
+                        egui::RichText::new(&self.status)
+                            .size(9.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(self.theme.text_dim),
+                    );
+                    a11y::live(&status);
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.add_space(25.0);
+                        ui.label(
+                            egui::RichText::new(format!("v{}", VERSION))
+                                .size(9.0)
+                                .family(egui::FontFamily::Monospace)
+                                .color(self.theme.text_dim),
+                        );
+
+                        ui.add_space(15.0);
+
+                        // Show last check time and next refresh
+                        if let Some(last) = self.last_refresh {
+                            let elapsed = last.elapsed().as_secs();
+                            let ago_str = format_age(elapsed);
+
+                            let time_info = if self.settings.auto_refresh && self.session.is_locked() {
+                                format!("LAST: {} | PAUSED (LOCKED)", ago_str)
+                            } else if self.settings.auto_refresh {
+                                let remaining = self.refresh_interval_secs().saturating_sub(elapsed as u32);
+                                format!("LAST: {} | NEXT: {}s", ago_str, remaining)
+                            } else {
+                                format!("LAST: {}", ago_str)
+                            };
+
+                            ui.label(
+                                egui::RichText::new(&time_info)
+                                    .size(9.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.theme.text_dim),
+                            );
+                        }
+
+                        // Simulation indicator
+                        if let Some(simulation) = self.simulation {
+                            ui.add_space(10.0);
+                            ui.label(
+                                egui::RichText::new(simulation.label())
+                                    .size(9.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.status_color(CheckStatus::Warning)),
+                            ).on_hover_text("Results are generated, no checks run; a seed makes every run identical");
+                        }
+
+                        // Traffic cap indicator
+                        if diagnostics::traffic::over_cap(self.settings.monthly_cap_mb) {
+                            ui.add_space(10.0);
+                            ui.label(
+                                egui::RichText::new("TRAFFIC CAP :: PAUSED")
+                                    .size(9.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.status_color(CheckStatus::Warning)),
+                            ).on_hover_text(format!(
+                                "{} of {} MB used this month: API, relay and endpoint certificate checks are paused",
+                                format_bytes(diagnostics::traffic::usage().month_bytes),
+                                self.settings.monthly_cap_mb
+                            ));
+                        }
+
+                        // Battery saver indicator
+                        if self.battery_low() {
+                            ui.add_space(10.0);
+                            let percent = self.power.lock().unwrap().and_then(|p| p.percent).unwrap_or(0);
+                            ui.label(
+                                egui::RichText::new(format!("BATTERY {}% :: SAVER", percent))
+                                    .size(9.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.status_color(CheckStatus::Warning)),
+                            ).on_hover_text(format!(
+                                "Below {}% on battery: GPU, firewall and root CA checks are skipped and auto-refresh runs {}x less often",
+                                self.settings.battery_threshold,
+                                BATTERY_INTERVAL_FACTOR
+                            ));
+                        }
+
+                        // Failure injection indicator
+                        if let Some((host, _, ref until)) = self.injection {
+                            ui.add_space(10.0);
+                            let indicator = ui.add(
+                                egui::Label::new(
+                                    egui::RichText::new(format!("INJECTING :: {} BLOCKED UNTIL {}", host, until))
+                                        .size(9.0)
+                                        .family(egui::FontFamily::Monospace)
+                                        .color(self.status_color(CheckStatus::Error)),
+                                )
+                                .sense(egui::Sense::click()),
+                            ).on_hover_text("Failure injection in progress: click to stop it now");
+                            if indicator.clicked() {
+                                self.open_inject();
+                            }
+                        }
+
+                        // Traffic capture indicator
+                        if let Some(ref capture) = self.capture {
+                            ui.add_space(10.0);
+                            let indicator = ui.add(
+                                egui::Label::new(
+                                    egui::RichText::new(format!("CAPTURING :: {}", capture.entries().len()))
+                                        .size(9.0)
+                                        .family(egui::FontFamily::Monospace)
+                                        .color(self.status_color(CheckStatus::Warning)),
+                                )
+                                .sense(egui::Sense::click()),
+                            ).on_hover_text(format!("Capture proxy on {}: click for the connections", capture.url()));
+                            if indicator.clicked() {
+                                self.open_capture();
+                            }
+                        }
+                    });
+                });
+                ui.add_space(10.0);
+            });
+    }
+
+    /// SETTINGS popup (an Area rather than a Window for better control); a click
+    /// outside closes it and applies the settings
+    fn render_settings(&mut self, ctx: &egui::Context) {
+        // Check for click outside to close
+        let popup_id = egui::Id::new("settings_popup");
+
+        // Draw a transparent overlay to detect clicks outside
+        let screen_rect = ctx.screen_rect();
+        let response = egui::Area::new(egui::Id::new("settings_overlay"))
+            .fixed_pos(screen_rect.min)
+            .order(egui::Order::Background)
+            .show(ctx, |ui| {
+                ui.allocate_response(screen_rect.size(), egui::Sense::click())
+            });
+
+        if response.inner.clicked() {
+            self.show_settings = false;
+            // Save settings when closing
+            self.apply_settings();
+        }
+
+        // The actual popup
+        egui::Area::new(popup_id)
+            .anchor(egui::Align2::RIGHT_TOP, [-25.0, 85.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(self.theme.panel)
+                    .stroke(egui::Stroke::new(1.0, self.theme.border))
+                    .rounding(0.0)  // Sharp corners
+                    .shadow(egui::Shadow::NONE)  // No shadow
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.set_min_width(180.0);
+
+                        // Scroll when the window is too short for every section
+                        let max_height = (ctx.screen_rect().height() - 130.0).max(150.0);
+                        egui::ScrollArea::vertical()
+                            .max_height(max_height)
+                            .show(ui, |ui| {
+                                self.render_settings_policy(ui);
+                                self.render_settings_role(ui);
+                                let sections: [fn(&mut Self, &mut egui::Ui); 17] = [
+                                    Self::render_settings_system,
+                                    Self::render_settings_network,
+                                    Self::render_settings_apis,
+                                    Self::render_settings_endpoints,
+                                    Self::render_settings_requests,
+                                    Self::render_settings_processes,
+                                    Self::render_settings_tags,
+                                    Self::render_settings_network_profiles,
+                                    Self::render_settings_auto_refresh,
+                                    Self::render_settings_hysteresis,
+                                    Self::render_settings_scale,
+                                    Self::render_settings_status_colors,
+                                    Self::render_settings_status_page,
+                                    Self::render_settings_upload,
+                                    Self::render_settings_signing,
+                                    Self::render_settings_injection,
+                                    Self::render_settings_capture,
+                                ];
+                                for section in sections {
+                                    ui.add_space(8.0);
+                                    ui.add(egui::Separator::default().spacing(1.0));
+                                    ui.add_space(8.0);
+                                    section(self, ui);
+                                }
+                                self.render_settings_admin(ui);
+                            });
+                    });
+            });
+    }
+
+    /// LOG popup: errors of past runs
+    fn render_error_log(&mut self, ctx: &egui::Context) {
+        // Check for click outside to close
+        let popup_id = egui::Id::new("history_popup");
+
+        // Draw a transparent overlay to detect clicks outside
+        let screen_rect = ctx.screen_rect();
+        let response = egui::Area::new(egui::Id::new("history_overlay"))
+            .fixed_pos(screen_rect.min)
+            .order(egui::Order::Background)
+            .show(ctx, |ui| {
+                ui.allocate_response(screen_rect.size(), egui::Sense::click())
+            });
+
+        if response.inner.clicked() {
+            self.show_history = false;
+        }
+
+        // The actual popup
+        egui::Area::new(popup_id)
+            .anchor(egui::Align2::RIGHT_TOP, [-25.0, 85.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(self.theme.panel)
+                    .stroke(egui::Stroke::new(1.0, self.theme.border))
+                    .rounding(0.0)
+                    .shadow(egui::Shadow::NONE)
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.set_min_width(280.0);
+
+                        self.render_popup_title(ui, "// ERROR LOG");
+                        ui.add_space(8.0);
+
+                        self.render_error_log_entries(ui);
+
+                        // Anonymized bundle for bug reports against opencode-diag itself
+                        ui.add_space(8.0);
+                        ui.add(egui::Separator::default().spacing(1.0));
+                        ui.add_space(8.0);
+                        let bundle_btn = egui::Button::new(
+                            egui::RichText::new("CREATE SUPPORT BUNDLE")
+                                .size(9.0)
+                                .family(egui::FontFamily::Monospace)
+                                .color(self.theme.text)
+                        )
+                        .fill(self.theme.panel)
+                        .stroke(egui::Stroke::new(1.0, self.theme.border))
+                        .rounding(0.0)
+                        .min_size(egui::vec2(140.0, 18.0));
+
+                        if ui.add_enabled(self.viewing.is_none(), bundle_btn).on_hover_text("Anonymized settings, history, log and report as .zip").clicked() {
+                            self.bundle_status = Some(self.create_support_bundle());
+                        }
+                        if let Some(ref status) = self.bundle_status {
+                            ui.add(egui::Label::new(
+                                egui::RichText::new(status)
+                                    .size(8.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.theme.text_dim),
+                            ).wrap());
+                        }
+
+                        // Encrypted copy for someone helping remotely
+                        ui.add_space(6.0);
+                        let share_btn = egui::Button::new(
+                            egui::RichText::new("EXPORT SESSION")
+                                .size(9.0)
+                                .family(egui::FontFamily::Monospace)
+                                .color(self.theme.text)
+                        )
+                        .fill(self.theme.panel)
+                        .stroke(egui::Stroke::new(1.0, self.theme.border))
+                        .rounding(0.0)
+                        .min_size(egui::vec2(140.0, 18.0));
+
+                        if ui.add_enabled(self.viewing.is_none(), share_btn)
+                            .on_hover_text("Encrypted report, history and log to open read-only in another copy")
+                            .clicked()
+                        {
+                            self.share_status = Some(self.export_session());
+                        }
+                        match self.share_status {
+                            Some(Ok((ref path, ref code))) => {
+                                ui.add(egui::Label::new(
+                                    egui::RichText::new(format!("Saved {}", path.display()))
+                                        .size(8.0)
+                                        .family(egui::FontFamily::Monospace)
+                                        .color(self.theme.text_dim),
+                                ).wrap());
+                                ui.horizontal(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(format!("ACCESS CODE {}", code))
+                                            .size(9.0)
+                                            .strong()
+                                            .family(egui::FontFamily::Monospace)
+                                            .color(self.theme.text),
+                                    )
+                                    .on_hover_text("Send it separately from the file; it is not stored anywhere");
+                                    let copy_btn = egui::Button::new(
+                                        egui::RichText::new("COPY")
+                                            .size(8.0)
+                                            .family(egui::FontFamily::Monospace)
+                                            .color(self.theme.text)
+                                    )
+                                    .fill(self.theme.panel)
+                                    .stroke(egui::Stroke::new(1.0, self.theme.border))
+                                    .rounding(0.0);
+                                    if ui.add(copy_btn).clicked() {
+                                        if let Ok(mut clipboard) = Clipboard::new() {
+                                            let _ = clipboard.set_text(code);
+                                        }
+                                    }
+                                });
+                            }
+                            Some(Err(ref e)) => {
+                                ui.add(egui::Label::new(
+                                    egui::RichText::new(e)
+                                        .size(8.0)
+                                        .family(egui::FontFamily::Monospace)
+                                        .color(self.theme.text_dim),
+                                ).wrap());
+                            }
+                            None => {}
+                        }
+                    });
+            });
+    }
+
+    /// AGENTS popup: other machines found via mDNS and their status
+    fn render_agents(&mut self, ctx: &egui::Context) {
+        if App::overlay_clicked(ctx, "agents_overlay") {
+            self.show_agents = false;
+        }
+
+        let agents = self.discovery.as_ref().map(|d| d.agents()).unwrap_or_default();
+        egui::Area::new(egui::Id::new("agents_popup"))
+            .anchor(egui::Align2::RIGHT_TOP, [-25.0, 85.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(self.theme.panel)
+                    .stroke(egui::Stroke::new(1.0, self.theme.border))
+                    .rounding(0.0)
+                    .shadow(egui::Shadow::NONE)
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.set_min_width(280.0);
+
+                        ui.label(
+                            egui::RichText::new("// LAN AGENTS")
+                                .size(9.0)
+                                .family(egui::FontFamily::Monospace)
+                                .color(self.theme.text_dim),
+                        );
+                        ui.add_space(8.0);
+
+                        if agents.is_empty() {
+                            ui.label(
+                                egui::RichText::new("Searching... (agents need status page on LAN)")
+                                    .size(9.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.theme.text_dim),
+                            );
+                        }
+
+                        for agent in &agents {
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    egui::RichText::new(&agent.name)
+                                        .size(9.0)
+                                        .family(egui::FontFamily::Monospace)
+                                        .strong()
+                                        .color(self.theme.text),
+                                );
+                                ui.add_space(10.0);
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "{}:{} {}",
+                                        agent.address,
+                                        agent.port,
+                                        agent.version.as_deref().map(|v| format!("v{}", v)).unwrap_or_default()
+                                    ))
+                                        .size(9.0)
+                                        .family(egui::FontFamily::Monospace)
+                                        .color(self.theme.text_dim),
+                                );
+
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    let open_btn = egui::Button::new(
+                                        egui::RichText::new("OPEN")
+                                            .size(9.0)
+                                            .family(egui::FontFamily::Monospace)
+                                            .color(self.theme.text)
+                                    )
+                                    .fill(self.theme.panel)
+                                    .stroke(egui::Stroke::new(1.0, self.theme.border))
+                                    .rounding(0.0)
+                                    .min_size(egui::vec2(40.0, 18.0));
+
+                                    if ui.add(open_btn).clicked() {
+                                        open_url(&agent.url());
+                                    }
+                                });
+                            });
+                            ui.add_space(3.0);
+                        }
+                    });
+            });
+    }
+
+    /// Main content: shared session banner, toolbar, check cards, action buttons
+    fn render_main(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none().fill(self.theme.window).inner_margin(25.0))
+            .show(ctx, |ui| {
+                self.render_session_banner(ui);
+                self.render_toolbar(ui);
+
+                ui.add_space(15.0);
+
+                // Calculate available height for scroll area
+                let available_height = ui.available_height() - 60.0; // Reserve space for buttons
+
+                // Scrollable area for check cards - now fills available height
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])  // Don't shrink
+                    .max_height(available_height)
+                    .show(ui, |ui| {
+                        // Get report data
+                        let report = self.report.snapshot();
+
+                        // Machine load while the checks ran
+                        if let Some(live) = self.live.clone() {
+                            self.render_live_strip(ui, &live);
+                        }
+
+                        // Is it me or them? (only once something has run)
+                        if report.timestamp.is_some() {
+                            self.render_verdict(ui, &report);
+                        }
+
+                        // Errors: offer to take the report to OpenCode
+                        if report.timestamp.is_some() {
+                            self.render_help_prompt(ui, &report);
+                        }
+
+                        // Where is it broken? (only once something has run)
+                        if report.timestamp.is_some() {
+                            self.render_path(ui, &report);
+                        }
+
+                        self.render_cards(ui, &report);
+                        self.render_diagnosis(ui, &report);
+                    });
+
+                ui.add_space(15.0);
+
+                self.render_action_buttons(ui);
+            });
+    }
+
+    /// Access code prompt for a dropped or `--view-session` file
+    fn render_session_prompt(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.session_file.clone() else {
+            return;
+        };
+        let theme = self.theme;
+        let text = |s: &str, color: egui::Color32| {
+            egui::RichText::new(s)
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(color)
+        };
+        let button = |label: &str| {
+            egui::Button::new(text(label, theme.text).strong())
+                .fill(theme.panel)
+                .stroke(egui::Stroke::new(1.0, theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(70.0, 20.0))
+        };
+
+        egui::Area::new(egui::Id::new("session_prompt"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(theme.panel)
+                    .stroke(egui::Stroke::new(1.0, theme.border))
+                    .rounding(0.0)
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.set_width(300.0);
+                        ui.label(text("// OPEN SHARED SESSION", theme.text_dim));
+                        ui.add_space(6.0);
+                        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                        ui.add(egui::Label::new(text(&name, theme.text)).wrap());
+                        ui.add_space(6.0);
+                        ui.label(text("ACCESS CODE", theme.text_dim));
+                        let input = ui.add(
+                            egui::TextEdit::singleline(&mut self.session_code)
+                                .font(egui::FontId::monospace(10.0))
+                                .hint_text("XXXX-XXXX-XXXX-XXXX-XXXX")
+                                .desired_width(f32::INFINITY),
+                        );
+                        let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        if let Some(ref error) = self.session_error {
+                            ui.add_space(4.0);
+                            ui.add(egui::Label::new(text(error, self.status_color(CheckStatus::Error))).wrap());
+                        }
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            if ui.add(button("OPEN")).clicked() || submitted {
+                                self.open_session();
+                            }
+                            if ui.add(button("CANCEL")).clicked() {
+                                self.session_file = None;
+                                self.session_code.clear();
+                                self.session_error = None;
+                            }
+                        });
+                        ui.add_space(4.0);
+                        ui.label(text("Opens read-only; no checks run while viewing.", theme.text_dim));
+                    });
+            });
+    }
+
+    /// Live OpenCode output: launch or follow, tagged lines link to their check
+    fn render_output(&mut self, ctx: &egui::Context) {
+        if App::overlay_clicked(ctx, "output_overlay") {
+            self.show_output = false;
+        }
+
+        let theme = self.theme;
+        let text = |s: &str, color: egui::Color32| {
+            egui::RichText::new(s)
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(color)
+        };
+        let button = |label: &str| {
+            egui::Button::new(text(label, theme.text).strong())
+                .fill(theme.panel)
+                .stroke(egui::Stroke::new(1.0, theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(60.0, 18.0))
+        };
+        let lines = self.tail.as_ref().map(|t| t.lines()).unwrap_or_default();
+        let mut rerun = None;
+
+        egui::Area::new(egui::Id::new("output_popup"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(theme.panel)
+                    .stroke(egui::Stroke::new(1.0, theme.border))
+                    .rounding(0.0)
+                    .shadow(egui::Shadow::NONE)
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.set_width(560.0);
+                        ui.label(text("// OPENCODE OUTPUT", theme.text_dim));
+                        ui.add_space(8.0);
+
+                        ui.horizontal(|ui| {
+                            match self.tail {
+                                Some(ref tail) => {
+                                    ui.label(text(&tail.source, theme.text));
+                                    let stop = ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                        let stop = ui.add(button("STOP")).on_hover_text("Stops a launched OpenCode too").clicked();
+                                        if ui.add(button("CLEAR")).clicked() {
+                                            tail.clear();
+                                        }
+                                        stop
+                                    }).inner;
+                                    if stop {
+                                        self.tail = None;
+                                    }
+                                }
+                                None => {
+                                    let command = match self.settings.tail_command.trim() {
+                                        "" => DEFAULT_TAIL_COMMAND,
+                                        command => command,
+                                    };
+                                    let hover = format!("Run `{}` with its output shown here", command);
+                                    if ui.add(button("LAUNCH")).on_hover_text(hover).clicked() {
+                                        let waker = Arc::new(self.scheduler.waker());
+                                        let started = OutputTail::launch(&self.settings.tail_command, move || waker());
+                                        self.tail_error = started.as_ref().err().cloned();
+                                        self.tail = started.ok();
+                                    }
+                                    if ui.add(button("FOLLOW LOG")).on_hover_text("Tail the newest file in OpenCode's log directory").clicked() {
+                                        let waker = self.scheduler.waker();
+                                        let started = OutputTail::follow_log(waker);
+                                        self.tail_error = started.as_ref().err().cloned();
+                                        self.tail = started.ok();
+                                    }
+                                }
+                            }
+                        });
+                        if let Some(ref error) = self.tail_error {
+                            ui.add(egui::Label::new(text(error, self.status_color(CheckStatus::Error))).wrap());
+                        }
+                        ui.add_space(4.0);
+                        App::render_styled_checkbox(ui, &mut self.tail_errors_only, "Errors only", theme.text);
+                        ui.add_space(6.0);
+
+                        egui::ScrollArea::vertical()
+                            .max_height(320.0)
+                            .auto_shrink([false, false])
+                            .stick_to_bottom(true)
+                            .show(ui, |ui| {
+                                for line in lines.iter().filter(|l| !self.tail_errors_only || l.is_error()) {
+                                    ui.horizontal_wrapped(|ui| {
+                                        ui.spacing_mut().item_spacing.x = 6.0;
+                                        ui.label(text(&line.time, theme.text_dim));
+                                        if let Some(tag) = line.tag {
+                                            let chip = egui::Button::new(
+                                                text(&format!("[{}]", tag.label), egui::Color32::WHITE).strong()
+                                            )
+                                            .fill(self.status_color(CheckStatus::Error))
+                                            .stroke(egui::Stroke::NONE)
+                                            .rounding(0.0);
+                                            let hover = format!("Re-run {}", tag.check.to_uppercase());
+                                            if ui.add(chip).on_hover_text(hover).clicked() {
+                                                rerun = Some(tag.check);
+                                            }
+                                        }
+                                        let color = match line.stream {
+                                            Stream::Stderr => self.status_color(CheckStatus::Warning),
+                                            Stream::Status => theme.text_dim,
+                                            Stream::Stdout | Stream::Log => theme.text,
+                                        };
+                                        ui.add(egui::Label::new(text(&line.text, color)).wrap());
+                                    });
+                                }
+                                if lines.is_empty() && self.tail.is_some() {
+                                    ui.label(text("Waiting for output...", theme.text_dim));
+                                }
+                            });
+                    });
+            });
+
+        if let Some(id) = rerun {
+            self.rerun_check(ctx, id);
+        }
+    }
+
+    /// Network quick actions, each confirmed before it runs
+    fn render_actions(&mut self, ctx: &egui::Context) {
+        if App::overlay_clicked(ctx, "actions_overlay") {
+            self.show_actions = false;
+            self.pending_action = None;
+        }
+        
+        let theme = self.theme;
+        let busy = self.action.is_some() || *self.is_running.lock().unwrap();
+        let elevated = diagnostics::elevation::is_elevated();
+        let text = |s: &str, color: egui::Color32| {
+            egui::RichText::new(s)
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(color)
+        };
+        let button = |label: &str, selected: bool| {
+            egui::Button::new(text(label, if selected { egui::Color32::WHITE } else { theme.text }).strong())
+                .fill(if selected { theme.accent_on } else { theme.panel })
+                .stroke(egui::Stroke::new(1.0, theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(90.0, 20.0))
+        };
+        let mut run = None;
+        
+        egui::Area::new(egui::Id::new("actions_popup"))
+            .anchor(egui::Align2::RIGHT_TOP, [-25.0, 85.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(theme.panel)
+                    .stroke(egui::Stroke::new(1.0, theme.border))
+                    .rounding(0.0)
+                    .shadow(egui::Shadow::NONE)
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.set_width(300.0);
+                        ui.label(text("// QUICK ACTIONS", theme.text_dim));
+                        ui.add_space(8.0);
+                        
+                        ui.horizontal(|ui| {
+                            for action in NETWORK_ACTIONS {
+                                let selected = self.pending_action.as_ref().map(|(a, _)| a) == Some(action);
+                                if ui.add_enabled(!busy, button(action.label(), selected)).clicked() {
+                                    self.pending_action = if selected { None } else { Some((*action, action.command_line())) };
+                                }
+                            }
+                        });
+                        
+                        // Confirmation
+                        if let Some((action, ref command_line)) = self.pending_action {
+                            ui.add_space(8.0);
+                            ui.add(egui::Label::new(text(action.description(), theme.text)).wrap());
+                            ui.add(egui::Label::new(text(&format!("> {}", command_line), theme.text_dim)).wrap());
+                            if action.needs_admin() && !elevated {
+                                ui.label(text("Needs administrator: Windows will ask for approval", theme.text_dim));
+                            }
+                            ui.add_space(4.0);
+                            ui.horizontal(|ui| {
+                                if ui.add_enabled(!busy, button("CONFIRM", true)).clicked() {
+                                    run = Some(action);
+                                }
+                                if ui.add(button("CANCEL", false)).clicked() {
+                                    self.pending_action = None;
+                                }
+                            });
+                        }
+                        
+                        // Output of the last quick action
+                        if let Some((label, ref result)) = self.action_status {
+                            if let Some(action) = NETWORK_ACTIONS.iter().find(|a| a.label() == label) {
+                                ui.add_space(8.0);
+                                ui.add(egui::Separator::default().spacing(1.0));
+                                ui.add_space(8.0);
+                                let (output, color) = match result {
+                                    Ok(output) => (output.as_str(), theme.text),
+                                    Err(e) => (e.as_str(), self.status_color(CheckStatus::Error)),
+                                };
+                                ui.label(text(action.label(), theme.text_dim));
+                                egui::ScrollArea::vertical().max_height(120.0).show(ui, |ui| {
+                                    ui.add(egui::Label::new(text(output, color)).wrap());
+                                });
+                            }
+                        }
+                        
+                        if let Some(path) = diagnostics::actions::log_path() {
+                            ui.add_space(8.0);
+                            ui.label(text(&format!("Log: {}", path.display()), theme.text_dim));
+                        }
+                    });
+            });
+        
+        if let Some(action) = run {
+            self.pending_action = None;
+            self.spawn_action(ctx, action.label(), move || action.run());
+        }
+    }
+
+    fn open_inject(&mut self) {
+        self.show_inject = true;
+        self.inject_consent = false;
+        self.show_settings = false;
+        self.show_actions = false;
+    }
+
+    /// Block the picked provider (consent given) and run to show it failing
+    fn start_injection(&mut self, ctx: &egui::Context) {
+        let (_, host) = inject::PROVIDERS[self.inject_provider];
+        let minutes = self.inject_minutes;
+        let result = inject::start(host, minutes);
+        if result.is_ok() {
+            let duration = Duration::from_secs(minutes as u64 * 60);
+            let until = (chrono::Local::now() + duration).format("%H:%M:%S").to_string();
+            self.injection = Some((host, Instant::now() + duration, until));
+            self.run_trigger = Some(format!("failure injection ({} blocked)", host));
+            self.run_diagnostics(ctx);
+        }
+        self.inject_status = Some(result);
+        self.inject_consent = false;
+    }
+
+    /// Unblock the provider (time up or STOP) and run to show it is back
+    fn stop_injection(&mut self, ctx: &egui::Context) {
+        self.injection = None;
+        self.inject_status = Some(inject::stop().map(|n| format!("Injection ended ({} hosts unblocked)", n)));
+        self.run_trigger = Some("failure injection ended".to_string());
+        self.run_diagnostics(ctx);
+    }
+
+    /// Failure injection: pick a provider and a duration, consent, start; or stop the one in place
+    fn render_inject(&mut self, ctx: &egui::Context) {
+        if App::overlay_clicked(ctx, "inject_overlay") {
+            self.show_inject = false;
+        }
+        
+        let theme = self.theme;
+        let error_color = self.status_color(CheckStatus::Error);
+        let text = |s: &str, color: egui::Color32| {
+            egui::RichText::new(s)
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(color)
+        };
+        let button = |label: &str, selected: bool| {
+            egui::Button::new(text(label, if selected { egui::Color32::WHITE } else { theme.text }).strong())
+                .fill(if selected { theme.accent_on } else { theme.panel })
+                .stroke(egui::Stroke::new(1.0, theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(60.0, 20.0))
+        };
+        let (mut start, mut stop) = (false, false);
+        
+        egui::Area::new(egui::Id::new("inject_popup"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(theme.panel)
+                    .stroke(egui::Stroke::new(1.0, theme.border))
+                    .rounding(0.0)
+                    .shadow(egui::Shadow::NONE)
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.set_width(320.0);
+                        ui.label(text("// FAILURE INJECTION :: PROVIDER OUTAGE", theme.text_dim));
+                        ui.add_space(8.0);
+                        
+                        if let Some((host, _, ref until)) = self.injection {
+                            ui.label(text(&format!("{} BLOCKED :: until {}", host, until), error_color).strong());
+                            ui.add(egui::Label::new(text("Requests OpenCode sends to it now fail: its fallback provider should take over.", theme.text)).wrap());
+                            ui.add_space(4.0);
+                            if ui.add(button("STOP NOW", true)).clicked() {
+                                stop = true;
+                            }
+                        } else {
+                            ui.horizontal(|ui| {
+                                for (i, (label, _)) in inject::PROVIDERS.iter().enumerate() {
+                                    if ui.add(button(label, self.inject_provider == i)).clicked() {
+                                        self.inject_provider = i;
+                                        self.inject_consent = false;
+                                    }
+                                }
+                            });
+                            ui.add_space(4.0);
+                            ui.horizontal(|ui| {
+                                ui.label(text("For", theme.text_dim));
+                                ui.add_space(5.0);
+                                for (minutes, label) in inject::DURATION_PRESETS {
+                                    if ui.add(button(label, self.inject_minutes == *minutes)).clicked() {
+                                        self.inject_minutes = *minutes;
+                                        self.inject_consent = false;
+                                    }
+                                }
+                            });
+                            
+                            let (label, host) = inject::PROVIDERS[self.inject_provider];
+                            ui.add_space(8.0);
+                            ui.add(egui::Label::new(text(
+                                &format!(
+                                    "OpenCode and these checks cannot reach {} for {} min. Connections already open may keep working until OpenCode reconnects.",
+                                    host, self.inject_minutes
+                                ),
+                                theme.text,
+                            )).wrap());
+                            ui.add(egui::Label::new(text(&format!("Adds to {}:", inject::hosts_path().display()), theme.text_dim)).wrap());
+                            for line in inject::hosts_lines(host, "HH:MM:SS") {
+                                ui.add(egui::Label::new(text(&format!("> {}", line), theme.text_dim)).wrap());
+                            }
+                            ui.add(egui::Label::new(text("Removed when the time is up, on STOP, when this app closes, or on its next start.", theme.text_dim)).wrap());
+                            if !diagnostics::elevation::is_elevated() {
+                                let needs = if cfg!(target_os = "windows") { "Needs administrator (SETTINGS > RESTART AS ADMINISTRATOR)" } else { "Needs root (start with sudo)" };
+                                ui.label(text(needs, error_color));
+                            }
+                            ui.add_space(4.0);
+                            App::render_styled_checkbox(ui, &mut self.inject_consent, &format!("I understand: block {} now", label), theme.text);
+                            ui.add_space(4.0);
+                            if ui.add_enabled(self.inject_consent, button("START", true)).clicked() {
+                                start = true;
+                            }
+                        }
+                        
+                        // Outcome of the last start / stop
+                        if let Some(ref result) = self.inject_status {
+                            ui.add_space(8.0);
+                            let (message, color) = match result {
+                                Ok(message) => (message.as_str(), theme.text),
+                                Err(e) => (e.as_str(), error_color),
+                            };
+                            ui.add(egui::Label::new(text(message, color)).wrap());
+                        }
+                        if let Some(path) = diagnostics::actions::log_path() {
+                            ui.add_space(8.0);
+                            ui.label(text(&format!("Log: {}", path.display()), theme.text_dim));
+                        }
+                    });
+            });
+        
+        if start {
+            self.start_injection(ctx);
+        }
+        if stop {
+            self.stop_injection(ctx);
+        }
+    }
+
+    /// After the morning readiness run: a desktop notification, only when something is wrong
+    fn notify_readiness(ctx: &egui::Context, report: &DiagnosticReport) -> Result<(), String> {
+        let Some(problems) = readiness::problems(report) else {
+            return Ok(());
+        };
+        ctx.send_viewport_cmd(egui::ViewportCommand::RequestUserAttention(egui::UserAttentionType::Informational));
+        notify::notify("OpenCode setup not ready", &problems)
+    }
+
+    /// Start the capture proxy if it is not running, and show it
+    fn open_capture(&mut self) {
+        if self.capture.is_none() {
+            // Each recorded connection wakes the UI for the header count
+            match CaptureProxy::start(proxy::DEFAULT_CAPTURE_PORT, self.scheduler.waker()) {
+                Ok(capture) => {
+                    self.capture = Some(capture);
+                    self.capture_error = None;
+                }
+                Err(e) => self.capture_error = Some(e),
+            }
+        }
+        self.show_capture = true;
+        self.show_settings = false;
+        self.show_inject = false;
+    }
+
+    /// Capture proxy: how to point OpenCode at it, connections per host next to
+    /// the check covering the host, and the latest connections
+    fn render_capture(&mut self, ctx: &egui::Context) {
+        if App::overlay_clicked(ctx, "capture_overlay") {
+            self.show_capture = false;
+        }
+        
+        let theme = self.theme;
+        let error_color = self.status_color(CheckStatus::Error);
+        let text = |s: &str, color: egui::Color32| {
+            egui::RichText::new(s)
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(color)
+        };
+        let button = |label: &str, selected: bool| {
+            egui::Button::new(text(label, if selected { egui::Color32::WHITE } else { theme.text }).strong())
+                .fill(if selected { theme.accent_on } else { theme.panel })
+                .stroke(egui::Stroke::new(1.0, theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(60.0, 20.0))
+        };
+        let report = self.report.snapshot();
+        let checks = report.checks();
+        let (mut copy, mut clear, mut stop) = (false, false, false);
+        
+        egui::Area::new(egui::Id::new("capture_popup"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(theme.panel)
+                    .stroke(egui::Stroke::new(1.0, theme.border))
+                    .rounding(0.0)
+                    .shadow(egui::Shadow::NONE)
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.set_width(380.0);
+                        ui.label(text("// TRAFFIC CAPTURE :: HOSTS, TIMINGS, SIZES (NO CONTENTS)", theme.text_dim));
+                        ui.add_space(8.0);
+                        
+                        let Some(ref capture) = self.capture else {
+                            let error = self.capture_error.as_deref().unwrap_or("Capture proxy not running");
+                            ui.add(egui::Label::new(text(error, error_color)).wrap());
+                            return;
+                        };
+                        let url = capture.url();
+                        let command = proxy::launch_command(&url);
+                        ui.add(egui::Label::new(text("Start OpenCode through the proxy, then use it as usual:", theme.text)).wrap());
+                        ui.horizontal(|ui| {
+                            ui.add(egui::Label::new(text(&format!("> {}", command), theme.text_dim)).wrap());
+                            if ui.add(button("COPY", false)).clicked() {
+                                if let Ok(mut clipboard) = Clipboard::new() {
+                                    let _ = clipboard.set_text(command.clone());
+                                }
+                            }
+                        });
+                        ui.add(egui::Label::new(text("HTTPS is tunneled, not decrypted: a tunnel shows 200 when it opened, 502 when the host could not be reached.", theme.text_dim)).wrap());
+                        
+                        // Per host, with the check covering it
+                        let entries = capture.entries();
+                        ui.add_space(8.0);
+                        if entries.is_empty() {
+                            ui.label(text("No connections yet.", theme.text_dim));
+                        }
+                        for summary in proxy::by_host(&entries) {
+                            let check = summary.check_id.and_then(|id| checks.iter().find(|(i, _)| *i == id)).map(|(_, check)| *check);
+                            ui.horizontal(|ui| {
+                                let color = if summary.failed > 0 { error_color } else { theme.text };
+                                let connect = summary.avg_connect_ms.map(|ms| format!(" :: {}ms", ms)).unwrap_or_default();
+                                ui.label(text(&format!("{} :: {} / {} failed{}", summary.host, summary.connections, summary.failed, connect), color));
+                                if let Some(check) = check {
+                                    ui.label(text(&format!("{} {}", check.name, check.status.label()), self.status_color(check.status)).strong());
+                                }
+                            });
+                        }
+                        
+                        // Latest connections
+                        if !entries.is_empty() {
+                            ui.add_space(8.0);
+                            egui::ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+                                for entry in entries.iter().rev().take(100) {
+                                    let color = if entry.failed() { error_color } else { theme.text_dim };
+                                    ui.add(egui::Label::new(text(&entry.summary(), color)).wrap());
+                                }
+                            });
+                        }
+                        
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            copy = ui.add_enabled(!entries.is_empty(), button("COPY", false)).clicked();
+                            clear = ui.add_enabled(!entries.is_empty(), button("CLEAR", false)).clicked();
+                            stop = ui.add(button("STOP CAPTURE", true)).on_hover_text("Start OpenCode without the proxy again afterwards").clicked();
+                        });
+                    });
+            });
+        
+        if let Some(ref capture) = self.capture {
+            if copy {
+                let text = proxy::to_text(&capture.entries(), &report);
+                self.status = match Clipboard::new().and_then(|mut c| c.set_text(text)) {
+                    Ok(()) => "SYS.STATUS: CAPTURE COPIED".to_string(),
+                    Err(_) => "SYS.STATUS: CLIPBOARD UNAVAILABLE".to_string(),
+                };
+            }
+            if clear {
+                capture.clear();
+            }
+        }
+        if stop {
+            self.capture = None;
+            self.show_capture = false;
+        }
+    }
+
+    /// Start a trace, or stop the running one early
+    fn toggle_trace(&mut self) {
+        if let Some(ref trace) = self.trace {
+            trace.stop();
+            self.status = "SYS.STATUS: STOPPING TRACE...".to_string();
+            return;
+        }
+        match Trace::start(&self.settings, self.scheduler.waker()) {
+            Ok(trace) => {
+                self.trace = Some(trace);
+                self.status = "SYS.STATUS: TRACING :: REPRODUCE THE PROBLEM IN OPENCODE NOW".to_string();
+            }
+            Err(e) => self.status = format!("SYS.STATUS: {}", e.to_uppercase()),
+        }
+    }
+
+    /// Summary of the last trace
+    fn render_trace(&mut self, ctx: &egui::Context) {
+        if App::overlay_clicked(ctx, "trace_overlay") {
+            self.show_trace = false;
+        }
+        let Some(summary) = self.trace_summary.clone() else {
+            self.show_trace = false;
+            return;
+        };
+
+        let theme = self.theme;
+        let text = |s: &str, color: egui::Color32| {
+            egui::RichText::new(s)
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(color)
+        };
+        let button = |label: &str| {
+            egui::Button::new(text(label, theme.text).strong())
+                .fill(theme.panel)
+                .stroke(egui::Stroke::new(1.0, theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(60.0, 20.0))
+        };
+        let (mut copy, mut close) = (false, false);
+
+        egui::Area::new(egui::Id::new("trace_popup"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(theme.panel)
+                    .stroke(egui::Stroke::new(1.0, theme.border))
+                    .rounding(0.0)
+                    .shadow(egui::Shadow::NONE)
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.set_width(420.0);
+                        ui.label(text("// TRACE SUMMARY", theme.text_dim));
+                        ui.add_space(8.0);
+                        egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                            for line in summary.lines() {
+                                let color = if line.starts_with("  ") { theme.text_dim } else { theme.text };
+                                ui.add(egui::Label::new(text(line, color)).wrap());
+                            }
+                        });
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            copy = ui.add(button("COPY")).clicked();
+                            close = ui.add(button("CLOSE")).clicked();
+                        });
+                    });
+            });
+
+        if copy {
+            self.status = match Clipboard::new().and_then(|mut c| c.set_text(summary)) {
+                Ok(()) => "SYS.STATUS: TRACE SUMMARY COPIED".to_string(),
+                Err(_) => "SYS.STATUS: CLIPBOARD UNAVAILABLE".to_string(),
+            };
+        }
+        if close {
+            self.show_trace = false;
+        }
+    }
+
+    /// Render the weekday x hour latency heatmap popup
+    fn render_heatmap(&mut self, ctx: &egui::Context) {
+        if App::overlay_clicked(ctx, "heatmap_overlay") {
+            self.show_heatmap = false;
+            self.show_digest = false;
+        }
+        
+        let theme = self.theme;
+        egui::Area::new(egui::Id::new("heatmap_popup"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(theme.panel)
+                    .stroke(egui::Stroke::new(1.0, theme.border))
+                    .rounding(0.0)
+                    .shadow(egui::Shadow::NONE)
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.set_width(HEATMAP_LABEL_WIDTH + HEATMAP_CELL * 24.0);
+                        self.render_popup_title(ui, "// LATENCY HEATMAP :: WEEKDAY x HOUR");
+                        ui.add_space(8.0);
+                        self.render_heatmap_contents(ui);
+                    });
+            });
+    }
+
+    /// Check picker and weekday x hour grid (heatmap popup and history window)
+    fn render_heatmap_contents(&mut self, ui: &mut egui::Ui) {
+        const DAYS: [&str; 7] = ["MON", "TUE", "WED", "THU", "FRI", "SAT", "SUN"];
+        let fast = self.status_color(CheckStatus::Ok);
+        let mid = self.status_color(CheckStatus::Warning);
+        let slow = self.status_color(CheckStatus::Error);
+        
+        let theme = self.theme;
+        let (check_ids, heatmap) = {
+            let history = self.history.lock().unwrap();
+            (history.latency_check_ids(), history.latency_heatmap(self.heatmap_check))
+        };
+        let names = self.check_names(&check_ids);
+        let values: Vec<u64> = heatmap.iter().flatten().filter_map(|v| *v).collect();
+        let min = values.iter().copied().min().unwrap_or(0);
+        let max = values.iter().copied().max().unwrap_or(0);
+        let mut selected = None;
+        
+        let text = |s: String, color: egui::Color32| {
+            egui::RichText::new(s)
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(color)
+        };
+        
+        if names.is_empty() {
+            ui.label(text("No latency history yet. Run diagnostics a few times.".to_string(), theme.text));
+            return;
+        }
+        
+        ui.horizontal_wrapped(|ui| {
+            for (id, name) in &names {
+                let is_selected = *id == self.heatmap_check;
+                let btn = egui::Button::new(
+                    text(name.clone(), if is_selected { egui::Color32::WHITE } else { theme.text })
+                )
+                .fill(if is_selected { theme.accent_on } else { theme.panel })
+                .stroke(egui::Stroke::new(1.0, theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(40.0, 18.0));
+                if ui.add(btn).clicked() {
+                    selected = Some(*id);
+                }
+            }
+        });
+        ui.add_space(8.0);
+        
+        let (rect, response) = ui.allocate_exact_size(
+            egui::vec2(HEATMAP_LABEL_WIDTH + HEATMAP_CELL * 24.0, HEATMAP_CELL * 8.0),
+            egui::Sense::hover(),
+        );
+        let slowest = (0..7)
+            .flat_map(|day| (0..24).map(move |hour| (day, hour)))
+            .filter_map(|(day, hour)| Some((day, hour, heatmap[day][hour]?)))
+            .max_by_key(|(_, _, ms)| *ms);
+        if let Some((day, hour, ms)) = slowest {
+            let spoken = format!("Latency heatmap by weekday and hour: fastest {}ms, slowest {}ms on {} {:02}:00", min, ms, DAYS[day], hour);
+            a11y::describe(&response, egui::WidgetType::Label, &spoken);
+        }
+        let painter = ui.painter_at(rect);
+        let font = egui::FontId::monospace(8.0);
+        
+        // Hour labels every 6 hours
+        for hour in (0..24).step_by(6) {
+            painter.text(
+                egui::pos2(rect.left() + HEATMAP_LABEL_WIDTH + hour as f32 * HEATMAP_CELL, rect.top()),
+                egui::Align2::LEFT_TOP,
+                format!("{:02}", hour),
+                font.clone(),
+                theme.text_dim,
+            );
+        }
+        
+        let mut hovered = None;
+        for (day, hours) in heatmap.iter().enumerate() {
+            let y = rect.top() + HEATMAP_CELL * (day as f32 + 1.0);
+            painter.text(
+                egui::pos2(rect.left(), y + HEATMAP_CELL / 2.0),
+                egui::Align2::LEFT_CENTER,
+                DAYS[day],
+                font.clone(),
+                theme.text_dim,
+            );
+            for (hour, value) in hours.iter().enumerate() {
+                let cell = egui::Rect::from_min_size(
+                    egui::pos2(rect.left() + HEATMAP_LABEL_WIDTH + hour as f32 * HEATMAP_CELL, y),
+                    egui::vec2(HEATMAP_CELL - 1.0, HEATMAP_CELL - 1.0),
+                );
+                let color = match value {
+                    Some(ms) => {
+                        let t = if max > min { (ms - min) as f32 / (max - min) as f32 } else { 0.0 };
+                        if t < 0.5 {
+                            fast.lerp_to_gamma(mid, t * 2.0)
+                        } else {
+                            mid.lerp_to_gamma(slow, (t - 0.5) * 2.0)
+                        }
+                    }
+                    None => theme.accent_off,
+                };
+                painter.rect_filled(cell, 0.0, color);
+                if response.hover_pos().map(|p| cell.contains(p)).unwrap_or(false) {
+                    hovered = Some((day, hour, *value));
+                }
+            }
+        }
+        
+        let footer = match hovered {
+            Some((day, hour, Some(ms))) => format!("{} {:02}:00 :: avg {}ms", DAYS[day], hour, ms),
+            Some((day, hour, None)) => format!("{} {:02}:00 :: no data", DAYS[day], hour),
+            None => format!("{}ms (green) .. {}ms (red) :: last {} days", min, max, HISTORY_RETENTION_DAYS),
+        };
+        ui.add_space(4.0);
+        ui.label(text(footer, theme.text_dim));
+
+        
+        if let Some(id) = selected {
+            self.heatmap_check = id;
+        }
+    }
+
+    /// Popup title with a WINDOW button that moves the history views to their own window
+    fn render_popup_title(&mut self, ui: &mut egui::Ui, title: &str) {
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new(title)
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let window_btn = egui::Button::new(
+                    egui::RichText::new("WINDOW")
+                        .size(8.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(self.theme.text)
+                )
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0);
+                if ui.add(window_btn).on_hover_text("Heatmap, latency chart and error log in a separate window").clicked() {
+                    self.open_history_window();
+                }
+            });
+        });
+    }
+
+    /// Move the history views to their own window, showing the check of an open latency chart
+    fn open_history_window(&mut self) {
+        if let Some(id) = self.latency_chart.take() {
+            self.heatmap_check = id;
+        }
+        self.show_history_window = true;
+        self.show_heatmap = false;
+        self.show_history = false;
+    }
+
+    /// Heatmap, latency chart and error log in a second viewport, so investigating
+    /// does not cover the live dashboard (an in-app window where the backend has one viewport)
+    fn render_history_window(&mut self, ctx: &egui::Context) {
+        let builder = egui::ViewportBuilder::default()
+            .with_title("OpenCode Diagnostics :: History")
+            .with_inner_size([360.0, 600.0])
+            .with_min_inner_size([340.0, 300.0]);
+        ctx.show_viewport_immediate(egui::ViewportId::from_hash_of("history_window"), builder, |ctx, class| {
+            let theme = self.theme;
+            let contents = |app: &mut Self, ui: &mut egui::Ui| {
+                let title = |ui: &mut egui::Ui, title: String| {
+                    ui.label(
+                        egui::RichText::new(title)
+                            .size(9.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(theme.text_dim),
+                    );
+                    ui.add_space(8.0);
+                };
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    title(ui, "// LATENCY HEATMAP :: WEEKDAY x HOUR".to_string());
+                    app.render_heatmap_contents(ui);
+                    ui.add_space(12.0);
+                    title(ui, format!("// LATENCY :: {}", diagnostics::ids::name(app.heatmap_check)));
+                    let id = app.heatmap_check;
+                    app.render_latency_chart_contents(ui, id);
+                    ui.add_space(12.0);
+                    title(ui, "// ERROR LOG".to_string());
+                    app.render_error_log_entries(ui);
+                });
+            };
+            if class == egui::ViewportClass::Embedded {
+                let mut open = true;
+                egui::Window::new("HISTORY").open(&mut open).show(ctx, |ui| contents(self, ui));
+                self.show_history_window = open;
+                return;
+            }
+            egui::CentralPanel::default()
+                .frame(egui::Frame::none().fill(theme.panel).inner_margin(12.0))
+                .show(ctx, |ui| contents(self, ui));
+            if ctx.input(|i| i.viewport().close_requested()) {
+                self.show_history_window = false;
+            }
+        });
+    }
+
+    /// Grouped error log entries (LOG popup and history window)
+    fn render_error_log_entries(&self, ui: &mut egui::Ui) {
+        if self.error_log.entries.is_empty() {
+            ui.label(
+                egui::RichText::new("No issues recorded.")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+        } else {
+            // Show grouped errors
+            for entry in &self.error_log.entries {
+                ui.horizontal(|ui| {
+                    // Error type name (fixed width)
+                    ui.label(
+                        egui::RichText::new(&entry.name)
+                            .size(9.0)
+                            .family(egui::FontFamily::Monospace)
+                            .strong()
+                            .color(self.status_color(CheckStatus::Error)),
+                    );
+
+                    ui.add_space(10.0);
+
+                    // Timestamps (comma-separated)
+                    ui.label(
+                        egui::RichText::new(entry.format_times())
+                            .size(9.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(self.theme.text_dim),
+                    );
+                });
+                ui.add_space(3.0);
+            }
+        }
+    }
+
+    /// Show the latency chart of check `id`, closing other popups
+    fn open_latency_chart(&mut self, id: &'static str) {
+        self.latency_chart = Some(id);
+        self.show_heatmap = false;
+        self.show_digest = false;
+        self.show_output = false;
+        self.show_settings = false;
+        self.show_history = false;
+        self.show_agents = false;
+        self.show_wizard = false;
+        self.show_compare = false;
+        self.show_actions = false;
+    }
+
+    /// Render the latency chart of one check: recorded latencies with ERROR runs marked
+    fn render_latency_chart(&mut self, ctx: &egui::Context) {
+        if App::overlay_clicked(ctx, "latency_overlay") {
+            self.latency_chart = None;
+            return;
+        }
+        let Some(id) = self.latency_chart else {
+            return;
+        };
+        
+        let theme = self.theme;
+        egui::Area::new(egui::Id::new("latency_popup"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(theme.panel)
+                    .stroke(egui::Stroke::new(1.0, theme.border))
+                    .rounding(0.0)
+                    .shadow(egui::Shadow::NONE)
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.set_width(LATENCY_CHART_WIDTH);
+                        self.render_popup_title(ui, &format!("// LATENCY :: {}", diagnostics::ids::name(id)));
+                        ui.add_space(8.0);
+                        self.render_latency_chart_contents(ui, id);
+                    });
+            });
+    }
+
+    /// Range picker, latency line and outage bands of check `id` (popup and history window)
+    fn render_latency_chart_contents(&mut self, ui: &mut egui::Ui, id: &'static str) {
+        const HEIGHT: f32 = 110.0;
+        let theme = self.theme;
+        let line_color = self.status_color(CheckStatus::Ok);
+        let outage_color = self.status_color(CheckStatus::Error);
+        let samples = self.history.lock().unwrap().latency_series(id, self.latency_hours);
+        let latencies: Vec<u64> = samples.iter().filter_map(|s| s.latency_ms).collect();
+        let max = latencies.iter().copied().max().unwrap_or(0).max(1);
+        let outages = samples
+            .iter()
+            .enumerate()
+            .filter(|(i, s)| s.status == CheckStatus::Error && (*i == 0 || samples[i - 1].status != CheckStatus::Error))
+            .count();
+        let mut range = None;
+        
+        let text = |s: String, color: egui::Color32| {
+            egui::RichText::new(s)
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(color)
+        };
+        
+        ui.horizontal(|ui| {
+            for (hours, label) in LATENCY_RANGES {
+                let is_selected = *hours == self.latency_hours;
+                let btn = egui::Button::new(
+                    text(label.to_string(), if is_selected { egui::Color32::WHITE } else { theme.text })
+                )
+                .fill(if is_selected { theme.accent_on } else { theme.panel })
+                .stroke(egui::Stroke::new(1.0, theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(35.0, 18.0));
+                if ui.add(btn).clicked() {
+                    range = Some(*hours);
+                }
+            }
+        });
+        ui.add_space(8.0);
+        
+        if samples.is_empty() {
+            ui.label(text("No runs recorded in this range.".to_string(), theme.text));
+            return;
+        }
+        
+        let (rect, response) = ui.allocate_exact_size(egui::vec2(ui.available_width(), HEIGHT), egui::Sense::hover());
+        a11y::describe(&response, egui::WidgetType::Label, &format!("Latency chart of {}: the summary follows", diagnostics::ids::name(id)));
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, theme.bg);
+        let end = chrono::Local::now().naive_local();
+        let start = end - chrono::Duration::hours(self.latency_hours);
+        let span = (end - start).num_seconds().max(1) as f32;
+        let x = |time: chrono::NaiveDateTime| rect.left() + (time - start).num_seconds() as f32 / span * rect.width();
+        let y = |ms: u64| rect.bottom() - 4.0 - ms as f32 / max as f32 * (rect.height() - 8.0);
+        
+        // Outages: ERROR runs up to the next run that was not ERROR
+        for (i, sample) in samples.iter().enumerate().filter(|(_, s)| s.status == CheckStatus::Error) {
+            let until = samples.get(i + 1).map(|next| x(next.time)).unwrap_or_else(|| x(sample.time) + 2.0);
+            let band = egui::Rect::from_x_y_ranges(x(sample.time)..=until.max(x(sample.time) + 2.0), rect.y_range());
+            painter.rect_filled(band, 0.0, outage_color.gamma_multiply(0.35));
+        }
+        
+        // Latency line, broken where a run recorded none (a lone point as a dot)
+        let draw = |segment: Vec<egui::Pos2>| {
+            if segment.len() == 1 {
+                painter.circle_filled(segment[0], 1.5, line_color);
+            } else if segment.len() > 1 {
+                painter.add(egui::Shape::line(segment, egui::Stroke::new(1.5, line_color)));
+            }
+        };
+        let mut segment = Vec::new();
+        for sample in &samples {
+            match sample.latency_ms {
+                Some(ms) => segment.push(egui::pos2(x(sample.time), y(ms))),
+                None => draw(std::mem::take(&mut segment)),
+            }
+        }
+        draw(segment);
+        if !latencies.is_empty() {
+            let font = egui::FontId::monospace(8.0);
+            painter.text(rect.left_top() + egui::vec2(3.0, 2.0), egui::Align2::LEFT_TOP, format!("{}ms", max), font, theme.text_dim);
+        }
+        
+        // Run nearest the pointer
+        let hovered = response.hover_pos().and_then(|pos| {
+            samples.iter().min_by(|a, b| (x(a.time) - pos.x).abs().total_cmp(&(x(b.time) - pos.x).abs()))
+        });
+        if let Some(sample) = hovered {
+            painter.vline(x(sample.time), rect.y_range(), egui::Stroke::new(1.0, theme.text_dim));
+        }
+        
+        let footer = match hovered {
+            Some(sample) => format!(
+                "{} :: {} :: {}",
+                sample.time.format("%a %H:%M"),
+                sample.latency_ms.map(|ms| format!("{}ms", ms)).unwrap_or_else(|| "--".to_string()),
+                sample.status.label()
+            ),
+            None if latencies.is_empty() => format!("{} runs, no latency recorded :: {} outages", samples.len(), outages),
+            None => format!(
+                "{} runs :: avg {}ms, max {}ms :: {} outage{}",
+                samples.len(),
+                latencies.iter().sum::<u64>() / latencies.len() as u64,
+                max,
+                outages,
+                if outages == 1 { "" } else { "s" }
+            ),
+        };
+        ui.add_space(4.0);
+        ui.label(text(footer, theme.text_dim));
+
+        
+        if let Some(hours) = range {
+            self.latency_hours = hours;
+        }
+    }
+
+    /// Show the weekly digest (`None`: build it from the history now), closing other popups
+    fn open_digest(&mut self, digest: Option<Digest>) {
+        self.digest = digest.or_else(|| Digest::build(&self.history.lock().unwrap(), chrono::Local::now().naive_local()));
+        self.digest_status = None;
+        self.show_digest = true;
+        self.show_heatmap = false;
+        self.latency_chart = None;
+        self.show_output = false;
+        self.show_settings = false;
+        self.show_history = false;
+        self.show_agents = false;
+        self.show_wizard = false;
+        self.show_compare = false;
+        self.show_actions = false;
+    }
+
+    /// Render the weekly digest popup
+    fn render_digest(&mut self, ctx: &egui::Context) {
+        if App::overlay_clicked(ctx, "digest_overlay") {
+            self.show_digest = false;
+        }
+        
+        let theme = self.theme;
+        let text = |s: &str, color: egui::Color32| {
+            egui::RichText::new(s)
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(color)
+        };
+        let button = |label: &str| {
+            egui::Button::new(text(label, theme.text))
+                .fill(theme.panel)
+                .stroke(egui::Stroke::new(1.0, theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(60.0, 18.0))
+        };
+        
+        egui::Area::new(egui::Id::new("digest_popup"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(theme.panel)
+                    .stroke(egui::Stroke::new(1.0, theme.border))
+                    .rounding(0.0)
+                    .shadow(egui::Shadow::NONE)
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.set_min_width(320.0);
+                        ui.label(text("// WEEKLY DIGEST", theme.text_dim));
+                        ui.add_space(8.0);
+                        
+                        let Some(ref digest) = self.digest else {
+                            ui.label(text(&format!("No runs in the last {} days.", digest::DIGEST_DAYS), theme.text));
+                            return;
+                        };
+                        // Skip the title line; section headings dim, rows bright
+                        for line in digest.to_text().lines().skip(1) {
+                            let color = if line.starts_with("  ") { theme.text } else { theme.text_dim };
+                            ui.label(text(line, color));
+                        }
+                        
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            if ui.add(button("SAVE")).on_hover_text("Save as text to Downloads").clicked() {
+                                self.digest_status = Some(match digest.export() {
+                                    Ok(path) => format!("Saved {}", path.display()),
+                                    Err(e) => e,
+                                });
+                            }
+                            if ui.add(button("COPY")).clicked() {
+                                let copied = Clipboard::new().and_then(|mut c| c.set_text(digest.to_text())).is_ok();
+                                self.digest_status = Some(if copied { "Copied".to_string() } else { "Clipboard unavailable".to_string() });
+                            }
+                        });
+                        if let Some(ref status) = self.digest_status {
+                            ui.add(egui::Label::new(text(status, theme.text_dim).size(8.0)).wrap());
+                        }
+                        ui.add_space(4.0);
+                        let enabled = self.settings.weekly_digest;
+                        App::render_setting_checkbox(ui, "weekly_digest", &mut self.settings.weekly_digest, "OPEN WEEKLY ON LAUNCH", theme.text);
+                        if self.settings.weekly_digest != enabled {
+                            let _ = self.settings.save();
+                        }
+                    });
+            });
+    }
+
+    /// Render the A/B compare popup
+    fn render_compare(&mut self, ctx: &egui::Context) {
+        if App::overlay_clicked(ctx, "compare_overlay") {
+            self.show_compare = false;
+        }
+        
+        let is_running = *self.is_running.lock().unwrap();
+        let theme = self.theme;
+        let status_colors = [
+            (CheckStatus::Ok, self.status_color(CheckStatus::Ok)),
+            (CheckStatus::Warning, self.status_color(CheckStatus::Warning)),
+            (CheckStatus::Error, self.status_color(CheckStatus::Error)),
+        ];
+        let color_of = |status: CheckStatus| {
+            status_colors.iter().find(|(s, _)| *s == status).map(|(_, c)| *c).unwrap_or(theme.text_dim)
+        };
+        let mut start_preset = None;
+        let mut run_variant = false;
+        let mut reset = false;
+        
+        let button = |label: &str, width: f32| {
+            egui::Button::new(
+                egui::RichText::new(label)
+                    .size(9.0)
+                    .strong()
+                    .family(egui::FontFamily::Monospace)
+                    .color(theme.text)
+            )
+            .fill(theme.panel)
+            .stroke(egui::Stroke::new(1.0, theme.border))
+            .rounding(0.0)
+            .min_size(egui::vec2(width, 22.0))
+        };
+        let text = |s: String, color: egui::Color32| {
+            egui::RichText::new(s)
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(color)
+        };
+        
+        egui::Area::new(egui::Id::new("compare_popup"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(theme.panel)
+                    .stroke(egui::Stroke::new(1.0, theme.border))
+                    .rounding(0.0)
+                    .shadow(egui::Shadow::NONE)
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.set_width(360.0);
+                        
+                        let Some(ref compare) = self.compare else {
+                            ui.label(text("// A/B COMPARE :: WHAT WILL YOU TOGGLE?".to_string(), theme.text_dim));
+                            ui.add_space(8.0);
+                            for (i, (label, _)) in COMPARE_PRESETS.iter().enumerate() {
+                                if ui.add_enabled(!is_running, button(label, 360.0)).clicked() {
+                                    start_preset = Some(i);
+                                }
+                                ui.add_space(4.0);
+                            }
+                            return;
+                        };
+                        
+                        ui.label(text(format!("// A/B COMPARE :: {}", compare.label()), theme.text_dim));
+                        ui.add_space(8.0);
+                        
+                        match compare.stage {
+                            CompareStage::Baseline => {
+                                ui.label(text("Running A checks...".to_string(), theme.text));
+                            }
+                            CompareStage::Variant => {
+                                ui.label(text("Running B checks...".to_string(), theme.text));
+                            }
+                            CompareStage::Toggle => {
+                                ui.label(text("A recorded. Now:".to_string(), theme.text_dim));
+                                ui.add(egui::Label::new(text(compare.instruction().to_string(), theme.text)).wrap());
+                                ui.add_space(8.0);
+                                if ui.add_enabled(!is_running, button("RUN B", 80.0)).clicked() {
+                                    run_variant = true;
+                                }
+                            }
+                            CompareStage::Done => {
+                                egui::Grid::new("compare_grid")
+                                    .num_columns(4)
+                                    .spacing([12.0, 4.0])
+                                    .show(ui, |ui| {
+                                        for header in ["CHECK", "A", "B", "DELTA"] {
+                                            ui.label(text(header.to_string(), theme.text_dim));
+                                        }
+                                        ui.end_row();
+                                        
+                                        for row in compare.rows() {
+                                            ui.label(text(row.name.clone(), theme.text));
+                                            for cell in [row.before, row.after] {
+                                                let color = cell.map(|(s, _)| color_of(s)).unwrap_or(theme.text_dim);
+                                                ui.label(text(format_cell(cell), color));
+                                            }
+                                            let (delta, color) = match row.latency_delta() {
+                                                Some(d) if d < 0 => (format!("{:+}ms", d), color_of(CheckStatus::Ok)),
+                                                Some(d) => (format!("{:+}ms", d), if d > 100 { color_of(CheckStatus::Warning) } else { theme.text_dim }),
+                                                None => (String::new(), theme.text_dim),
+                                            };
+                                            ui.label(text(delta, color));
+                                            ui.end_row();
+                                        }
+                                    });
+                                
+                                ui.add_space(8.0);
+                                ui.horizontal(|ui| {
+                                    if ui.add(button("COPY", 60.0)).clicked() {
+                                        if let Ok(mut clipboard) = Clipboard::new() {
+                                            let _ = clipboard.set_text(compare.to_text());
+                                        }
+                                    }
+                                    if ui.add(button("NEW", 60.0)).clicked() {
+                                        reset = true;
+                                    }
+                                });
+                            }
+                        }
+                    });
+            });
+        
+        if reset {
+            self.compare = None;
+        }
+        if let Some(preset) = start_preset {
+            self.compare = Some(Comparison::new(preset));
+            self.run_diagnostics(ctx);
+        }
+        if run_variant {
+            if let Some(ref mut compare) = self.compare {
+                compare.stage = CompareStage::Variant;
+            }
+            self.run_diagnostics(ctx);
+        }
+    }
+
+    /// Render the troubleshooting wizard popup
+    fn render_wizard(&mut self, ctx: &egui::Context) {
+        if App::overlay_clicked(ctx, "wizard_overlay") {
+            self.show_wizard = false;
+        }
+        
+        let is_running = *self.is_running.lock().unwrap();
+        let ok_color = self.status_color(CheckStatus::Ok);
+        let warn_color = self.status_color(CheckStatus::Warning);
+        let mut start_run = false;
+        let mut restart = false;
+        
+        egui::Area::new(egui::Id::new("wizard_popup"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(self.theme.panel)
+                    .stroke(egui::Stroke::new(1.0, self.theme.border))
+                    .rounding(0.0)
+                    .shadow(egui::Shadow::NONE)
+                    .inner_margin(12.0)
+                    .show(ui, |ui| {
+                        ui.set_width(340.0);
+                        
+                        let Some(ref mut wizard) = self.wizard else {
+                            // Symptom selection
+                            ui.label(
+                                egui::RichText::new("// TROUBLESHOOT :: WHAT IS HAPPENING?")
+                                    .size(9.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.theme.text_dim),
+                            );
+                            ui.add_space(8.0);
+                            
+                            for (symptom, label) in SYMPTOMS.iter() {
+                                let btn = egui::Button::new(
+                                    egui::RichText::new(*label)
+                                        .size(10.0)
+                                        .family(egui::FontFamily::Monospace)
+                                        .color(self.theme.text)
+                                )
+                                .fill(self.theme.panel)
+                                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                                .rounding(0.0)
+                                .min_size(egui::vec2(340.0, 24.0));
+                                
+                                if ui.add_enabled(!is_running, btn).clicked() {
+                                    self.wizard = Some(Wizard::new(*symptom));
+                                    start_run = true;
+                                }
+                                ui.add_space(4.0);
+                            }
+                            return;
+                        };
+                        
+                        ui.label(
+                            egui::RichText::new(format!("// {}", wizard.symptom.label().to_uppercase()))
+                                .size(9.0)
+                                .family(egui::FontFamily::Monospace)
+                                .color(self.theme.text_dim),
+                        );
+                        ui.add_space(8.0);
+                        
+                        // Step overview
+                        for (i, step) in wizard.symptom.steps().iter().enumerate() {
+                            let (marker, color) = match wizard.results[i] {
+                                Some(ref r) if r.passed => ("[OK]", ok_color),
+                                Some(_) => ("[!!]", warn_color),
+                                None => ("[  ]", self.theme.text_dim),
+                            };
+                            let text_color = if i == wizard.step { self.theme.text } else { self.theme.text_dim };
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    egui::RichText::new(marker)
+                                        .size(9.0)
+                                        .family(egui::FontFamily::Monospace)
+                                        .color(color),
+                                );
+                                ui.label(
+                                    egui::RichText::new(format!("{}. {}", i + 1, step.title))
+                                        .size(9.0)
+                                        .family(egui::FontFamily::Monospace)
+                                        .color(text_color),
+                                );
+                            });
+                        }
+                        
+                        ui.add_space(8.0);
+                        ui.add(egui::Separator::default().spacing(1.0));
+                        ui.add_space(8.0);
+                        
+                        if wizard.awaiting.is_some() {
+                            ui.label(
+                                egui::RichText::new("Running checks...")
+                                    .size(10.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.theme.text_dim),
+                            );
+                            return;
+                        }
+                        
+                        // Current step
+                        let step = wizard.current();
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "STEP {}/{} :: {}",
+                                wizard.step + 1,
+                                wizard.symptom.steps().len(),
+                                step.title.to_uppercase()
+                            ))
+                                .size(11.0)
+                                .strong()
+                                .family(egui::FontFamily::Monospace)
+                                .color(self.theme.text),
+                        );
+                        ui.add_space(4.0);
+                        ui.add(
+                            egui::Label::new(
+                                egui::RichText::new(step.instruction)
+                                    .size(10.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.theme.text)
+                            ).wrap()
+                        );
+                        ui.add_space(6.0);
+                        
+                        match wizard.results[wizard.step] {
+                            Some(ref r) if r.passed => {
+                                ui.label(
+                                    egui::RichText::new("VERIFIED :: checks pass")
+                                        .size(9.0)
+                                        .family(egui::FontFamily::Monospace)
+                                        .color(ok_color),
+                                );
+                            }
+                            Some(ref r) => {
+                                ui.label(
+                                    egui::RichText::new("NEEDS ATTENTION:")
+                                        .size(9.0)
+                                        .family(egui::FontFamily::Monospace)
+                                        .color(warn_color),
+                                );
+                                for line in &r.failing {
+                                    ui.add(
+                                        egui::Label::new(
+                                            egui::RichText::new(line)
+                                                .size(9.0)
+                                                .family(egui::FontFamily::Monospace)
+                                                .color(self.theme.text_dim)
+                                        ).wrap()
+                                    );
+                                }
+                            }
+                            None => {
+                                ui.label(
+                                    egui::RichText::new("Not verified yet")
+                                        .size(9.0)
+                                        .family(egui::FontFamily::Monospace)
+                                        .color(self.theme.text_dim),
+                                );
+                            }
+                        }
+                        
+                        ui.add_space(10.0);
+                        
+                        // Navigation
+                        ui.horizontal(|ui| {
+                            let nav_btn = |label: &str| {
+                                egui::Button::new(
+                                    egui::RichText::new(label)
+                                        .size(9.0)
+                                        .strong()
+                                        .family(egui::FontFamily::Monospace)
+                                        .color(self.theme.text)
+                                )
+                                .fill(self.theme.panel)
+                                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                                .rounding(0.0)
+                                .min_size(egui::vec2(60.0, 22.0))
+                            };
+                            
+                            if ui.add_enabled(wizard.step > 0, nav_btn("BACK")).clicked() {
+                                wizard.step -= 1;
+                            }
+                            if ui.add_enabled(!is_running, nav_btn("VERIFY")).clicked() {
+                                wizard.awaiting = Some(diagnostics::wizard::Awaiting::Step(wizard.step));
+                                start_run = true;
+                            }
+                            let next_label = if wizard.is_last() { "DONE" } else { "NEXT" };
+                            if ui.add(nav_btn(next_label)).clicked() {
+                                if wizard.is_last() {
+                                    self.show_wizard = false;
+                                } else {
+                                    wizard.step += 1;
+                                }
+                            }
+                            if ui.add(nav_btn("RESTART")).clicked() {
+                                restart = true;
+                            }
+                        });
+                    });
+            });
+        
+        if restart {
+            self.wizard = None;
+        }
+        
+        if start_run {
+            if let Some(ref wizard) = self.wizard {
+                let settings = wizard.settings_for(&self.settings);
+                self.run_diagnostics_with(ctx, settings);
+            }
+        }
+    }
+
+    /// Status line for a finished run: the verdict and what changed, so a
+    /// screen reader announces both (the time keeps repeated runs distinct)
+    fn completion_status(&self, report: &DiagnosticReport) -> String {
+        let time = report.timestamp.as_deref().and_then(|t| t.split_whitespace().nth(1)).unwrap_or_default();
+        let mut status = format!("SYS.STATUS: RUN {} DONE", time);
+        let issue = report.diagnosis.as_deref().is_some_and(|d| !d.contains("operational"));
+        match verdict::decide(report, &self.history.lock().unwrap()) {
+            Some(verdict) => status.push_str(&format!(" :: {}", verdict.headline().to_uppercase())),
+            None if issue => status.push_str(" :: ISSUE FOUND"),
+            None => {}
+        }
+        if let Some((_, ref names)) = self.changed_cards {
+            let checks = report.checks();
+            let changed: Vec<String> = names
+                .iter()
+                .take(3)
+                .map(|name| match checks.iter().find(|(_, c)| c.name == *name) {
+                    Some((_, check)) => format!("{} {}", name, check.status.label()),
+                    None => name.clone(),
+                })
+                .collect();
+            status.push_str(&format!(" :: CHANGED: {}", changed.join(", ")));
+            if names.len() > 3 {
+                status.push_str(&format!(" +{}", names.len() - 3));
+            }
+        }
+        status
+    }
+
+    /// Banner over a shared session: what is shown, and closing it
+    fn render_session_banner(&mut self, ui: &mut egui::Ui) {
+        if let Some(ref viewing) = self.viewing {
+            let mut close = false;
+            egui::Frame::none()
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.status_color(CheckStatus::Warning)))
+                .rounding(0.0)
+                .inner_margin(8.0)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(format!("READ-ONLY :: {}", viewing.title))
+                            .size(9.0)
+                            .strong()
+                            .family(egui::FontFamily::Monospace)
+                            .color(self.status_color(CheckStatus::Warning)),
+                        )
+                        .on_hover_text(&viewing.hover);
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            let close_btn = egui::Button::new(
+                                egui::RichText::new("CLOSE")
+                                    .size(9.0)
+                                    .strong()
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.theme.text),
+                            )
+                            .fill(self.theme.panel)
+                            .stroke(egui::Stroke::new(1.0, self.theme.border))
+                            .rounding(0.0);
+                            close = ui.add(close_btn).on_hover_text("Back to this machine's results").clicked();
+                        });
+                    });
+                });
+            if close {
+                self.close_session();
+            }
+            ui.add_space(10.0);
+        }
+    }
+
+    /// Section title and the popup buttons (SETTINGS, LOG, A/B, ...)
+    fn render_toolbar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("// SYSTEM CHECK")
+                    .size(10.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                // SETTINGS text button with border and hover effect (like COPY REPORT)
+                let settings_btn = egui::Button::new(
+                    egui::RichText::new("SETTINGS")
+                        .size(9.0)
+                        .strong()
+                        .family(egui::FontFamily::Monospace)
+                        .color(if self.show_settings { 
+                            self.theme.accent_on 
+                        } else { 
+                            self.theme.text 
+                        })
+                )
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(70.0, 22.0));
+
+                if ui.add(settings_btn).clicked() {
+                    if self.show_settings {
+                        self.apply_settings();
+                    }
+                    self.show_settings = !self.show_settings;
+                    self.show_history = false; // Close history when opening settings
+                    self.show_agents = false;
+                    self.show_compare = false;
+                    self.show_heatmap = false;
+                    self.show_digest = false;
+                    self.latency_chart = None;
+                    self.show_output = false;
+                    self.show_actions = false;
+                }
+
+                ui.add_space(5.0);
+
+                // LOG button for error log
+                let log_count = self.error_log.len();
+                let log_label = if log_count > 0 {
+                    format!("LOG ({})", log_count)
+                } else {
+                    "LOG".to_string()
+                };
+                let log_btn = egui::Button::new(
+                    egui::RichText::new(&log_label)
+                        .size(9.0)
+                        .strong()
+                        .family(egui::FontFamily::Monospace)
+                        .color(if self.show_history { 
+                            self.theme.accent_on 
+                        } else { 
+                            self.theme.text 
+                        })
+                )
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(55.0, 22.0));
+
+                if ui.add(log_btn).clicked() {
+                    self.show_history = !self.show_history;
+                    self.show_settings = false; // Close settings when opening log
+                    self.show_agents = false;
+                    self.show_compare = false;
+                    self.show_heatmap = false;
+                    self.show_digest = false;
+                    self.latency_chart = None;
+                    self.show_output = false;
+                    self.show_actions = false;
+                }
+
+                // A/B compare button
+                ui.add_space(5.0);
+                let compare_btn = egui::Button::new(
+                    egui::RichText::new("A/B")
+                        .size(9.0)
+                        .strong()
+                        .family(egui::FontFamily::Monospace)
+                        .color(if self.show_compare { 
+                            self.theme.accent_on 
+                        } else { 
+                            self.theme.text 
+                        })
+                )
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(40.0, 22.0));
+
+                if ui.add(compare_btn).on_hover_text("Compare runs before/after toggling VPN, Wi-Fi, ...").clicked() {
+                    self.show_compare = !self.show_compare;
+                    self.show_settings = false;
+                    self.show_history = false;
+                    self.show_agents = false;
+                    self.show_wizard = false;
+                    self.show_heatmap = false;
+                    self.show_digest = false;
+                    self.latency_chart = None;
+                    self.show_output = false;
+                    self.show_actions = false;
+                }
+
+                // Latency heatmap button
+                ui.add_space(5.0);
+                let heatmap_btn = egui::Button::new(
+                    egui::RichText::new("HEAT")
+                        .size(9.0)
+                        .strong()
+                        .family(egui::FontFamily::Monospace)
+                        .color(if self.show_heatmap { 
+                            self.theme.accent_on 
+                        } else { 
+                            self.theme.text 
+                        })
+                )
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(45.0, 22.0));
+
+                if ui.add(heatmap_btn).on_hover_text("Latency by weekday and hour").clicked() {
+                    self.show_heatmap = !self.show_heatmap;
+                    self.latency_chart = None;
+                    self.show_output = false;
+                    self.show_settings = false;
+                    self.show_history = false;
+                    self.show_agents = false;
+                    self.show_wizard = false;
+                    self.show_compare = false;
+                    self.show_actions = false;
+                }
+
+                // Weekly digest button
+                ui.add_space(5.0);
+                let digest_btn = egui::Button::new(
+                    egui::RichText::new("WEEK")
+                        .size(9.0)
+                        .strong()
+                        .family(egui::FontFamily::Monospace)
+                        .color(if self.show_digest { 
+                            self.theme.accent_on 
+                        } else { 
+                            self.theme.text 
+                        })
+                )
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(45.0, 22.0));
+
+                if ui.add(digest_btn).on_hover_text("Uptime, incidents and load of the last 7 days").clicked() {
+                    if self.show_digest {
+                        self.show_digest = false;
+                    } else {
+                        self.open_digest(None);
+                    }
+                }
+
+                // Network quick actions (Windows commands)
+                if cfg!(target_os = "windows") {
+                    ui.add_space(5.0);
+                    let fix_btn = egui::Button::new(
+                        egui::RichText::new("FIX")
+                            .size(9.0)
+                            .strong()
+                            .family(egui::FontFamily::Monospace)
+                            .color(if self.show_actions { 
+                                self.theme.accent_on 
+                            } else { 
+                                self.theme.text 
+                            })
+                    )
+                    .fill(self.theme.panel)
+                    .stroke(egui::Stroke::new(1.0, self.theme.border))
+                    .rounding(0.0)
+                    .min_size(egui::vec2(40.0, 22.0));
+
+                    if ui.add(fix_btn).on_hover_text("Flush DNS, renew DHCP, restart Wi-Fi").clicked() {
+                        self.show_actions = !self.show_actions;
+                        self.pending_action = None;
+                        self.show_settings = false;
+                        self.show_history = false;
+                        self.show_agents = false;
+                        self.show_wizard = false;
+                        self.show_compare = false;
+                        self.show_heatmap = false;
+                        self.show_digest = false;
+                        self.latency_chart = None;
+                        self.show_output = false;
+                    }
+                }
+
+                // OpenCode output tail
+                ui.add_space(5.0);
+                let output_btn = egui::Button::new(
+                    egui::RichText::new(if self.tail.is_some() { "OUTPUT *" } else { "OUTPUT" })
+                        .size(9.0)
+                        .strong()
+                        .family(egui::FontFamily::Monospace)
+                        .color(if self.show_output { 
+                            self.theme.accent_on 
+                        } else { 
+                            self.theme.text 
+                        })
+                )
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(60.0, 22.0));
+
+                if ui.add(output_btn).on_hover_text("Live OpenCode output with recognized errors").clicked() {
+                    self.show_output = !self.show_output;
+                    self.show_settings = false;
+                    self.show_history = false;
+                    self.show_agents = false;
+                    self.show_wizard = false;
+                    self.show_compare = false;
+                    self.show_heatmap = false;
+                    self.show_digest = false;
+                    self.latency_chart = None;
+                    self.show_actions = false;
+                }
+
+                // AGENTS button (only while discovery is on)
+                if self.settings.discover_agents && self.discovery.is_some() {
+                    ui.add_space(5.0);
+                    let agent_count = self.discovery.as_ref().map(|d| d.agents().len()).unwrap_or(0);
+                    let agents_btn = egui::Button::new(
+                        egui::RichText::new(format!("AGENTS ({})", agent_count))
+                            .size(9.0)
+                            .strong()
+                            .family(egui::FontFamily::Monospace)
+                            .color(if self.show_agents { 
+                                self.theme.accent_on 
+                            } else { 
+                                self.theme.text 
+                            })
+                    )
+                    .fill(self.theme.panel)
+                    .stroke(egui::Stroke::new(1.0, self.theme.border))
+                    .rounding(0.0)
+                    .min_size(egui::vec2(70.0, 22.0));
+
+                    if ui.add(agents_btn).clicked() {
+                        self.show_agents = !self.show_agents;
+                        self.show_settings = false;
+                        self.show_history = false;
+                        self.show_compare = false;
+                        self.show_heatmap = false;
+                        self.show_digest = false;
+                        self.latency_chart = None;
+                        self.show_output = false;
+                        self.show_actions = false;
+                    }
+                }
+
+                ui.add_space(10.0);
+
+                // Show enabled checks count (and role when not custom)
+                let checks_label = if self.settings.view_role == ViewRole::Custom {
+                    format!("{} checks", self.settings.enabled_count())
+                } else {
+                    format!("{} checks :: {}", self.settings.enabled_count(), self.settings.view_role.label())
+                };
+                ui.label(
+                    egui::RichText::new(checks_label)
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(self.theme.text_dim),
+                );
+            });
+        });
+    }
+
+    /// A card per enabled check, a placeholder until it has run
+    fn render_cards(&mut self, ui: &mut egui::Ui, report: &DiagnosticReport) {
+        let ctx = ui.ctx().clone();
+        if self.settings.check_cpu_ram {
+            if let Some(ref check) = report.local_resources {
+                let samples = self.resources.samples();
+                self.render_check_card_with(ui, check, |app, ui| app.render_resource_graph(ui, &samples));
+            } else {
+                self.render_placeholder_card(ui, "LOCAL RESOURCES", "CPU :: RAM");
+            }
+        }
+
+        if self.settings.check_gpu {
+            if let Some(ref check) = report.gpu {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "GPU", "Video card status");
+            }
+        }
+
+        if self.settings.check_long_paths {
+            if let Some(ref check) = report.long_paths {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "LONG PATHS", "Windows / git path limit");
+            }
+        }
+
+        if self.settings.check_defender {
+            if let Some(ref check) = report.defender {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "DEFENDER", "Real-time scan overhead");
+            }
+        }
+
+        if self.settings.check_data_dirs {
+            if let Some(ref check) = report.data_dirs {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "DATA DIRS", "OpenCode config/cache permissions");
+            }
+        }
+
+        if self.settings.check_cloud_sync {
+            if let Some(ref check) = report.cloud_sync {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "CLOUD SYNC", "OneDrive/Dropbox interference");
+            }
+        }
+
+        if self.settings.check_internet {
+            if let Some(ref check) = report.gateway {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "ROUTER", "Default gateway");
+            }
+            if let Some(ref check) = report.internet {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "INTERNET", "Connectivity check");
+            }
+        }
+
+        if self.settings.check_firewall {
+            if let Some(ref check) = report.firewall {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "FIREWALL", "Loopback / inbound / outbound");
+            }
+        }
+
+        if self.settings.check_quic {
+            if let Some(ref check) = report.quic {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "QUIC", "UDP 443 / HTTP/3");
+            }
+        }
+
+        if self.settings.check_bandwidth {
+            if let Some(ref check) = report.bandwidth {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "BANDWIDTH", "Throughput per process");
+            }
+        }
+
+        if self.settings.check_root_store {
+            if let Some(ref check) = report.root_store {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "ROOT CA", "Certificate root store");
+            }
+        }
+
+        if self.settings.check_claude {
+            if let Some(ref check) = report.claude_api {
+                self.render_api_card(ui, diagnostics::ids::CLAUDE, check);
+            } else {
+                self.render_placeholder_card(ui, "CLAUDE API", "api.anthropic.com");
+            }
+        }
+
+        if self.settings.check_openai {
+            if let Some(ref check) = report.openai_api {
+                self.render_api_card(ui, diagnostics::ids::OPENAI, check);
+            } else {
+                self.render_placeholder_card(ui, "OPENAI API", "api.openai.com");
+            }
+        }
+
+        if self.settings.check_google_ai {
+            if let Some(ref check) = report.google_api {
+                self.render_api_card(ui, diagnostics::ids::GOOGLE, check);
+            } else {
+                self.render_placeholder_card(ui, "GOOGLE AI", "googleapis.com");
+            }
+        }
+
+        if self.settings.check_relay {
+            if let Some(ref check) = report.relay {
+                self.render_api_card(ui, diagnostics::ids::RELAY, check);
+            } else {
+                self.render_placeholder_card(ui, "RELAY", "Provider results from the internet");
+            }
+        }
+
+        if self.settings.check_endpoint_certs {
+            if let Some(ref check) = report.endpoint_certs {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "ENDPOINT CERTS", "Custom endpoint TLS expiry");
+            }
+        }
+
+        if self.settings.check_opencode {
+            if let Some(ref check) = report.opencode {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "OPENCODE", "Process status");
+            }
+            self.render_restart_row(ui, &ctx);
+        }
+
+        if self.settings.check_crashes {
+            if let Some(ref check) = report.crashes {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "CRASHES", "OpenCode crash reports, last 7 days");
+            }
+        }
+
+        if self.settings.check_leaks {
+            if let Some(ref check) = report.leaks {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "LEAKS", "Open handles and temp growth");
+            }
+        }
+
+        if self.settings.check_ipc {
+            if let Some(ref check) = report.ipc {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "EDITOR IPC", "OpenCode local server");
+            }
+        }
+
+        if self.settings.check_editors {
+            if let Some(ref check) = report.editors {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "EDITORS", "VS Code / JetBrains plugins");
+            }
+        }
+
+        if self.settings.check_terminals {
+            if let Some(ref check) = report.terminals {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "TERMINALS", "cmd, powershell, wt");
+            }
+        }
+
+        if self.settings.check_smoke {
+            if let Some(ref check) = report.smoke {
+                self.render_check_card(ui, check);
+            } else {
+                self.render_placeholder_card(ui, "SMOKE TEST", "opencode run \"say hi\"");
+            }
+        }
+    }
+
+    /// Diagnosis and trigger of the latest run
+    fn render_diagnosis(&mut self, ui: &mut egui::Ui, report: &DiagnosticReport) {
+        if let Some(ref diagnosis) = report.diagnosis {
+            ui.add_space(10.0);
+            egui::Frame::none()
+                .fill(self.theme.panel)
+                .inner_margin(egui::Margin::symmetric(15.0, 10.0))
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new("DIAGNOSIS:")
+                                .size(10.0)
+                                .family(egui::FontFamily::Monospace)
+                                .strong()
+                                .color(self.theme.text),
+                        );
+                        ui.label(
+                            egui::RichText::new(diagnosis)
+                                .size(10.0)
+                                .family(egui::FontFamily::Monospace)
+                                .color(self.theme.text_dim),
+                        );
+                    });
+
+                    if let Some(ref trigger) = report.trigger {
+                        ui.label(
+                            egui::RichText::new(format!("TRIGGER: {}", trigger))
+                                .size(9.0)
+                                .family(egui::FontFamily::Monospace)
+                                .color(self.theme.text_dim),
+                        );
+                    }
+
+                    self.render_why(ui, report);
+                });
+        }
+    }
+
+    /// RUN DIAGNOSTICS, COPY REPORT, TROUBLESHOOT and TRACE
+    fn render_action_buttons(&mut self, ui: &mut egui::Ui) {
+        let ctx = ui.ctx().clone();
+        ui.horizontal(|ui| {
+            let is_running = *self.is_running.lock().unwrap();
+
+            // RUN DIAGNOSTICS button
+            let run_btn_text = if is_running { "RUNNING..." } else { "RUN DIAGNOSTICS" };
+            let run_btn = egui::Button::new(
+                egui::RichText::new(run_btn_text)
+                    .size(11.0)
+                    .strong()
+                    .family(egui::FontFamily::Monospace)
+                    .color(egui::Color32::WHITE)
+            )
+            .fill(self.theme.accent_on)
+            .stroke(egui::Stroke::NONE)
+            .rounding(0.0)
+            .min_size(egui::vec2(160.0, 32.0));
+
+            if ui.add_enabled(!is_running && self.viewing.is_none(), run_btn).clicked() {
+                self.run_diagnostics_skipping_fresh(&ctx);
+            }
+
+            ui.add_space(10.0);
+
+            // COPY REPORT button
+            let copy_text = if self.copied_feedback.is_some() { "COPIED!" } else { "COPY REPORT" };
+            if ui.add(
+                egui::Button::new(
+                    egui::RichText::new(copy_text)
+                        .size(11.0)
+                        .strong()
+                        .family(egui::FontFamily::Monospace)
+                        .color(self.theme.text)
+                )
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(115.0, 32.0))
+            ).clicked() {
+                self.copy_report();
+            }
+
+            ui.add_space(10.0);
+
+            // TROUBLESHOOT button (guided wizard)
+            if ui.add(
+                egui::Button::new(
+                    egui::RichText::new("TROUBLESHOOT")
+                        .size(11.0)
+                        .strong()
+                        .family(egui::FontFamily::Monospace)
+                        .color(if self.show_wizard { 
+                            self.theme.accent_on 
+                        } else { 
+                            self.theme.text 
+                        })
+                )
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(115.0, 32.0))
+            ).clicked() {
+                self.show_wizard = !self.show_wizard;
+                self.show_settings = false;
+                self.show_history = false;
+                self.show_agents = false;
+                self.show_compare = false;
+                self.show_heatmap = false;
+                self.show_digest = false;
+                self.latency_chart = None;
+                self.show_output = false;
+                self.show_actions = false;
+            }
+
+            ui.add_space(10.0);
+
+            // TRACE button: network checks every few seconds while the problem is reproduced
+            let trace_text = match self.trace {
+                Some(ref trace) => {
+                    let left = trace.remaining().as_secs();
+                    format!("TRACING {}:{:02}", left / 60, left % 60)
+                }
+                None => "TRACE 5 MIN".to_string(),
+            };
+            let trace_btn = egui::Button::new(
+                egui::RichText::new(trace_text)
+                    .size(11.0)
+                    .strong()
+                    .family(egui::FontFamily::Monospace)
+                    .color(if self.trace.is_some() { self.theme.accent_on } else { self.theme.text })
+            )
+            .fill(self.theme.panel)
+            .stroke(egui::Stroke::new(1.0, self.theme.border))
+            .rounding(0.0)
+            .min_size(egui::vec2(100.0, 32.0));
+            let hover = match self.trace {
+                Some(_) => "Stop now and show the summary".to_string(),
+                None => format!("Repeat the network checks every {}s for 5 minutes while you reproduce the problem", TRACE_INTERVAL.as_secs()),
+            };
+            if ui.add_enabled(self.viewing.is_none(), trace_btn).on_hover_text(hover).clicked() {
+                self.toggle_trace();
+            }
+        });
+    }
+
+    /// The verdict banner, recomputed once per report
+    fn render_verdict(&mut self, ui: &mut egui::Ui, report: &DiagnosticReport) {
+        if self.verdict_for != report.timestamp {
+            self.verdict = verdict::decide(report, &self.history.lock().unwrap());
+            self.verdict_for = report.timestamp.clone();
+        }
+        let Some(ref verdict) = self.verdict else {
+            return;
+        };
+        let color = self.status_color(verdict.side.status());
+        egui::Frame::none()
+            .fill(self.theme.panel)
+            .stroke(egui::Stroke::new(2.0, color))
+            .inner_margin(egui::Margin::symmetric(15.0, 10.0))
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.label(
+                    egui::RichText::new(verdict.headline().to_uppercase())
+                        .size(15.0)
+                        .family(egui::FontFamily::Monospace)
+                        .strong()
+                        .color(color),
+                );
+                for line in &verdict.evidence {
+                    ui.label(
+                        egui::RichText::new(format!("- {}", line))
+                            .size(9.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(self.theme.text_dim),
+                    );
+                }
+                // Provider down, another up: say which to switch to and how
+                if let Some(fallback) = fallback::recommend(report) {
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(format!("> {} :: opencode.json {}", fallback.advice, fallback.config))
+                                .size(9.0)
+                                .family(egui::FontFamily::Monospace)
+                                .color(self.theme.text),
+                        );
+                        let docs = egui::Button::new(
+                            egui::RichText::new("MODEL DOCS").size(9.0).family(egui::FontFamily::Monospace).color(self.theme.text),
+                        )
+                        .fill(self.theme.panel)
+                        .stroke(egui::Stroke::new(1.0, self.theme.border))
+                        .rounding(0.0);
+                        if ui.add(docs).on_hover_text("Open OpenCode's documentation of the model setting").clicked() {
+                            open_url(fallback::DOCS_URL);
+                        }
+                    });
+                }
+            });
+        ui.add_space(10.0);
+    }
+
+    /// Copy the report as Markdown and open where it gets pasted
+    fn ask_for_help(&mut self, url: &str, place: &str) {
+        let report = self.report.snapshot();
+        let (text, _) = self.report_text(&report);
+        let headline = self.verdict.as_ref().map(|v| v.headline());
+        let markdown = support::to_markdown(&report, &text, headline.as_deref());
+        let copied = Clipboard::new().is_ok_and(|mut clipboard| clipboard.set_text(&markdown).is_ok());
+        open_url(url);
+        self.status = if copied {
+            self.copied_feedback = Some(Instant::now());
+            format!("SYS.STATUS: MARKDOWN REPORT COPIED :: PASTE IT INTO THE {}", place)
+        } else {
+            "SYS.STATUS: COULD NOT COPY REPORT :: USE COPY REPORT".to_string()
+        };
+    }
+
+    /// One-line offer under the verdict after a run with errors, until closed
+    fn render_help_prompt(&mut self, ui: &mut egui::Ui, report: &DiagnosticReport) {
+        let failed = support::failed_checks(report);
+        if failed.is_empty() || self.viewing.is_some() || self.help_dismissed == report.timestamp {
+            return;
+        }
+        let button = |text: &str| {
+            egui::Button::new(egui::RichText::new(text).size(9.0).family(egui::FontFamily::Monospace).color(self.theme.text))
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+        };
+        let mut clicked = None;
+        let mut dismissed = false;
+        egui::Frame::none()
+            .fill(self.theme.panel)
+            .stroke(egui::Stroke::new(1.0, self.theme.border))
+            .inner_margin(egui::Margin::symmetric(10.0, 6.0))
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.horizontal(|ui| {
+                    let what = if failed.len() == 1 { failed[0].clone() } else { format!("{} checks", failed.len()) };
+                    ui.label(
+                        egui::RichText::new(format!("{} FAILED :: ASK FOR HELP WITH THE REPORT?", what))
+                            .size(9.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(self.theme.text_dim),
+                    );
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let close = ui.add(button("X")).on_hover_text("Not for this run");
+                        a11y::describe(&close, egui::WidgetType::Button, "Close the help offer");
+                        if close.clicked() {
+                            dismissed = true;
+                        }
+                        if ui.add(button("COPY + DISCORD")).on_hover_text("Copy the report as Markdown and open the OpenCode Discord").clicked() {
+                            clicked = Some((support::DISCORD_URL, "DISCORD MESSAGE"));
+                        }
+                        if ui.add(button("COPY + OPEN ISSUE")).on_hover_text("Copy the report as Markdown and open a new OpenCode issue").clicked() {
+                            clicked = Some((support::ISSUE_URL, "ISSUE"));
+                        }
+                    });
+                });
+            });
+        ui.add_space(10.0);
+        if let Some((url, place)) = clicked {
+            self.ask_for_help(url, place);
+            dismissed = true;
+        }
+        if dismissed {
+            self.help_dismissed = report.timestamp.clone();
+        }
+    }
+
+    /// Render the [PC] -> [ROUTER] -> [INTERNET] -> [API] -> [OPENCODE] diagram
+    fn render_path(&self, ui: &mut egui::Ui, report: &DiagnosticReport) {
+        let hops = report.path();
+        let arrow_width = 18.0;
+        let height = 34.0;
+        let width = ui.available_width();
+        let box_width = ((width - arrow_width * (hops.len() - 1) as f32) / hops.len() as f32).max(40.0);
+        
+        let (rect, response) = ui.allocate_exact_size(egui::vec2(width, height), egui::Sense::hover());
+        let spoken: Vec<String> = hops
+            .iter()
+            .map(|hop| format!("{} {}", hop.label, hop.status.map(a11y::status_word).unwrap_or("not checked")))
+            .collect();
+        a11y::describe(&response, egui::WidgetType::Label, &format!("Connection path: {}", spoken.join(", ")));
+        let painter = ui.painter();
+        
+        for (i, hop) in hops.iter().enumerate() {
+            let x = rect.min.x + i as f32 * (box_width + arrow_width);
+            let hop_rect = egui::Rect::from_min_size(egui::pos2(x, rect.min.y), egui::vec2(box_width, height));
+            let color = match hop.status {
+                Some(status) => self.status_color(status),
+                None => self.theme.text_dim,
+            };
+            
+            painter.rect_filled(hop_rect, 0.0, self.theme.panel);
+            painter.rect_stroke(hop_rect, 0.0, egui::Stroke::new(1.0, self.theme.border));
+            // Status bar along the bottom, same language as the card accent bar
+            painter.rect_filled(
+                egui::Rect::from_min_max(egui::pos2(hop_rect.min.x, hop_rect.max.y - 3.0), hop_rect.max),
+                0.0,
+                color,
+            );
+            painter.text(
+                hop_rect.center() - egui::vec2(0.0, 5.0),
+                egui::Align2::CENTER_CENTER,
+                hop.label,
+                egui::FontId::new(9.0, egui::FontFamily::Monospace),
+                self.theme.text,
+            );
+            painter.text(
+                hop_rect.center() + egui::vec2(0.0, 7.0),
+                egui::Align2::CENTER_CENTER,
+                hop.status.map(|s| s.label()).unwrap_or("..."),
+                egui::FontId::new(8.0, egui::FontFamily::Monospace),
+                color,
+            );
+            
+            // Arrow to the next hop
+            if i + 1 < hops.len() {
+                let y = hop_rect.center().y;
+                let start = egui::pos2(hop_rect.max.x + 3.0, y);
+                let end = egui::pos2(hop_rect.max.x + arrow_width - 3.0, y);
+                let stroke = egui::Stroke::new(1.0, self.theme.text_dim);
+                painter.line_segment([start, end], stroke);
+                painter.line_segment([end, end + egui::vec2(-4.0, -3.0)], stroke);
+                painter.line_segment([end, end + egui::vec2(-4.0, 3.0)], stroke);
+            }
+        }
+        
+        ui.add_space(10.0);
+    }
+
+    /// CPU sparkline with current values while running, peaks once the run is done
+    fn render_live_strip(&self, ui: &mut egui::Ui, live: &LiveMetrics) {
+        let samples = live.samples();
+        let running = live.is_running();
+        let values = if running { samples.last().copied() } else { live.peak() };
+        let text = match values {
+            Some(s) => format!(
+                "{} CPU {}% :: RAM {}% :: RX {}/s :: TX {}/s",
+                if running { "LIVE" } else { "RUN PEAK" },
+                s.cpu as u32,
+                s.ram as u32,
+                format_bytes(s.rx),
+                format_bytes(s.tx)
+            ),
+            None => "LIVE :: sampling...".to_string(),
+        };
+
+        let height = 16.0;
+        let width = ui.available_width();
+        let (rect, response) = ui.allocate_exact_size(egui::vec2(width, height), egui::Sense::hover());
+        a11y::describe(&response, egui::WidgetType::Label, &text);
+        let painter = ui.painter();
+        painter.rect_filled(rect, 0.0, self.theme.panel);
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, self.theme.border));
+
+        // One bar per sample on the right half, newest at the right edge
+        let bar_width = 2.0;
+        let graph_width = (width / 2.0).min(samples.len() as f32 * bar_width);
+        for (i, sample) in samples.iter().rev().enumerate() {
+            let x = rect.max.x - 1.0 - (i as f32 + 1.0) * bar_width;
+            if x < rect.max.x - 1.0 - graph_width {
+                break;
+            }
+            let bar_height = (sample.cpu / 100.0).clamp(0.02, 1.0) * (height - 4.0);
+            let color = match sample.cpu {
+                c if c > 90.0 => self.status_color(CheckStatus::Error),
+                c if c > 70.0 => self.status_color(CheckStatus::Warning),
+                _ => self.theme.text_dim,
+            };
+            painter.rect_filled(
+                egui::Rect::from_min_max(egui::pos2(x, rect.max.y - 2.0 - bar_height), egui::pos2(x + bar_width - 0.5, rect.max.y - 2.0)),
+                0.0,
+                color,
+            );
+        }
+        painter.text(
+            rect.left_center() + egui::vec2(6.0, 0.0),
+            egui::Align2::LEFT_CENTER,
+            text,
+            egui::FontId::new(8.0, egui::FontFamily::Monospace),
+            self.theme.text,
+        );
+
+        ui.add_space(6.0);
+    }
+
+    /// CPU/RAM (and GPU) lines over the monitor window, with averages and peaks
+    fn render_resource_graph(&self, ui: &mut egui::Ui, samples: &[ResourceSample]) {
+        if samples.len() < 2 {
+            return;
+        }
+        let gpu = self.settings.check_gpu && samples.iter().any(|s| s.gpu.is_some());
+
+        ui.add_space(3.0);
+        let width = ui.available_width().min(320.0);
+        let (rect, response) = ui.allocate_exact_size(egui::vec2(width, 24.0), egui::Sense::hover());
+        a11y::describe(&response, egui::WidgetType::Label, "Graph of CPU and RAM load over the last minutes");
+        let painter = ui.painter();
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, self.theme.border));
+
+        let step = rect.width() / (MONITOR_WINDOW as f32 - 1.0);
+        let line = |value: fn(&ResourceSample) -> Option<f32>, color: egui::Color32| {
+            // Newest sample at the right edge
+            let points: Vec<egui::Pos2> = samples
+                .iter()
+                .rev()
+                .enumerate()
+                .filter_map(|(i, s)| {
+                    let y = rect.max.y - value(s)?.clamp(0.0, 100.0) / 100.0 * rect.height();
+                    Some(egui::pos2(rect.max.x - i as f32 * step, y))
+                })
+                .collect();
+            painter.add(egui::Shape::line(points, egui::Stroke::new(1.0, color)));
+        };
+        line(|s| Some(s.ram), self.theme.text_dim);
+        line(|s| Some(s.cpu), self.theme.accent_on);
+        if gpu {
+            line(|s| s.gpu, self.status_color(CheckStatus::Warning));
+        }
+
+        let stats = |value: fn(&ResourceSample) -> Option<f32>| {
+            let values: Vec<f32> = samples.iter().filter_map(value).collect();
+            let avg = values.iter().sum::<f32>() / values.len().max(1) as f32;
+            let max = values.iter().copied().fold(0.0, f32::max);
+            (avg as u32, max as u32)
+        };
+        let (cpu_avg, cpu_max) = stats(|s| Some(s.cpu));
+        let (ram_avg, ram_max) = stats(|s| Some(s.ram));
+        let mut summary = format!(
+            "{}s :: CPU avg {}% max {}% :: RAM avg {}% max {}%",
+            samples.len() as u64 * MONITOR_INTERVAL.as_secs(),
+            cpu_avg,
+            cpu_max,
+            ram_avg,
+            ram_max
+        );
+        if gpu {
+            let (gpu_avg, gpu_max) = stats(|s| s.gpu);
+            summary.push_str(&format!(" :: GPU avg {}% max {}%", gpu_avg, gpu_max));
+        }
+        ui.label(
+            egui::RichText::new(summary)
+                .size(8.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+    }
+
+    /// "WHY?" toggle, RIGHT/WRONG feedback and, when open, each finding with its confidence and evidence
+    fn render_why(&mut self, ui: &mut egui::Ui, report: &DiagnosticReport) {
+        let mono = |text: String, size: f32, color: egui::Color32| {
+            egui::RichText::new(text).size(size).family(egui::FontFamily::Monospace).color(color)
+        };
+        let rule = report.headline().map(|f| f.rule.as_str()).unwrap_or(feedback::ALL_OK);
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            if !report.findings.is_empty() {
+                let toggle = format!("[{}] WHY?", if self.show_why { "-" } else { "+" });
+                if ui.add(egui::Button::new(mono(toggle, 9.0, self.theme.accent_on)).frame(false)).clicked() {
+                    self.show_why = !self.show_why;
+                }
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                // Votes are about this machine's diagnoses
+                if self.viewing.is_some() {
+                    return;
+                }
+                if self.feedback_voted.is_some() && self.feedback_voted == report.timestamp {
+                    let stats = feedback::stats(rule);
+                    let text = match self.feedback_error {
+                        Some(ref e) => e.clone(),
+                        None => format!("THANKS :: {}/{} RIGHT", stats.right, stats.votes()),
+                    };
+                    ui.label(mono(text, 8.0, self.theme.text_dim));
+                    return;
+                }
+                // Right-to-left: WRONG first so RIGHT reads first
+                for (label, right) in [("WRONG", false), ("RIGHT", true)] {
+                    let button = egui::Button::new(mono(label.to_string(), 8.0, self.theme.text))
+                        .fill(self.theme.panel)
+                        .stroke(egui::Stroke::new(1.0, self.theme.border))
+                        .rounding(0.0);
+                    if ui.add(button).clicked() {
+                        self.feedback_error = feedback::record(rule, right).err();
+                        self.feedback_voted = report.timestamp.clone();
+                    }
+                }
+                ui.label(mono("THIS DIAGNOSIS WAS".to_string(), 8.0, self.theme.text_dim));
+            });
+        });
+        if !self.show_why || report.findings.is_empty() {
+            return;
+        }
+
+        for finding in &report.findings {
+            ui.add_space(4.0);
+            let color = if finding.level() == "HIGH" { self.theme.text } else { self.theme.text_dim };
+            ui.add(
+                egui::Label::new(mono(
+                    format!("[{} {}%] {}", finding.level(), finding.confidence, finding.message),
+                    9.0,
+                    color,
+                ))
+                .wrap(),
+            );
+            for evidence in &finding.evidence {
+                ui.add(egui::Label::new(mono(format!("   - {}", evidence), 8.0, self.theme.text_dim)).wrap());
+            }
+            let stats = feedback::stats(&finding.rule);
+            if stats.votes() > 0 {
+                ui.label(mono(format!("   feedback: {}/{} right", stats.right, stats.votes()), 8.0, self.theme.text_dim));
+            }
+        }
+    }
+
+    /// SETTINGS: what the managed policy locks, or why its file was not applied
+    fn render_settings_policy(&mut self, ui: &mut egui::Ui) {
+        if let Some(policy) = policy::current() {
+            let (title, detail) = match policy.error {
+                Some(ref e) => ("// MANAGED POLICY NOT APPLIED", format!("{}: {}", policy.path.display(), e)),
+                None => ("// MANAGED BY YOUR ORGANIZATION", format!("Locked: {}", policy.locked().collect::<Vec<_>>().join(", "))),
+            };
+            ui.label(
+                egui::RichText::new(title)
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(if policy.error.is_some() { self.status_color(CheckStatus::Error) } else { self.theme.text_dim }),
+            );
+            ui.add(egui::Label::new(
+                egui::RichText::new(detail)
+                    .size(8.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            ).wrap());
+            ui.add_space(8.0);
+            ui.add(egui::Separator::default().spacing(1.0));
+            ui.add_space(8.0);
+        }
+    }
+
+    /// SETTINGS > VIEW: role presets
+    fn render_settings_role(&mut self, ui: &mut egui::Ui) {
+        ui.label(
+            egui::RichText::new("// VIEW")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+
+        ui.horizontal(|ui| {
+            for (role, label) in ROLE_PRESETS.iter() {
+                let is_selected = self.settings.view_role == *role;
+                let btn = egui::Button::new(
+                    egui::RichText::new(*label)
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(if is_selected { 
+                            egui::Color32::WHITE 
+                        } else { 
+                            self.theme.text 
+                        })
+                )
+                .fill(if is_selected { 
+                    self.theme.accent_on 
+                } else { 
+                    self.theme.panel 
+                })
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(50.0, 18.0));
+
+                let hint = match role {
+                    ViewRole::Custom => "Your own check selection",
+                    ViewRole::Developer => "API latency and status only",
+                    ViewRole::Support => "All checks, full report detail",
+                };
+                if App::add_setting(ui, "view_role", btn).on_hover_text(hint).clicked() {
+                    self.settings.apply_role(*role);
+                    self.settings.enforce_policy();
+                }
+            }
+        });
+    }
+
+    /// SETTINGS > SYSTEM: local checks and how often hardware is sampled
+    fn render_settings_system(&mut self, ui: &mut egui::Ui) {
+        ui.label(
+            egui::RichText::new("// SYSTEM")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        let text_color = self.theme.text;
+        App::render_setting_checkbox(ui, "check_cpu_ram", &mut self.settings.check_cpu_ram, "CPU / RAM", text_color);
+        App::render_setting_checkbox(ui, "check_gpu", &mut self.settings.check_gpu, "GPU", text_color);
+        // CPU/RAM and GPU re-run at most this often, runs in between reuse their results
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("HW EVERY")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+            ui.add_space(10.0);
+            for (secs, label) in HARDWARE_INTERVAL_PRESETS {
+                let is_selected = self.settings.hardware_interval_secs == *secs;
+                let btn = egui::Button::new(
+                    egui::RichText::new(*label)
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(if is_selected { egui::Color32::WHITE } else { self.theme.text })
+                )
+                .fill(if is_selected { self.theme.accent_on } else { self.theme.panel })
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(30.0, 18.0));
+                if App::add_setting(ui, "hardware_interval_secs", btn).clicked() {
+                    self.settings.hardware_interval_secs = *secs;
+                }
+            }
+        });
+        App::render_setting_checkbox(ui, "check_long_paths", &mut self.settings.check_long_paths, "Long paths", text_color);
+        let defender_label = if diagnostics::elevation::limited_without_admin(diagnostics::ids::DEFENDER) {
+            "Defender scan overhead (exclusions require admin)"
+        } else {
+            "Defender scan overhead"
+        };
+        App::render_setting_checkbox(ui, "check_defender", &mut self.settings.check_defender, defender_label, text_color);
+        App::render_setting_checkbox(ui, "check_data_dirs", &mut self.settings.check_data_dirs, "OpenCode data dirs", text_color);
+        App::render_setting_checkbox(ui, "check_cloud_sync", &mut self.settings.check_cloud_sync, "Cloud sync (OneDrive/Dropbox)", text_color);
+    }
+
+    /// SETTINGS > NETWORK: connectivity checks
+    fn render_settings_network(&mut self, ui: &mut egui::Ui) {
+        let text_color = self.theme.text;
+        ui.label(
+            egui::RichText::new("// NETWORK")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        App::render_setting_checkbox(ui, "check_internet", &mut self.settings.check_internet, "Internet", text_color);
+        let firewall_label = if diagnostics::elevation::limited_without_admin(diagnostics::ids::FIREWALL) {
+            "Firewall (event log requires admin)"
+        } else {
+            "Firewall"
+        };
+        App::render_setting_checkbox(ui, "check_firewall", &mut self.settings.check_firewall, firewall_label, text_color);
+        App::render_setting_checkbox(ui, "check_quic", &mut self.settings.check_quic, "QUIC (UDP 443)", text_color);
+        let bandwidth_label = if diagnostics::elevation::limited_without_admin(diagnostics::ids::BANDWIDTH) {
+            "Bandwidth by process (throughput requires admin)"
+        } else {
+            "Bandwidth by process"
+        };
+        App::render_setting_checkbox(ui, "check_bandwidth", &mut self.settings.check_bandwidth, bandwidth_label, text_color);
+        App::render_setting_checkbox(ui, "check_root_store", &mut self.settings.check_root_store, "Root CAs", text_color);
+    }
+
+    /// SETTINGS > AI APIS: provider checks and the relay to compare with
+    fn render_settings_apis(&mut self, ui: &mut egui::Ui) {
+        let text_color = self.theme.text;
+        ui.label(
+            egui::RichText::new("// AI APIS")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        App::render_setting_checkbox(ui, "check_claude", &mut self.settings.check_claude, "Claude", text_color);
+        App::render_setting_checkbox(ui, "check_openai", &mut self.settings.check_openai, "OpenAI", text_color);
+        App::render_setting_checkbox(ui, "check_google_ai", &mut self.settings.check_google_ai, "Google AI", text_color);
+        App::render_setting_checkbox(ui, "check_relay", &mut self.settings.check_relay, "Compare with relay", text_color);
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("RELAY")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+            ui.add_space(10.0);
+            App::add_setting(
+                ui,
+                "relay_url",
+                egui::TextEdit::singleline(&mut self.settings.relay_url)
+                    .font(egui::FontId::monospace(9.0))
+                    .hint_text("https://relay.example.com")
+                    .desired_width(190.0),
+            );
+        });
+    }
+
+    /// SETTINGS > CUSTOM ENDPOINTS: proxies and self-hosted gateways, cert expiry
+    fn render_settings_endpoints(&mut self, ui: &mut egui::Ui) {
+        let text_color = self.theme.text;
+        ui.label(
+            egui::RichText::new("// CUSTOM ENDPOINTS")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        App::render_setting_checkbox(ui, "check_endpoint_certs", &mut self.settings.check_endpoint_certs, "Cert expiry", text_color);
+
+        let mut remove = None;
+        for (i, endpoint) in self.settings.custom_endpoints.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let remove_btn = egui::Button::new(
+                    egui::RichText::new("X")
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(self.theme.text)
+                )
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(18.0, 18.0));
+                let remove_btn = App::add_setting(ui, "custom_endpoints", remove_btn);
+                a11y::describe(&remove_btn, egui::WidgetType::Button, &format!("Remove endpoint {}", endpoint));
+                if remove_btn.clicked() {
+                    remove = Some(i);
+                }
+                ui.label(
+                    egui::RichText::new(endpoint)
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(text_color),
+                );
+            });
+        }
+        if let Some(i) = remove {
+            self.settings.custom_endpoints.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            App::add_setting(
+                ui,
+                "custom_endpoints",
+                egui::TextEdit::singleline(&mut self.new_endpoint)
+                    .font(egui::FontId::monospace(9.0))
+                    .hint_text("https://gateway.example.com")
+                    .desired_width(190.0),
+            );
+            let add_btn = egui::Button::new(
+                egui::RichText::new("ADD")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text)
+            )
+            .fill(self.theme.panel)
+            .stroke(egui::Stroke::new(1.0, self.theme.border))
+            .rounding(0.0)
+            .min_size(egui::vec2(40.0, 18.0));
+            if App::add_setting(ui, "custom_endpoints", add_btn).clicked() && !self.new_endpoint.trim().is_empty() {
+                let endpoint = diagnostics::certs::normalize_endpoint(&self.new_endpoint);
+                if !self.settings.custom_endpoints.contains(&endpoint) {
+                    self.settings.custom_endpoints.push(endpoint);
+                }
+                self.new_endpoint.clear();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("WARN")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+            ui.add_space(10.0);
+            for (days, label) in CERT_WARN_PRESETS {
+                let is_selected = self.settings.cert_warn_days == *days;
+                let btn = egui::Button::new(
+                    egui::RichText::new(*label)
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(if is_selected { egui::Color32::WHITE } else { self.theme.text })
+                )
+                .fill(if is_selected { self.theme.accent_on } else { self.theme.panel })
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(40.0, 18.0));
+                if App::add_setting(ui, "cert_warn_days", btn).clicked() {
+                    self.settings.cert_warn_days = *days;
+                }
+            }
+        });
+    }
+
+    /// SETTINGS > REQUESTS: request profile, User-Agent, extra headers, concurrency
+    fn render_settings_requests(&mut self, ui: &mut egui::Ui) {
+        let text_color = self.theme.text;
+        ui.label(
+            egui::RichText::new("// REQUESTS")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        App::render_setting_checkbox(ui, "opencode_mode", &mut self.settings.opencode_mode, "OpenCode request profile", text_color);
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("UA")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+            ui.add_space(10.0);
+            App::add_setting(
+                ui,
+                "user_agent",
+                egui::TextEdit::singleline(&mut self.settings.user_agent)
+                    .font(egui::FontId::monospace(9.0))
+                    .hint_text(diagnostics::http::DEFAULT_USER_AGENT)
+                    .desired_width(210.0),
+            );
+        });
+
+        let mut remove = None;
+        for (i, header) in self.settings.extra_headers.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let remove_btn = egui::Button::new(
+                    egui::RichText::new("X")
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(self.theme.text)
+                )
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(18.0, 18.0));
+                let remove_btn = App::add_setting(ui, "extra_headers", remove_btn);
+                a11y::describe(&remove_btn, egui::WidgetType::Button, &format!("Remove header {}", header.split(':').next().unwrap_or_default()));
+                if remove_btn.clicked() {
+                    remove = Some(i);
+                }
+                ui.label(
+                    egui::RichText::new(header)
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(text_color),
+                );
+            });
+        }
+        if let Some(i) = remove {
+            self.settings.extra_headers.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            App::add_setting(
+                ui,
+                "extra_headers",
+                egui::TextEdit::singleline(&mut self.new_header)
+                    .font(egui::FontId::monospace(9.0))
+                    .hint_text("X-Header: value")
+                    .desired_width(190.0),
+            );
+            let add_btn = egui::Button::new(
+                egui::RichText::new("ADD")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text)
+            )
+            .fill(self.theme.panel)
+            .stroke(egui::Stroke::new(1.0, self.theme.border))
+            .rounding(0.0)
+            .min_size(egui::vec2(40.0, 18.0));
+            if App::add_setting(ui, "extra_headers", add_btn).clicked() && !self.new_header.trim().is_empty() {
+                match diagnostics::http::parse_header(&self.new_header) {
+                    Ok((name, value)) => {
+                        self.settings.extra_headers.push(format!("{}: {}", name, value));
+                        self.new_header.clear();
+                        self.header_error = None;
+                    }
+                    Err(e) => self.header_error = Some(e),
+                }
+            }
+        });
+        if let Some(ref error) = self.header_error {
+            ui.label(
+                egui::RichText::new(error)
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.status_color(CheckStatus::Error)),
+            );
+        }
+
+        // Network checks running at once
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("PARALLEL")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+            ui.add_space(10.0);
+            for (limit, label) in CONCURRENCY_PRESETS {
+                let is_selected = self.settings.network_concurrency == *limit;
+                let btn = egui::Button::new(
+                    egui::RichText::new(*label)
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(if is_selected { egui::Color32::WHITE } else { self.theme.text })
+                )
+                .fill(if is_selected { self.theme.accent_on } else { self.theme.panel })
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(30.0, 18.0));
+                if App::add_setting(ui, "network_concurrency", btn).clicked() {
+                    self.settings.network_concurrency = *limit;
+                }
+            }
+        });
+    }
+
+    /// SETTINGS > PROCESSES: OpenCode checks, restart and output commands
+    fn render_settings_processes(&mut self, ui: &mut egui::Ui) {
+        let text_color = self.theme.text;
+        ui.label(
+            egui::RichText::new("// PROCESSES")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        App::render_setting_checkbox(ui, "check_opencode", &mut self.settings.check_opencode, "OpenCode", text_color);
+        App::render_setting_checkbox(ui, "check_crashes", &mut self.settings.check_crashes, "Crash reports", text_color);
+        App::render_setting_checkbox(ui, "check_leaks", &mut self.settings.check_leaks, "Handle/temp leaks", text_color);
+        App::render_setting_checkbox(ui, "check_ipc", &mut self.settings.check_ipc, "Editor IPC", text_color);
+        App::render_setting_checkbox(ui, "check_editors", &mut self.settings.check_editors, "Editor plugins", text_color);
+        App::render_setting_checkbox(ui, "check_terminals", &mut self.settings.check_terminals, "Terminals", text_color);
+        App::render_setting_checkbox(ui, "check_smoke", &mut self.settings.check_smoke, "Smoke test: opencode run \"say hi\" (spends tokens)", text_color);
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("Restart cmd")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+            ui.add_space(10.0);
+            App::add_setting(
+                ui,
+                "restart_command",
+                egui::TextEdit::singleline(&mut self.settings.restart_command)
+                    .font(egui::FontId::monospace(9.0))
+                    .hint_text(diagnostics::actions::DEFAULT_RESTART_COMMAND)
+                    .desired_width(180.0),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("Output cmd ")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+            ui.add_space(10.0);
+            App::add_setting(
+                ui,
+                "tail_command",
+                egui::TextEdit::singleline(&mut self.settings.tail_command)
+                    .font(egui::FontId::monospace(9.0))
+                    .hint_text(DEFAULT_TAIL_COMMAND)
+                    .desired_width(180.0),
+            );
+        });
+    }
+
+    /// SETTINGS > RUN TAGS: values set by hand, detected ones as hints
+    fn render_settings_tags(&mut self, ui: &mut egui::Ui) {
+        ui.label(
+            egui::RichText::new("// RUN TAGS (EMPTY = DETECTED)")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        let detected = self.report.snapshot().tags.clone();
+        let tags = &mut self.settings.tags;
+        for (label, value, hint) in [
+            ("Project", &mut tags.project, &detected.project),
+            ("Model  ", &mut tags.model, &detected.model),
+            ("Network", &mut tags.network, &detected.network),
+        ] {
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(label)
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(self.theme.text_dim),
+                );
+                ui.add_space(10.0);
+                App::add_setting(
+                    ui,
+                    "tags",
+                    egui::TextEdit::singleline(value)
+                        .font(egui::FontId::monospace(9.0))
+                        .hint_text(if hint.is_empty() { "detected on each run" } else { hint.as_str() })
+                        .desired_width(180.0),
+                );
+            });
+        }
+    }
+
+    /// SETTINGS > NETWORK PROFILES: check toggles per network
+    fn render_settings_network_profiles(&mut self, ui: &mut egui::Ui) {
+        let text_color = self.theme.text;
+        ui.label(
+            egui::RichText::new("// NETWORK PROFILES (CHECKS ON + / OFF - PER NETWORK)")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        let network = self.net_change.network();
+        let current = match (&network, self.settings.active_network_profile.as_str()) {
+            (None, _) => "Network: not read yet".to_string(),
+            (Some(network), "") => format!("Network: {}", network),
+            (Some(network), profile) => format!("Network: {} :: profile {}", network, profile),
+        };
+        ui.label(
+            egui::RichText::new(current)
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(text_color),
+        );
+        let mut profiles_changed = false;
+        let mut remove = None;
+        for (i, profile) in self.settings.network_profiles.iter_mut().enumerate() {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                for (value, hint, width) in [(&mut profile.name, "name", 70.0), (&mut profile.network, "Wi-Fi or gw 10.0.0.1", 120.0)] {
+                    profiles_changed |= App::add_setting(
+                        ui,
+                        "network_profiles",
+                        egui::TextEdit::singleline(value)
+                            .font(egui::FontId::monospace(9.0))
+                            .hint_text(hint)
+                            .desired_width(width),
+                    ).changed();
+                }
+                let del_btn = egui::Button::new(
+                    egui::RichText::new("DEL")
+                        .size(8.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(self.theme.text)
+                )
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0);
+                if App::add_setting(ui, "network_profiles", del_btn).clicked() {
+                    remove = Some(i);
+                }
+            });
+            ui.horizontal_wrapped(|ui| {
+                for id in diagnostics::ids::ALL {
+                    let (label, fill, color) = match profile.toggle(id) {
+                        Some(true) => (format!("+{}", diagnostics::ids::name(id)), self.theme.accent_on, egui::Color32::WHITE),
+                        Some(false) => (format!("-{}", diagnostics::ids::name(id)), self.theme.accent_off, self.theme.text),
+                        None => (diagnostics::ids::name(id), self.theme.panel, self.theme.text_dim),
+                    };
+                    let btn = egui::Button::new(
+                        egui::RichText::new(label)
+                            .size(8.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(color)
+                    )
+                    .fill(fill)
+                    .stroke(egui::Stroke::new(1.0, self.theme.border))
+                    .rounding(0.0);
+                    if App::add_setting(ui, "network_profiles", btn).on_hover_text("Left alone -> on -> off").clicked() {
+                        profile.cycle(id);
+                        profiles_changed = true;
+                    }
+                }
+            });
+        }
+        if let Some(i) = remove {
+            self.settings.network_profiles.remove(i);
+            profiles_changed = true;
+        }
+        ui.add_space(4.0);
+        let add_btn = egui::Button::new(
+            egui::RichText::new("ADD FOR THIS NETWORK")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text)
+        )
+        .fill(self.theme.panel)
+        .stroke(egui::Stroke::new(1.0, self.theme.border))
+        .rounding(0.0)
+        .min_size(egui::vec2(140.0, 18.0));
+        if App::add_setting(ui, "network_profiles", add_btn).clicked() {
+            self.settings.network_profiles.push(NetworkProfile {
+                network: network.unwrap_or_default(),
+                ..NetworkProfile::default()
+            });
+            profiles_changed = true;
+        }
+        // Undo the profile in effect; the next frame applies the edited one
+        if profiles_changed {
+            self.settings.leave_network_profile();
+        }
+    }
+
+    /// SETTINGS > AUTO-REFRESH: schedule, battery saver, traffic cap, fresh checks
+    fn render_settings_auto_refresh(&mut self, ui: &mut egui::Ui) {
+        let text_color = self.theme.text;
+        ui.label(
+            egui::RichText::new("// AUTO-REFRESH")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+
+        // Enable/disable checkbox
+        App::render_setting_checkbox(ui, "auto_refresh", &mut self.settings.auto_refresh, "Enabled", text_color);
+        App::render_setting_checkbox(ui, "run_on_network_change", &mut self.settings.run_on_network_change, "Run on network change", text_color);
+        App::render_setting_checkbox(ui, "readiness_enabled", &mut self.settings.readiness_enabled, "Morning readiness", text_color);
+        if self.settings.readiness_enabled {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.add_space(22.0); // Align with checkboxes
+                ui.label(
+                    egui::RichText::new("Daily at:")
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(self.theme.text_dim),
+                );
+                ui.add_space(5.0);
+                App::add_setting(
+                    ui,
+                    "readiness_time",
+                    egui::TextEdit::singleline(&mut self.settings.readiness_time)
+                        .font(egui::FontId::monospace(9.0))
+                        .hint_text(readiness::DEFAULT_TIME)
+                        .desired_width(40.0),
+                );
+                let note = if readiness::parse_time(&self.settings.readiness_time).is_some() {
+                    ("notifies only when something is wrong", self.theme.text_dim)
+                } else {
+                    ("not HH:MM", self.status_color(CheckStatus::Error))
+                };
+                ui.label(
+                    egui::RichText::new(note.0)
+                        .size(8.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(note.1),
+                );
+            });
+        }
+
+        // Interval selector (only show if enabled)
+        if self.settings.auto_refresh {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.add_space(22.0); // Align with checkboxes
+                ui.label(
+                    egui::RichText::new("Interval:")
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(self.theme.text_dim),
+                );
+                ui.add_space(5.0);
+
+                // Preset buttons
+                for (i, (_, label)) in REFRESH_PRESETS.iter().enumerate() {
+                    let is_selected = self.settings.current_preset_index() == i;
+                    let btn = egui::Button::new(
+                        egui::RichText::new(*label)
+                            .size(9.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(if is_selected { 
+                                egui::Color32::WHITE 
+                            } else { 
+                                self.theme.text 
+                            })
+                    )
+                    .fill(if is_selected { 
+                        self.theme.accent_on 
+                    } else { 
+                        self.theme.panel 
+                    })
+                    .stroke(egui::Stroke::new(1.0, self.theme.border))
+                    .rounding(0.0)
+                    .min_size(egui::vec2(30.0, 18.0));
+
+                    if App::add_setting(ui, "refresh_interval_secs", btn).clicked() {
+                        self.settings.set_preset(i);
+                    }
+                }
+            });
+        }
+
+        // Battery saver
+        ui.add_space(4.0);
+        App::render_setting_checkbox(ui, "battery_saver", &mut self.settings.battery_saver, "Battery saver", text_color);
+        if self.settings.battery_saver {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.add_space(22.0); // Align with checkboxes
+                ui.label(
+                    egui::RichText::new("Below:")
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(self.theme.text_dim),
+                );
+                ui.add_space(5.0);
+                for (percent, label) in BATTERY_PRESETS {
+                    let is_selected = self.settings.battery_threshold == *percent;
+                    let btn = egui::Button::new(
+                        egui::RichText::new(*label)
+                            .size(9.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(if is_selected { egui::Color32::WHITE } else { self.theme.text })
+                    )
+                    .fill(if is_selected { self.theme.accent_on } else { self.theme.panel })
+                    .stroke(egui::Stroke::new(1.0, self.theme.border))
+                    .rounding(0.0)
+                    .min_size(egui::vec2(30.0, 18.0));
+                    if App::add_setting(ui, "battery_threshold", btn).clicked() {
+                        self.settings.battery_threshold = *percent;
+                    }
+                }
+            });
+        }
+
+        // Monthly traffic cap and today's usage per check
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("Traffic cap:")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+            ui.add_space(5.0);
+            for (cap, label) in CAP_PRESETS {
+                let is_selected = self.settings.monthly_cap_mb == *cap;
+                let btn = egui::Button::new(
+                    egui::RichText::new(*label)
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(if is_selected { egui::Color32::WHITE } else { self.theme.text })
+                )
+                .fill(if is_selected { self.theme.accent_on } else { self.theme.panel })
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(30.0, 18.0));
+                if App::add_setting(ui, "monthly_cap_mb", btn).clicked() {
+                    self.settings.monthly_cap_mb = *cap;
+                }
+            }
+        });
+        let usage = diagnostics::traffic::usage();
+        ui.label(
+            egui::RichText::new(format!(
+                "TODAY {} :: MONTH {}",
+                format_bytes(usage.today_bytes()),
+                format_bytes(usage.month_bytes)
+            ))
+            .size(9.0)
+            .family(egui::FontFamily::Monospace)
+            .color(text_color),
+        );
+        for (check, bytes) in &usage.today {
+            ui.label(
+                egui::RichText::new(format!("  {:<16} {}", check, format_bytes(*bytes)))
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+        }
+
+        // Reuse recent results on RUN DIAGNOSTICS
+        ui.add_space(4.0);
+        App::render_setting_checkbox(ui, "skip_fresh", &mut self.settings.skip_fresh, "Skip fresh checks", text_color);
+        if self.settings.skip_fresh {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.add_space(22.0); // Align with checkboxes
+                ui.label(
+                    egui::RichText::new("Newer than:")
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(self.theme.text_dim),
+                );
+                ui.add_space(5.0);
+                for (secs, label) in FRESH_TTL_PRESETS {
+                    let is_selected = self.settings.fresh_ttl_secs == *secs;
+                    let btn = egui::Button::new(
+                        egui::RichText::new(*label)
+                            .size(9.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(if is_selected { egui::Color32::WHITE } else { self.theme.text })
+                    )
+                    .fill(if is_selected { self.theme.accent_on } else { self.theme.panel })
+                    .stroke(egui::Stroke::new(1.0, self.theme.border))
+                    .rounding(0.0)
+                    .min_size(egui::vec2(30.0, 18.0));
+                    if App::add_setting(ui, "fresh_ttl_secs", btn).clicked() {
+                        self.settings.fresh_ttl_secs = *secs;
+                    }
+                }
+            });
+        }
+    }
+
+    /// SETTINGS > HYSTERESIS: runs in a row per enabled check
+    fn render_settings_hysteresis(&mut self, ui: &mut egui::Ui) {
+        let text_color = self.theme.text;
+        ui.label(
+            egui::RichText::new("// HYSTERESIS (RUNS IN A ROW BEFORE A STATUS CHANGES)")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        let enabled: Vec<&'static str> = diagnostics::ids::ALL
+            .iter()
+            .copied()
+            .filter(|id| self.settings.is_check_enabled(id))
+            .collect();
+        for (id, name) in self.check_names(&enabled) {
+            ui.horizontal(|ui| {
+                ui.add_sized(
+                    [120.0, 18.0],
+                    egui::Label::new(
+                        egui::RichText::new(name)
+                            .size(9.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(text_color),
+                    )
+                    .truncate(),
+                );
+                let current = self.settings.hysteresis_for(id);
+                for runs in HYSTERESIS_PRESETS {
+                    let is_selected = current == *runs;
+                    let btn = egui::Button::new(
+                        egui::RichText::new(format!("{}", runs))
+                            .size(9.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(if is_selected { egui::Color32::WHITE } else { self.theme.text })
+                    )
+                    .fill(if is_selected { self.theme.accent_on } else { self.theme.panel })
+                    .stroke(egui::Stroke::new(1.0, self.theme.border))
+                    .rounding(0.0)
+                    .min_size(egui::vec2(22.0, 18.0));
+                    if App::add_setting(ui, "hysteresis", btn).clicked() {
+                        self.settings.set_hysteresis(id, *runs);
+                    }
+                }
+            });
+        }
+    }
+
+    /// SETTINGS > SCALE
+    fn render_settings_scale(&mut self, ui: &mut egui::Ui) {
+        ui.label(
+            egui::RichText::new("// SCALE")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+
+        ui.horizontal(|ui| {
+            // Current scale display
+            ui.label(
+                egui::RichText::new(self.settings.format_scale())
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+            ui.add_space(10.0);
+
+            // Preset buttons
+            for (i, (_, label)) in SCALE_PRESETS.iter().enumerate() {
+                let is_selected = self.settings.current_scale_index() == Some(i);
+                let btn = egui::Button::new(
+                    egui::RichText::new(*label)
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(if is_selected { 
+                            egui::Color32::WHITE 
+                        } else { 
+                            self.theme.text 
+                        })
+                )
+                .fill(if is_selected { 
+                    self.theme.accent_on 
+                } else { 
+                    self.theme.panel 
+                })
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(40.0, 18.0));
+
+                if App::add_setting(ui, "ui_scale", btn).clicked() {
+                    self.settings.set_scale_preset(i);
+                }
+            }
+        });
+
+        ui.add_space(3.0);
+        ui.label(
+            egui::RichText::new("Ctrl+Scroll to adjust")
+                .size(8.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+    }
+
+    /// SETTINGS > STATUS COLORS: presets and per-status overrides
+    fn render_settings_status_colors(&mut self, ui: &mut egui::Ui) {
+        let text_color = self.theme.text;
+        ui.label(
+            egui::RichText::new("// STATUS COLORS")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        let mut colors_changed = false;
+        ui.horizontal(|ui| {
+            let current = self.settings.status_colors.preset_name();
+            for (name, colors) in palette::PRESETS {
+                let is_selected = current == Some(*name);
+                let btn = egui::Button::new(
+                    egui::RichText::new(*name)
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(if is_selected { egui::Color32::WHITE } else { self.theme.text })
+                )
+                .fill(if is_selected { self.theme.accent_on } else { self.theme.panel })
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(40.0, 18.0));
+                if App::add_setting(ui, "status_colors", btn).clicked() {
+                    self.settings.status_colors = palette::StatusColors::preset(*colors);
+                    colors_changed = true;
+                }
+            }
+        });
+        ui.add_space(4.0);
+        let theme = self.theme;
+        let colors = &mut self.settings.status_colors;
+        for (status, value) in [
+            (CheckStatus::Ok, &mut colors.ok),
+            (CheckStatus::Warning, &mut colors.warning),
+            (CheckStatus::Error, &mut colors.error),
+        ] {
+            ui.horizontal(|ui| {
+                let (swatch, _) = ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+                ui.painter().rect_filled(swatch, 0.0, theme.status_color(status));
+                ui.add_space(4.0);
+                ui.add_sized(
+                    [60.0, 14.0],
+                    egui::Label::new(
+                        egui::RichText::new(status.label())
+                            .size(9.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(text_color),
+                    ),
+                );
+                colors_changed |= App::add_setting(
+                    ui,
+                    "status_colors",
+                    egui::TextEdit::singleline(value)
+                        .font(egui::FontId::monospace(9.0))
+                        .hint_text("#rrggbb (theme)")
+                        .desired_width(80.0),
+                ).changed();
+                if !value.trim().is_empty() && palette::parse_hex(value).is_none() {
+                    ui.label(
+                        egui::RichText::new("not #rrggbb")
+                            .size(8.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(theme.status_color(CheckStatus::Error)),
+                    );
+                }
+            });
+        }
+        if colors_changed {
+            self.rebuild_theme();
+        }
+        ui.add_space(3.0);
+        ui.label(
+            egui::RichText::new("Cards, badges, charts and the status page")
+                .size(8.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+    }
+
+    /// SETTINGS > STATUS PAGE: server, LAN, discovery, REST token
+    fn render_settings_status_page(&mut self, ui: &mut egui::Ui) {
+        let text_color = self.theme.text;
+        ui.label(
+            egui::RichText::new("// STATUS PAGE")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        App::render_setting_checkbox(ui, "status_page_enabled", &mut self.settings.status_page_enabled, "Enabled", text_color);
+        App::render_setting_checkbox(ui, "status_page_lan", &mut self.settings.status_page_lan, "Visible on LAN", text_color);
+        App::render_setting_checkbox(ui, "discover_agents", &mut self.settings.discover_agents, "Discover agents", text_color);
+
+        // Address or bind error (applied when the popup closes)
+        let status_line = if let Some(ref err) = self.status_server_error {
+            err.clone()
+        } else if let Some(ref server) = self.status_server {
+            server.url()
+        } else {
+            format!("port {} :: applies on close", self.settings.status_page_port)
+        };
+        ui.label(
+            egui::RichText::new(status_line)
+                .size(8.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+
+        // REST API token (Authorization: Bearer <token>)
+        if self.status_server.is_some() {
+            ui.add_space(4.0);
+            let token_btn = egui::Button::new(
+                egui::RichText::new("COPY API TOKEN")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text)
+            )
+            .fill(self.theme.panel)
+            .stroke(egui::Stroke::new(1.0, self.theme.border))
+            .rounding(0.0)
+            .min_size(egui::vec2(100.0, 18.0));
+
+            if ui.add(token_btn).on_hover_text("/api/report, /api/history").clicked() {
+                if let Ok(mut clipboard) = Clipboard::new() {
+                    let _ = clipboard.set_text(&self.settings.api_token);
+                }
+            }
+        }
+    }
+
+    /// SETTINGS > HISTORY UPLOAD
+    fn render_settings_upload(&mut self, ui: &mut egui::Ui) {
+        let text_color = self.theme.text;
+        ui.label(
+            egui::RichText::new("// HISTORY UPLOAD (YOUR OWN ENDPOINT)")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        App::render_setting_checkbox(ui, "upload_enabled", &mut self.settings.upload_enabled, "Enabled", text_color);
+        for (label, key, value, hint, password) in [
+            ("URL ", "upload_url", &mut self.settings.upload_url, "https://... or presigned S3 URL", false),
+            ("Auth", "upload_auth", &mut self.settings.upload_auth, "Authorization header (optional)", true),
+        ] {
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(label)
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(self.theme.text_dim),
+                );
+                ui.add_space(10.0);
+                App::add_setting(
+                    ui,
+                    key,
+                    egui::TextEdit::singleline(value)
+                        .font(egui::FontId::monospace(9.0))
+                        .hint_text(hint)
+                        .password(password)
+                        .desired_width(200.0),
+                );
+            });
+        }
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("Every")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+            ui.add_space(5.0);
+            for (mins, label) in UPLOAD_INTERVAL_PRESETS {
+                let is_selected = self.settings.upload_interval_mins == *mins;
+                let btn = egui::Button::new(
+                    egui::RichText::new(*label)
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(if is_selected { egui::Color32::WHITE } else { self.theme.text })
+                )
+                .fill(if is_selected { self.theme.accent_on } else { self.theme.panel })
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(30.0, 18.0));
+                if App::add_setting(ui, "upload_interval_mins", btn).clicked() {
+                    self.settings.upload_interval_mins = *mins;
+                }
+            }
+        });
+        let upload_status = upload::status().unwrap_or_else(|| "Runs since the last upload, as JSON, after a run".to_string());
+        ui.add(egui::Label::new(
+            egui::RichText::new(upload_status)
+                .size(8.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        ).wrap());
+    }
+
+    /// SETTINGS > SIGNED REPORTS
+    fn render_settings_signing(&mut self, ui: &mut egui::Ui) {
+        let text_color = self.theme.text;
+        ui.label(
+            egui::RichText::new("// SIGNED REPORTS")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        App::render_setting_checkbox(ui, "sign_reports", &mut self.settings.sign_reports, "Sign copied reports", text_color);
+        if self.settings.sign_reports {
+            ui.add_space(4.0);
+            let key_btn = egui::Button::new(
+                egui::RichText::new("COPY PUBLIC KEY")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text)
+            )
+            .fill(self.theme.panel)
+            .stroke(egui::Stroke::new(1.0, self.theme.border))
+            .rounding(0.0)
+            .min_size(egui::vec2(100.0, 18.0));
+
+            if ui.add(key_btn).on_hover_text("For whoever verifies the reports (--verify FILE)").clicked() {
+                self.status = match signing::public_key() {
+                    Ok(key) => match Clipboard::new().and_then(|mut c| c.set_text(key)) {
+                        Ok(()) => "SYS.STATUS: PUBLIC KEY COPIED".to_string(),
+                        Err(_) => "SYS.STATUS: CLIPBOARD UNAVAILABLE".to_string(),
+                    },
+                    Err(e) => format!("SYS.STATUS: {}", e.to_uppercase()),
+                };
+            }
+        }
+    }
+
+    /// SETTINGS > FAILURE INJECTION
+    fn render_settings_injection(&mut self, ui: &mut egui::Ui) {
+        ui.label(
+            egui::RichText::new("// FAILURE INJECTION (ADVANCED)")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        let inject_btn = egui::Button::new(
+            egui::RichText::new("SIMULATE PROVIDER OUTAGE")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text)
+        )
+        .fill(self.theme.panel)
+        .stroke(egui::Stroke::new(1.0, self.theme.border))
+        .rounding(0.0)
+        .min_size(egui::vec2(160.0, 18.0));
+        if ui.add(inject_btn).on_hover_text("Block a provider for a few minutes to test OpenCode's fallback providers").clicked() {
+            self.open_inject();
+        }
+    }
+
+    /// SETTINGS > TRAFFIC CAPTURE
+    fn render_settings_capture(&mut self, ui: &mut egui::Ui) {
+        ui.label(
+            egui::RichText::new("// TRAFFIC CAPTURE (LOCAL PROXY)")
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text_dim),
+        );
+        ui.add_space(5.0);
+        let capture_btn = egui::Button::new(
+            egui::RichText::new(if self.capture.is_some() { "OPEN CAPTURE" } else { "START CAPTURE" })
+                .size(9.0)
+                .family(egui::FontFamily::Monospace)
+                .color(self.theme.text)
+        )
+        .fill(self.theme.panel)
+        .stroke(egui::Stroke::new(1.0, self.theme.border))
+        .rounding(0.0)
+        .min_size(egui::vec2(160.0, 18.0));
+        if ui.add(capture_btn).on_hover_text("A proxy to point OpenCode at: records hosts, timings and sizes of its traffic, never contents").clicked() {
+            self.open_capture();
+        }
+    }
+
+    /// SETTINGS > ADMINISTRATOR (Windows)
+    fn render_settings_admin(&mut self, ui: &mut egui::Ui) {
+        let text_color = self.theme.text;
+        if cfg!(target_os = "windows") {
+            ui.add_space(8.0);
+            ui.add(egui::Separator::default().spacing(1.0));
+            ui.add_space(8.0);
+
+            ui.label(
+                egui::RichText::new("// ADMINISTRATOR")
+                    .size(9.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text_dim),
+            );
+            ui.add_space(5.0);
+            if diagnostics::elevation::is_elevated() {
+                ui.label(
+                    egui::RichText::new("Running as administrator")
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(text_color),
+                );
+            } else {
+                let admin_btn = egui::Button::new(
+                    egui::RichText::new("RESTART AS ADMINISTRATOR")
+                        .size(9.0)
+                        .family(egui::FontFamily::Monospace)
+                        .color(self.theme.text)
+                )
+                .fill(self.theme.panel)
+                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                .rounding(0.0)
+                .min_size(egui::vec2(160.0, 18.0));
+
+                let hover = "Keeps the current results, error log and settings";
+                if ui.add(admin_btn).on_hover_text(hover).clicked() {
+                    let ctx = ui.ctx().clone();
+                    self.restart_as_admin(&ctx);
+                }
+                if let Some(ref error) = self.admin_error {
+                    ui.label(
+                        egui::RichText::new(error)
+                            .size(9.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(self.status_color(CheckStatus::Error)),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Add a settings widget; disabled, with a hint, when the managed policy locks `key`
+    fn add_setting(ui: &mut egui::Ui, key: &str, widget: impl egui::Widget) -> egui::Response {
+        ui.add_enabled(!policy::is_locked(key), widget).on_disabled_hover_text(POLICY_HINT)
+    }
+
+    /// Styled checkbox for the setting `key`; see `add_setting`
+    fn render_setting_checkbox(ui: &mut egui::Ui, key: &str, value: &mut bool, label: &str, text_color: egui::Color32) {
+        ui.add_enabled_ui(!policy::is_locked(key), |ui| App::render_styled_checkbox(ui, value, label, text_color))
+            .response
+            .on_disabled_hover_text(POLICY_HINT);
+    }
+
+    /// Render a styled checkbox with monospace label (whole row is interactive)
+    fn render_styled_checkbox(ui: &mut egui::Ui, value: &mut bool, label: &str, text_color: egui::Color32) {
+        // Allocate space for the whole row
+        let desired_size = egui::vec2(ui.available_width().min(200.0), 18.0);
+        let (rect, mut response) = ui.allocate_exact_size(desired_size, egui::Sense::click());
+        
+        if response.clicked() {
+            *value = !*value;
+            response.mark_changed();
+        }
+        let (checked, enabled) = (*value, response.enabled());
+        response.widget_info(|| egui::WidgetInfo::selected(egui::WidgetType::Checkbox, enabled, checked, label));
+        
+        // Determine colors based on hover
+        let label_color = if response.hovered() {
+            // Brighter on hover
+            egui::Color32::from_rgba_unmultiplied(text_color.r(), text_color.g(), text_color.b(), 255)
+        } else {
+            text_color
+        };
+        
+        // Draw checkbox manually
+        let checkbox_size = 14.0;
+        let checkbox_rect = egui::Rect::from_min_size(
+            rect.min + egui::vec2(0.0, (rect.height() - checkbox_size) / 2.0),
+            egui::vec2(checkbox_size, checkbox_size),
+        );
+        
+        // Checkbox background
+        let checkbox_fill = if *value {
+            ui.visuals().widgets.active.bg_fill
+        } else {
+            ui.visuals().widgets.inactive.bg_fill
+        };
+        ui.painter().rect_filled(checkbox_rect, 2.0, checkbox_fill);
+        ui.painter().rect_stroke(checkbox_rect, 2.0, egui::Stroke::new(1.0, ui.visuals().widgets.inactive.fg_stroke.color));
+        
+        // Checkmark
+        if *value {
+            let check_color = ui.visuals().widgets.active.fg_stroke.color;
+            let center = checkbox_rect.center();
+            let size = checkbox_size * 0.3;
+            ui.painter().line_segment(
+                [center + egui::vec2(-size, 0.0), center + egui::vec2(-size * 0.3, size * 0.7)],
+                egui::Stroke::new(2.0, check_color),
+            );
+            ui.painter().line_segment(
+                [center + egui::vec2(-size * 0.3, size * 0.7), center + egui::vec2(size, -size * 0.5)],
+                egui::Stroke::new(2.0, check_color),
+            );
+        }
+        
+        // Label
+        let label_pos = rect.min + egui::vec2(checkbox_size + 6.0, (rect.height() - 10.0) / 2.0);
+        ui.painter().text(
+            label_pos,
+            egui::Align2::LEFT_TOP,
+            label,
+            egui::FontId::new(10.0, egui::FontFamily::Monospace),
+            label_color,
+        );
+        
+        // Hover highlight
+        if response.hovered() {
+            ui.painter().rect_stroke(rect, 0.0, egui::Stroke::new(1.0, label_color.gamma_multiply(0.3)));
+        }
+    }
+
+    /// RESTART OPENCODE button and the outcome of the last restart
+    fn render_restart_row(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let busy = self.action.is_some() || *self.is_running.lock().unwrap();
+        ui.horizontal(|ui| {
+            ui.add_space(18.0);
+            let restart_btn = egui::Button::new(
+                egui::RichText::new(if self.action.is_some() { "BUSY..." } else { RESTART_LABEL })
+                    .size(9.0)
+                    .strong()
+                    .family(egui::FontFamily::Monospace)
+                    .color(self.theme.text)
+            )
+            .fill(self.theme.panel)
+            .stroke(egui::Stroke::new(1.0, self.theme.border))
+            .rounding(0.0)
+            .min_size(egui::vec2(120.0, 20.0));
+            let hover = "Stop OpenCode, relaunch it and re-run diagnostics";
+            if ui.add_enabled(!busy, restart_btn).on_hover_text(hover).clicked() {
+                let command = self.settings.restart_command.clone();
+                self.spawn_action(ctx, RESTART_LABEL, move || diagnostics::actions::restart_opencode(&command));
+            }
+            if let Some((RESTART_LABEL, ref result)) = self.action_status {
+                let (text, color) = match result {
+                    Ok(summary) => (summary.as_str(), self.theme.text_dim),
+                    Err(e) => (e.as_str(), self.status_color(CheckStatus::Error)),
+                };
+                ui.add(
+                    egui::Label::new(
+                        egui::RichText::new(text)
+                            .size(9.0)
+                            .family(egui::FontFamily::Monospace)
+                            .color(color),
+                    )
+                    .wrap(),
+                );
+            }
+        });
+        ui.add_space(4.0);
+    }
+
+    fn render_check_card(&mut self, ui: &mut egui::Ui, check: &CheckResult) {
+        self.render_check_card_with(ui, check, |_, _| {});
+    }
+
+    /// API card whose latency opens the chart of its recorded latencies
+    fn render_api_card(&mut self, ui: &mut egui::Ui, id: &'static str, check: &CheckResult) {
+        let clicked = self.render_check_card_with(ui, check, |app, ui| {
+            let Some(ms) = check.latency_ms else {
+                return false;
+            };
+            let link = egui::Label::new(
+                egui::RichText::new(format!("LATENCY {}ms >", ms))
+                    .size(8.0)
+                    .family(egui::FontFamily::Monospace)
+                    .color(app.theme.accent_on),
+            )
+            .sense(egui::Sense::click());
+            ui.add(link)
+                .on_hover_cursor(egui::CursorIcon::PointingHand)
+                .on_hover_text("Recorded latencies and outages")
+                .clicked()
+        });
+        if clicked {
+            self.open_latency_chart(id);
+        }
+    }
+
+    /// Pulse of a card that changed in the latest run: two beats fading out (0 = none)
+    fn change_pulse(&self, name: &str) -> f32 {
+        match self.changed_cards {
+            Some((at, ref names)) if self.viewing.is_none() && names.iter().any(|n| n == name) => {
+                let t = at.elapsed().as_secs_f32() / CHANGE_HIGHLIGHT.as_secs_f32();
+                (1.0 - t).max(0.0) * (0.5 - 0.5 * (t * 4.0 * std::f32::consts::PI).cos())
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Check card with extra content under the details (returns what `extra` returned)
+    fn render_check_card_with<R: Default>(&mut self, ui: &mut egui::Ui, check: &CheckResult, extra: impl FnOnce(&Self, &mut egui::Ui) -> R) -> R {
+        let status_color = self.status_color(check.status);
+        let mut result = R::default();
+        let pulse = self.change_pulse(&check.name);
+        let id = diagnostics::ids::ALL.iter().copied().find(|id| diagnostics::ids::name(id) == check.name);
+        let mut mute = None;
+        
+        egui::Frame::none()
+            .fill(self.theme.panel.lerp_to_gamma(status_color, 0.3 * pulse))
+            .stroke(egui::Stroke::new(1.0, status_color.gamma_multiply(pulse)))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    // Left accent bar
+                    let accent_color = if ui.rect_contains_pointer(ui.max_rect()) {
+                        self.theme.accent_on
+                    } else {
+                        status_color
+                    };
+                    
+                    let (rect, _) = ui.allocate_exact_size(
+                        egui::vec2(3.0, 50.0),
+                        egui::Sense::hover(),
+                    );
+                    ui.painter().rect_filled(rect, 0.0, accent_color);
+                    
+                    ui.add_space(15.0);
+                    
+                    // Calculate available width for text (leave space for badge)
+                    let badge_width = 70.0; // 55px button + 15px spacing
+                    let available_width = ui.available_width() - badge_width - 20.0;
+                    
+                    // Content - constrained width
+                    ui.vertical(|ui| {
+                        ui.set_max_width(available_width.max(100.0));
+                        ui.add_space(8.0);
+                        
+                        ui.label(
+                            egui::RichText::new(&check.name)
+                                .size(12.0)
+                                .strong()
+                                .color(self.theme.text),
+                        );
+                        
+                        // Details with text wrapping
+                        ui.add(
+                            egui::Label::new(
+                                egui::RichText::new(&check.details)
+                                    .size(9.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.theme.text_dim)
+                            ).wrap()
+                        );
+
+                        result = extra(self, ui);
+                        
+                        // Freshness
+                        if let Some(age) = check.age_secs() {
+                            ui.label(
+                                egui::RichText::new(format!("checked {}", format_age(age)))
+                                    .size(8.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.theme.text_dim),
+                            );
+                        }
+                        
+                        ui.add_space(8.0);
+                    });
+                    
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.add_space(15.0);
+                        
+                        // Status badge, opening the quick mute menu
+                        let badge = egui::Button::new(
+                            egui::RichText::new(check.status.label())
+                                .size(10.0)
+                                .strong()
+                                .family(egui::FontFamily::Monospace)
+                                .color(self.badge_text_color(check.status))
+                        )
+                        .fill(status_color)
+                        .stroke(egui::Stroke::NONE)
+                        .rounding(0.0)
+                        .min_size(egui::vec2(55.0, 24.0));
+                        match id.filter(|_| self.viewing.is_none()) {
+                            Some(id) => {
+                                let menu = egui::menu::menu_custom_button(ui, badge, |ui| {
+                                    let item = |ui: &mut egui::Ui, text: &str, locked: bool| {
+                                        let button = egui::Button::new(egui::RichText::new(text).size(9.0).family(egui::FontFamily::Monospace));
+                                        ui.add_enabled(!locked, button).on_disabled_hover_text(POLICY_HINT).clicked()
+                                    };
+                                    let now = chrono::Local::now().naive_local();
+                                    let snooze_locked = policy::is_locked("snoozed");
+                                    if let Some(until) = self.settings.snoozed_until(id, now) {
+                                        if item(ui, &format!("WAKE NOW (SNOOZED UNTIL {})", until.format("%H:%M")), snooze_locked) {
+                                            mute = Some(CardMute::Wake);
+                                        }
+                                    } else if item(ui, "SNOOZE FOR 1 HOUR", snooze_locked) {
+                                        mute = Some(CardMute::Snooze);
+                                    }
+                                    if item(ui, "DON'T CHECK THIS ANYMORE", self.settings.check_locked_on(id)) {
+                                        mute = Some(CardMute::Disable);
+                                    }
+                                    if mute.is_some() {
+                                        ui.close_menu();
+                                    }
+                                });
+                                a11y::describe(&menu.response, egui::WidgetType::Button, &format!("{}: {}. {}. Snooze or turn off", check.name, a11y::status_word(check.status), check.details));
+                                menu.response.on_hover_text("Snooze or turn off this check");
+                            }
+                            None => {
+                                let badge = ui.add(badge);
+                                a11y::describe(&badge, egui::WidgetType::Label, &format!("{}: {}. {}", check.name, a11y::status_word(check.status), check.details));
+                            }
+                        }
+                    });
+                });
+            });
+        
+        ui.add_space(5.0);
+        if let (Some(id), Some(mute)) = (id, mute) {
+            self.mute_check(ui.ctx(), id, mute);
+        }
+        result
+    }
+
+    /// Apply a quick mute from a card, saved like a change in SETTINGS
+    fn mute_check(&mut self, ctx: &egui::Context, id: &'static str, mute: CardMute) {
+        let name = diagnostics::ids::name(id);
+        let now = chrono::Local::now().naive_local();
+        match mute {
+            CardMute::Disable => {
+                self.settings.set_check_enabled(id, false);
+                self.status = format!("SYS.STATUS: {} TURNED OFF (SETTINGS TO TURN IT BACK ON)", name);
+            }
+            CardMute::Snooze => {
+                self.settings.snooze(id, SNOOZE_MINUTES, now);
+                self.status = format!("SYS.STATUS: {} SNOOZED FOR 1 HOUR", name);
+            }
+            CardMute::Wake => {
+                self.settings.unsnooze(id);
+                self.status = format!("SYS.STATUS: {} AWAKE", name);
+            }
+        }
+        // A policy lock wins over the card
+        if self.settings.enforce_policy() {
+            self.status = format!("SYS.STATUS: {} IS LOCKED BY YOUR ORGANIZATION", name);
+        }
+        if let Err(e) = self.settings.save() {
+            self.status = format!("SYS.STATUS: {}", e.to_uppercase());
+        }
+        // Snoozed and woken cards update right away
+        if !matches!(mute, CardMute::Disable) {
+            self.rerun_check(ctx, id);
+        }
+    }
+
+    fn render_placeholder_card(&self, ui: &mut egui::Ui, name: &str, details: &str) {
+        egui::Frame::none()
+            .fill(self.theme.panel)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    // Left accent bar (dim)
+                    let (rect, _) = ui.allocate_exact_size(
+                        egui::vec2(3.0, 50.0),
+                        egui::Sense::hover(),
+                    );
+                    ui.painter().rect_filled(rect, 0.0, self.theme.text_dim);
+                    
+                    ui.add_space(15.0);
+                    
+                    // Calculate available width for text
+                    let badge_width = 70.0;
+                    let available_width = ui.available_width() - badge_width - 20.0;
+                    
+                    // Content
+                    ui.vertical(|ui| {
+                        ui.set_max_width(available_width.max(100.0));
+                        ui.add_space(8.0);
+                        
+                        ui.label(
+                            egui::RichText::new(name)
+                                .size(12.0)
+                                .strong()
+                                .color(self.theme.text_dim),
+                        );
+                        
+                        ui.add(
+                            egui::Label::new(
+                                egui::RichText::new(details)
+                                    .size(9.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.theme.text_dim)
+                            ).wrap()
+                        );
+                        
+                        ui.add_space(8.0);
+                    });
+                    
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.add_space(15.0);
+                        
+                        // Status badge placeholder
+                        let badge = ui.add(
+                            egui::Button::new(
+                                egui::RichText::new("...")
+                                    .size(10.0)
+                                    .family(egui::FontFamily::Monospace)
+                                    .color(self.theme.text_dim)
+                            )
+                            .fill(self.theme.accent_off)
+                            .stroke(egui::Stroke::NONE)
+                            .rounding(0.0)
+                            .min_size(egui::vec2(55.0, 24.0))
+                        );
+                        a11y::describe(&badge, egui::WidgetType::Label, &format!("{}: not checked yet", name));
+                    });
+                });
+            });
+        
+        ui.add_space(5.0);
+    }
+}
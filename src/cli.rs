@@ -0,0 +1,124 @@
+//! Command line flags
+//!
+//! Parsed once at startup with clap, which also provides `--help`,
+//! `--version`, shell completions (`--completions bash|zsh|powershell|...`)
+//! and a man page (`--man`) so scripts and support docs can discover them.
+
+use clap::{value_parser, Arg, ArgAction, Command};
+use clap_complete::Shell;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+pub const SELF_TEST_ARG: &str = "--self-test";
+pub const SIMULATE_ARG: &str = "--simulate";
+pub const SEED_ARG: &str = "--seed";
+pub const VIEW_SESSION_ARG: &str = "--view-session";
+pub const RUN_AND_COPY_ARG: &str = "--run-and-copy";
+pub const DIGEST_ARG: &str = "--digest";
+pub const VERIFY_ARG: &str = "--verify";
+pub const CONFIG_ARG: &str = "--config";
+/// Passed to the elevated instance with the path of the saved state
+pub const RESTORE_STATE_ARG: &str = "--restore-state";
+const COMPLETIONS_ARG: &str = "--completions";
+const MAN_ARG: &str = "--man";
+
+/// Parsed flags
+pub struct Args {
+    pub self_test: bool,
+    pub simulate: bool,
+    pub seed: Option<u64>,
+    pub view_session: Option<PathBuf>,
+    pub run_and_copy: bool,
+    pub digest: bool,
+    pub verify: Option<PathBuf>,
+    pub restore_state: Option<PathBuf>,
+    pub config: Option<PathBuf>,
+}
+
+/// "--self-test" -> "self-test"
+fn long(flag: &'static str) -> &'static str {
+    flag.trim_start_matches('-')
+}
+
+fn flag(name: &'static str, help: &'static str) -> Arg {
+    Arg::new(long(name)).long(long(name)).action(ArgAction::SetTrue).help(help)
+}
+
+fn path(name: &'static str, value: &'static str, help: &'static str) -> Arg {
+    Arg::new(long(name)).long(long(name)).value_name(value).value_parser(value_parser!(PathBuf)).help(help)
+}
+
+/// Full command definition (also the source of completions and the man page)
+pub fn command() -> Command {
+    let command = Command::new(env!("CARGO_PKG_NAME"))
+        .version(env!("CARGO_PKG_VERSION"))
+        .about(env!("CARGO_PKG_DESCRIPTION"))
+        .arg(flag(SELF_TEST_ARG, "Verify the tool itself against mock endpoints and print a pass/fail matrix"))
+        .arg(flag(SIMULATE_ARG, "Show generated results instead of running real checks (not recorded in history)"))
+        .arg(
+            Arg::new(long(SEED_ARG))
+                .long(long(SEED_ARG))
+                .value_name("N")
+                .value_parser(value_parser!(u64))
+                .requires(long(SIMULATE_ARG))
+                .help("Make every simulated run identical, timestamps included"),
+        )
+        .arg(
+            Arg::new(long(COMPLETIONS_ARG))
+                .long(long(COMPLETIONS_ARG))
+                .value_name("SHELL")
+                .value_parser(value_parser!(Shell))
+                .help("Print a completion script for the shell and exit"),
+        )
+        .arg(flag(DIGEST_ARG, "Print the weekly digest of the run history and exit"))
+        .arg(path(VERIFY_ARG, "FILE", "Check the signature of a signed report (\"-\" reads stdin) and exit"))
+        .arg(path(CONFIG_ARG, "FILE", "Use this settings file instead of the default one (created when missing)"))
+        .arg(flag(MAN_ARG, "Print the man page (roff) and exit"));
+
+    // Window-only flags
+    #[cfg(feature = "gui")]
+    let command = command
+        .arg(path(VIEW_SESSION_ARG, "FILE", "Open an exported .ocdsession file read-only (asks for its access code)"))
+        .arg(flag(RUN_AND_COPY_ARG, "Run diagnostics at startup and copy the report to the clipboard"))
+        .arg(path(RESTORE_STATE_ARG, "FILE", "State carried over by \"Restart as administrator\"").hide(true));
+    command
+}
+
+static ARGS: OnceLock<Args> = OnceLock::new();
+
+/// Flags of this process (parsed on first use; exits on `--help` or a bad flag)
+pub fn args() -> &'static Args {
+    ARGS.get_or_init(|| {
+        let matches = command().try_get_matches().unwrap_or_else(|e| {
+            crate::attach_console();
+            e.exit()
+        });
+        if let Some(shell) = matches.get_one::<Shell>(long(COMPLETIONS_ARG)) {
+            crate::attach_console();
+            // Into a buffer: `generate` panics when stdout is closed early (`| head`)
+            let mut script = Vec::new();
+            clap_complete::generate(*shell, &mut command(), env!("CARGO_PKG_NAME"), &mut script);
+            let written = std::io::stdout().write_all(&script);
+            std::process::exit(if written.is_ok() { 0 } else { 1 });
+        }
+        if matches.get_flag(long(MAN_ARG)) {
+            crate::attach_console();
+            let written = clap_mangen::Man::new(command()).render(&mut std::io::stdout());
+            std::process::exit(if written.is_ok() { 0 } else { 1 });
+        }
+        let path = |name| matches.try_get_one::<PathBuf>(long(name)).ok().flatten().cloned();
+        let set = |name| matches.try_get_one::<bool>(long(name)).ok().flatten().copied().unwrap_or(false);
+        Args {
+            self_test: set(SELF_TEST_ARG),
+            simulate: set(SIMULATE_ARG),
+            seed: matches.get_one::<u64>(long(SEED_ARG)).copied(),
+            view_session: path(VIEW_SESSION_ARG),
+            run_and_copy: set(RUN_AND_COPY_ARG),
+            digest: set(DIGEST_ARG),
+            verify: path(VERIFY_ARG),
+            restore_state: path(RESTORE_STATE_ARG),
+            config: path(CONFIG_ARG),
+        }
+    })
+}
@@ -0,0 +1,263 @@
+//! One-click remediation actions
+//!
+//! Each action returns a short summary for the status line; the caller re-runs
+//! the relevant checks afterwards to verify the fix took.
+
+use crate::diagnostics::{processes, SystemSnapshot};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessesToUpdate, System};
+
+/// Used when no restart command is configured (run through the shell once per
+/// stopped instance, in its project directory, which is also `$PWD`)
+#[cfg(target_os = "windows")]
+pub const DEFAULT_RESTART_COMMAND: &str = "start \"OpenCode\" opencode";
+/// The directory goes to AppleScript as an argument and is quoted there, so
+/// quotes in it cannot break out of the script
+#[cfg(target_os = "macos")]
+pub const DEFAULT_RESTART_COMMAND: &str = "osascript -e 'on run argv' \
+    -e 'tell application \"Terminal\" to do script \"cd \" & quoted form of item 1 of argv & \" && opencode\"' \
+    -e 'end run' \"$PWD\"";
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub const DEFAULT_RESTART_COMMAND: &str = "x-terminal-emulator -e opencode";
+
+/// How long OpenCode gets to exit before it is killed
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time for the relaunched instance to come up before it is verified
+const START_GRACE: Duration = Duration::from_secs(3);
+
+/// Stop every OpenCode instance (gracefully, then forcefully) and launch
+/// `command` once for each, in that instance's directory
+pub fn restart_opencode(command: &str) -> Result<String, String> {
+    let command = match command.trim() {
+        "" => DEFAULT_RESTART_COMMAND,
+        command => command,
+    };
+
+    let mut snapshot = SystemSnapshot::new();
+    let instances = processes::opencode_roots(&mut snapshot);
+    let pids: Vec<Pid> = instances.iter().map(|(pid, _)| *pid).collect();
+
+    for pid in &pids {
+        request_stop(pid.as_u32());
+    }
+    let mut sys = System::new();
+    let mut killed = 0;
+    if !wait_for_exit(&mut sys, &pids) {
+        for pid in pids.iter().filter(|pid| sys.process(**pid).is_some()) {
+            force_stop(pid.as_u32());
+            killed += 1;
+        }
+        if !wait_for_exit(&mut sys, &pids) {
+            return Err("OpenCode did not stop".to_string());
+        }
+    }
+
+    // Relaunch every instance where it was running (one, here, when none was)
+    let here = || std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let mut dirs: Vec<PathBuf> = instances.into_iter().map(|(_, cwd)| cwd.unwrap_or_else(here)).collect();
+    if dirs.is_empty() {
+        dirs.push(here());
+    }
+    for dir in &dirs {
+        let mut child = shell(command)
+            .current_dir(dir)
+            .env("PWD", dir)
+            .spawn()
+            .map_err(|e| format!("Failed to launch \"{}\" in {}: {}", command, dir.display(), e))?;
+        std::thread::spawn(move || child.wait());
+    }
+    std::thread::sleep(START_GRACE);
+
+    let stopped = match (pids.len(), killed) {
+        (0, _) => "Nothing to stop".to_string(),
+        (n, 0) => format!("Stopped {}", n),
+        (n, k) => format!("Stopped {} ({} forcefully)", n, k),
+    };
+    let launched = if dirs.len() == 1 { String::new() } else { format!(" {} times", dirs.len()) };
+    Ok(format!("{}, launched `{}`{}", stopped, command, launched))
+}
+
+/// Network quick actions (Windows)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum NetworkAction {
+    FlushDns,
+    RenewDhcp,
+    RestartWifi,
+}
+
+pub const NETWORK_ACTIONS: &[NetworkAction] = &[
+    NetworkAction::FlushDns,
+    NetworkAction::RenewDhcp,
+    NetworkAction::RestartWifi,
+];
+
+impl NetworkAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NetworkAction::FlushDns => "FLUSH DNS",
+            NetworkAction::RenewDhcp => "RENEW DHCP",
+            NetworkAction::RestartWifi => "RESTART WI-FI",
+        }
+    }
+
+    /// Shown in the confirmation
+    pub fn description(&self) -> &'static str {
+        match self {
+            NetworkAction::FlushDns => "Clears cached DNS answers, e.g. after a provider changed IPs.",
+            NetworkAction::RenewDhcp => "Releases and renews the IP address. Connections drop for a few seconds.",
+            NetworkAction::RestartWifi => "Disables and re-enables the Wi-Fi adapter. Wi-Fi drops for a few seconds.",
+        }
+    }
+
+    /// Releasing the address and switching the adapter both need elevation
+    pub fn needs_admin(&self) -> bool {
+        matches!(self, NetworkAction::RenewDhcp | NetworkAction::RestartWifi)
+    }
+
+    /// The `cmd` command line it runs (looks up the Wi-Fi adapter name)
+    pub fn command_line(&self) -> String {
+        match self {
+            NetworkAction::FlushDns => "ipconfig /flushdns".to_string(),
+            NetworkAction::RenewDhcp => "ipconfig /release & ipconfig /renew".to_string(),
+            NetworkAction::RestartWifi => {
+                let name = crate::diagnostics::wlan::interface().unwrap_or_else(|| "Wi-Fi".to_string());
+                format!(
+                    "netsh interface set interface name=\"{0}\" admin=disabled & ping -n 3 127.0.0.1 >nul & \
+                     netsh interface set interface name=\"{0}\" admin=enabled",
+                    name
+                )
+            }
+        }
+    }
+
+    /// Run it (through UAC only when needed) and log the output
+    #[cfg(target_os = "windows")]
+    pub fn run(&self) -> Result<String, String> {
+        let command_line = self.command_line();
+        let elevate = self.needs_admin() && !crate::diagnostics::elevation::is_elevated();
+        let result = crate::diagnostics::elevation::run_command_line(&command_line, elevate);
+        log_action(self.label(), &command_line, &result);
+        result.map(|output| {
+            let output = output.trim();
+            if output.is_empty() {
+                format!("{} done", self.label())
+            } else {
+                output.to_string()
+            }
+        })
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    pub fn run(&self) -> Result<String, String> {
+        Err(format!("{} is only available on Windows", self.label()))
+    }
+}
+
+/// Quick action output log, next to the settings
+pub fn log_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("opencode-diag").join("actions.log"))
+}
+
+/// Append a run of `label` to the action log
+pub fn log_action(label: &str, command_line: &str, result: &Result<String, String>) {
+    let Some(path) = log_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(&path) else {
+        return;
+    };
+    let outcome = match result {
+        Ok(output) => output.trim().to_string(),
+        Err(e) => format!("FAILED: {}", e),
+    };
+    let _ = writeln!(
+        file,
+        "[{}] {} :: {}\n{}\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        label,
+        command_line,
+        outcome
+    );
+}
+
+/// Wait up to `STOP_TIMEOUT` for all `pids` to exit
+fn wait_for_exit(sys: &mut System, pids: &[Pid]) -> bool {
+    let started = Instant::now();
+    loop {
+        sys.refresh_processes(ProcessesToUpdate::Some(pids), true);
+        if pids.iter().all(|pid| sys.process(*pid).is_none()) {
+            return true;
+        }
+        if started.elapsed() > STOP_TIMEOUT {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+}
+
+/// Ask a process tree to close (WM_CLOSE / SIGTERM)
+#[cfg(target_os = "windows")]
+fn request_stop(pid: u32) {
+    let _ = crate::diagnostics::command("taskkill").args(["/PID", &pid.to_string(), "/T"]).output();
+}
+
+/// Ask a process tree to close (WM_CLOSE / SIGTERM)
+#[cfg(not(target_os = "windows"))]
+fn request_stop(pid: u32) {
+    signal_tree(pid, "-TERM");
+}
+
+/// Kill a process tree
+#[cfg(target_os = "windows")]
+pub fn force_stop(pid: u32) {
+    let _ = crate::diagnostics::command("taskkill").args(["/F", "/T", "/PID", &pid.to_string()]).output();
+}
+
+/// Kill a process tree
+#[cfg(not(target_os = "windows"))]
+pub fn force_stop(pid: u32) {
+    signal_tree(pid, "-KILL");
+}
+
+/// Signal `pid`'s process group when it leads one (a terminal job, or a child
+/// spawned into its own group), which reaches grandchildren too; else the
+/// process and its children
+#[cfg(not(target_os = "windows"))]
+fn signal_tree(pid: u32, signal: &str) {
+    let pid = pid.to_string();
+    let group = crate::diagnostics::command("ps")
+        .args(["-o", "pgid=", "-p", &pid])
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+    // Only a group the process leads, so our own group is never signalled
+    if group.as_deref() == Some(pid.as_str()) {
+        let _ = crate::diagnostics::command("kill").args([signal, "--", &format!("-{}", pid)]).output();
+    }
+    let _ = crate::diagnostics::command("pkill").args([signal, "-P", &pid]).output();
+    let _ = crate::diagnostics::command("kill").args([signal, &pid]).output();
+}
+
+/// Command line run through the platform shell
+#[cfg(target_os = "windows")]
+pub fn shell(command: &str) -> std::process::Command {
+    use std::os::windows::process::CommandExt;
+    let mut cmd = crate::diagnostics::command("cmd");
+    // Passed verbatim so quoted arguments survive
+    cmd.arg("/C").raw_arg(command);
+    cmd
+}
+
+/// Command line run through the platform shell
+#[cfg(not(target_os = "windows"))]
+pub fn shell(command: &str) -> std::process::Command {
+    let mut cmd = crate::diagnostics::command("sh");
+    cmd.args(["-c", command]);
+    cmd
+}
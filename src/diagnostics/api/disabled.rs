@@ -0,0 +1,17 @@
+//! API checks in builds without the `net-checks` feature
+
+use crate::diagnostics::{CheckResult, CheckStatus, HttpProfile};
+
+const NOT_BUILT: &str = "Network checks not included in this build";
+
+pub fn check_claude_api(_http: &HttpProfile) -> CheckResult {
+    CheckResult::new("CLAUDE API", CheckStatus::Inactive, NOT_BUILT)
+}
+
+pub fn check_openai_api(_http: &HttpProfile) -> CheckResult {
+    CheckResult::new("OPENAI API", CheckStatus::Inactive, NOT_BUILT)
+}
+
+pub fn check_google_api(_http: &HttpProfile) -> CheckResult {
+    CheckResult::new("GOOGLE AI", CheckStatus::Inactive, NOT_BUILT)
+}
@@ -1,8 +1,20 @@
 //! API health checks for various AI services
 
-use crate::diagnostics::{CheckResult, CheckStatus};
+use crate::diagnostics::capacity::Evidence;
+use crate::diagnostics::{traffic, CheckResult, CheckStatus, HttpProfile};
 use std::time::{Duration, Instant};
 
+pub mod opencode;
+
+const CLAUDE_URL: &str = "https://api.anthropic.com";
+const OPENAI_URL: &str = "https://api.openai.com/v1/models";
+const GOOGLE_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+
+/// Headers only the providers themselves send (name, value prefix), to tell their answers from a proxy's
+const CLAUDE_HEADERS: &[(&str, &str)] = &[("request-id", ""), ("cf-ray", "")];
+const OPENAI_HEADERS: &[(&str, &str)] = &[("x-request-id", ""), ("openai-processing-ms", ""), ("cf-ray", "")];
+const GOOGLE_HEADERS: &[(&str, &str)] = &[("server", "ESF")];
+
 /// Extract error message from JSON response
 fn extract_error_message(body: &str) -> Option<String> {
     if let Ok(json) = serde_json::from_str::<serde_json::Value>(body) {
@@ -25,14 +37,16 @@ fn extract_error_message(body: &str) -> Option<String> {
 }
 
 /// Check Claude/Anthropic API status
-pub fn check_claude_api() -> CheckResult {
-    let client = match reqwest::blocking::Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
-    {
+pub fn check_claude_api(http: &HttpProfile) -> CheckResult {
+    check_claude_api_at(http, if http.opencode_mode { opencode::CLAUDE_URL } else { CLAUDE_URL })
+}
+
+/// Claude check against another URL (used by `--self-test` mock endpoints)
+pub fn check_claude_api_at(http: &HttpProfile, url: &str) -> CheckResult {
+    let client = match http.client(Duration::from_secs(10)) {
         Ok(c) => c,
-        Err(_) => {
-            return CheckResult::new("CLAUDE API", CheckStatus::Error, "Failed to create HTTP client");
+        Err(e) => {
+            return CheckResult::new("CLAUDE API", CheckStatus::Error, "Failed to create HTTP client").with_message(&e);
         }
     };
 
@@ -40,14 +54,20 @@ pub fn check_claude_api() -> CheckResult {
     
     // Use HEAD request to check if API is reachable without triggering 405
     // Or use the root domain which typically returns a valid response
-    let result = client.head("https://api.anthropic.com")
-        .send();
+    let result = if http.opencode_mode {
+        opencode::claude_request(&client, url)
+    } else {
+        client.head(url)
+    }
+    .send();
+    traffic::record_http(url, &result);
 
     let elapsed = start.elapsed().as_millis();
 
     match result {
         Ok(response) => {
             let status_code = response.status().as_u16();
+            let evidence = Evidence::from_response(&response, CLAUDE_HEADERS);
             
             let (status, details) = match status_code {
                 // HEAD to root may return various codes
@@ -67,7 +87,7 @@ pub fn check_claude_api() -> CheckResult {
                 }
             };
 
-            CheckResult::new("CLAUDE API", status, &details)
+            CheckResult::new("CLAUDE API", status, &details).with_latency(elapsed).with_evidence(evidence)
         }
         Err(e) => {
             let details = if e.is_timeout() {
@@ -78,34 +98,42 @@ pub fn check_claude_api() -> CheckResult {
                 format!("api.anthropic.com :: {}", e)
             };
             
-            CheckResult::new("CLAUDE API", CheckStatus::Error, &details)
+            CheckResult::new("CLAUDE API", CheckStatus::Error, &details).with_evidence(Evidence::from_error(&e))
         }
     }
 }
 
 /// Check OpenAI API status
-pub fn check_openai_api() -> CheckResult {
-    let client = match reqwest::blocking::Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
-    {
+pub fn check_openai_api(http: &HttpProfile) -> CheckResult {
+    check_openai_api_at(http, if http.opencode_mode { opencode::OPENAI_URL } else { OPENAI_URL })
+}
+
+/// OpenAI check against another URL (used by `--self-test` mock endpoints)
+pub fn check_openai_api_at(http: &HttpProfile, url: &str) -> CheckResult {
+    let client = match http.client(Duration::from_secs(10)) {
         Ok(c) => c,
-        Err(_) => {
-            return CheckResult::new("OPENAI API", CheckStatus::Error, "Failed to create HTTP client");
+        Err(e) => {
+            return CheckResult::new("OPENAI API", CheckStatus::Error, "Failed to create HTTP client").with_message(&e);
         }
     };
 
     let start = Instant::now();
     
     // Check OpenAI API - models endpoint with no auth returns 401 but proves reachability
-    let result = client.get("https://api.openai.com/v1/models")
-        .send();
+    let result = if http.opencode_mode {
+        opencode::openai_request(&client, url)
+    } else {
+        client.get(url)
+    }
+    .send();
+    traffic::record_http(url, &result);
 
     let elapsed = start.elapsed().as_millis();
 
     match result {
         Ok(response) => {
             let status_code = response.status().as_u16();
+            let evidence = Evidence::from_response(&response, OPENAI_HEADERS);
             let body = response.text().unwrap_or_default();
             let error_msg = extract_error_message(&body);
             
@@ -117,7 +145,7 @@ pub fn check_openai_api() -> CheckResult {
                 _ => (CheckStatus::Warning, format!("api.openai.com :: {} :: {}ms", status_code, elapsed)),
             };
 
-            let mut check = CheckResult::new("OPENAI API", status, &details);
+            let mut check = CheckResult::new("OPENAI API", status, &details).with_latency(elapsed).with_evidence(evidence);
             if let Some(msg) = error_msg {
                 check = check.with_message(&msg);
             }
@@ -132,34 +160,42 @@ pub fn check_openai_api() -> CheckResult {
                 format!("api.openai.com :: {}", e)
             };
             
-            CheckResult::new("OPENAI API", CheckStatus::Error, &details)
+            CheckResult::new("OPENAI API", CheckStatus::Error, &details).with_evidence(Evidence::from_error(&e))
         }
     }
 }
 
 /// Check Google AI (Gemini) API status
-pub fn check_google_api() -> CheckResult {
-    let client = match reqwest::blocking::Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
-    {
+pub fn check_google_api(http: &HttpProfile) -> CheckResult {
+    check_google_api_at(http, if http.opencode_mode { opencode::GOOGLE_URL } else { GOOGLE_URL })
+}
+
+/// Google AI check against another URL (used by `--self-test` mock endpoints)
+pub fn check_google_api_at(http: &HttpProfile, url: &str) -> CheckResult {
+    let client = match http.client(Duration::from_secs(10)) {
         Ok(c) => c,
-        Err(_) => {
-            return CheckResult::new("GOOGLE AI", CheckStatus::Error, "Failed to create HTTP client");
+        Err(e) => {
+            return CheckResult::new("GOOGLE AI", CheckStatus::Error, "Failed to create HTTP client").with_message(&e);
         }
     };
 
     let start = Instant::now();
     
     // Check Google AI API endpoint
-    let result = client.get("https://generativelanguage.googleapis.com/v1beta/models")
-        .send();
+    let result = if http.opencode_mode {
+        opencode::google_request(&client, url)
+    } else {
+        client.get(url)
+    }
+    .send();
+    traffic::record_http(url, &result);
 
     let elapsed = start.elapsed().as_millis();
 
     match result {
         Ok(response) => {
             let status_code = response.status().as_u16();
+            let evidence = Evidence::from_response(&response, GOOGLE_HEADERS);
             
             let (status, details) = match status_code {
                 200..=299 => (CheckStatus::Ok, format!("googleapis.com :: {} :: {}ms", status_code, elapsed)),
@@ -169,7 +205,7 @@ pub fn check_google_api() -> CheckResult {
                 _ => (CheckStatus::Warning, format!("googleapis.com :: {} :: {}ms", status_code, elapsed)),
             };
 
-            CheckResult::new("GOOGLE AI", status, &details)
+            CheckResult::new("GOOGLE AI", status, &details).with_latency(elapsed).with_evidence(evidence)
         }
         Err(e) => {
             let details = if e.is_timeout() {
@@ -180,7 +216,7 @@ pub fn check_google_api() -> CheckResult {
                 format!("googleapis.com :: {}", e)
             };
             
-            CheckResult::new("GOOGLE AI", CheckStatus::Error, &details)
+            CheckResult::new("GOOGLE AI", CheckStatus::Error, &details).with_evidence(Evidence::from_error(&e))
         }
     }
 }
@@ -0,0 +1,56 @@
+//! OpenCode request profile
+//!
+//! The default API checks send a cheap HEAD/GET to each provider. In OpenCode
+//! mode they send what OpenCode itself sends instead: a streaming POST to the
+//! real completion endpoint over HTTP/1.1 with the provider's SDK headers, so
+//! proxies and gateways that treat streaming POSTs differently show up. No key
+//! is attached, so a reachable provider answers 401/403.
+
+use reqwest::blocking::{Client, RequestBuilder};
+use serde_json::json;
+
+/// User-Agent used in OpenCode mode when none is configured
+pub const USER_AGENT: &str = "opencode";
+
+pub const CLAUDE_URL: &str = "https://api.anthropic.com/v1/messages";
+pub const OPENAI_URL: &str = "https://api.openai.com/v1/chat/completions";
+pub const GOOGLE_URL: &str =
+    "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-flash:streamGenerateContent?alt=sse";
+
+/// Minimal one-token prompt
+const PROMPT: &str = "ping";
+
+pub fn claude_request(client: &Client, url: &str) -> RequestBuilder {
+    client
+        .post(url)
+        .header("anthropic-version", "2023-06-01")
+        .header("accept", "text/event-stream")
+        .json(&json!({
+            "model": "claude-sonnet-4-5",
+            "max_tokens": 1,
+            "stream": true,
+            "messages": [{ "role": "user", "content": PROMPT }],
+        }))
+}
+
+pub fn openai_request(client: &Client, url: &str) -> RequestBuilder {
+    client
+        .post(url)
+        .header("accept", "text/event-stream")
+        .json(&json!({
+            "model": "gpt-4o-mini",
+            "max_tokens": 1,
+            "stream": true,
+            "messages": [{ "role": "user", "content": PROMPT }],
+        }))
+}
+
+pub fn google_request(client: &Client, url: &str) -> RequestBuilder {
+    client
+        .post(url)
+        .header("accept", "text/event-stream")
+        .json(&json!({
+            "contents": [{ "role": "user", "parts": [{ "text": PROMPT }] }],
+            "generationConfig": { "maxOutputTokens": 1 },
+        }))
+}
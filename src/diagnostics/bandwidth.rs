@@ -0,0 +1,389 @@
+//! Network throughput per process
+//!
+//! When latency is poor, the first question is who else is using the line:
+//! OpenCode itself, a browser, or a sync client uploading in the background.
+//! TCP byte counters of every established connection are read twice, one
+//! second apart, and the difference is summed per process name.
+//!
+//! On Windows the counters come from the TCP extended statistics
+//! (GetPerTcpConnectionEStats), which only an administrator can switch on;
+//! without admin only the connections per process are counted. On Linux `ss`
+//! reports the counters of the user's own sockets.
+
+use crate::diagnostics::traffic::format_bytes;
+use crate::diagnostics::{elevation, CheckResult, CheckStatus, SystemSnapshot};
+use std::collections::HashMap;
+use std::time::Duration;
+use sysinfo::Pid;
+
+/// Time between the two counter readings
+const SAMPLE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Throughput (received + sent per second) of a single process that slows the others down
+const BUSY_BYTES_PER_SEC: u64 = 1_000_000;
+
+/// Processes listed in the details
+const TOP_PROCESSES: usize = 3;
+
+/// Process name fragments (lowercase) of well-known bandwidth users
+const BROWSERS: &[&str] = &["chrome", "msedge", "firefox", "brave", "opera", "vivaldi", "safari"];
+const SYNC_CLIENTS: &[&str] =
+    &["onedrive", "dropbox", "googledrivefs", "google drive", "icloud", "nextcloud", "owncloud", "syncthing", "megasync", "pcloud"];
+const DOWNLOADERS: &[&str] = &["steam", "epicgameslauncher", "battle.net", "qbittorrent", "utorrent", "transmission"];
+
+/// Traffic of one TCP connection during the sample window
+struct Flow {
+    pid: u32,
+    received: u64,
+    sent: u64,
+}
+
+/// Flows of all established non-loopback connections; `counted` is false when
+/// only the connections are known (no byte counters)
+struct Measurement {
+    flows: Vec<Flow>,
+    counted: bool,
+}
+
+/// One process name with its connections and throughput
+struct ProcessUsage {
+    name: String,
+    connections: usize,
+    per_sec: u64,
+}
+
+/// Which process is using the bandwidth
+pub fn check_bandwidth(snapshot: &mut SystemSnapshot) -> CheckResult {
+    let measurement = match measure() {
+        Ok(measurement) => measurement,
+        Err(e) => {
+            return CheckResult::new("BANDWIDTH", CheckStatus::Inactive, "Per-process traffic unavailable").with_message(&e)
+        }
+    };
+
+    let own = std::process::id();
+    let sys = snapshot.processes();
+    let mut by_name: HashMap<String, ProcessUsage> = HashMap::new();
+    for flow in measurement.flows.iter().filter(|f| f.pid != own && f.pid != 0) {
+        let name = sys
+            .process(Pid::from_u32(flow.pid))
+            .map(|p| p.name().to_string_lossy().trim_end_matches(".exe").to_string())
+            .unwrap_or_else(|| format!("PID {}", flow.pid));
+        let usage = by_name.entry(name.clone()).or_insert(ProcessUsage { name, connections: 0, per_sec: 0 });
+        usage.connections += 1;
+        usage.per_sec += ((flow.received + flow.sent) as f64 / SAMPLE_WINDOW.as_secs_f64()) as u64;
+    }
+
+    let mut usage: Vec<ProcessUsage> = by_name.into_values().collect();
+    usage.sort_by(|a, b| b.per_sec.cmp(&a.per_sec).then(b.connections.cmp(&a.connections)));
+    if usage.is_empty() {
+        return CheckResult::new("BANDWIDTH", CheckStatus::Ok, "No open connections");
+    }
+
+    let mut parts: Vec<String> = usage
+        .iter()
+        .take(TOP_PROCESSES)
+        .map(|u| {
+            if measurement.counted {
+                format!("{} {}/s ({} conn)", u.name, format_bytes(u.per_sec), u.connections)
+            } else {
+                format!("{} {} conn", u.name, u.connections)
+            }
+        })
+        .collect();
+    if !measurement.counted {
+        parts.push(format!("throughput {}", elevation::ADMIN_NOTE));
+    }
+    let details = parts.join(" :: ");
+
+    let top = &usage[0];
+    if measurement.counted && top.per_sec >= BUSY_BYTES_PER_SEC {
+        let kind = kind_of(&top.name).map(|k| format!(" ({})", k)).unwrap_or_default();
+        return CheckResult::new("BANDWIDTH", CheckStatus::Warning, &details).with_message(&format!(
+            "{}{} is using {}/s. Pause it if API latency is poor.",
+            top.name,
+            kind,
+            format_bytes(top.per_sec)
+        ));
+    }
+
+    CheckResult::new("BANDWIDTH", CheckStatus::Ok, &details)
+}
+
+/// "browser", "sync client"... for well-known bandwidth users
+fn kind_of(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    let matches = |list: &[&str]| list.iter().any(|fragment| name.contains(fragment));
+    if name.contains("opencode") {
+        Some("OpenCode")
+    } else if matches(BROWSERS) {
+        Some("browser")
+    } else if matches(SYNC_CLIENTS) {
+        Some("sync client")
+    } else if matches(DOWNLOADERS) {
+        Some("downloader")
+    } else if name == "svchost" {
+        Some("Windows service, e.g. Windows Update")
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn measure() -> Result<Measurement, String> {
+    let connections = estats::established()?;
+    if elevation::limited_without_admin(crate::diagnostics::ids::BANDWIDTH) {
+        let flows = connections.iter().map(|c| Flow { pid: c.pid, received: 0, sent: 0 }).collect();
+        return Ok(Measurement { flows, counted: false });
+    }
+
+    // Collection starts when enabled, so the first reading is the baseline
+    let before: Vec<Option<(u64, u64)>> = connections.iter().map(|c| c.enable().then(|| c.bytes()).flatten()).collect();
+    std::thread::sleep(SAMPLE_WINDOW);
+    let flows = connections
+        .iter()
+        .zip(before)
+        .map(|(connection, before)| {
+            let (received, sent) = match (before, connection.bytes()) {
+                (Some((rx0, tx0)), Some((rx1, tx1))) => (rx1.saturating_sub(rx0), tx1.saturating_sub(tx0)),
+                _ => (0, 0),
+            };
+            Flow { pid: connection.pid, received, sent }
+        })
+        .collect();
+    Ok(Measurement { flows, counted: true })
+}
+
+#[cfg(target_os = "linux")]
+fn measure() -> Result<Measurement, String> {
+    let before = ss_counters()?;
+    std::thread::sleep(SAMPLE_WINDOW);
+    let after = ss_counters()?;
+    let flows = after
+        .into_iter()
+        .map(|(key, (pid, rx1, tx1))| {
+            // Connections opened during the window count from zero
+            let (rx0, tx0) = before.get(&key).map(|&(_, rx, tx)| (rx, tx)).unwrap_or((0, 0));
+            Flow {
+                pid,
+                received: rx1.saturating_sub(rx0),
+                sent: tx1.saturating_sub(tx0),
+            }
+        })
+        .collect();
+    Ok(Measurement { flows, counted: true })
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn measure() -> Result<Measurement, String> {
+    Err("Per-process traffic is only available on Windows and Linux".to_string())
+}
+
+/// Byte counters of established sockets, keyed by "local peer", as (pid, received, acked)
+#[cfg(target_os = "linux")]
+fn ss_counters() -> Result<HashMap<String, (u32, u64, u64)>, String> {
+    // "0 0 192.168.1.5:51234 1.2.3.4:443 users:(("firefox",pid=1234,fd=80))"
+    // "\t cubic ... bytes_acked:1234 bytes_received:5678 ..."
+    let output = crate::diagnostics::command("ss")
+        .args(["-tinpH", "state", "established"])
+        .output()
+        .map_err(|e| format!("ss failed: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let mut counters = HashMap::new();
+    let mut socket: Option<(String, u32)> = None;
+    for line in stdout.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            let pid = line
+                .split("pid=")
+                .nth(1)
+                .and_then(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+                .and_then(|pid| pid.parse().ok());
+            socket = match (cols.get(2), cols.get(3), pid) {
+                (Some(local), Some(peer), Some(pid)) if !is_loopback(peer) => Some((format!("{} {}", local, peer), pid)),
+                _ => None,
+            };
+            continue;
+        }
+        let Some((key, pid)) = socket.take() else {
+            continue;
+        };
+        let field = |name: &str| {
+            line.split_whitespace()
+                .find_map(|f| f.strip_prefix(name))
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(0)
+        };
+        counters.insert(key, (pid, field("bytes_received:"), field("bytes_acked:")));
+    }
+    Ok(counters)
+}
+
+#[cfg(target_os = "linux")]
+fn is_loopback(address: &str) -> bool {
+    address.starts_with("127.") || address.starts_with("[::1]") || address.starts_with("[::ffff:127.")
+}
+
+/// TCP extended statistics (the data behind Resource Monitor's Network tab)
+#[cfg(target_os = "windows")]
+mod estats {
+    use winapi::shared::iprtrmib::TCP_TABLE_OWNER_PID_CONNECTIONS;
+    use winapi::shared::tcpestats::{TcpConnectionEstatsData, TCP_ESTATS_DATA_ROD_v0, TCP_ESTATS_DATA_RW_v0};
+    use winapi::shared::tcpmib::{
+        MIB_TCP6ROW, MIB_TCP6ROW_OWNER_PID, MIB_TCP6TABLE_OWNER_PID, MIB_TCPROW, MIB_TCPROW_OWNER_PID,
+        MIB_TCPTABLE_OWNER_PID, MIB_TCP_STATE_ESTAB,
+    };
+    use winapi::shared::winerror::{ERROR_INSUFFICIENT_BUFFER, NO_ERROR};
+    use winapi::shared::ws2def::{AF_INET, AF_INET6};
+    use winapi::um::iphlpapi::{
+        GetExtendedTcpTable, GetPerTcp6ConnectionEStats, GetPerTcpConnectionEStats, SetPerTcp6ConnectionEStats,
+        SetPerTcpConnectionEStats,
+    };
+
+    enum Row {
+        V4(MIB_TCPROW),
+        V6(MIB_TCP6ROW),
+    }
+
+    pub struct Connection {
+        pub pid: u32,
+        row: Row,
+    }
+
+    impl Connection {
+        /// Switch on data statistics for this connection (administrator only)
+        pub fn enable(&self) -> bool {
+            let mut rw = TCP_ESTATS_DATA_RW_v0 { EnableCollection: 1 };
+            let rw_ptr = &mut rw as *mut _ as *mut u8;
+            let rw_size = std::mem::size_of::<TCP_ESTATS_DATA_RW_v0>() as u32;
+            let status = match &self.row {
+                Row::V4(row) => unsafe {
+                    SetPerTcpConnectionEStats(row as *const _ as *mut _, TcpConnectionEstatsData, rw_ptr, 0, rw_size, 0)
+                },
+                Row::V6(row) => unsafe {
+                    SetPerTcp6ConnectionEStats(row as *const _ as *mut _, TcpConnectionEstatsData, rw_ptr, 0, rw_size, 0)
+                },
+            };
+            status == NO_ERROR
+        }
+
+        /// Cumulative payload bytes (received, sent) since collection was enabled
+        pub fn bytes(&self) -> Option<(u64, u64)> {
+            let mut rod: TCP_ESTATS_DATA_ROD_v0 = unsafe { std::mem::zeroed() };
+            let rod_ptr = &mut rod as *mut _ as *mut u8;
+            let rod_size = std::mem::size_of::<TCP_ESTATS_DATA_ROD_v0>() as u32;
+            let null = std::ptr::null_mut();
+            let status = match &self.row {
+                Row::V4(row) => unsafe {
+                    GetPerTcpConnectionEStats(
+                        row as *const _ as *mut _,
+                        TcpConnectionEstatsData,
+                        null,
+                        0,
+                        0,
+                        null,
+                        0,
+                        0,
+                        rod_ptr,
+                        0,
+                        rod_size,
+                    )
+                },
+                Row::V6(row) => unsafe {
+                    GetPerTcp6ConnectionEStats(
+                        row as *const _ as *mut _,
+                        TcpConnectionEstatsData,
+                        null,
+                        0,
+                        0,
+                        null,
+                        0,
+                        0,
+                        rod_ptr,
+                        0,
+                        rod_size,
+                    )
+                },
+            };
+            (status == NO_ERROR).then_some((rod.DataBytesIn, rod.DataBytesOut))
+        }
+    }
+
+    /// Established IPv4 and IPv6 connections with a non-loopback remote end
+    pub fn established() -> Result<Vec<Connection>, String> {
+        let mut connections = Vec::new();
+
+        let table = owner_pid_table(AF_INET as u32)?;
+        let table = unsafe { &*(table.as_ptr() as *const MIB_TCPTABLE_OWNER_PID) };
+        let rows = unsafe { std::slice::from_raw_parts(table.table.as_ptr(), table.dwNumEntries as usize) };
+        for row in rows.iter().filter(|r| r.dwState == MIB_TCP_STATE_ESTAB) {
+            // dwRemoteAddr is in network byte order: 127.x.x.x has 127 in the low byte
+            if row.dwRemoteAddr & 0xff == 127 {
+                continue;
+            }
+            connections.push(Connection { pid: row.dwOwningPid, row: Row::V4(v4_row(row)) });
+        }
+
+        let table = owner_pid_table(AF_INET6 as u32)?;
+        let table = unsafe { &*(table.as_ptr() as *const MIB_TCP6TABLE_OWNER_PID) };
+        let rows = unsafe { std::slice::from_raw_parts(table.table.as_ptr(), table.dwNumEntries as usize) };
+        for row in rows.iter().filter(|r| r.dwState == MIB_TCP_STATE_ESTAB) {
+            if std::net::Ipv6Addr::from(row.ucRemoteAddr).is_loopback() {
+                continue;
+            }
+            connections.push(Connection { pid: row.dwOwningPid, row: Row::V6(v6_row(row)) });
+        }
+
+        Ok(connections)
+    }
+
+    /// GetExtendedTcpTable for `family`, in a u32 buffer for the table's alignment
+    fn owner_pid_table(family: u32) -> Result<Vec<u32>, String> {
+        let mut size = 0u32;
+        let mut buffer: Vec<u32> = Vec::new();
+        // The table can grow between the size query and the call
+        for _ in 0..3 {
+            let status = unsafe {
+                GetExtendedTcpTable(
+                    buffer.as_mut_ptr() as *mut _,
+                    &mut size,
+                    0,
+                    family,
+                    TCP_TABLE_OWNER_PID_CONNECTIONS,
+                    0,
+                )
+            };
+            match status {
+                NO_ERROR => return Ok(buffer),
+                ERROR_INSUFFICIENT_BUFFER => buffer = vec![0; (size as usize).div_ceil(4)],
+                _ => return Err(format!("GetExtendedTcpTable failed: {}", status)),
+            }
+        }
+        Err("GetExtendedTcpTable: table keeps growing".to_string())
+    }
+
+    fn v4_row(row: &MIB_TCPROW_OWNER_PID) -> MIB_TCPROW {
+        MIB_TCPROW {
+            State: row.dwState,
+            dwLocalAddr: row.dwLocalAddr,
+            dwLocalPort: row.dwLocalPort,
+            dwRemoteAddr: row.dwRemoteAddr,
+            dwRemotePort: row.dwRemotePort,
+        }
+    }
+
+    fn v6_row(row: &MIB_TCP6ROW_OWNER_PID) -> MIB_TCP6ROW {
+        let mut v6: MIB_TCP6ROW = unsafe { std::mem::zeroed() };
+        v6.State = row.dwState;
+        unsafe {
+            *v6.LocalAddr.u.Byte_mut() = row.ucLocalAddr;
+            *v6.RemoteAddr.u.Byte_mut() = row.ucRemoteAddr;
+        }
+        v6.dwLocalScopeId = row.dwLocalScopeId;
+        v6.dwLocalPort = row.dwLocalPort;
+        v6.dwRemoteScopeId = row.dwRemoteScopeId;
+        v6.dwRemotePort = row.dwRemotePort;
+        v6
+    }
+}
@@ -0,0 +1,180 @@
+//! Support bundle: one anonymized .zip to attach to opencode-diag bug reports
+//!
+//! Contains settings, the last two days of history, the error log and the
+//! latest report. Host name, user name, IPv4 addresses, custom endpoint hosts
+//! and the API token are replaced with placeholders before anything is written.
+
+use crate::diagnostics::certs::endpoint_host;
+use crate::diagnostics::profiles::ViewRole;
+use crate::diagnostics::{feedback, system_summary, DiagnosticReport, DiagnosticSettings, History};
+use chrono::{Datelike, Timelike};
+use std::io::Write;
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+
+/// History included in the bundle; older runs are left out
+const BUNDLE_HISTORY_HOURS: i64 = 48;
+
+/// Replaces identifying strings with stable placeholders
+struct Anonymizer {
+    replacements: Vec<(String, String)>,
+    ips: Vec<Ipv4Addr>,
+}
+
+impl Anonymizer {
+    fn new(settings: &DiagnosticSettings) -> Self {
+        let mut replacements = Vec::new();
+        if !settings.api_token.is_empty() {
+            replacements.push((settings.api_token.clone(), "<token>".to_string()));
+        }
+        for (i, endpoint) in settings.custom_endpoints.iter().enumerate() {
+            if let Some(host) = endpoint_host(endpoint) {
+                replacements.push((host, format!("<endpoint-{}>", i + 1)));
+            }
+        }
+        if let Some(host) = sysinfo::System::host_name().filter(|h| h.len() > 2) {
+            replacements.push((host, "<host>".to_string()));
+        }
+        let user = std::env::var("USERNAME").or_else(|_| std::env::var("USER")).ok();
+        if let Some(user) = user.map(|u| u.trim().to_string()).filter(|u| u.len() > 2) {
+            replacements.push((user, "<user>".to_string()));
+        }
+        // Longest first so a host containing the user name is replaced whole
+        replacements.sort_by_key(|(needle, _)| std::cmp::Reverse(needle.len()));
+
+        Self {
+            replacements,
+            ips: Vec::new(),
+        }
+    }
+
+    fn apply(&mut self, text: &str) -> String {
+        let mut out = text.to_string();
+        for (needle, placeholder) in &self.replacements {
+            out = out.replace(needle.as_str(), placeholder);
+        }
+        self.mask_ips(&out)
+    }
+
+    /// Replace IPv4 addresses (except loopback/unspecified) with `<ip-N>`
+    fn mask_ips(&mut self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while !rest.is_empty() {
+            let start = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+            out.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let end = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+            let token = &rest[..end];
+            match token.trim_end_matches('.').parse::<Ipv4Addr>() {
+                Ok(ip) if !ip.is_loopback() && !ip.is_unspecified() => {
+                    let index = match self.ips.iter().position(|known| *known == ip) {
+                        Some(i) => i,
+                        None => {
+                            self.ips.push(ip);
+                            self.ips.len() - 1
+                        }
+                    };
+                    out.push_str(&format!("<ip-{}>", index + 1));
+                    out.push_str(&token[token.trim_end_matches('.').len()..]);
+                }
+                _ => out.push_str(token),
+            }
+            rest = &rest[end..];
+        }
+        out
+    }
+}
+
+/// Write the bundle to the Downloads folder, returns the file path
+pub fn create_support_bundle(
+    settings: &DiagnosticSettings,
+    report: &DiagnosticReport,
+    history: &History,
+    error_log: &str,
+) -> Result<PathBuf, String> {
+    let mut anon = Anonymizer::new(settings);
+
+    let mut safe_settings = settings.clone();
+    safe_settings.api_token = String::new();
+    // Header values are often credentials (Authorization, API keys)
+    for header in &mut safe_settings.extra_headers {
+        if let Some((name, _)) = header.split_once(':') {
+            *header = format!("{}: <redacted>", name.trim());
+        }
+    }
+    let settings_json = serde_json::to_string_pretty(&safe_settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    let history_lines: Vec<String> = history
+        .since_hours(BUNDLE_HISTORY_HOURS)
+        .filter_map(|entry| serde_json::to_string(entry).ok())
+        .collect();
+
+    let report_json = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("Failed to serialize report: {}", e))?;
+
+    let feedback_json = serde_json::to_string_pretty(&feedback::all())
+        .map_err(|e| format!("Failed to serialize feedback: {}", e))?;
+
+    let files = [
+        ("README.txt", readme()),
+        ("system.txt", anon.apply(&system_summary())),
+        ("settings.json", anon.apply(&settings_json)),
+        ("history.jsonl", anon.apply(&history_lines.join("\n"))),
+        ("report.json", anon.apply(&report_json)),
+        ("report.txt", anon.apply(&report.to_text_report(ViewRole::Support.sections()))),
+        ("error_log.txt", anon.apply(error_log)),
+        ("feedback.json", feedback_json),
+    ];
+
+    let dir = dirs::download_dir()
+        .or_else(dirs::desktop_dir)
+        .or_else(dirs::home_dir)
+        .ok_or("Could not determine Downloads folder")?;
+    let name = format!("opencode-diag-support-{}.zip", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let path = dir.join(name);
+
+    let file = std::fs::File::create(&path).map_err(|e| format!("Failed to create bundle: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let now = chrono::Local::now().naive_local();
+    let mut options = zip::write::SimpleFileOptions::default();
+    if let Ok(modified) = zip::DateTime::from_date_and_time(
+        now.year() as u16,
+        now.month() as u8,
+        now.day() as u8,
+        now.hour() as u8,
+        now.minute() as u8,
+        now.second() as u8,
+    ) {
+        options = options.last_modified_time(modified);
+    }
+    for (name, contents) in files {
+        zip.start_file(name, options)
+            .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+        zip.write_all(contents.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+    }
+    zip.finish().map_err(|e| format!("Failed to finish bundle: {}", e))?;
+
+    Ok(path)
+}
+
+fn readme() -> String {
+    format!(
+        "opencode-diag v{} support bundle\n\
+         Created: {}\n\n\
+         system.txt     OS, CPU and memory totals\n\
+         settings.json  settings (API token and header values removed)\n\
+         history.jsonl  runs from the last {} hours\n\
+         report.json    latest report\n\
+         report.txt     latest report as text\n\
+         error_log.txt  grouped error log\n\
+         feedback.json  right/wrong votes per diagnosis rule\n\n\
+         Host name, user name, IPv4 addresses and custom endpoint hosts are\n\
+         replaced with <host>, <user>, <ip-N> and <endpoint-N>.\n",
+        env!("CARGO_PKG_VERSION"),
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        BUNDLE_HISTORY_HOURS,
+    )
+}
@@ -0,0 +1,376 @@
+//! "Server at capacity" analyzer
+//!
+//! OpenCode shows "server at capacity" for any 5xx or failed connection, but
+//! a real provider 529 is only one of the causes. A corporate proxy answering
+//! 503 on the provider's behalf, exhausted local sockets and a skewed clock
+//! look the same from inside OpenCode. The API checks record what the
+//! response looked like (`Evidence`); the analyzer weighs it together with
+//! the relay and the other providers and names the most likely cause.
+
+use crate::diagnostics::{relay, CheckResult, CheckStatus, DiagnosticReport};
+use serde::{Deserialize, Serialize};
+
+/// Clock difference that breaks token and certificate validation
+const SKEW_LIMIT_SECS: i64 = 300;
+
+/// Clock difference worth mentioning
+const SKEW_NOTICE_SECS: i64 = 60;
+
+/// OS errors of an exhausted socket table: WSAENOBUFS, WSAEADDRINUSE, WSAEMFILE
+/// (Windows); EADDRNOTAVAIL, EMFILE, ENOBUFS (Linux, macOS)
+const SOCKET_EXHAUSTION_ERRORS: &[i32] = &[10055, 10048, 10024, 99, 49, 24, 105, 55];
+
+/// Highest confidence the analyzer claims
+const MAX_CONFIDENCE: u32 = 95;
+
+/// What an API (or internet) check's response (or failure) looked like
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Evidence {
+    pub status_code: Option<u16>,
+    /// The response carried the provider's own headers (it was not made up by a proxy)
+    pub from_provider: bool,
+    /// Intermediary named by a Via / Proxy-Agent / X-Cache / Server header
+    pub proxy: Option<String>,
+    /// Server `Date` minus the local clock (seconds)
+    pub clock_skew_secs: Option<i64>,
+    /// OS error code of a failed connection
+    pub os_error: Option<i32>,
+    /// TLS rejected the certificate as expired or not yet valid
+    pub cert_time_error: bool,
+    /// A host was unreachable by name while one was reachable by address (DNS)
+    #[serde(default)]
+    pub unreachable_by_name: bool,
+}
+
+/// Likely cause of a "server at capacity" error
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Cause {
+    Provider,
+    Proxy,
+    Sockets,
+    Clock,
+}
+
+impl Cause {
+    const ALL: [Cause; 4] = [Cause::Provider, Cause::Proxy, Cause::Sockets, Cause::Clock];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Cause::Provider => "provider overloaded",
+            Cause::Proxy => "proxy or gateway error, not the provider",
+            Cause::Sockets => "local sockets exhausted",
+            Cause::Clock => "system clock is off",
+        }
+    }
+
+    /// Diagnosis rule id, for feedback
+    pub fn rule(&self) -> &'static str {
+        match self {
+            Cause::Provider => "capacity.provider",
+            Cause::Proxy => "capacity.proxy",
+            Cause::Sockets => "capacity.sockets",
+            Cause::Clock => "capacity.clock",
+        }
+    }
+
+    fn advice(&self) -> &'static str {
+        match self {
+            Cause::Provider => "Wait and retry; nothing to fix locally.",
+            Cause::Proxy => "Bypass the proxy or VPN, or ask IT about the gateway.",
+            Cause::Sockets => "Close apps holding many connections or restart the PC.",
+            Cause::Clock => "Sync the system clock (Date & time > Sync now).",
+        }
+    }
+}
+
+/// Most likely cause with a confidence and the evidence behind it
+pub struct Verdict {
+    pub cause: Cause,
+    /// 0-100
+    pub confidence: u32,
+    pub evidence: Vec<String>,
+}
+
+impl Verdict {
+    /// One line for the diagnosis
+    pub fn summary(&self) -> String {
+        format!(
+            "Server at capacity: {} ({}% confidence: {}). {}",
+            self.cause.label(),
+            self.confidence,
+            self.evidence.join(", "),
+            self.cause.advice()
+        )
+    }
+}
+
+/// Points per cause, with the evidence that earned them
+#[derive(Default)]
+struct Scores(Vec<(Cause, u32, String)>);
+
+impl Scores {
+    fn add(&mut self, cause: Cause, points: u32, evidence: String) {
+        self.0.push((cause, points, evidence));
+    }
+
+    fn total(&self, cause: Cause) -> u32 {
+        self.0.iter().filter(|(c, _, _)| *c == cause).map(|(_, p, _)| p).sum()
+    }
+}
+
+/// Verdict for the report, `None` when no API check shows the symptom
+///
+/// Confidence is the winner's points capped at 95, minus half the runner-up's:
+/// two causes with similar evidence both end up uncertain.
+pub fn analyze(report: &DiagnosticReport) -> Option<Verdict> {
+    let apis = [
+        ("Claude", &report.claude_api),
+        ("OpenAI", &report.openai_api),
+        ("Google", &report.google_api),
+    ];
+    let failing: Vec<(&str, &CheckResult, &Evidence)> = apis
+        .iter()
+        .filter_map(|(name, check)| {
+            let check = check.as_ref()?;
+            let evidence = check.evidence.as_ref()?;
+            (check.status == CheckStatus::Error && symptom(evidence)).then_some((*name, check, evidence))
+        })
+        .collect();
+    if failing.is_empty() {
+        return None;
+    }
+
+    let mut scores = Scores::default();
+    for (name, _, evidence) in &failing {
+        match (evidence.status_code, evidence.from_provider) {
+            (Some(529), true) => scores.add(Cause::Provider, 70, format!("{} 529 from the provider", name)),
+            (Some(code), true) => scores.add(Cause::Provider, 50, format!("{} {} from the provider", name, code)),
+            (Some(code), false) => scores.add(Cause::Proxy, 50, format!("{} {} without provider headers", name, code)),
+            (None, _) => {}
+        }
+        if let Some(ref proxy) = evidence.proxy {
+            scores.add(Cause::Proxy, 30, format!("answered via {}", proxy));
+        }
+        if let Some(code) = evidence.os_error.filter(|e| SOCKET_EXHAUSTION_ERRORS.contains(e)) {
+            scores.add(Cause::Sockets, 80, format!("{} connect failed with OS error {}", name, code));
+        }
+        if evidence.cert_time_error {
+            scores.add(Cause::Clock, 50, format!("{} certificate rejected as expired/not yet valid", name));
+        }
+    }
+
+    // Clock skew from any provider that answered, failing or not
+    if let Some(skew) = apis
+        .iter()
+        .filter_map(|(_, check)| check.as_ref()?.evidence.as_ref()?.clock_skew_secs)
+        .max_by_key(|s| s.abs())
+    {
+        if skew.abs() >= SKEW_LIMIT_SECS {
+            scores.add(Cause::Clock, 60, format!("clock {}", format_skew(skew)));
+        } else if skew.abs() >= SKEW_NOTICE_SECS {
+            scores.add(Cause::Clock, 20, format!("clock {}", format_skew(skew)));
+        }
+    }
+
+    // Others fine while one fails points at that provider; all failing alike at something local
+    let answered = apis.iter().filter(|(_, check)| check.is_some()).count();
+    let healthy = apis
+        .iter()
+        .filter(|(_, check)| check.as_ref().map(|c| c.status == CheckStatus::Ok).unwrap_or(false))
+        .count();
+    if healthy > 0 && failing.len() == 1 {
+        scores.add(Cause::Provider, 10, "other providers fine".to_string());
+    }
+    if answered > 1 && failing.len() == answered {
+        scores.add(Cause::Proxy, 15, "every provider fails".to_string());
+    }
+
+    if let Some(ref relay) = report.relay {
+        if relay.status == CheckStatus::Warning {
+            scores.add(Cause::Proxy, 25, "relay reaches the provider".to_string());
+        } else if relay.message.as_deref().map(|m| m.contains(relay::OUTAGE_NOTE)).unwrap_or(false) {
+            scores.add(Cause::Provider, 30, "relay sees the outage too".to_string());
+        }
+    }
+
+    if let Some(proxy) = system_proxy() {
+        scores.add(Cause::Proxy, 15, format!("system proxy {}", proxy));
+    }
+
+    let mut totals: Vec<(Cause, u32)> = Cause::ALL.iter().map(|&c| (c, scores.total(c))).collect();
+    totals.sort_by_key(|t| std::cmp::Reverse(t.1));
+    let (cause, best) = totals[0];
+    if best == 0 {
+        return None;
+    }
+    let confidence = best.min(MAX_CONFIDENCE).saturating_sub(totals[1].1 / 2).max(10);
+    let evidence = scores.0.into_iter().filter(|(c, _, _)| *c == cause).map(|(_, _, e)| e).collect();
+    Some(Verdict { cause, confidence, evidence })
+}
+
+/// A 5xx, or a connection that never got a response
+fn symptom(evidence: &Evidence) -> bool {
+    match evidence.status_code {
+        Some(code) => (500..=599).contains(&code),
+        None => evidence.os_error.is_some() || evidence.cert_time_error,
+    }
+}
+
+/// "7m ahead" / "2h behind" (positive skew: the server is ahead of this clock)
+fn format_skew(skew: i64) -> String {
+    let secs = skew.unsigned_abs();
+    let amount = if secs >= 3600 { format!("{}h", secs / 3600) } else { format!("{}m", secs / 60) };
+    format!("{} {}", amount, if skew > 0 { "behind" } else { "ahead" })
+}
+
+/// Proxy from the environment, or the Windows Internet Settings one
+fn system_proxy() -> Option<String> {
+    ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|v| !v.trim().is_empty()))
+        .or_else(windows_proxy)
+}
+
+#[cfg(target_os = "windows")]
+fn windows_proxy() -> Option<String> {
+    use winapi::shared::minwindef::DWORD;
+    use winapi::um::winreg::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD, RRF_RT_REG_SZ};
+
+    let wide = |s: &str| s.encode_utf16().chain(std::iter::once(0)).collect::<Vec<u16>>();
+    let key = wide(r"Software\Microsoft\Windows\CurrentVersion\Internet Settings");
+
+    let value = wide("ProxyEnable");
+    let mut enabled: DWORD = 0;
+    let mut size = std::mem::size_of::<DWORD>() as DWORD;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            key.as_ptr(),
+            value.as_ptr(),
+            RRF_RT_REG_DWORD,
+            std::ptr::null_mut(),
+            &mut enabled as *mut DWORD as *mut _,
+            &mut size,
+        )
+    };
+    if status != 0 || enabled == 0 {
+        return None;
+    }
+
+    let value = wide("ProxyServer");
+    let mut server = [0u16; 512];
+    let mut size = std::mem::size_of_val(&server) as DWORD;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            key.as_ptr(),
+            value.as_ptr(),
+            RRF_RT_REG_SZ,
+            std::ptr::null_mut(),
+            server.as_mut_ptr() as *mut _,
+            &mut size,
+        )
+    };
+    if status != 0 {
+        return None;
+    }
+    let len = server.iter().position(|&c| c == 0).unwrap_or(server.len());
+    Some(String::from_utf16_lossy(&server[..len])).filter(|s| !s.is_empty())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn windows_proxy() -> Option<String> {
+    None
+}
+
+/// Headers that name an intermediary
+#[cfg(feature = "net-checks")]
+const PROXY_HEADERS: &[&str] = &["via", "proxy-agent", "x-squid-error", "x-cache"];
+
+/// Server header values of common proxies and secure web gateways (lowercase)
+#[cfg(feature = "net-checks")]
+const PROXY_SERVERS: &[&str] = &["squid", "zscaler", "bluecoat", "mcafee web gateway", "fortigate", "netskope"];
+
+#[cfg(feature = "net-checks")]
+impl Evidence {
+    /// From a response; `provider` lists (header, value prefix) pairs only the
+    /// provider sends (empty prefix: any value)
+    pub fn from_response(response: &reqwest::blocking::Response, provider: &[(&str, &str)]) -> Self {
+        let headers = response.headers();
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+        let from_provider = provider
+            .iter()
+            .any(|(name, prefix)| header(name).map(|v| v.starts_with(prefix)).unwrap_or(false));
+        let proxy = PROXY_HEADERS
+            .iter()
+            .find_map(|name| header(name).map(|v| format!("{}: {}", name, v)))
+            .or_else(|| {
+                header("server")
+                    .filter(|server| PROXY_SERVERS.iter().any(|p| server.to_lowercase().contains(p)))
+                    .map(|server| format!("server: {}", server))
+            });
+        let clock_skew_secs = header("date")
+            .and_then(|date| chrono::DateTime::parse_from_rfc2822(date).ok())
+            .map(|date| date.timestamp() - chrono::Utc::now().timestamp());
+
+        Self {
+            status_code: Some(response.status().as_u16()),
+            from_provider,
+            proxy,
+            clock_skew_secs,
+            os_error: None,
+            cert_time_error: false,
+            unreachable_by_name: false,
+        }
+    }
+
+    /// From a request that got no response
+    pub fn from_error(error: &reqwest::Error) -> Self {
+        let mut os_error = None;
+        let mut text = error.to_string().to_lowercase();
+        let mut source = std::error::Error::source(error);
+        while let Some(e) = source {
+            if let Some(io) = e.downcast_ref::<std::io::Error>() {
+                os_error = os_error.or(io.raw_os_error());
+            }
+            text.push(' ');
+            text.push_str(&e.to_string().to_lowercase());
+            source = e.source();
+        }
+
+        Self {
+            os_error,
+            cert_time_error: text.contains("certificate") && (text.contains("expired") || text.contains("not yet valid")),
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claude(status_code: u16, from_provider: bool, proxy: Option<&str>) -> DiagnosticReport {
+        let evidence = Evidence { status_code: Some(status_code), from_provider, proxy: proxy.map(str::to_string), ..Default::default() };
+        let claude = CheckResult::new("CLAUDE API", CheckStatus::Error, "HTTP error").with_evidence(evidence);
+        DiagnosticReport { claude_api: Some(claude), ..Default::default() }
+    }
+
+    #[test]
+    fn provider_529_is_the_provider() {
+        let verdict = analyze(&claude(529, true, None)).map(|v| v.cause);
+        assert_eq!(verdict, Some(Cause::Provider));
+    }
+
+    #[test]
+    fn bare_503_via_a_proxy_is_the_proxy() {
+        let verdict = analyze(&claude(503, false, Some("1.1 corp-proxy"))).map(|v| v.cause);
+        assert_eq!(verdict, Some(Cause::Proxy));
+    }
+
+    #[test]
+    fn client_errors_are_no_symptom() {
+        assert!(analyze(&claude(401, true, None)).is_none());
+    }
+}
@@ -0,0 +1,287 @@
+//! Certificate checks
+//!
+//! Older Windows builds with automatic root updates turned off keep a stale
+//! root store, and API calls then fail with opaque TLS errors. Users proxying
+//! OpenCode through their own gateways also get an expiry pre-warning for
+//! those endpoints.
+
+use crate::diagnostics::{CheckResult, CheckStatus, HttpProfile};
+#[cfg(feature = "net-checks")]
+use crate::diagnostics::traffic;
+#[cfg(feature = "net-checks")]
+use std::time::Duration;
+
+/// Subject CNs of the root CAs the AI providers chain to
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const PROVIDER_ROOTS: &[&str] = &[
+    "ISRG Root X1",
+    "GTS Root R1",
+    "GTS Root R4",
+    "DigiCert Global Root G2",
+];
+
+/// A root certificate from the machine store
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+struct RootCert {
+    subject: String,
+    not_after: chrono::NaiveDate,
+}
+
+/// Check the Windows root store has current provider roots and auto-updates on
+#[cfg(target_os = "windows")]
+pub fn check_root_store() -> CheckResult {
+    let roots = match load_root_store() {
+        Ok(roots) => roots,
+        Err(e) => return CheckResult::new("ROOT CA", CheckStatus::Unknown, &e),
+    };
+    evaluate(&roots, root_auto_update_disabled())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn check_root_store() -> CheckResult {
+    CheckResult::new("ROOT CA", CheckStatus::Inactive, "Root store check only available on Windows")
+}
+
+/// Compare the store against the provider roots
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn evaluate(roots: &[RootCert], auto_update_disabled: bool) -> CheckResult {
+    let today = chrono::Local::now().date_naive();
+    let mut missing = Vec::new();
+    let mut expired = Vec::new();
+
+    for cn in PROVIDER_ROOTS {
+        let needle = format!("CN={}", cn);
+        let newest = roots
+            .iter()
+            .filter(|r| r.subject.split(", ").any(|part| part == needle))
+            .map(|r| r.not_after)
+            .max();
+        match newest {
+            None => missing.push(*cn),
+            Some(date) if date < today => expired.push(*cn),
+            Some(_) => {}
+        }
+    }
+
+    let present = PROVIDER_ROOTS.len() - missing.len() - expired.len();
+    let mut details = format!("{}/{} provider roots", present, PROVIDER_ROOTS.len());
+    if !missing.is_empty() {
+        details.push_str(&format!(" :: missing {}", missing.join(", ")));
+    }
+    if !expired.is_empty() {
+        details.push_str(&format!(" :: expired {}", expired.join(", ")));
+    }
+    details.push_str(if auto_update_disabled { " :: auto-update OFF" } else { " :: auto-update on" });
+
+    let stale = !missing.is_empty() || !expired.is_empty();
+    match (stale, auto_update_disabled) {
+        (true, true) => CheckResult::new("ROOT CA", CheckStatus::Error, &details).with_message(
+            "Root store is stale and automatic root updates are disabled by policy. API TLS handshakes will fail.",
+        ),
+        // Windows fetches missing roots on first use, expired ones are replaced the same way
+        (true, false) if !expired.is_empty() => CheckResult::new("ROOT CA", CheckStatus::Warning, &details)
+            .with_message("Expired provider root in the store. Run Windows Update to refresh it."),
+        (false, true) => CheckResult::new("ROOT CA", CheckStatus::Warning, &details)
+            .with_message("Automatic root updates are disabled. Future root changes will break API TLS."),
+        _ => CheckResult::new("ROOT CA", CheckStatus::Ok, &details),
+    }
+}
+
+/// List LocalMachine\Root as "subject|yyyy-MM-dd" lines
+#[cfg(target_os = "windows")]
+fn load_root_store() -> Result<Vec<RootCert>, String> {
+    let script = "Get-ChildItem Cert:\\LocalMachine\\Root | ForEach-Object { $_.Subject + '|' + $_.NotAfter.ToString('yyyy-MM-dd') }";
+    let output = crate::diagnostics::command("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .output()
+        .map_err(|e| format!("powershell failed: {}", e))?;
+    if !output.status.success() {
+        return Err("Root store query failed".to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let roots: Vec<RootCert> = stdout
+        .lines()
+        .filter_map(|line| {
+            let (subject, date) = line.trim().rsplit_once('|')?;
+            Some(RootCert {
+                subject: subject.to_string(),
+                not_after: chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?,
+            })
+        })
+        .collect();
+
+    if roots.is_empty() {
+        return Err("Root store is empty or unreadable".to_string());
+    }
+    Ok(roots)
+}
+
+/// DisableRootAutoUpdate policy (set by some "debloat" tools and older GPOs)
+#[cfg(target_os = "windows")]
+fn root_auto_update_disabled() -> bool {
+    let output = crate::diagnostics::command("reg")
+        .args([
+            "query",
+            "HKLM\\SOFTWARE\\Policies\\Microsoft\\SystemCertificates\\AuthRoot",
+            "/v",
+            "DisableRootAutoUpdate",
+        ])
+        .output();
+    match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).contains("0x1"),
+        Err(_) => false,
+    }
+}
+
+/// Days until the TLS certificate of each custom endpoint expires
+#[cfg(feature = "net-checks")]
+pub fn check_endpoint_certs(http: &HttpProfile, endpoints: &[String], warn_days: u32) -> CheckResult {
+    if endpoints.is_empty() {
+        return CheckResult::new("ENDPOINT CERTS", CheckStatus::Inactive, "No custom endpoints configured");
+    }
+
+    let client = match http.builder(Duration::from_secs(10)).tls_info(true).build() {
+        Ok(c) => c,
+        Err(_) => {
+            return CheckResult::new("ENDPOINT CERTS", CheckStatus::Error, "Failed to create HTTP client");
+        }
+    };
+
+    let now = chrono::Utc::now().naive_utc();
+    let mut status = CheckStatus::Ok;
+    let mut parts = Vec::new();
+    let mut expiring = Vec::new();
+
+    for endpoint in endpoints {
+        let url = normalize_endpoint(endpoint);
+        let host = endpoint_host(&url).unwrap_or_else(|| endpoint.clone());
+
+        if !url.starts_with("https://") {
+            parts.push(format!("{}: no TLS", host));
+            continue;
+        }
+
+        let response = client.head(&url).send();
+        traffic::record_http(&url, &response);
+        let not_after = response
+            .map_err(|e| if e.is_timeout() { "timeout" } else { "TLS/connect failed" })
+            .and_then(|response| {
+                response
+                    .extensions()
+                    .get::<reqwest::tls::TlsInfo>()
+                    .and_then(|info| info.peer_certificate())
+                    .and_then(cert_not_after)
+                    .ok_or("no certificate")
+            });
+
+        match not_after {
+            Ok(not_after) => {
+                let days = (not_after - now).num_days();
+                if days < 0 {
+                    status = CheckStatus::Error;
+                    parts.push(format!("{}: EXPIRED", host));
+                    expiring.push(format!("{} expired {}", host, not_after.format("%Y-%m-%d")));
+                } else if days <= warn_days as i64 {
+                    if status != CheckStatus::Error {
+                        status = CheckStatus::Warning;
+                    }
+                    parts.push(format!("{}: {}d", host, days));
+                    expiring.push(format!("{} expires {}", host, not_after.format("%Y-%m-%d")));
+                } else {
+                    parts.push(format!("{}: {}d", host, days));
+                }
+            }
+            Err(reason) => {
+                status = CheckStatus::Error;
+                parts.push(format!("{}: {}", host, reason));
+            }
+        }
+    }
+
+    let result = CheckResult::new("ENDPOINT CERTS", status, &parts.join(" :: "));
+    if expiring.is_empty() {
+        result
+    } else {
+        result.with_message(&format!("Renew certificates: {}", expiring.join(", ")))
+    }
+}
+
+#[cfg(not(feature = "net-checks"))]
+pub fn check_endpoint_certs(_http: &HttpProfile, _endpoints: &[String], _warn_days: u32) -> CheckResult {
+    CheckResult::new("ENDPOINT CERTS", CheckStatus::Inactive, "Network checks not included in this build")
+}
+
+/// Host part of an endpoint URL
+pub fn endpoint_host(url: &str) -> Option<String> {
+    let rest = normalize_endpoint(url);
+    let rest = rest.split_once("://").map(|(_, r)| r).unwrap_or(&rest);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let authority = authority.rsplit_once('@').map(|(_, h)| h).unwrap_or(authority);
+    let host = match authority.strip_prefix('[') {
+        Some(v6) => v6.split(']').next()?,
+        None => authority.split(':').next()?,
+    };
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
+/// Add https:// when the user typed a bare host
+pub fn normalize_endpoint(endpoint: &str) -> String {
+    let endpoint = endpoint.trim();
+    if endpoint.contains("://") {
+        endpoint.to_string()
+    } else {
+        format!("https://{}", endpoint)
+    }
+}
+
+/// notAfter of a DER-encoded X.509 certificate
+#[cfg_attr(not(feature = "net-checks"), allow(dead_code))]
+///
+/// Certificate ::= SEQUENCE { tbsCertificate SEQUENCE { [0] version OPTIONAL,
+/// serialNumber, signature, issuer, validity SEQUENCE { notBefore, notAfter } ... } ... }
+fn cert_not_after(der: &[u8]) -> Option<chrono::NaiveDateTime> {
+    let (_, cert, _) = read_tlv(der)?;
+    let (_, tbs, _) = read_tlv(cert)?;
+
+    // Skip [0] version when present, then the serial number
+    let (tag, _, after_first) = read_tlv(tbs)?;
+    let rest = if tag == 0xA0 { read_tlv(after_first)?.2 } else { after_first };
+    let (_, _, rest) = read_tlv(rest)?; // signature
+    let (_, _, rest) = read_tlv(rest)?; // issuer
+    let (_, validity, _) = read_tlv(rest)?;
+
+    let (_, _, validity) = read_tlv(validity)?; // notBefore
+    let (tag, time, _) = read_tlv(validity)?;
+    let time = std::str::from_utf8(time).ok()?;
+    match tag {
+        // UTCTime: YYMMDDHHMMSSZ, years 50-99 are 19xx
+        0x17 => {
+            let year: u32 = time.get(0..2)?.parse().ok()?;
+            let century = if year >= 50 { "19" } else { "20" };
+            chrono::NaiveDateTime::parse_from_str(&format!("{}{}", century, time), "%Y%m%d%H%M%SZ").ok()
+        }
+        // GeneralizedTime: YYYYMMDDHHMMSSZ
+        0x18 => chrono::NaiveDateTime::parse_from_str(time, "%Y%m%d%H%M%SZ").ok(),
+        _ => None,
+    }
+}
+
+/// Split one DER element into (tag, contents, remaining bytes)
+#[cfg_attr(not(feature = "net-checks"), allow(dead_code))]
+fn read_tlv(data: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let tag = *data.first()?;
+    let first = *data.get(1)? as usize;
+    let (len, header) = if first < 0x80 {
+        (first, 2)
+    } else {
+        let count = first & 0x7F;
+        if count == 0 || count > 4 {
+            return None;
+        }
+        let bytes = data.get(2..2 + count)?;
+        (bytes.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize), 2 + count)
+    };
+    let contents = data.get(header..header + len)?;
+    Some((tag, contents, &data[header + len..]))
+}
@@ -0,0 +1,267 @@
+//! OneDrive / Dropbox / iCloud sync under the project or OpenCode config
+//!
+//! Sync clients lock files while uploading them and, with files on demand,
+//! replace local copies with online-only placeholders. Both break agent file
+//! edits in ways that look like OpenCode bugs: EBUSY/EPERM on write, reads
+//! that stall while a file downloads, edits "reverted" by a sync conflict.
+
+use crate::diagnostics::{datadirs, processes, CheckResult, CheckStatus, SystemSnapshot};
+use std::path::{Path, PathBuf};
+
+/// Entries looked at per directory when counting placeholders
+const MAX_FILES: usize = 2000;
+
+/// How deep the placeholder count goes below each directory
+const MAX_DEPTH: usize = 3;
+
+/// Windows attributes of cloud files that are not on disk
+#[cfg(target_os = "windows")]
+const PLACEHOLDER_ATTRIBUTES: u32 = 0x0040_0000 /* RECALL_ON_DATA_ACCESS */ | 0x0004_0000 /* RECALL_ON_OPEN */ | 0x1000 /* OFFLINE */;
+
+/// "Always keep on this device"
+#[cfg(target_os = "windows")]
+const ATTRIBUTE_PINNED: u32 = 0x0008_0000;
+
+/// "Free up space": contents are made online-only
+#[cfg(target_os = "windows")]
+const ATTRIBUTE_UNPINNED: u32 = 0x0010_0000;
+
+/// A folder kept in sync by a cloud client
+pub struct SyncRoot {
+    pub provider: &'static str,
+    pub path: PathBuf,
+}
+
+/// Sync folders of the installed clients
+pub fn sync_roots() -> Vec<SyncRoot> {
+    let mut roots = Vec::new();
+    let mut add = |provider: &'static str, path: PathBuf| {
+        if path.is_dir() && !roots.iter().any(|r: &SyncRoot| r.path == path) {
+            roots.push(SyncRoot { provider, path });
+        }
+    };
+
+    // Set by the OneDrive client for personal and work accounts
+    for var in ["OneDrive", "OneDriveConsumer", "OneDriveCommercial"] {
+        if let Some(path) = std::env::var_os(var) {
+            add("OneDrive", PathBuf::from(path));
+        }
+    }
+    for path in dropbox_paths() {
+        add("Dropbox", path);
+    }
+
+    let Some(home) = dirs::home_dir() else {
+        return roots;
+    };
+    add("OneDrive", home.join("OneDrive"));
+    add("Dropbox", home.join("Dropbox"));
+    add("iCloud Drive", home.join("iCloudDrive"));
+    add("iCloud Drive", home.join("Library").join("Mobile Documents").join("com~apple~CloudDocs"));
+    // File Provider locations on macOS: "OneDrive-Personal", "GoogleDrive-me@example.com", ...
+    let cloud_storage = home.join("Library").join("CloudStorage");
+    for entry in std::fs::read_dir(cloud_storage).into_iter().flatten().flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let provider = match name.split('-').next().unwrap_or_default() {
+            "OneDrive" => "OneDrive",
+            "Dropbox" => "Dropbox",
+            "GoogleDrive" => "Google Drive",
+            "Box" => "Box",
+            _ => "cloud storage",
+        };
+        add(provider, entry.path());
+    }
+    roots
+}
+
+/// Folders listed in Dropbox's `info.json` (`{"personal": {"path": ...}, "business": {...}}`)
+fn dropbox_paths() -> Vec<PathBuf> {
+    let candidates = [
+        std::env::var_os("APPDATA").map(|p| PathBuf::from(p).join("Dropbox")),
+        std::env::var_os("LOCALAPPDATA").map(|p| PathBuf::from(p).join("Dropbox")),
+        dirs::home_dir().map(|home| home.join(".dropbox")),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .filter_map(|dir| std::fs::read_to_string(dir.join("info.json")).ok())
+        .filter_map(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+        .flat_map(|info| {
+            ["personal", "business"]
+                .iter()
+                .filter_map(|account| info[*account]["path"].as_str().map(PathBuf::from))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// The sync root `dir` is in, if any
+pub fn sync_root_of<'a>(dir: &Path, roots: &'a [SyncRoot]) -> Option<&'a SyncRoot> {
+    // Case-insensitive: Windows and macOS paths usually are
+    let dir = dir.display().to_string().to_lowercase();
+    roots.iter().find(|root| {
+        let root = root.path.display().to_string().to_lowercase();
+        dir == root || dir.starts_with(&format!("{}{}", root, std::path::MAIN_SEPARATOR))
+    })
+}
+
+#[cfg(target_os = "windows")]
+pub fn is_placeholder(metadata: &std::fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    metadata.file_attributes() & PLACEHOLDER_ATTRIBUTES != 0
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn is_placeholder(_metadata: &std::fs::Metadata) -> bool {
+    false
+}
+
+/// Pinned ("Always keep on this device"), unpinned ("Free up space") or neither
+#[cfg(target_os = "windows")]
+fn pin_state(dir: &Path) -> Option<&'static str> {
+    use std::os::windows::fs::MetadataExt;
+    let attributes = std::fs::metadata(dir).ok()?.file_attributes();
+    if attributes & ATTRIBUTE_PINNED != 0 {
+        Some("always kept")
+    } else if attributes & ATTRIBUTE_UNPINNED != 0 {
+        Some("free up space")
+    } else {
+        None
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn pin_state(_dir: &Path) -> Option<&'static str> {
+    None
+}
+
+/// Days after which Storage Sense makes unused OneDrive files online-only, `None` when off
+#[cfg(target_os = "windows")]
+fn storage_sense_days() -> Option<u32> {
+    let output = crate::diagnostics::command("reg")
+        .args(["query", "HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\StorageSense\\Parameters\\StoragePolicy"])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // "    01    REG_DWORD    0x1" is Storage Sense itself;
+    // "    OneDrive!S-1-5-...!Personal|abc    REG_DWORD    0x1e" the per-account setting
+    let value = |line: &str| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let data = fields.last()?.strip_prefix("0x")?;
+        Some((fields[0].to_string(), u32::from_str_radix(data, 16).ok()?))
+    };
+    let values: Vec<(String, u32)> = stdout.lines().filter_map(value).collect();
+    if !values.iter().any(|(name, data)| name == "01" && *data == 1) {
+        return None;
+    }
+    values
+        .iter()
+        .filter(|(name, data)| name.starts_with("OneDrive!") && *data > 0)
+        .map(|(_, days)| *days)
+        .min()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn storage_sense_days() -> Option<u32> {
+    None
+}
+
+/// Online-only files below `dir`, up to the walk limits
+fn count_placeholders(dir: &Path) -> usize {
+    let mut placeholders = 0;
+    let mut scanned = 0;
+    let mut pending = vec![(dir.to_path_buf(), 0)];
+    while let Some((current, depth)) = pending.pop() {
+        for entry in std::fs::read_dir(&current).into_iter().flatten().flatten() {
+            if scanned >= MAX_FILES {
+                return placeholders;
+            }
+            scanned += 1;
+            // Attributes come from the directory listing; no download is triggered
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if is_placeholder(&metadata) {
+                placeholders += 1;
+            } else if metadata.is_dir() && depth < MAX_DEPTH {
+                pending.push((entry.path(), depth + 1));
+            }
+        }
+    }
+    placeholders
+}
+
+/// (label, directory): project directories of running instances and OpenCode's config
+fn watched_dirs(snapshot: &mut SystemSnapshot) -> Vec<(&'static str, PathBuf)> {
+    let mut dirs: Vec<(&'static str, PathBuf)> = processes::opencode_roots(snapshot)
+        .into_iter()
+        .filter_map(|(_, cwd)| Some(("project", cwd?)))
+        .collect();
+    dirs.extend(datadirs::opencode_dirs().into_iter().filter(|(label, dir)| *label == "config" && dir.exists()));
+    let mut seen = std::collections::HashSet::new();
+    dirs.retain(|(_, dir)| seen.insert(dir.display().to_string().to_lowercase()));
+    dirs
+}
+
+pub fn check_cloud_sync(snapshot: &mut SystemSnapshot) -> CheckResult {
+    let roots = sync_roots();
+    let dirs = watched_dirs(snapshot);
+    if dirs.is_empty() {
+        return CheckResult::new("CLOUD SYNC", CheckStatus::Inactive, "No OpenCode project or config directory found");
+    }
+
+    let mut details = Vec::new();
+    let mut synced = Vec::new();
+    let mut on_demand = Vec::new();
+    for (label, dir) in &dirs {
+        let Some(root) = sync_root_of(dir, &roots) else {
+            details.push(format!("{} {} not synced", label, dir.display()));
+            continue;
+        };
+        let mut notes = vec![root.provider.to_string()];
+        let pin = pin_state(dir);
+        if let Some(pin) = pin {
+            notes.push(pin.to_string());
+        }
+        let placeholders = count_placeholders(dir);
+        if placeholders > 0 {
+            notes.push(format!("{} online-only", placeholders));
+        }
+        details.push(format!("{} {} {}", label, dir.display(), notes.join(", ")));
+
+        if placeholders > 0 || pin == Some("free up space") {
+            on_demand.push((dir, root.provider, placeholders));
+        } else {
+            synced.push((dir, root.provider));
+        }
+    }
+
+    let storage_sense = storage_sense_days();
+    if let Some(days) = storage_sense {
+        details.push(format!("Storage Sense frees OneDrive files after {} days", days));
+    }
+    let details = details.join(" :: ");
+
+    if let Some((dir, provider, placeholders)) = on_demand.first() {
+        return CheckResult::new("CLOUD SYNC", CheckStatus::Warning, &details).with_message(&format!(
+            "{} in {} has {} online-only files; agent reads stall and edits fail while they download. Right-click the folder > Always keep on this device, or move the project out of {}.",
+            dir.display(),
+            provider,
+            placeholders,
+            provider
+        ));
+    }
+    if let Some((dir, provider)) = synced.first() {
+        let sense = match storage_sense {
+            Some(days) if *provider == "OneDrive" => format!(" Storage Sense will make unused files online-only after {} days.", days),
+            _ => String::new(),
+        };
+        return CheckResult::new("CLOUD SYNC", CheckStatus::Warning, &details).with_message(&format!(
+            "{} is synced by {}, which locks files while uploading (EBUSY/EPERM on agent edits) and can revert them with sync conflicts. Move the project out or pause syncing while working.{}",
+            dir.display(),
+            provider,
+            sense
+        ));
+    }
+    CheckResult::new("CLOUD SYNC", CheckStatus::Ok, &details)
+}
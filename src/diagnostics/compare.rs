@@ -0,0 +1,185 @@
+//! A/B compare mode: run the same checks before and after the user toggles
+//! one variable (VPN, Wi-Fi vs wired, ...) and show the delta side by side.
+//! The same delta picks the cards to highlight after each refresh.
+
+use crate::diagnostics::{CheckStatus, DiagnosticReport};
+
+/// Variables offered for comparison: (label, what the user should do between runs)
+pub const COMPARE_PRESETS: &[(&str, &str)] = &[
+    ("WIRED vs WI-FI", "Switch between Ethernet and Wi-Fi."),
+    ("VPN ON vs OFF", "Toggle your VPN connection."),
+    ("PROXY ON vs OFF", "Toggle the system or OpenCode proxy."),
+    ("OTHER NETWORK", "Switch to another network (e.g. a phone hotspot)."),
+];
+
+/// Where the comparison currently is
+#[derive(Clone, Copy, PartialEq)]
+pub enum CompareStage {
+    /// Running the A checks
+    Baseline,
+    /// Waiting for the user to toggle the variable
+    Toggle,
+    /// Running the B checks
+    Variant,
+    Done,
+}
+
+/// One check row of the delta table
+pub struct DeltaRow {
+    pub name: String,
+    pub before: Option<(CheckStatus, Option<u64>)>,
+    pub after: Option<(CheckStatus, Option<u64>)>,
+}
+
+impl DeltaRow {
+    /// Latency change B - A in ms, when both sides have latency
+    pub fn latency_delta(&self) -> Option<i64> {
+        let a = self.before?.1?;
+        let b = self.after?.1?;
+        Some(b as i64 - a as i64)
+    }
+}
+
+/// Smallest latency change worth highlighting: this many ms...
+const MATERIAL_LATENCY_MS: u64 = 100;
+/// ...and this share of the previous latency
+const MATERIAL_LATENCY_RATIO: f64 = 0.5;
+
+/// Names of the checks whose status changed, or whose latency moved
+/// materially, between two consecutive runs (checks in only one are skipped)
+pub fn changed_checks(before: &DiagnosticReport, after: &DiagnosticReport) -> Vec<String> {
+    let before = before.checks();
+    after
+        .checks()
+        .into_iter()
+        .filter(|(id, b)| {
+            let Some((_, a)) = before.iter().find(|(i, _)| i == id) else {
+                return false;
+            };
+            let latency_moved = match (a.latency_ms, b.latency_ms) {
+                (Some(x), Some(y)) => {
+                    let delta = x.abs_diff(y);
+                    delta >= MATERIAL_LATENCY_MS && delta as f64 >= x as f64 * MATERIAL_LATENCY_RATIO
+                }
+                _ => false,
+            };
+            a.status != b.status || latency_moved
+        })
+        .map(|(_, check)| check.name.clone())
+        .collect()
+}
+
+/// An A/B comparison in progress
+pub struct Comparison {
+    pub preset: usize,
+    pub stage: CompareStage,
+    pub before: Option<DiagnosticReport>,
+    pub after: Option<DiagnosticReport>,
+}
+
+impl Comparison {
+    pub fn new(preset: usize) -> Self {
+        Self {
+            preset,
+            stage: CompareStage::Baseline,
+            before: None,
+            after: None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        COMPARE_PRESETS[self.preset].0
+    }
+
+    pub fn instruction(&self) -> &'static str {
+        COMPARE_PRESETS[self.preset].1
+    }
+
+    /// Store a completed run for the stage that was waiting on it
+    pub fn apply_report(&mut self, report: &DiagnosticReport) {
+        match self.stage {
+            CompareStage::Baseline => {
+                self.before = Some(report.clone());
+                self.stage = CompareStage::Toggle;
+            }
+            CompareStage::Variant => {
+                self.after = Some(report.clone());
+                self.stage = CompareStage::Done;
+            }
+            _ => {}
+        }
+    }
+
+    /// Rows for every check present in either run, A order first
+    pub fn rows(&self) -> Vec<DeltaRow> {
+        let before = self.before.as_ref().map(|r| r.checks()).unwrap_or_default();
+        let after = self.after.as_ref().map(|r| r.checks()).unwrap_or_default();
+
+        let mut ids: Vec<&str> = before.iter().map(|(id, _)| *id).collect();
+        for (id, _) in &after {
+            if !ids.contains(id) {
+                ids.push(id);
+            }
+        }
+
+        ids.into_iter()
+            .map(|id| {
+                let a = before.iter().find(|(i, _)| *i == id).map(|(_, c)| *c);
+                let b = after.iter().find(|(i, _)| *i == id).map(|(_, c)| *c);
+                DeltaRow {
+                    name: a.or(b).map(|c| c.name.clone()).unwrap_or_default(),
+                    before: a.map(|c| (c.status, c.latency_ms)),
+                    after: b.map(|c| (c.status, c.latency_ms)),
+                }
+            })
+            .collect()
+    }
+
+    /// Plain-text table for the clipboard
+    pub fn to_text(&self) -> String {
+        let mut text = format!("=== A/B Compare: {} ===\n", self.label());
+        text.push_str(&format!("{:<16} {:<14} {:<14} {}\n", "CHECK", "A", "B", "DELTA"));
+        for row in self.rows() {
+            let delta = row.latency_delta().map(|d| format!("{:+}ms", d)).unwrap_or_default();
+            text.push_str(&format!(
+                "{:<16} {:<14} {:<14} {}\n",
+                row.name,
+                format_cell(row.before),
+                format_cell(row.after),
+                delta
+            ));
+        }
+        text
+    }
+}
+
+/// "OK 120ms" / "ERROR" / "-"
+pub fn format_cell(cell: Option<(CheckStatus, Option<u64>)>) -> String {
+    match cell {
+        Some((status, Some(ms))) => format!("{} {}ms", status.label(), ms),
+        Some((status, None)) => status.label().to_string(),
+        None => "-".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::CheckResult;
+
+    fn run(claude: (CheckStatus, u128), openai: (CheckStatus, u128), google: (CheckStatus, u128)) -> DiagnosticReport {
+        let check = |name: &str, (status, ms): (CheckStatus, u128)| Some(CheckResult::new(name, status, "").with_latency(ms));
+        let mut report = DiagnosticReport::new();
+        report.claude_api = check("CLAUDE API", claude);
+        report.openai_api = check("OPENAI API", openai);
+        report.google_api = check("GOOGLE API", google);
+        report
+    }
+
+    #[test]
+    fn status_flip_and_doubled_latency_not_jitter() {
+        let before = run((CheckStatus::Ok, 300), (CheckStatus::Ok, 300), (CheckStatus::Ok, 300));
+        let after = run((CheckStatus::Error, 300), (CheckStatus::Ok, 700), (CheckStatus::Ok, 380));
+        assert_eq!(changed_checks(&before, &after), ["CLAUDE API", "OPENAI API"]);
+    }
+}
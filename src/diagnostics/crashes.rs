@@ -0,0 +1,198 @@
+//! Recent crashes of OpenCode (and the Node/Bun runtimes it runs on)
+//!
+//! A crashing OpenCode looks much like a dropped API session from the user's
+//! side. The check lists crash reports and dumps from the last week so
+//! repeated silent crashes stand out: Windows Error Reporting and
+//! `%LOCALAPPDATA%\CrashDumps` on Windows, systemd-coredump and
+//! `/var/crash` on Linux, DiagnosticReports on macOS.
+
+use crate::diagnostics::{CheckResult, CheckStatus};
+use chrono::{DateTime, Local};
+#[cfg(not(target_os = "linux"))]
+use std::path::Path;
+
+/// How far back crashes are counted
+const CRASH_WINDOW_DAYS: i64 = 7;
+
+/// Crashes at which the check turns red
+const REPEATED_CRASHES: usize = 3;
+
+/// Crashes listed in the details
+const MAX_LISTED: usize = 5;
+
+/// Process names that count (without extension)
+const WATCHED: &[&str] = &["opencode", "node", "bun"];
+
+/// One crash report or dump
+#[cfg_attr(not(any(target_os = "windows", target_os = "linux", target_os = "macos")), allow(dead_code))]
+struct Crash {
+    process: String,
+    time: DateTime<Local>,
+    /// "AppCrash", "dump", "SIGSEGV", ...
+    kind: String,
+}
+
+/// Whether a file or directory name belongs to a watched process
+#[cfg_attr(not(any(target_os = "windows", target_os = "linux", target_os = "macos")), allow(dead_code))]
+fn is_watched(process: &str) -> bool {
+    let lower = process.to_lowercase();
+    let stem = lower.strip_suffix(".exe").unwrap_or(&lower);
+    WATCHED.contains(&stem)
+}
+
+pub fn check_crashes() -> CheckResult {
+    let since = Local::now() - chrono::Duration::days(CRASH_WINDOW_DAYS);
+    let mut crashes: Vec<Crash> = find_crashes().into_iter().filter(|c| c.time >= since).collect();
+    crashes.sort_by_key(|c| std::cmp::Reverse(c.time));
+    // A WER report and its dump describe the same crash
+    crashes.dedup_by(|a, b| a.process.eq_ignore_ascii_case(&b.process) && (b.time - a.time).num_seconds().abs() < 60);
+
+    let Some(last) = crashes.first() else {
+        return CheckResult::new("CRASHES", CheckStatus::Ok, &format!("None in the last {} days", CRASH_WINDOW_DAYS));
+    };
+
+    let listed: Vec<String> = crashes
+        .iter()
+        .take(MAX_LISTED)
+        .map(|c| format!("{} {} ({})", c.process, c.time.format("%Y-%m-%d %H:%M"), c.kind))
+        .collect();
+    let more = match crashes.len().saturating_sub(MAX_LISTED) {
+        0 => String::new(),
+        n => format!(" :: +{} more", n),
+    };
+    let details = format!("{} in {} days :: {}{}", crashes.len(), CRASH_WINDOW_DAYS, listed.join(" :: "), more);
+
+    let hours = (Local::now() - last.time).num_hours();
+    let ago = if hours < 1 { "under an hour ago".to_string() } else if hours < 48 { format!("{}h ago", hours) } else { format!("{}d ago", hours / 24) };
+    if crashes.len() >= REPEATED_CRASHES {
+        CheckResult::new("CRASHES", CheckStatus::Error, &details).with_message(&format!(
+            "{} crashed {} times in {} days (last {}). Dropped sessions may be crashes, not the API; check OpenCode's log.",
+            last.process,
+            crashes.len(),
+            CRASH_WINDOW_DAYS,
+            ago
+        ))
+    } else {
+        CheckResult::new("CRASHES", CheckStatus::Warning, &details)
+            .with_message(&format!("{} crashed {}.", last.process, ago))
+    }
+}
+
+/// Modification time of a file or directory
+#[cfg(not(target_os = "linux"))]
+fn modified(path: &Path) -> Option<DateTime<Local>> {
+    std::fs::metadata(path).ok()?.modified().ok().map(DateTime::<Local>::from)
+}
+
+/// WER reports (`AppCrash_opencode.exe_...`) and `CrashDumps\opencode.exe.1234.dmp`
+#[cfg(target_os = "windows")]
+fn find_crashes() -> Vec<Crash> {
+    let mut crashes = Vec::new();
+    let roots = [std::env::var_os("LOCALAPPDATA"), std::env::var_os("ProgramData")];
+    for root in roots.into_iter().flatten().map(std::path::PathBuf::from) {
+        for queue in ["ReportArchive", "ReportQueue"] {
+            let dir = root.join("Microsoft").join("Windows").join("WER").join(queue);
+            for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let mut parts = name.splitn(3, '_');
+                let (Some(kind), Some(process)) = (parts.next(), parts.next()) else {
+                    continue;
+                };
+                if !matches!(kind, "AppCrash" | "AppHang") || !is_watched(process) {
+                    continue;
+                }
+                let report = entry.path().join("Report.wer");
+                if let Some(time) = modified(&report).or_else(|| modified(&entry.path())) {
+                    crashes.push(Crash { process: process.to_string(), time, kind: kind.to_string() });
+                }
+            }
+        }
+    }
+
+    let dumps = std::env::var_os("LOCALAPPDATA").map(|p| std::path::PathBuf::from(p).join("CrashDumps"));
+    for entry in dumps.into_iter().flat_map(|dir| std::fs::read_dir(dir).into_iter().flatten().flatten()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // "<exe>.<pid>.dmp"
+        let process = name.split(".exe.").next().map(|stem| format!("{}.exe", stem)).unwrap_or_default();
+        if name.ends_with(".dmp") && is_watched(&process) {
+            if let Some(time) = modified(&entry.path()) {
+                crashes.push(Crash { process, time, kind: "dump".to_string() });
+            }
+        }
+    }
+    crashes
+}
+
+/// systemd-coredump entries and apport reports in `/var/crash`
+#[cfg(target_os = "linux")]
+fn find_crashes() -> Vec<Crash> {
+    let mut crashes = Vec::new();
+    let since = format!("-{}d", CRASH_WINDOW_DAYS);
+    let output = crate::diagnostics::command("coredumpctl")
+        .args(["list", "--no-pager", "--no-legend", "--since", &since])
+        .output();
+    if let Ok(output) = output {
+        // "Thu 2026-10-15 08:00:00 UTC  1234  1000  1000 SIGSEGV present  /usr/bin/node  1.2M"
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                continue;
+            }
+            let process = fields[9].rsplit('/').next().unwrap_or_default();
+            if !is_watched(process) {
+                continue;
+            }
+            let stamp = format!("{} {}", fields[1], fields[2]);
+            let Some(time) = chrono::NaiveDateTime::parse_from_str(&stamp, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .and_then(|t| t.and_local_timezone(Local).single())
+            else {
+                continue;
+            };
+            crashes.push(Crash { process: process.to_string(), time, kind: fields[7].to_string() });
+        }
+    }
+
+    // "_usr_bin_node.1000.crash"
+    for entry in std::fs::read_dir("/var/crash").into_iter().flatten().flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(stem) = name.strip_suffix(".crash") else {
+            continue;
+        };
+        let exe = stem.split('.').next().unwrap_or_default();
+        let process = exe.rsplit('_').next().unwrap_or_default();
+        if !is_watched(process) {
+            continue;
+        }
+        if let Some(time) = entry.metadata().ok().and_then(|m| m.modified().ok()) {
+            crashes.push(Crash { process: process.to_string(), time: time.into(), kind: "apport".to_string() });
+        }
+    }
+    crashes
+}
+
+/// `~/Library/Logs/DiagnosticReports/opencode-2026-10-15-080000.ips`
+#[cfg(target_os = "macos")]
+fn find_crashes() -> Vec<Crash> {
+    let mut crashes = Vec::new();
+    let dir = dirs::home_dir().map(|home| home.join("Library").join("Logs").join("DiagnosticReports"));
+    for entry in dir.into_iter().flat_map(|dir| std::fs::read_dir(dir).into_iter().flatten().flatten()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !(name.ends_with(".ips") || name.ends_with(".crash")) {
+            continue;
+        }
+        let process = name.split(['-', '_', '.']).next().unwrap_or_default();
+        if !is_watched(process) {
+            continue;
+        }
+        if let Some(time) = modified(&entry.path()) {
+            crashes.push(Crash { process: process.to_string(), time, kind: "report".to_string() });
+        }
+    }
+    crashes
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn find_crashes() -> Vec<Crash> {
+    Vec::new()
+}
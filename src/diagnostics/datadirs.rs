@@ -0,0 +1,202 @@
+//! Permissions in OpenCode's config, data, cache and state directories
+//!
+//! A directory OpenCode cannot write (an ACL, files left behind by `sudo`),
+//! a dangling symlink, or OneDrive "files on demand" placeholders in a synced
+//! home folder all surface mid-run as EPERM/ENOENT errors that name a file
+//! deep in the cache rather than the cause.
+
+use crate::diagnostics::{cloudsync, CheckResult, CheckStatus};
+use std::path::{Path, PathBuf};
+
+/// Files looked at per directory; caches can hold many thousands
+const MAX_FILES: usize = 5000;
+
+/// How deep the walk goes below each directory
+const MAX_DEPTH: usize = 6;
+
+/// `$var/opencode`, or `~/<default>/opencode` when the variable is unset
+fn xdg_dir(var: &str, default: &[&str]) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| default.iter().fold(home, |path, part| path.join(part))))
+        .map(|base| base.join("opencode"))
+}
+
+/// OpenCode's data directory (logs, sessions), `~/.local/share/opencode` by default
+pub fn data_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", &[".local", "share"])
+}
+
+/// Each XDG base directory OpenCode uses (the same layout on Windows)
+pub fn opencode_dirs() -> Vec<(&'static str, PathBuf)> {
+    [
+        ("config", xdg_dir("XDG_CONFIG_HOME", &[".config"])),
+        ("data", data_dir()),
+        ("cache", xdg_dir("XDG_CACHE_HOME", &[".cache"])),
+        ("state", xdg_dir("XDG_STATE_HOME", &[".local", "state"])),
+    ]
+    .into_iter()
+    .filter_map(|(label, dir)| Some((label, dir?)))
+    .collect()
+}
+
+/// What was found in one directory
+#[derive(Default)]
+struct DirReport {
+    /// Why writing a probe file failed
+    unwritable: Option<String>,
+    /// Symlinks whose target is gone
+    broken_links: Vec<PathBuf>,
+    read_only: usize,
+    /// Owned by another user (typically created under sudo)
+    foreign: usize,
+    /// Cloud files not on disk (OneDrive files on demand)
+    placeholders: usize,
+    scanned: usize,
+}
+
+pub fn check_data_dirs() -> CheckResult {
+    let dirs = opencode_dirs();
+    if dirs.is_empty() {
+        return CheckResult::new("DATA DIRS", CheckStatus::Unknown, "Could not determine home directory");
+    }
+
+    let sync_roots = cloudsync::sync_roots();
+    let mut details = Vec::new();
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    for (label, dir) in &dirs {
+        // Dangling symlink in place of the directory itself
+        if std::fs::symlink_metadata(dir).is_ok() && !dir.exists() {
+            details.push(format!("{} broken symlink", label));
+            errors.push(format!("{} is a symlink to a missing target", dir.display()));
+            continue;
+        }
+        if !dir.exists() {
+            details.push(format!("{} not created yet", label));
+            continue;
+        }
+
+        let report = inspect(dir);
+        let mut notes = Vec::new();
+        if let Some(root) = cloudsync::sync_root_of(dir, &sync_roots) {
+            notes.push(format!("in {}", root.provider));
+        }
+        if std::fs::symlink_metadata(dir).map(|m| m.file_type().is_symlink()).unwrap_or(false) {
+            notes.push("symlink".to_string());
+        }
+        if let Some(ref e) = report.unwritable {
+            notes.push("NOT WRITABLE".to_string());
+            errors.push(format!("Cannot write to {}: {}", dir.display(), e));
+        }
+        if !report.broken_links.is_empty() {
+            notes.push(format!("{} broken links", report.broken_links.len()));
+            errors.push(format!("Broken symlink {}", report.broken_links[0].display()));
+        }
+        if report.placeholders > 0 {
+            notes.push(format!("{} online-only", report.placeholders));
+            warnings.push(format!(
+                "{} files in {} are online-only cloud files; right-click the folder > Always keep on this device",
+                report.placeholders,
+                dir.display()
+            ));
+        }
+        if report.foreign > 0 {
+            notes.push(format!("{} owned by another user", report.foreign));
+            warnings.push(format!(
+                "{} files in {} belong to another user (created with sudo?): sudo chown -R $USER \"{}\"",
+                report.foreign,
+                dir.display(),
+                dir.display()
+            ));
+        }
+        if report.read_only > 0 {
+            notes.push(format!("{} read-only", report.read_only));
+            warnings.push(format!("{} read-only files in {}: {}", report.read_only, dir.display(), read_only_fix(dir)));
+        }
+        if notes.is_empty() {
+            notes.push(format!("OK ({} files)", report.scanned));
+        }
+        details.push(format!("{} {} {}", label, dir.display(), notes.join(", ")));
+    }
+
+    let details = details.join(" :: ");
+    if let Some(error) = errors.first() {
+        CheckResult::new("DATA DIRS", CheckStatus::Error, &details).with_message(error)
+    } else if let Some(warning) = warnings.first() {
+        CheckResult::new("DATA DIRS", CheckStatus::Warning, &details).with_message(warning)
+    } else {
+        CheckResult::new("DATA DIRS", CheckStatus::Ok, &details)
+    }
+}
+
+/// Write probe, then walk the tree (without following links)
+fn inspect(dir: &Path) -> DirReport {
+    let mut report = DirReport::default();
+    let probe = dir.join(format!(".opencode-diag-probe-{}", std::process::id()));
+    let written = std::fs::write(&probe, b"probe").and_then(|_| std::fs::symlink_metadata(&probe));
+    let _ = std::fs::remove_file(&probe);
+    let owner = match written {
+        Ok(metadata) => owner_of(&metadata),
+        Err(e) => {
+            report.unwritable = Some(e.to_string());
+            None
+        }
+    };
+
+    let mut pending = vec![(dir.to_path_buf(), 0)];
+    while let Some((current, depth)) = pending.pop() {
+        for entry in std::fs::read_dir(&current).into_iter().flatten().flatten() {
+            if report.scanned >= MAX_FILES {
+                return report;
+            }
+            report.scanned += 1;
+            let path = entry.path();
+            let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if metadata.file_type().is_symlink() {
+                if !path.exists() {
+                    report.broken_links.push(path);
+                }
+                continue;
+            }
+            if cloudsync::is_placeholder(&metadata) {
+                report.placeholders += 1;
+            }
+            if owner.is_some() && owner_of(&metadata) != owner {
+                report.foreign += 1;
+            }
+            if metadata.is_dir() {
+                if depth < MAX_DEPTH {
+                    pending.push((path, depth + 1));
+                }
+            } else if metadata.permissions().readonly() {
+                report.read_only += 1;
+            }
+        }
+    }
+    report
+}
+
+#[cfg(unix)]
+fn owner_of(metadata: &std::fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.uid())
+}
+
+/// Ownership problems show up as a failed write probe on Windows
+#[cfg(not(unix))]
+fn owner_of(_metadata: &std::fs::Metadata) -> Option<u32> {
+    None
+}
+
+#[cfg(target_os = "windows")]
+fn read_only_fix(dir: &Path) -> String {
+    format!("attrib -r \"{}\\*\" /s /d", dir.display())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn read_only_fix(dir: &Path) -> String {
+    format!("chmod -R u+w \"{}\"", dir.display())
+}
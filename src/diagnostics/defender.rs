@@ -0,0 +1,168 @@
+//! Windows Defender scan overhead on OpenCode's directories
+//!
+//! Real-time scanning of every file an agent writes or reads can make tool
+//! calls in large projects crawl. The check measures file-access latency in
+//! the OpenCode install directory and the project directories of running
+//! instances, looks up whether they are excluded, and suggests (never
+//! applies) an exclusion when scanning is the bottleneck.
+
+use crate::diagnostics::{CheckResult, CheckStatus, SystemSnapshot};
+#[cfg(target_os = "windows")]
+use crate::diagnostics::{elevation, processes};
+#[cfg(target_os = "windows")]
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "windows")]
+use std::time::Instant;
+
+/// Files written, read back and deleted per directory
+#[cfg(target_os = "windows")]
+const PROBE_FILES: usize = 20;
+
+/// Average per probe file above which scanning is called severe
+#[cfg(target_os = "windows")]
+const SEVERE_MS: f64 = 5.0;
+
+/// Whether a directory is in Defender's exclusion list
+#[cfg(target_os = "windows")]
+#[derive(Clone, Copy, PartialEq)]
+enum Coverage {
+    Scanned,
+    Excluded,
+    /// Exclusions are only readable by administrators
+    Unknown,
+}
+
+#[cfg(target_os = "windows")]
+pub fn check_defender(snapshot: &mut SystemSnapshot) -> CheckResult {
+    let (realtime, exclusions) = match defender_state() {
+        Ok(state) => state,
+        Err(e) => return CheckResult::new("DEFENDER", CheckStatus::Inactive, &e),
+    };
+    if !realtime {
+        return CheckResult::new("DEFENDER", CheckStatus::Inactive, "Real-time protection off");
+    }
+
+    let mut details = vec!["real-time ON".to_string()];
+    let mut severe = Vec::new();
+    for (label, dir) in watched_dirs(snapshot) {
+        let coverage = match exclusions {
+            Some(ref exclusions) if is_excluded(&dir, exclusions) => Coverage::Excluded,
+            Some(_) => Coverage::Scanned,
+            None => Coverage::Unknown,
+        };
+        let coverage_label = match coverage {
+            Coverage::Scanned => "scanned",
+            Coverage::Excluded => "excluded",
+            Coverage::Unknown => elevation::ADMIN_NOTE,
+        };
+        match probe_latency(&dir) {
+            Some(ms) => {
+                details.push(format!("{} {} {:.1}ms/file ({})", label, dir.display(), ms, coverage_label));
+                if ms >= SEVERE_MS && coverage != Coverage::Excluded {
+                    severe.push((dir, ms));
+                }
+            }
+            None => details.push(format!("{} {} not writable ({})", label, dir.display(), coverage_label)),
+        }
+    }
+    if details.len() == 1 {
+        details.push("no OpenCode directories found".to_string());
+    }
+
+    let details = details.join(" :: ");
+    let Some((dir, ms)) = severe.first() else {
+        return CheckResult::new("DEFENDER", CheckStatus::Ok, &details);
+    };
+    CheckResult::new("DEFENDER", CheckStatus::Warning, &details).with_message(&format!(
+        "Real-time scanning adds ~{:.0}ms per file in {}. If you trust it, exclude it (admin PowerShell): Add-MpPreference -ExclusionPath '{}'",
+        ms,
+        dir.display(),
+        dir.display()
+    ))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn check_defender(_snapshot: &mut SystemSnapshot) -> CheckResult {
+    CheckResult::new("DEFENDER", CheckStatus::Inactive, "Defender check only available on Windows")
+}
+
+/// Real-time protection on, and the excluded paths (`None` without admin)
+#[cfg(target_os = "windows")]
+fn defender_state() -> Result<(bool, Option<Vec<String>>), String> {
+    let script = "$s = Get-MpComputerStatus -ErrorAction Stop; 'RTP=' + $s.RealTimeProtectionEnabled; \
+                  (Get-MpPreference).ExclusionPath | ForEach-Object { 'EXCL=' + $_ }";
+    let output = crate::diagnostics::command("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .output()
+        .map_err(|e| format!("powershell failed: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let realtime = stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("RTP="))
+        .ok_or("Defender not active (another antivirus?)")?
+        .eq_ignore_ascii_case("true");
+    let exclusions: Vec<String> = stdout
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("EXCL="))
+        .map(|path| path.trim_end_matches('\\').to_string())
+        .collect();
+    // Non-admins get a single "N/A: Must be an administrator to view exclusions"
+    if exclusions.iter().any(|e| e.starts_with("N/A")) {
+        return Ok((realtime, None));
+    }
+    Ok((realtime, Some(exclusions)))
+}
+
+#[cfg(target_os = "windows")]
+fn is_excluded(dir: &Path, exclusions: &[String]) -> bool {
+    let dir = dir.display().to_string().to_lowercase();
+    exclusions.iter().any(|excluded| {
+        let excluded = excluded.to_lowercase();
+        dir == excluded || dir.starts_with(&format!("{}\\", excluded))
+    })
+}
+
+/// (label, directory): the install directory and the projects OpenCode runs in
+#[cfg(target_os = "windows")]
+fn watched_dirs(snapshot: &mut SystemSnapshot) -> Vec<(&'static str, PathBuf)> {
+    let mut installs: Vec<PathBuf> = processes::opencode_exes(snapshot)
+        .into_iter()
+        .filter_map(|exe| exe.parent().map(Path::to_path_buf))
+        .collect();
+    if installs.is_empty() {
+        installs.extend(find_on_path("opencode"));
+    }
+
+    let mut dirs: Vec<(&'static str, PathBuf)> = installs.into_iter().map(|dir| ("install", dir)).collect();
+    for (_, cwd) in processes::opencode_roots(snapshot) {
+        if let Some(cwd) = cwd {
+            dirs.push(("project", cwd));
+        }
+    }
+    let mut seen = std::collections::HashSet::new();
+    dirs.retain(|(_, dir)| seen.insert(dir.display().to_string().to_lowercase()));
+    dirs
+}
+
+/// Directory of `name` (.exe/.cmd) on PATH
+#[cfg(target_os = "windows")]
+fn find_on_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find(|dir| ["exe", "cmd"].iter().any(|ext| dir.join(format!("{}.{}", name, ext)).is_file()))
+}
+
+/// Average ms to write, read back and delete a small script file in `dir`
+#[cfg(target_os = "windows")]
+fn probe_latency(dir: &Path) -> Option<f64> {
+    let started = Instant::now();
+    for i in 0..PROBE_FILES {
+        let path = dir.join(format!(".opencode-diag-probe-{}-{}.js", std::process::id(), i));
+        // Unique contents so no scan result is reused
+        let contents = format!("// opencode-diag probe {:?} {}\nmodule.exports = {{}};\n", Instant::now(), i);
+        let written = std::fs::write(&path, &contents).and_then(|_| std::fs::read(&path));
+        let _ = std::fs::remove_file(&path);
+        written.ok()?;
+    }
+    Some(started.elapsed().as_secs_f64() * 1000.0 / PROBE_FILES as f64)
+}
@@ -0,0 +1,282 @@
+//! Weekly digest of the run history
+//!
+//! A monitoring install records hundreds of runs nobody reads. Once a week
+//! the app opens a digest of them on launch: uptime per provider, the longest
+//! outages, how often the machine itself was under load (each against the
+//! week before) and failures per run tag. It can be saved as text or printed
+//! with `--digest`.
+
+use crate::diagnostics::history::{History, HistoryEntry};
+use crate::diagnostics::{ids, CheckStatus};
+use chrono::NaiveDateTime;
+use std::path::PathBuf;
+
+/// Days covered by one digest
+pub const DIGEST_DAYS: i64 = 7;
+
+/// Incidents listed, longest first
+const MAX_INCIDENTS: usize = 5;
+
+/// Checks reported with their uptime
+const PROVIDERS: &[&str] = &[ids::INTERNET, ids::CLAUDE, ids::OPENAI, ids::GOOGLE, ids::RELAY];
+
+/// Checks reported with how often they were under load
+const LOAD_CHECKS: &[&str] = &[ids::RESOURCES, ids::GPU, ids::LEAKS];
+
+/// Share of measured runs a provider answered (WARN counts as up)
+pub struct Uptime {
+    pub id: &'static str,
+    pub percent: f64,
+    pub previous: Option<f64>,
+    pub avg_latency_ms: Option<u64>,
+}
+
+/// Consecutive ERROR runs of one check
+pub struct Incident {
+    pub id: &'static str,
+    pub start: NaiveDateTime,
+    /// First run that was no longer ERROR (or the last ERROR run)
+    pub end: NaiveDateTime,
+    pub runs: usize,
+}
+
+/// Share of measured runs a local check was WARN or ERROR
+pub struct Load {
+    pub id: &'static str,
+    pub percent: f64,
+    pub previous: Option<f64>,
+}
+
+/// Runs made with one tag value (see `RunTags`), and how many had an ERROR
+pub struct Context {
+    pub kind: &'static str,
+    pub value: String,
+    pub runs: usize,
+    pub failed: usize,
+}
+
+pub struct Digest {
+    pub from: NaiveDateTime,
+    pub to: NaiveDateTime,
+    pub runs: usize,
+    pub uptime: Vec<Uptime>,
+    pub incidents: Vec<Incident>,
+    pub load: Vec<Load>,
+    /// Only tags that took more than one value during the week
+    pub contexts: Vec<Context>,
+}
+
+/// Percent of measured runs of `id` whose status matches `counts`
+fn share(entries: &[&HistoryEntry], id: &str, counts: fn(CheckStatus) -> bool) -> Option<f64> {
+    let measured: Vec<CheckStatus> = entries
+        .iter()
+        .filter_map(|e| e.check(id))
+        .map(|c| c.status)
+        .filter(|s| matches!(s, CheckStatus::Ok | CheckStatus::Warning | CheckStatus::Error))
+        .collect();
+    if measured.is_empty() {
+        return None;
+    }
+    Some(measured.iter().filter(|s| counts(**s)).count() as f64 * 100.0 / measured.len() as f64)
+}
+
+fn incidents(entries: &[&HistoryEntry]) -> Vec<Incident> {
+    let mut incidents = Vec::new();
+    for id in ids::ALL {
+        let mut open: Option<Incident> = None;
+        for entry in entries {
+            let (Some(time), Some(check)) = (entry.time(), entry.check(id)) else {
+                continue;
+            };
+            match (check.status, open.as_mut()) {
+                (CheckStatus::Error, Some(incident)) => {
+                    incident.end = time;
+                    incident.runs += 1;
+                }
+                (CheckStatus::Error, None) => open = Some(Incident { id, start: time, end: time, runs: 1 }),
+                (_, Some(incident)) => {
+                    incident.end = time;
+                    incidents.extend(open.take());
+                }
+                (_, None) => {}
+            }
+        }
+        incidents.extend(open);
+    }
+    incidents.sort_by_key(|i| (std::cmp::Reverse(i.end - i.start), std::cmp::Reverse(i.runs)));
+    incidents.truncate(MAX_INCIDENTS);
+    incidents
+}
+
+fn contexts(entries: &[&HistoryEntry]) -> Vec<Context> {
+    let mut contexts = Vec::new();
+    for kind in 0..3 {
+        let mut slices: Vec<Context> = Vec::new();
+        for entry in entries {
+            let (kind, value) = entry.tags.pairs()[kind];
+            if value.is_empty() {
+                continue;
+            }
+            let failed = entry.checks.iter().any(|c| c.status == CheckStatus::Error) as usize;
+            match slices.iter_mut().find(|c| c.value == value) {
+                Some(slice) => {
+                    slice.runs += 1;
+                    slice.failed += failed;
+                }
+                None => slices.push(Context { kind, value: value.to_string(), runs: 1, failed }),
+            }
+        }
+        if slices.len() > 1 {
+            slices.sort_by_key(|c| std::cmp::Reverse(c.runs));
+            contexts.extend(slices);
+        }
+    }
+    contexts
+}
+
+impl Digest {
+    /// Digest of the `DIGEST_DAYS` before `to`; None without runs in them
+    pub fn build(history: &History, to: NaiveDateTime) -> Option<Self> {
+        let from = to - chrono::Duration::days(DIGEST_DAYS);
+        let before = from - chrono::Duration::days(DIGEST_DAYS);
+        let in_range = |start, end| -> Vec<&HistoryEntry> {
+            history.entries.iter().filter(|e| e.time().is_some_and(|t| t >= start && t < end)).collect()
+        };
+        let week = in_range(from, to);
+        if week.is_empty() {
+            return None;
+        }
+        let previous = in_range(before, from);
+
+        let up = |s: CheckStatus| s != CheckStatus::Error;
+        let busy = |s: CheckStatus| s != CheckStatus::Ok;
+        let uptime = PROVIDERS
+            .iter()
+            .filter_map(|id| {
+                let latencies: Vec<u64> = week.iter().filter_map(|e| e.check(id)?.latency_ms).collect();
+                Some(Uptime {
+                    id,
+                    percent: share(&week, id, up)?,
+                    previous: share(&previous, id, up),
+                    avg_latency_ms: (!latencies.is_empty()).then(|| latencies.iter().sum::<u64>() / latencies.len() as u64),
+                })
+            })
+            .collect();
+        let load = LOAD_CHECKS
+            .iter()
+            .filter_map(|id| Some(Load { id, percent: share(&week, id, busy)?, previous: share(&previous, id, busy) }))
+            .collect();
+
+        Some(Self { from, to, runs: week.len(), uptime, incidents: incidents(&week), load, contexts: contexts(&week) })
+    }
+
+    /// Plain text for saving or printing
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        text.push_str("=== OpenCode Diagnostics Weekly Digest ===\n");
+        text.push_str(&format!("{} .. {} :: {} runs\n", self.from.format("%Y-%m-%d"), self.to.format("%Y-%m-%d"), self.runs));
+
+        text.push_str("\nUPTIME (vs previous week)\n");
+        if self.uptime.is_empty() {
+            text.push_str("  no provider checks ran\n");
+        }
+        for uptime in &self.uptime {
+            let change = uptime.previous.map(|p| format!("{:+.1}", uptime.percent - p)).unwrap_or_else(|| "--".to_string());
+            let latency = uptime.avg_latency_ms.map(|ms| format!("avg {}ms", ms)).unwrap_or_default();
+            let line = format!("  {:<18}{:>6.1}%  {:>6}  {}", ids::name(uptime.id), uptime.percent, change, latency);
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+
+        text.push_str("\nWORST INCIDENTS\n");
+        if self.incidents.is_empty() {
+            text.push_str("  none\n");
+        }
+        for incident in &self.incidents {
+            let minutes = (incident.end - incident.start).num_minutes();
+            text.push_str(&format!(
+                "  {:<18}ERROR {} for {}m ({} run{})\n",
+                ids::name(incident.id),
+                incident.start.format("%a %H:%M"),
+                minutes,
+                incident.runs,
+                if incident.runs == 1 { "" } else { "s" }
+            ));
+        }
+
+        text.push_str("\nMACHINE LOAD (runs with WARN or ERROR)\n");
+        if self.load.is_empty() {
+            text.push_str("  no local checks ran\n");
+        }
+        for load in &self.load {
+            let previous = load.previous.map(|p| format!("(was {:.0}%)", p)).unwrap_or_default();
+            let line = format!("  {:<18}{:>5.0}%  {}", ids::name(load.id), load.percent, previous);
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+
+        if !self.contexts.is_empty() {
+            text.push_str("\nRUNS WITH AN ERROR, BY CONTEXT\n");
+        }
+        for context in &self.contexts {
+            let tag = format!("{}={}", context.kind, context.value);
+            let percent = context.failed as f64 * 100.0 / context.runs as f64;
+            text.push_str(&format!("  {:<30}{:>5.0}%  of {} runs\n", tag, percent, context.runs));
+        }
+        text
+    }
+
+    /// Write the text to the Downloads folder
+    pub fn export(&self) -> Result<PathBuf, String> {
+        let dir = dirs::download_dir()
+            .or_else(dirs::desktop_dir)
+            .or_else(dirs::home_dir)
+            .ok_or("Could not determine Downloads folder")?;
+        let path = dir.join(format!("opencode-diag-digest-{}.txt", self.to.format("%Y%m%d")));
+        std::fs::write(&path, self.to_text()).map_err(|e| format!("Failed to write digest: {}", e))?;
+        Ok(path)
+    }
+}
+
+/// Whether to open the digest on launch: a week after the last one, or
+/// once the history first covers a whole week
+pub fn due(history: &History, last_shown: &str, now: NaiveDateTime) -> bool {
+    let week = chrono::Duration::days(DIGEST_DAYS);
+    match NaiveDateTime::parse_from_str(last_shown, crate::diagnostics::history::TIMESTAMP_FORMAT) {
+        Ok(shown) => now - shown >= week,
+        Err(_) => history.entries.front().and_then(|e| e.time()).is_some_and(|first| now - first >= week),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::history::{HistoryCheck, TIMESTAMP_FORMAT};
+    use crate::diagnostics::RunTags;
+
+    /// Hourly runs over a day, CLAUDE API down for three in a row, all three on the office network
+    #[test]
+    fn three_hour_outage_on_the_office_network() {
+        let now = chrono::Local::now().naive_local();
+        let mut history = History::default();
+        for hour in (1..=24).rev() {
+            let office = (10..13).contains(&hour);
+            history.entries.push_back(HistoryEntry {
+                timestamp: (now - chrono::Duration::hours(hour)).format(TIMESTAMP_FORMAT).to_string(),
+                checks: vec![HistoryCheck {
+                    id: ids::CLAUDE.to_string(),
+                    status: if office { CheckStatus::Error } else { CheckStatus::Ok },
+                    latency_ms: Some(100),
+                }],
+                tags: RunTags { network: if office { "office" } else { "home" }.to_string(), ..RunTags::default() },
+            });
+        }
+        let digest = Digest::build(&history, now).unwrap();
+        assert_eq!(digest.runs, 24);
+        assert!((digest.uptime[0].percent - 87.5).abs() < 0.1);
+        let incident = &digest.incidents[0];
+        assert_eq!((incident.runs, (incident.end - incident.start).num_minutes()), (3, 180));
+        let office = digest.contexts.iter().find(|c| c.value == "office").unwrap();
+        assert_eq!((office.runs, office.failed), (3, 3));
+    }
+}
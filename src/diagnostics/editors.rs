@@ -0,0 +1,223 @@
+//! Editor integration status (VS Code family and JetBrains plugins)
+//!
+//! Lists installed OpenCode editor extensions with their versions and whether
+//! the host editor is running, and flags combinations known not to work:
+//! - the plugin launches `opencode` from PATH, so it breaks when the CLI is missing
+//! - an extension updated while its editor kept running stays on the old code
+//!   until the editor reloads
+
+use crate::diagnostics::{processes, CheckResult, CheckStatus, SystemSnapshot};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use sysinfo::System;
+
+/// VS Code-based editors: (label, extensions dir under home, process names)
+const VSCODE_EDITORS: &[(&str, &str, &[&str])] = &[
+    ("VS Code", ".vscode/extensions", &["code", "code.exe", "code helper"]),
+    ("VS Code Insiders", ".vscode-insiders/extensions", &["code - insiders", "code - insiders.exe"]),
+    ("VSCodium", ".vscode-oss/extensions", &["codium", "codium.exe", "vscodium.exe"]),
+    ("Cursor", ".cursor/extensions", &["cursor", "cursor.exe"]),
+    ("Windsurf", ".windsurf/extensions", &["windsurf", "windsurf.exe"]),
+];
+
+/// JetBrains config dir prefix -> launcher name ("idea64.exe", "idea")
+const JETBRAINS_IDES: &[(&str, &str, &str)] = &[
+    ("IntelliJIdea", "IntelliJ IDEA", "idea"),
+    ("IdeaIC", "IntelliJ IDEA CE", "idea"),
+    ("PyCharm", "PyCharm", "pycharm"),
+    ("WebStorm", "WebStorm", "webstorm"),
+    ("GoLand", "GoLand", "goland"),
+    ("Rider", "Rider", "rider"),
+    ("CLion", "CLion", "clion"),
+    ("PhpStorm", "PhpStorm", "phpstorm"),
+    ("RubyMine", "RubyMine", "rubymine"),
+    ("RustRover", "RustRover", "rustrover"),
+];
+
+/// One installed OpenCode plugin
+struct Plugin {
+    editor: String,
+    version: Option<String>,
+    /// Install/update time of the plugin directory (unix seconds)
+    installed_at: Option<u64>,
+    /// Editor process names (lowercase, prefix match)
+    processes: Vec<String>,
+}
+
+/// Check installed OpenCode editor plugins and their editors
+pub fn check_editors(snapshot: &mut SystemSnapshot) -> CheckResult {
+    let mut plugins = vscode_plugins();
+    plugins.extend(jetbrains_plugins());
+    if plugins.is_empty() {
+        return CheckResult::new("EDITORS", CheckStatus::Inactive, "No OpenCode editor plugins");
+    }
+
+    let sys = snapshot.processes();
+    let mut parts = Vec::new();
+    let mut warnings = Vec::new();
+    for plugin in &plugins {
+        let started = editor_started_at(sys, &plugin.processes);
+        let version = plugin.version.as_deref().map(|v| format!(" {}", v)).unwrap_or_default();
+        let state = if started.is_some() { "running" } else { "closed" };
+        parts.push(format!("{}{} ({})", plugin.editor, version, state));
+
+        if let (Some(started), Some(installed)) = (started, plugin.installed_at) {
+            if installed > started {
+                warnings.push(format!("Reload {} to load the updated OpenCode plugin", plugin.editor));
+            }
+        }
+    }
+
+    match cli_on_path() {
+        Some(cli) => {
+            let version = processes::version_of(&cli).map(|v| format!(" {}", v)).unwrap_or_default();
+            parts.push(format!("CLI{}", version));
+        }
+        None => warnings.push("`opencode` is not on PATH; editor plugins can't start it".to_string()),
+    }
+
+    let details = parts.join(" :: ");
+    if warnings.is_empty() {
+        CheckResult::new("EDITORS", CheckStatus::Ok, &details)
+    } else {
+        CheckResult::new("EDITORS", CheckStatus::Warning, &details).with_message(&warnings.join("; "))
+    }
+}
+
+/// Earliest start time of a running editor process (unix seconds)
+fn editor_started_at(sys: &System, names: &[String]) -> Option<u64> {
+    sys.processes()
+        .values()
+        .filter(|p| {
+            let name = p.name().to_string_lossy().to_lowercase();
+            names.iter().any(|n| name.starts_with(n.as_str()))
+        })
+        .map(|p| p.start_time())
+        .min()
+}
+
+/// Extensions named `<publisher>.opencode-<version>` in each VS Code-based editor
+fn vscode_plugins() -> Vec<Plugin> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    let mut plugins = Vec::new();
+    for (label, dir, names) in VSCODE_EDITORS {
+        // Several versions can sit side by side until the editor cleans up; report the newest
+        let newest = plugin_dirs(&home.join(dir))
+            .into_iter()
+            .map(|path| {
+                let version = package_version(&path).or_else(|| dir_version(&path));
+                (path, version)
+            })
+            .max_by_key(|(_, version)| version.as_deref().map(version_key).unwrap_or_default());
+        if let Some((path, version)) = newest {
+            plugins.push(Plugin {
+                editor: label.to_string(),
+                version,
+                installed_at: modified_secs(&path),
+                processes: names.iter().map(|n| n.to_string()).collect(),
+            });
+        }
+    }
+    plugins
+}
+
+/// Plugins in `<JetBrains config>/<Product><year>/plugins` (directly in the product dir on Linux)
+fn jetbrains_plugins() -> Vec<Plugin> {
+    let roots: Vec<PathBuf> = [dirs::config_dir(), dirs::data_dir()]
+        .into_iter()
+        .flatten()
+        .map(|dir| dir.join("JetBrains"))
+        .collect();
+
+    let mut plugins = Vec::new();
+    for root in roots {
+        let Ok(entries) = std::fs::read_dir(&root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let product = entry.file_name().to_string_lossy().to_string();
+            let Some((_, label, launcher)) = JETBRAINS_IDES.iter().find(|(prefix, _, _)| product.starts_with(prefix)) else {
+                continue;
+            };
+            let dir = entry.path();
+            let mut found = plugin_dirs(&dir.join("plugins"));
+            found.extend(plugin_dirs(&dir));
+            if let Some(path) = found.into_iter().next() {
+                plugins.push(Plugin {
+                    editor: format!("{} {}", label, product.trim_start_matches(|c: char| c.is_alphabetic())),
+                    version: jar_version(&path),
+                    installed_at: modified_secs(&path),
+                    processes: vec![launcher.to_string()],
+                });
+            }
+        }
+    }
+    plugins
+}
+
+/// Subdirectories of `dir` whose name mentions opencode
+fn plugin_dirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.path().is_dir() && e.file_name().to_string_lossy().to_lowercase().contains("opencode"))
+        .map(|e| e.path())
+        .collect()
+}
+
+fn package_version(dir: &Path) -> Option<String> {
+    let text = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    let package: serde_json::Value = serde_json::from_str(&text).ok()?;
+    package["version"].as_str().map(str::to_string)
+}
+
+/// "sst-dev.opencode-0.0.12" -> "0.0.12"
+fn dir_version(dir: &Path) -> Option<String> {
+    let name = dir.file_name()?.to_string_lossy().to_string();
+    let (_, version) = name.rsplit_once('-')?;
+    version.starts_with(|c: char| c.is_ascii_digit()).then(|| version.to_string())
+}
+
+/// `<version>` from META-INF/plugin.xml inside the plugin's lib/*.jar
+fn jar_version(dir: &Path) -> Option<String> {
+    let jars = std::fs::read_dir(dir.join("lib")).ok()?;
+    jars.flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "jar"))
+        .find_map(|jar| {
+            let file = std::fs::File::open(jar.path()).ok()?;
+            let mut archive = zip::ZipArchive::new(file).ok()?;
+            let mut xml = String::new();
+            archive.by_name("META-INF/plugin.xml").ok()?.read_to_string(&mut xml).ok()?;
+            let start = xml.find("<version>")? + "<version>".len();
+            let end = start + xml[start..].find("</version>")?;
+            Some(xml[start..end].trim().to_string())
+        })
+}
+
+/// Numeric parts for ordering "0.0.9" before "0.0.12"
+fn version_key(version: &str) -> Vec<u64> {
+    version.split(['.', '-']).map(|part| part.parse().unwrap_or(0)).collect()
+}
+
+fn modified_secs(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+/// The `opencode` executable editor plugins would launch
+fn cli_on_path() -> Option<PathBuf> {
+    let names: &[&str] = if cfg!(windows) {
+        &["opencode.exe", "opencode.cmd", "opencode.bat"]
+    } else {
+        &["opencode"]
+    };
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
@@ -0,0 +1,129 @@
+//! Administrator rights (Windows UAC)
+//!
+//! Checks run fine without admin; the parts that need it (e.g. the firewall
+//! event log) are skipped and marked "requires admin" in the details.
+
+use crate::diagnostics::ids;
+use std::sync::OnceLock;
+
+/// Shown in place of results that need administrator rights
+pub const ADMIN_NOTE: &str = "requires admin";
+
+/// Checks that are only complete when elevated (on Windows)
+pub const ADMIN_CHECKS: &[&str] = &[ids::FIREWALL, ids::BANDWIDTH, ids::DEFENDER];
+
+/// Whether `id` loses detail without administrator rights right now
+pub fn limited_without_admin(id: &str) -> bool {
+    cfg!(target_os = "windows") && ADMIN_CHECKS.contains(&id) && !is_elevated()
+}
+
+/// Whether this process runs with administrator rights (fixed for its lifetime)
+pub fn is_elevated() -> bool {
+    static ELEVATED: OnceLock<bool> = OnceLock::new();
+    *ELEVATED.get_or_init(query_elevated)
+}
+
+#[cfg(target_os = "windows")]
+fn query_elevated() -> bool {
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::{GetCurrentProcess, OpenProcessToken};
+    use winapi::um::securitybaseapi::GetTokenInformation;
+    use winapi::um::winnt::{TokenElevation, HANDLE, TOKEN_ELEVATION, TOKEN_QUERY};
+
+    let mut token: HANDLE = std::ptr::null_mut();
+    if unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) } == 0 {
+        return false;
+    }
+    let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+    let mut size = 0;
+    let ok = unsafe {
+        GetTokenInformation(
+            token,
+            TokenElevation,
+            &mut elevation as *mut _ as *mut _,
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut size,
+        )
+    };
+    unsafe { CloseHandle(token) };
+    ok != 0 && elevation.TokenIsElevated != 0
+}
+
+/// Root counts as elevated elsewhere
+#[cfg(not(target_os = "windows"))]
+fn query_elevated() -> bool {
+    crate::diagnostics::command("id")
+        .arg("-u")
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim() == "0")
+        .unwrap_or(false)
+}
+
+/// Run a `cmd` command line and return its combined output. With `elevate`
+/// it runs through a UAC prompt, its output captured via a temp file.
+#[cfg(target_os = "windows")]
+pub fn run_command_line(command_line: &str, elevate: bool) -> Result<String, String> {
+    use std::os::windows::process::CommandExt;
+
+    if !elevate {
+        let output = crate::diagnostics::command("cmd")
+            .arg("/S")
+            .arg("/C")
+            .raw_arg(format!("\"({}) 2>&1\"", command_line))
+            .output()
+            .map_err(|e| format!("Failed to run: {}", e))?;
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+
+    let log = std::env::temp_dir().join(format!("opencode-diag-{}.txt", std::process::id()));
+    let _ = std::fs::remove_file(&log);
+    let inner = format!("/S /C \"({}) > \"{}\" 2>&1\"", command_line, log.display());
+    // PowerShell single-quoted string: ' doubles
+    let script = format!(
+        "Start-Process -FilePath cmd -ArgumentList '{}' -Verb RunAs -Wait -WindowStyle Hidden",
+        inner.replace('\'', "''")
+    );
+    let status = crate::diagnostics::command("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .status()
+        .map_err(|e| format!("Failed to start PowerShell: {}", e))?;
+    if !status.success() {
+        return Err("Administrator approval was declined".to_string());
+    }
+    let output = std::fs::read(&log).map_err(|e| format!("No output from elevated command: {}", e))?;
+    let _ = std::fs::remove_file(&log);
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
+
+/// Start this executable again elevated (UAC prompt) with `args`
+#[cfg(target_os = "windows")]
+pub fn relaunch_elevated(args: &[String]) -> Result<(), String> {
+    use winapi::um::shellapi::ShellExecuteW;
+    use winapi::um::winuser::SW_SHOWNORMAL;
+
+    let exe = std::env::current_exe().map_err(|e| format!("Cannot find executable: {}", e))?;
+    let wide = |s: &str| s.encode_utf16().chain(std::iter::once(0)).collect::<Vec<u16>>();
+    let params = args.iter().map(|a| format!("\"{}\"", a)).collect::<Vec<_>>().join(" ");
+    let (verb, file, params) = (wide("runas"), wide(&exe.to_string_lossy()), wide(&params));
+    let result = unsafe {
+        ShellExecuteW(
+            std::ptr::null_mut(),
+            verb.as_ptr(),
+            file.as_ptr(),
+            params.as_ptr(),
+            std::ptr::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+    // Values above 32 mean success; the prompt being declined returns an error
+    if result as isize > 32 {
+        Ok(())
+    } else {
+        Err("Administrator approval was declined".to_string())
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn relaunch_elevated(_args: &[String]) -> Result<(), String> {
+    Err("Restart as administrator is only available on Windows".to_string())
+}
@@ -0,0 +1,102 @@
+//! Provider fallback recommendation
+//!
+//! When the provider OpenCode uses is down and another answers, waiting is
+//! rarely the best option. The provider in use is read from the `model` tag
+//! of the run ("anthropic/claude-sonnet-4-5"), else taken to be the failing
+//! one; the healthy providers are offered fastest first, with the config
+//! line that switches OpenCode to the first of them.
+
+use crate::diagnostics::{ids, CheckResult, CheckStatus, DiagnosticReport};
+
+/// OpenCode's models and providers documentation (the `model` key)
+pub const DOCS_URL: &str = "https://opencode.ai/docs/models/";
+
+/// Check id, OpenCode provider id and name of the providers checked here
+pub const PROVIDERS: &[(&str, &str, &str)] = &[
+    (ids::CLAUDE, "anthropic", "Anthropic"),
+    (ids::OPENAI, "openai", "OpenAI"),
+    (ids::GOOGLE, "google", "Google"),
+];
+
+pub struct Recommendation {
+    /// "OpenAI and Google healthy: consider switching OpenCode from Anthropic"
+    pub advice: String,
+    /// `"model": "openai/<model>"` for opencode.json
+    pub config: String,
+}
+
+fn check<'a>(report: &'a DiagnosticReport, id: &str) -> Option<&'a CheckResult> {
+    match id {
+        ids::CLAUDE => report.claude_api.as_ref(),
+        ids::OPENAI => report.openai_api.as_ref(),
+        ids::GOOGLE => report.google_api.as_ref(),
+        _ => None,
+    }
+}
+
+/// The provider is failing: its check errors or its status page reports a
+/// major incident
+fn is_down(report: &DiagnosticReport, id: &str) -> bool {
+    check(report, id).is_some_and(|c| c.status == CheckStatus::Error)
+        || report.status_pages.iter().any(|s| s.id == id && s.is_major())
+}
+
+/// Provider of the run's model tag, when it is one checked here
+fn provider_in_use(report: &DiagnosticReport) -> Option<&'static (&'static str, &'static str, &'static str)> {
+    let (provider, _) = report.tags.model.split_once('/')?;
+    PROVIDERS.iter().find(|(_, id, _)| *id == provider)
+}
+
+/// A switch to recommend, when the provider in use is down and another is healthy
+pub fn recommend(report: &DiagnosticReport) -> Option<Recommendation> {
+    let down = match provider_in_use(report) {
+        Some(provider) if is_down(report, provider.0) => provider,
+        Some(_) => return None,
+        None => PROVIDERS.iter().find(|(id, _, _)| is_down(report, id))?,
+    };
+    let mut healthy: Vec<(&str, &str, u64)> = PROVIDERS
+        .iter()
+        .filter(|(id, _, _)| *id != down.0 && !is_down(report, id) && !report.status_pages.iter().any(|s| s.id == *id && s.has_incident()))
+        .filter_map(|(id, provider, name)| {
+            let check = check(report, id).filter(|c| c.status == CheckStatus::Ok)?;
+            Some((*provider, *name, check.latency_ms.unwrap_or(u64::MAX)))
+        })
+        .collect();
+    healthy.sort_by_key(|(_, _, latency)| *latency);
+    let (provider, _, _) = *healthy.first()?;
+    let names: Vec<&str> = healthy.iter().map(|(_, name, _)| *name).collect();
+    let names = match names.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => names.join(""),
+    };
+    Some(Recommendation {
+        advice: format!("{} healthy: consider switching OpenCode from {}", names, down.2),
+        config: format!("\"model\": \"{}/<model>\"", provider),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anthropic_down(model: &str) -> DiagnosticReport {
+        let mut report = DiagnosticReport::new();
+        report.tags.model = model.to_string();
+        report.claude_api = Some(CheckResult::new("CLAUDE API", CheckStatus::Error, "HTTP 529 overloaded"));
+        report.openai_api = Some(CheckResult::new("OPENAI API", CheckStatus::Ok, "Reachable").with_latency(300));
+        report.google_api = Some(CheckResult::new("GOOGLE API", CheckStatus::Ok, "Reachable").with_latency(100));
+        report
+    }
+
+    #[test]
+    fn offers_the_faster_healthy_provider() {
+        let recommendation = recommend(&anthropic_down("anthropic/claude-sonnet-4-5")).unwrap();
+        assert_eq!(recommendation.advice, "Google and OpenAI healthy: consider switching OpenCode from Anthropic");
+        assert_eq!(recommendation.config, "\"model\": \"google/<model>\"");
+    }
+
+    #[test]
+    fn outage_of_an_unused_provider_needs_no_switch() {
+        assert!(recommend(&anthropic_down("openai/gpt-5")).is_none());
+    }
+}
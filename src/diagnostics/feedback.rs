@@ -0,0 +1,105 @@
+//! Right/wrong votes on the diagnosis
+//!
+//! The confidence of each rule is a guess. Votes from RIGHT / WRONG under the
+//! DIAGNOSIS panel are kept per rule in `feedback.json` and pull the rule's
+//! confidence toward its observed accuracy, so a rule that keeps being wrong
+//! on this machine stops taking the headline.
+
+use crate::diagnostics::Finding;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Rule id of "All systems operational." (no finding)
+pub const ALL_OK: &str = "all_ok";
+
+/// Votes that weigh as much as a rule's built-in confidence
+const PRIOR_WEIGHT: u32 = 5;
+
+/// Below this a finding only takes the headline when nothing else was found
+pub const HEADLINE_MIN_CONFIDENCE: u8 = 30;
+
+/// Votes for one rule
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct RuleStats {
+    pub right: u32,
+    pub wrong: u32,
+}
+
+impl RuleStats {
+    pub fn votes(&self) -> u32 {
+        self.right + self.wrong
+    }
+}
+
+/// Votes per rule id
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Feedback {
+    pub rules: BTreeMap<String, RuleStats>,
+}
+
+impl Feedback {
+    fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|p| p.join("opencode-diag").join("feedback.json"))
+    }
+
+    fn load() -> Self {
+        Self::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = Self::path().ok_or("No config directory")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize feedback: {}", e))?;
+        std::fs::write(&path, json).map_err(|e| format!("Failed to write feedback: {}", e))
+    }
+}
+
+static FEEDBACK: Mutex<Option<Feedback>> = Mutex::new(None);
+
+fn with_feedback<T>(f: impl FnOnce(&mut Feedback) -> T) -> T {
+    let mut feedback = FEEDBACK.lock().unwrap_or_else(|e| e.into_inner());
+    f(feedback.get_or_insert_with(Feedback::load))
+}
+
+/// Record a vote on `rule` and save it
+pub fn record(rule: &str, right: bool) -> Result<(), String> {
+    with_feedback(|feedback| {
+        let stats = feedback.rules.entry(rule.to_string()).or_default();
+        if right {
+            stats.right += 1;
+        } else {
+            stats.wrong += 1;
+        }
+        feedback.save()
+    })
+}
+
+/// Votes for `rule` so far
+pub fn stats(rule: &str) -> RuleStats {
+    with_feedback(|feedback| feedback.rules.get(rule).copied().unwrap_or_default())
+}
+
+/// Votes for every rule
+pub fn all() -> Feedback {
+    with_feedback(|feedback| feedback.clone())
+}
+
+/// Blend each finding's confidence with the accuracy users reported for its rule
+pub fn adjust(findings: &mut [Finding]) {
+    with_feedback(|feedback| {
+        for finding in findings.iter_mut() {
+            let Some(stats) = feedback.rules.get(&finding.rule).filter(|s| s.votes() > 0) else {
+                continue;
+            };
+            let blended = (finding.confidence as u32 * PRIOR_WEIGHT + stats.right * 100) / (PRIOR_WEIGHT + stats.votes());
+            finding.confidence = blended.min(100) as u8;
+        }
+    });
+}
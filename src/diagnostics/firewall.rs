@@ -0,0 +1,176 @@
+//! Firewall self-test using a temporary local listener
+//!
+//! Three probes tell apart "the network is down" from "security software is
+//! filtering this binary":
+//! - loopback: connect to our own listener on 127.0.0.1
+//! - inbound: connect to our own listener through the LAN address
+//! - outbound: our own TCP connect vs. the system `curl` to the same host
+//!
+//! The listener speaks plain HTTP; filtering happens at the TCP level, so a
+//! TLS handshake would not change what the probes can see.
+//!
+//! On Windows, when elevated, the Security event log is also searched for
+//! Filtering Platform drops of this binary (needs "Filtering Platform
+//! Connection" auditing to record anything).
+
+use crate::diagnostics::{elevation, ids, CheckResult, CheckStatus};
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::thread;
+use std::time::Duration;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Host used for the outbound comparison
+const OUTBOUND_HOST: &str = "api.anthropic.com";
+
+/// Run the firewall self-test
+pub fn check_firewall() -> CheckResult {
+    let loopback = probe_listener(Ipv4Addr::LOCALHOST, None);
+    let inbound = match crate::diagnostics::network::local_ip() {
+        Some(IpAddr::V4(ip)) => Some(probe_listener(Ipv4Addr::UNSPECIFIED, Some(ip))),
+        _ => None,
+    };
+    let own_outbound = outbound_tcp(OUTBOUND_HOST);
+    let curl_outbound = outbound_curl(OUTBOUND_HOST);
+
+    let mut parts = vec![format!("loopback {}", ok_str(loopback))];
+    if let Some(inbound) = inbound {
+        parts.push(format!("LAN inbound {}", ok_str(inbound)));
+    }
+    parts.push(format!("outbound {}", ok_str(own_outbound)));
+    if let Some(curl) = curl_outbound {
+        parts.push(format!("curl {}", ok_str(curl)));
+    }
+    let drops = if elevation::limited_without_admin(ids::FIREWALL) {
+        parts.push(format!("event log {}", elevation::ADMIN_NOTE));
+        None
+    } else {
+        let drops = filtering_drops();
+        if let Some(n) = drops {
+            parts.push(format!("event log {} drops/1h", n));
+        }
+        drops
+    };
+    let details = parts.join(" :: ");
+
+    // Only this binary is filtered: the system curl gets out, we don't
+    if !own_outbound && curl_outbound == Some(true) {
+        return CheckResult::new("FIREWALL", CheckStatus::Error, &details)
+            .with_message("Outbound traffic from opencode-diag is blocked while other apps get through");
+    }
+
+    if !loopback {
+        return CheckResult::new("FIREWALL", CheckStatus::Error, &details)
+            .with_message("Loopback connections are blocked by security software");
+    }
+
+    if inbound == Some(false) {
+        return CheckResult::new("FIREWALL", CheckStatus::Warning, &details)
+            .with_message("Inbound connections to this app are filtered (status page won't be reachable on LAN)");
+    }
+
+    if let Some(n) = drops.filter(|&n| n > 0) {
+        return CheckResult::new("FIREWALL", CheckStatus::Warning, &details).with_message(&format!(
+            "Windows Filtering Platform dropped {} connections of opencode-diag in the last hour",
+            n
+        ));
+    }
+
+    CheckResult::new("FIREWALL", CheckStatus::Ok, &details)
+}
+
+fn ok_str(ok: bool) -> &'static str {
+    if ok { "OK" } else { "BLOCKED" }
+}
+
+/// Start a one-shot HTTP listener on `bind_ip`, connect to it via `connect_ip`
+/// (defaults to the bind address) and check the reply arrives
+pub fn probe_listener(bind_ip: Ipv4Addr, connect_ip: Option<Ipv4Addr>) -> bool {
+    let Ok(listener) = TcpListener::bind((bind_ip, 0)) else {
+        return false;
+    };
+    let Ok(addr) = listener.local_addr() else {
+        return false;
+    };
+
+    let server = thread::spawn(move || {
+        let _ = listener.set_nonblocking(true);
+        let deadline = std::time::Instant::now() + PROBE_TIMEOUT;
+        while std::time::Instant::now() < deadline {
+            if let Ok((mut stream, _)) = listener.accept() {
+                let _ = stream.set_nonblocking(false);
+                let _ = stream.set_read_timeout(Some(PROBE_TIMEOUT));
+                let mut buf = [0u8; 512];
+                let _ = stream.read(&mut buf);
+                let _ = stream.write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n");
+                return;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+    });
+
+    let target = SocketAddr::new(IpAddr::V4(connect_ip.unwrap_or(bind_ip)), addr.port());
+    let ok = (|| {
+        let mut stream = TcpStream::connect_timeout(&target, PROBE_TIMEOUT).ok()?;
+        stream.set_read_timeout(Some(PROBE_TIMEOUT)).ok()?;
+        stream.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").ok()?;
+        let mut buf = [0u8; 64];
+        let n = stream.read(&mut buf).ok()?;
+        Some(buf[..n].starts_with(b"HTTP/1.1 204"))
+    })()
+    .unwrap_or(false);
+
+    let _ = server.join();
+    ok
+}
+
+/// TCP connect from this process to host:443
+fn outbound_tcp(host: &str) -> bool {
+    let Ok(mut addrs) = (host, 443).to_socket_addrs() else {
+        return false;
+    };
+    addrs.any(|addr| TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).is_ok())
+}
+
+/// Same request from the system curl (ships with Windows 10+), `None` if unavailable
+fn outbound_curl(host: &str) -> Option<bool> {
+    let output = crate::diagnostics::command("curl")
+        .args(["-s", "-o", if cfg!(windows) { "NUL" } else { "/dev/null" }])
+        .args(["-w", "%{http_code}", "--max-time", "5"])
+        .arg(format!("https://{}", host))
+        .output()
+        .ok()?;
+    let code = String::from_utf8_lossy(&output.stdout);
+    // Any HTTP status (even 404) proves the connection got through
+    Some(code.trim() != "000" && !code.trim().is_empty())
+}
+
+/// Filtering Platform drops (events 5152/5157) of this executable in the last
+/// hour, from the Security log (admin only)
+#[cfg(target_os = "windows")]
+fn filtering_drops() -> Option<usize> {
+    let exe = std::env::current_exe().ok()?;
+    let name = exe.file_name()?.to_string_lossy().to_lowercase();
+    let output = crate::diagnostics::command("wevtutil")
+        .args(["qe", "Security", "/f:text", "/rd:true", "/c:500"])
+        .arg("/q:*[System[(EventID=5152 or EventID=5157) and TimeCreated[timediff(@SystemTime) <= 3600000]]]")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // "	Application Name:	\device\harddiskvolume3\tools\opencode-diag.exe"
+    let stdout = String::from_utf8_lossy(&output.stdout).to_lowercase();
+    Some(
+        stdout
+            .lines()
+            .filter(|line| line.contains("application name:") && line.trim_end().ends_with(&name))
+            .count(),
+    )
+}
+
+#[cfg(not(target_os = "windows"))]
+fn filtering_drops() -> Option<usize> {
+    None
+}
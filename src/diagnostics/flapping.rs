@@ -0,0 +1,79 @@
+//! Flapping detection: a check alternating between OK and failing
+//!
+//! A check that goes OK, ERROR, OK, ERROR within minutes has different causes
+//! than one that stays down (an unstable link, one bad backend behind a load
+//! balancer, a value hovering at a threshold), so it gets its own finding.
+//! Every measured status is recorded here before hysteresis holds it back.
+
+use crate::diagnostics::{ids, CheckStatus};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Window the transitions are counted in
+pub const FLAP_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Status changes within the window that count as flapping
+pub const FLAP_TRANSITIONS: usize = 4;
+
+static MEASURED: Mutex<BTreeMap<String, VecDeque<(Instant, CheckStatus)>>> = Mutex::new(BTreeMap::new());
+
+/// Record a measured status of check `id` (one call per run)
+pub fn observe(id: &str, status: CheckStatus) {
+    if !matches!(status, CheckStatus::Ok | CheckStatus::Warning | CheckStatus::Error) {
+        return;
+    }
+    let mut measured = MEASURED.lock().unwrap_or_else(|e| e.into_inner());
+    let samples = measured.entry(id.to_string()).or_default();
+    samples.push_back((Instant::now(), status));
+    while samples.front().is_some_and(|(at, _)| at.elapsed() > FLAP_WINDOW) {
+        samples.pop_front();
+    }
+}
+
+/// Status changes of check `id` within the window
+pub fn transitions(id: &str) -> usize {
+    let measured = MEASURED.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(samples) = measured.get(id) else {
+        return 0;
+    };
+    let recent: Vec<CheckStatus> = samples.iter().filter(|(at, _)| at.elapsed() <= FLAP_WINDOW).map(|(_, s)| *s).collect();
+    recent.windows(2).filter(|pair| pair[0] != pair[1]).count()
+}
+
+/// Checks flapping right now, with their transition counts
+pub fn flapping() -> Vec<(&'static str, usize)> {
+    ids::ALL
+        .iter()
+        .map(|id| (*id, transitions(id)))
+        .filter(|(_, count)| *count >= FLAP_TRANSITIONS)
+        .collect()
+}
+
+/// Where to look, by kind of check
+pub fn likely_cause(id: &str) -> &'static str {
+    match id {
+        ids::GATEWAY | ids::INTERNET | ids::QUIC | ids::FIREWALL | ids::BANDWIDTH => {
+            "An unstable link rather than an outage: Wi-Fi roaming or power saving, a loose cable, an overloaded router."
+        }
+        ids::CLAUDE | ids::OPENAI | ids::GOOGLE | ids::RELAY | ids::ENDPOINT_CERTS => {
+            "Only some requests fail: a bad backend behind the provider's or a proxy's load balancer, or rate limiting at the edge."
+        }
+        _ => "The value hovers at a threshold; set hysteresis for this check to steady it.",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alternating_status_is_flapping() {
+        let id = "test.flapping";
+        for run in 0..7 {
+            observe(id, if run % 2 == 0 { CheckStatus::Ok } else { CheckStatus::Error });
+        }
+        assert_eq!(transitions(id), 6);
+        assert!(transitions(id) >= FLAP_TRANSITIONS);
+    }
+}
@@ -1,26 +1,15 @@
-//! GPU monitoring for Windows using WMI
+//! GPU monitoring for Windows using DXGI and D3DKMT
+//!
+//! Adapters are enumerated through DXGI; usage comes from the kernel
+//! graphics statistics (D3DKMTQueryStatistics), the same source Task
+//! Manager uses. No COM apartment or WMI service is needed.
 //!
 //! Supports Intel iGPU, NVIDIA, and AMD GPUs
 
 use crate::diagnostics::{CheckResult, CheckStatus};
 
-#[cfg(target_os = "windows")]
-use wmi::{COMLibrary, WMIConnection};
-
-#[cfg(target_os = "windows")]
-use serde::Deserialize;
-
-#[cfg(target_os = "windows")]
-#[derive(Deserialize, Debug)]
-#[serde(rename_all = "PascalCase")]
-struct Win32VideoController {
-    name: Option<String>,
-    adapter_ram: Option<u64>,
-    #[serde(rename = "CurrentHorizontalResolution")]
-    current_horizontal_resolution: Option<u32>,
-}
-
 /// GPU usage info
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct GpuInfo {
     pub name: String,
@@ -29,10 +18,9 @@ pub struct GpuInfo {
 }
 
 /// Check GPU status
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", feature = "gpu"))]
 pub fn check_gpu() -> CheckResult {
-    // Try to get GPU info via WMI
-    match get_gpu_info_wmi() {
+    match get_gpu_info() {
         Ok(gpus) => {
             if gpus.is_empty() {
                 return CheckResult::new("GPU", CheckStatus::Inactive, "No GPU detected");
@@ -69,18 +57,7 @@ pub fn check_gpu() -> CheckResult {
 
             CheckResult::new("GPU", status, &details)
         }
-        Err(_) => {
-            // Graceful fallback: try to at least list GPUs
-            match get_gpu_names_only() {
-                Ok(names) if !names.is_empty() => {
-                    let details = format!("{} (usage N/A)", names.join(", "));
-                    CheckResult::new("GPU", CheckStatus::Ok, &details)
-                }
-                _ => {
-                    CheckResult::new("GPU", CheckStatus::Warning, "GPU detected (monitoring unavailable)")
-                }
-            }
-        }
+        Err(e) => CheckResult::new("GPU", CheckStatus::Warning, "GPU monitoring unavailable").with_message(&e),
     }
 }
 
@@ -89,7 +66,13 @@ pub fn check_gpu() -> CheckResult {
     CheckResult::new("GPU", CheckStatus::Inactive, "GPU monitoring only available on Windows")
 }
 
+#[cfg(all(target_os = "windows", not(feature = "gpu")))]
+pub fn check_gpu() -> CheckResult {
+    CheckResult::new("GPU", CheckStatus::Inactive, "GPU monitoring not included in this build")
+}
+
 /// Shorten common GPU names for display
+#[cfg_attr(not(all(target_os = "windows", feature = "gpu")), allow(dead_code))]
 fn shorten_gpu_name(name: &str) -> String {
     let name = name.trim();
     
@@ -129,7 +112,15 @@ fn shorten_gpu_name(name: &str) -> String {
                 return format!("RX {}", model);
             }
         }
-        return name.replace("AMD ", "").replace("Radeon ", "Radeon ");
+        return name.replace("AMD ", "");
+    }
+    
+    // Qualcomm (Windows on ARM)
+    if name.contains("Adreno") {
+        if let Some(model) = name.split("Adreno").nth(1).and_then(|rest| rest.split_whitespace().nth(1)) {
+            return format!("Adreno {}", model);
+        }
+        return "Adreno".to_string();
     }
     
     // Return as-is if unknown
@@ -140,6 +131,7 @@ fn shorten_gpu_name(name: &str) -> String {
     }
 }
 
+#[cfg_attr(not(all(target_os = "windows", feature = "gpu")), allow(dead_code))]
 fn extract_number_after(s: &str, prefix: &str) -> Option<String> {
     if let Some(idx) = s.find(prefix) {
         let after = &s[idx + prefix.len()..];
@@ -154,6 +146,7 @@ fn extract_number_after(s: &str, prefix: &str) -> Option<String> {
     None
 }
 
+#[cfg_attr(not(all(target_os = "windows", feature = "gpu")), allow(dead_code))]
 fn extract_rtx_model(s: &str) -> Option<String> {
     if let Some(idx) = s.find("RTX") {
         let after = &s[idx + 3..];
@@ -169,6 +162,7 @@ fn extract_rtx_model(s: &str) -> Option<String> {
     None
 }
 
+#[cfg_attr(not(all(target_os = "windows", feature = "gpu")), allow(dead_code))]
 fn extract_gtx_model(s: &str) -> Option<String> {
     if let Some(idx) = s.find("GTX") {
         let after = &s[idx + 3..];
@@ -184,6 +178,7 @@ fn extract_gtx_model(s: &str) -> Option<String> {
     None
 }
 
+#[cfg_attr(not(all(target_os = "windows", feature = "gpu")), allow(dead_code))]
 fn extract_rx_model(s: &str) -> Option<String> {
     if let Some(idx) = s.find("RX") {
         let after = &s[idx + 2..];
@@ -199,53 +194,338 @@ fn extract_rx_model(s: &str) -> Option<String> {
     None
 }
 
-/// Get GPU info using WMI
-#[cfg(target_os = "windows")]
-fn get_gpu_info_wmi() -> Result<Vec<GpuInfo>, String> {
-    let com_con = COMLibrary::new().map_err(|e| format!("COM init failed: {:?}", e))?;
-    let wmi_con = WMIConnection::new(com_con).map_err(|e| format!("WMI connection failed: {:?}", e))?;
+/// How long engine running time is sampled for usage
+#[cfg(all(target_os = "windows", feature = "gpu"))]
+const USAGE_SAMPLE: std::time::Duration = std::time::Duration::from_millis(250);
 
-    // Query video controllers
-    let results: Vec<Win32VideoController> = wmi_con
-        .raw_query("SELECT Name, AdapterRAM FROM Win32_VideoController")
-        .map_err(|e| format!("WMI query failed: {:?}", e))?;
+/// GPU usage between consecutive calls, for continuous monitoring without a sleep per sample
+#[derive(Default)]
+pub struct UsageSampler {
+    #[cfg(all(target_os = "windows", feature = "gpu"))]
+    previous: Option<(std::time::Instant, Vec<Option<Vec<i64>>>)>,
+    #[cfg(all(target_os = "windows", feature = "gpu"))]
+    adapters: Option<Vec<dxgi::Adapter>>,
+}
 
-    let gpus: Vec<GpuInfo> = results
-        .into_iter()
-        .filter_map(|vc| {
-            let name = vc.name?;
-            Some(GpuInfo {
-                name,
-                usage_percent: None, // WMI doesn't provide real-time usage easily
-                memory_mb: vc.adapter_ram.map(|r| r / (1024 * 1024)),
+impl UsageSampler {
+    /// Busiest engine of any adapter since the previous call (`None` on the first call)
+    #[cfg(all(target_os = "windows", feature = "gpu"))]
+    pub fn sample(&mut self) -> Option<f32> {
+        let adapters = self.adapters.get_or_insert_with(|| dxgi::hardware_adapters().unwrap_or_default());
+        let times: Vec<Option<Vec<i64>>> = adapters.iter().map(|a| d3dkmt::node_running_times(a.luid)).collect();
+        let now = std::time::Instant::now();
+        let (then, before) = self.previous.replace((now, times.clone()))?;
+        // Running time is in 100ns units
+        let window = now.duration_since(then).as_nanos() as f64 / 100.0;
+        if window <= 0.0 {
+            return None;
+        }
+        before
+            .iter()
+            .zip(&times)
+            .filter_map(|pair| match pair {
+                (Some(before), Some(after)) => before
+                    .iter()
+                    .zip(after)
+                    .map(|(b, a)| ((a - b) as f64 / window * 100.0).clamp(0.0, 100.0) as f32)
+                    .reduce(f32::max),
+                _ => None,
             })
+            .reduce(f32::max)
+    }
+
+    #[cfg(not(all(target_os = "windows", feature = "gpu")))]
+    pub fn sample(&mut self) -> Option<f32> {
+        None
+    }
+}
+
+/// Enumerate hardware adapters via DXGI and sample their engine usage
+#[cfg(all(target_os = "windows", feature = "gpu"))]
+fn get_gpu_info() -> Result<Vec<GpuInfo>, String> {
+    let adapters = match dxgi::hardware_adapters() {
+        Ok(adapters) if !adapters.is_empty() => adapters,
+        // Some Windows-on-ARM (Snapdragon) drivers are not listed as DXGI hardware adapters
+        Ok(_) => return fallback_gpu_info(),
+        Err(e) => return fallback_gpu_info().map_err(|_| e),
+    };
+
+    let before: Vec<Option<Vec<i64>>> = adapters.iter().map(|a| d3dkmt::node_running_times(a.luid)).collect();
+    let started = std::time::Instant::now();
+    std::thread::sleep(USAGE_SAMPLE);
+    let after: Vec<Option<Vec<i64>>> = adapters.iter().map(|a| d3dkmt::node_running_times(a.luid)).collect();
+    // Running time is in 100ns units
+    let window = started.elapsed().as_nanos() as f64 / 100.0;
+
+    let mut gpus: Vec<(String, GpuInfo)> = adapters
+        .into_iter()
+        .zip(before.into_iter().zip(after))
+        .map(|(adapter, samples)| {
+            // Busiest engine (3D, copy, video...), like Task Manager's headline figure
+            let usage_percent = match samples {
+                (Some(before), Some(after)) if window > 0.0 => before
+                    .iter()
+                    .zip(&after)
+                    .map(|(b, a)| ((a - b) as f64 / window * 100.0).clamp(0.0, 100.0) as f32)
+                    .reduce(f32::max),
+                _ => None,
+            };
+            let gpu = GpuInfo {
+                name: adapter.name,
+                usage_percent,
+                memory_mb: Some(adapter.dedicated_memory / (1024 * 1024)),
+            };
+            (pdh::luid_key(adapter.luid), gpu)
         })
         .collect();
 
-    // Try to get GPU usage from performance counters
-    // This is more complex and may require additional queries
-    // For now, we'll just return the GPU list
-    
-    Ok(gpus)
+    // Drivers without D3DKMT node statistics (seen on ARM64) still feed the GPU Engine counters
+    if gpus.iter().any(|(_, gpu)| gpu.usage_percent.is_none()) {
+        if let Ok(engines) = pdh::engine_usage() {
+            for (luid, gpu) in gpus.iter_mut().filter(|(_, gpu)| gpu.usage_percent.is_none()) {
+                gpu.usage_percent = engines.iter().find(|(key, _)| key == luid).map(|(_, usage)| *usage);
+            }
+        }
+    }
+
+    Ok(gpus.into_iter().map(|(_, gpu)| gpu).collect())
 }
 
-/// Simple fallback to just get GPU names via different method
-#[cfg(target_os = "windows")]
-fn get_gpu_names_only() -> Result<Vec<String>, String> {
-    let com_con = COMLibrary::new().map_err(|e| format!("COM: {:?}", e))?;
-    let wmi_con = WMIConnection::new(com_con).map_err(|e| format!("WMI: {:?}", e))?;
-    
-    #[derive(Deserialize, Debug)]
-    #[serde(rename_all = "PascalCase")]
-    struct SimpleGpu {
-        name: Option<String>,
-    }
-    
-    let results: Vec<SimpleGpu> = wmi_con
-        .raw_query("SELECT Name FROM Win32_VideoController")
-        .map_err(|e| format!("{:?}", e))?;
-    
-    Ok(results.into_iter()
-        .filter_map(|g| g.name.map(|n| shorten_gpu_name(&n)))
+/// Adapters from the display device class when DXGI lists none
+#[cfg(all(target_os = "windows", feature = "gpu"))]
+fn fallback_gpu_info() -> Result<Vec<GpuInfo>, String> {
+    let names = display_driver_names()?;
+    // Counters are keyed by LUID, which the registry does not give; only attribute a lone adapter
+    let usage_percent = if names.len() == 1 {
+        pdh::engine_usage().ok().and_then(|engines| engines.into_iter().map(|(_, u)| u).reduce(f32::max))
+    } else {
+        None
+    };
+    Ok(names
+        .into_iter()
+        .map(|name| GpuInfo {
+            name,
+            usage_percent,
+            memory_mb: None,
+        })
         .collect())
 }
+
+/// DriverDesc of every display adapter, skipping Microsoft's basic drivers
+#[cfg(all(target_os = "windows", feature = "gpu"))]
+fn display_driver_names() -> Result<Vec<String>, String> {
+    let output = crate::diagnostics::command("reg")
+        .args([
+            "query",
+            r"HKLM\SYSTEM\CurrentControlSet\Control\Class\{4d36e968-e325-11ce-bfc1-08002be10318}",
+            "/s",
+            "/v",
+            "DriverDesc",
+        ])
+        .output()
+        .map_err(|e| format!("reg query failed: {}", e))?;
+
+    // "    DriverDesc    REG_SZ    Qualcomm(R) Adreno(TM) X1-85 GPU"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut names: Vec<String> = stdout
+        .lines()
+        .filter_map(|line| line.split_once("REG_SZ").map(|(_, name)| name.trim().to_string()))
+        .filter(|name| !name.is_empty() && !name.starts_with("Microsoft Basic"))
+        .collect();
+    names.dedup();
+    Ok(names)
+}
+
+#[cfg(all(target_os = "windows", feature = "gpu"))]
+mod dxgi {
+    use winapi::shared::dxgi::{CreateDXGIFactory1, IDXGIAdapter1, IDXGIFactory1, DXGI_ADAPTER_DESC1, DXGI_ADAPTER_FLAG_SOFTWARE};
+    use winapi::shared::winerror::{DXGI_ERROR_NOT_FOUND, FAILED};
+    use winapi::um::winnt::LUID;
+    use winapi::Interface;
+
+    pub struct Adapter {
+        pub name: String,
+        pub luid: LUID,
+        pub dedicated_memory: u64,
+    }
+
+    /// All non-software adapters (skips the Microsoft Basic Render Driver)
+    pub fn hardware_adapters() -> Result<Vec<Adapter>, String> {
+        let mut factory: *mut IDXGIFactory1 = std::ptr::null_mut();
+        let hr = unsafe { CreateDXGIFactory1(&IDXGIFactory1::uuidof(), &mut factory as *mut _ as *mut _) };
+        if FAILED(hr) || factory.is_null() {
+            return Err(format!("CreateDXGIFactory1 failed: 0x{:08X}", hr));
+        }
+
+        let mut adapters = Vec::new();
+        let mut result = Ok(());
+        for index in 0.. {
+            let mut adapter: *mut IDXGIAdapter1 = std::ptr::null_mut();
+            let hr = unsafe { (*factory).EnumAdapters1(index, &mut adapter) };
+            if hr == DXGI_ERROR_NOT_FOUND {
+                break;
+            }
+            if FAILED(hr) || adapter.is_null() {
+                result = Err(format!("EnumAdapters1 failed: 0x{:08X}", hr));
+                break;
+            }
+
+            let mut desc: DXGI_ADAPTER_DESC1 = unsafe { std::mem::zeroed() };
+            let hr = unsafe { (*adapter).GetDesc1(&mut desc) };
+            unsafe { (*adapter).Release() };
+            if FAILED(hr) || desc.Flags & DXGI_ADAPTER_FLAG_SOFTWARE != 0 {
+                continue;
+            }
+
+            let len = desc.Description.iter().position(|&c| c == 0).unwrap_or(desc.Description.len());
+            adapters.push(Adapter {
+                name: String::from_utf16_lossy(&desc.Description[..len]),
+                luid: desc.AdapterLuid,
+                dedicated_memory: desc.DedicatedVideoMemory as u64,
+            });
+        }
+        unsafe { (*factory).Release() };
+
+        result.map(|_| adapters)
+    }
+}
+
+#[cfg(all(target_os = "windows", feature = "gpu"))]
+mod d3dkmt {
+    use winapi::shared::ntdef::{HANDLE, NTSTATUS};
+    use winapi::um::winnt::LUID;
+
+    const QUERYSTATISTICS_ADAPTER: u32 = 0;
+    const QUERYSTATISTICS_NODE: u32 = 5;
+
+    /// D3DKMT_QUERYSTATISTICS (808 bytes on x64). The result union is only
+    /// read through its first field, so it is kept as raw storage.
+    #[repr(C)]
+    struct QueryStatistics {
+        kind: u32,
+        adapter_luid: LUID,
+        process: HANDLE,
+        /// D3DKMT_QUERYSTATISTICS_RESULT (776 bytes)
+        result: [u64; 97],
+        /// QueryNode.NodeId (other query kinds ignore it)
+        node_id: u32,
+    }
+
+    #[link(name = "gdi32")]
+    extern "system" {
+        fn D3DKMTQueryStatistics(stats: *mut QueryStatistics) -> NTSTATUS;
+    }
+
+    fn query(kind: u32, luid: LUID, node_id: u32) -> Option<[u64; 97]> {
+        let mut stats: QueryStatistics = unsafe { std::mem::zeroed() };
+        stats.kind = kind;
+        stats.adapter_luid = luid;
+        stats.node_id = node_id;
+        let status = unsafe { D3DKMTQueryStatistics(&mut stats) };
+        (status >= 0).then_some(stats.result)
+    }
+
+    /// Cumulative running time of every engine node (100ns units)
+    pub fn node_running_times(luid: LUID) -> Option<Vec<i64>> {
+        // AdapterInformation starts with NbSegments, NodeCount (two u32)
+        let adapter = query(QUERYSTATISTICS_ADAPTER, luid, 0)?;
+        let node_count = (adapter[0] >> 32) as u32;
+        // NodeInformation.GlobalInformation.RunningTime is the first field
+        (0..node_count)
+            .map(|node| query(QUERYSTATISTICS_NODE, luid, node).map(|r| r[0] as i64))
+            .collect()
+    }
+}
+
+/// "GPU Engine" performance counters (the Task Manager GPU graphs)
+#[cfg(all(target_os = "windows", feature = "gpu"))]
+mod pdh {
+    use super::USAGE_SAMPLE;
+    use winapi::um::pdh::{
+        PdhAddEnglishCounterW, PdhCloseQuery, PdhCollectQueryData, PdhGetFormattedCounterArrayW, PdhOpenQueryW,
+        PDH_FMT_COUNTERVALUE_ITEM_W, PDH_FMT_DOUBLE, PDH_HCOUNTER, PDH_HQUERY,
+    };
+    use winapi::um::winnt::LUID;
+
+    const PDH_MORE_DATA: i32 = 0x8000_07D2_u32 as i32;
+
+    /// Counter instance key for an adapter, e.g. "0x00000000_0x0000D1E5"
+    pub fn luid_key(luid: LUID) -> String {
+        format!("0x{:08X}_0x{:08X}", luid.HighPart as u32, luid.LowPart)
+    }
+
+    /// Busiest engine utilization per adapter LUID key
+    pub fn engine_usage() -> Result<Vec<(String, f32)>, String> {
+        let mut query: PDH_HQUERY = std::ptr::null_mut();
+        if unsafe { PdhOpenQueryW(std::ptr::null(), 0, &mut query) } != 0 {
+            return Err("PdhOpenQuery failed".to_string());
+        }
+        let result = sample(query);
+        unsafe { PdhCloseQuery(query) };
+        result
+    }
+
+    fn sample(query: PDH_HQUERY) -> Result<Vec<(String, f32)>, String> {
+        let path: Vec<u16> = "\\GPU Engine(*)\\Utilization Percentage\0".encode_utf16().collect();
+        let mut counter: PDH_HCOUNTER = std::ptr::null_mut();
+        if unsafe { PdhAddEnglishCounterW(query, path.as_ptr(), 0, &mut counter) } != 0 {
+            return Err("GPU Engine counters not available".to_string());
+        }
+
+        // Rate counters need two samples
+        unsafe { PdhCollectQueryData(query) };
+        std::thread::sleep(USAGE_SAMPLE);
+        if unsafe { PdhCollectQueryData(query) } != 0 {
+            return Err("PdhCollectQueryData failed".to_string());
+        }
+
+        let (mut size, mut count) = (0u32, 0u32);
+        let status = unsafe {
+            PdhGetFormattedCounterArrayW(counter, PDH_FMT_DOUBLE, &mut size, &mut count, std::ptr::null_mut())
+        };
+        if status != PDH_MORE_DATA {
+            return Ok(Vec::new());
+        }
+        // u64 storage keeps the item array aligned
+        let mut buffer = vec![0u64; (size as usize).div_ceil(8)];
+        let items = buffer.as_mut_ptr() as *mut PDH_FMT_COUNTERVALUE_ITEM_W;
+        if unsafe { PdhGetFormattedCounterArrayW(counter, PDH_FMT_DOUBLE, &mut size, &mut count, items) } != 0 {
+            return Err("PdhGetFormattedCounterArray failed".to_string());
+        }
+        let items = unsafe { std::slice::from_raw_parts(items, count as usize) };
+
+        // "pid_1234_luid_0x00000000_0x0000D1E5_phys_0_eng_3_engtype_Copy": sum each engine over processes
+        let mut engines: Vec<(String, String, f64)> = Vec::new();
+        for item in items {
+            let name = unsafe { wide_to_string(item.szName) };
+            let value = unsafe { *item.FmtValue.u.doubleValue() };
+            let Some(rest) = name.split_once("luid_").map(|(_, r)| r) else {
+                continue;
+            };
+            let luid = rest.get(..21).unwrap_or(rest).to_string();
+            let engine = rest.split_once("eng_").map(|(_, e)| e.to_string()).unwrap_or_default();
+            match engines.iter_mut().find(|(l, e, _)| *l == luid && *e == engine) {
+                Some(entry) => entry.2 += value,
+                None => engines.push((luid, engine, value)),
+            }
+        }
+
+        let mut usage: Vec<(String, f32)> = Vec::new();
+        for (luid, _, value) in engines {
+            let value = value.clamp(0.0, 100.0) as f32;
+            match usage.iter_mut().find(|(l, _)| *l == luid) {
+                Some(entry) => entry.1 = entry.1.max(value),
+                None => usage.push((luid, value)),
+            }
+        }
+        Ok(usage)
+    }
+
+    unsafe fn wide_to_string(ptr: *const u16) -> String {
+        if ptr.is_null() {
+            return String::new();
+        }
+        let len = (0..).take_while(|&i| *ptr.add(i) != 0).count();
+        String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len))
+    }
+}
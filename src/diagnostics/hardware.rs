@@ -0,0 +1,220 @@
+//! Hardware checks on their own worker thread and cadence
+//!
+//! LOCAL RESOURCES (sysinfo) and GPU (DXGI, D3DKMT, performance counters)
+//! query the local machine, and a driver or counter query can stall on a
+//! busy or broken system. In the app they run here instead of inside a
+//! diagnostic run: a run takes the latest hardware results and never waits
+//! for them, and fresh results are patched into the current report when the
+//! worker finishes. Only results from a round started for the run go into
+//! its history entry, so a sample is not recorded again by every run. The
+//! worker samples only when a run asks, and at most once per interval.
+
+use crate::diagnostics::{check_local_resources, flapping, gpu, hysteresis, ids, isolated, CheckResult, DiagnosticReport, DiagnosticSettings, SystemSnapshot};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Checks that run on the worker
+pub const HARDWARE_CHECKS: &[&str] = &[ids::RESOURCES, ids::GPU];
+
+#[derive(Default)]
+struct State {
+    /// Checks wanted, from the settings of the last request
+    resources: bool,
+    gpu: bool,
+    interval: Duration,
+    /// Per-check hysteresis of the last request
+    settings: DiagnosticSettings,
+    /// A run asked for results; cleared by the round that answers it, so
+    /// nothing is sampled between runs (auto-refresh off, paused, on battery)
+    requested: bool,
+    /// Run now, regardless of the interval
+    force: bool,
+    last_run: Option<Instant>,
+    /// When the round behind the results below started
+    results_started: Option<Instant>,
+    resources_result: Option<CheckResult>,
+    gpu_result: Option<CheckResult>,
+    /// Results newer than the report shown
+    updated: bool,
+    stop: bool,
+}
+
+impl State {
+    fn due(&self) -> bool {
+        self.requested && (self.resources || self.gpu) && (self.force || self.interval_passed())
+    }
+
+    fn interval_passed(&self) -> bool {
+        self.last_run.map(|at| at.elapsed() >= self.interval).unwrap_or(true)
+    }
+}
+
+/// Worker thread, stopped (once its current check returns) on drop
+pub struct HardwareWorker {
+    shared: Arc<(Mutex<State>, Condvar)>,
+}
+
+impl HardwareWorker {
+    /// Start the worker; `on_update` runs after each completed round
+    pub fn start(on_update: impl Fn() + Send + 'static) -> Self {
+        let shared = Arc::new((Mutex::new(State::default()), Condvar::new()));
+        let thread_shared = Arc::clone(&shared);
+        std::thread::spawn(move || {
+            let (lock, cond) = &*thread_shared;
+            loop {
+                let (resources, gpu, settings) = {
+                    let mut state = lock.lock().unwrap();
+                    while !state.stop && !state.due() {
+                        // Sampled within the interval: the run takes the last results
+                        state.requested = false;
+                        state = cond.wait(state).unwrap();
+                    }
+                    if state.stop {
+                        return;
+                    }
+                    state.requested = false;
+                    state.force = false;
+                    (state.resources, state.gpu, state.settings.clone())
+                };
+                let started = Instant::now();
+
+                let mut snapshot = SystemSnapshot::new();
+                let mut resources_result = resources.then(|| isolated("LOCAL RESOURCES", || check_local_resources(&mut snapshot)));
+                let mut gpu_result = gpu.then(|| isolated("GPU", gpu::check_gpu));
+                if let Some(ref mut result) = resources_result {
+                    flapping::observe(ids::RESOURCES, result.status);
+                    hysteresis::settle(ids::RESOURCES, result, &settings);
+                }
+                if let Some(ref mut result) = gpu_result {
+                    flapping::observe(ids::GPU, result.status);
+                    hysteresis::settle(ids::GPU, result, &settings);
+                }
+
+                {
+                    let mut state = lock.lock().unwrap();
+                    if resources_result.is_some() {
+                        state.resources_result = resources_result;
+                    }
+                    if gpu_result.is_some() {
+                        state.gpu_result = gpu_result;
+                    }
+                    state.last_run = Some(Instant::now());
+                    state.results_started = Some(started);
+                    state.updated = true;
+                }
+                on_update();
+            }
+        });
+        Self { shared }
+    }
+
+    /// Take over the checks of a run (`reuse` counts as enabled); a hardware
+    /// check re-run on its own runs now instead of when due
+    pub fn request(&self, settings: &DiagnosticSettings, reuse: &[&str]) {
+        let wanted = |id: &str| settings.is_check_enabled(id) || reuse.contains(&id);
+        let force = !reuse.is_empty() && HARDWARE_CHECKS.iter().any(|id| settings.is_check_enabled(id) && !reuse.contains(id));
+        let (lock, cond) = &*self.shared;
+        let mut state = lock.lock().unwrap();
+        state.resources = wanted(ids::RESOURCES);
+        state.gpu = wanted(ids::GPU);
+        state.interval = Duration::from_secs(settings.hardware_interval_secs.max(1) as u64);
+        state.settings = settings.clone();
+        state.requested = true;
+        state.force |= force;
+        cond.notify_one();
+    }
+
+    /// Whether results arrived since the last call
+    pub fn take_updated(&self) -> bool {
+        std::mem::take(&mut self.shared.0.lock().unwrap().updated)
+    }
+
+    /// Put the latest results of the enabled hardware checks into `report`;
+    /// returns when the round behind them started
+    pub fn apply_to(&self, report: &mut DiagnosticReport) -> Option<Instant> {
+        let state = self.shared.0.lock().unwrap();
+        let started = state.results_started;
+        if state.resources {
+            report.local_resources = state.resources_result.clone();
+        }
+        if state.gpu {
+            report.gpu = state.gpu_result.clone();
+        }
+        drop(state);
+        report.diagnose();
+        started
+    }
+}
+
+impl Drop for HardwareWorker {
+    fn drop(&mut self) {
+        let (lock, cond) = &*self.shared;
+        lock.lock().unwrap().stop = true;
+        cond.notify_one();
+    }
+}
+
+/// `report` without its hardware results (stale ones are kept out of history)
+pub fn without_hardware_results(report: &DiagnosticReport) -> DiagnosticReport {
+    DiagnosticReport { local_resources: None, gpu: None, ..report.clone() }
+}
+
+/// Settings for a run that leaves the hardware checks to the worker
+pub fn without_hardware(settings: &DiagnosticSettings) -> DiagnosticSettings {
+    let mut settings = settings.clone();
+    for id in HARDWARE_CHECKS {
+        settings.set_check_enabled(id, false);
+    }
+    settings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn rounds_after(rounds: &AtomicUsize, wait: Duration) -> usize {
+        let deadline = Instant::now() + wait;
+        while rounds.load(Ordering::SeqCst) == 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        rounds.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn one_round_per_request() {
+        let rounds = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&rounds);
+        let worker = HardwareWorker::start(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        let mut settings = DiagnosticSettings::default();
+        settings.set_check_enabled(ids::RESOURCES, true);
+        settings.set_check_enabled(ids::GPU, false);
+        settings.hardware_interval_secs = 1;
+
+        worker.request(&settings, &[]);
+        assert_eq!(rounds_after(&rounds, Duration::from_secs(10)), 1);
+        // No run asks again: nothing is sampled once the interval has passed
+        std::thread::sleep(Duration::from_millis(1500));
+        assert_eq!(rounds.load(Ordering::SeqCst), 1);
+
+        rounds.store(0, Ordering::SeqCst);
+        worker.request(&settings, &[]);
+        assert_eq!(rounds_after(&rounds, Duration::from_secs(10)), 1);
+    }
+
+    #[test]
+    fn request_within_the_interval_reuses_the_last_round() {
+        let mut state = State {
+            requested: true,
+            resources: true,
+            interval: Duration::from_secs(60),
+            last_run: Some(Instant::now()),
+            ..Default::default()
+        };
+        assert!(!state.due());
+        state.force = true;
+        assert!(state.due());
+    }
+}
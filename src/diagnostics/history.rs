@@ -0,0 +1,217 @@
+//! Run history persisted as JSON lines next to the settings file
+//!
+//! Each completed run appends one line to `history.jsonl`. Old entries are
+//! pruned on load so the file never grows without bound.
+
+use crate::diagnostics::{ids, CheckStatus, DiagnosticReport, RunTags};
+use chrono::{Datelike, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// How long runs are kept on disk and in memory
+pub const HISTORY_RETENTION_DAYS: i64 = 14;
+
+/// Timestamp format shared with `DiagnosticReport::timestamp`
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Average latency per weekday (Mon..Sun) and hour of day
+pub type Heatmap = [[Option<u64>; 24]; 7];
+
+/// One recorded run of a check, for latency charts
+pub struct LatencySample {
+    pub time: chrono::NaiveDateTime,
+    pub status: CheckStatus,
+    pub latency_ms: Option<u64>,
+}
+
+/// One check inside a recorded run
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HistoryCheck {
+    pub id: String,
+    pub status: CheckStatus,
+    pub latency_ms: Option<u64>,
+}
+
+/// A single recorded run
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: String,
+    pub checks: Vec<HistoryCheck>,
+    #[serde(default, skip_serializing_if = "RunTags::is_empty")]
+    pub tags: RunTags,
+}
+
+impl HistoryEntry {
+    pub fn from_report(report: &DiagnosticReport) -> Self {
+        Self {
+            timestamp: report.timestamp.clone().unwrap_or_default(),
+            checks: report.checks()
+                .into_iter()
+                .map(|(id, check)| HistoryCheck {
+                    id: id.to_string(),
+                    status: check.status,
+                    latency_ms: check.latency_ms,
+                })
+                .collect(),
+            tags: report.tags.clone(),
+        }
+    }
+
+    /// Parse the entry timestamp (local time)
+    pub fn time(&self) -> Option<chrono::NaiveDateTime> {
+        chrono::NaiveDateTime::parse_from_str(&self.timestamp, TIMESTAMP_FORMAT).ok()
+    }
+
+    /// Find a check by id
+    pub fn check(&self, id: &str) -> Option<&HistoryCheck> {
+        self.checks.iter().find(|c| c.id == id)
+    }
+}
+
+/// All recorded runs, oldest first
+#[derive(Default)]
+pub struct History {
+    pub entries: VecDeque<HistoryEntry>,
+}
+
+impl History {
+    /// Get the history file path
+    fn history_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|p| p.join("opencode-diag").join("history.jsonl"))
+    }
+
+    /// Load history from disk, dropping entries older than the retention window
+    pub fn load() -> Self {
+        let mut history = Self::default();
+        if let Some(path) = Self::history_path() {
+            if let Ok(contents) = std::fs::read_to_string(&path) {
+                history.entries = contents
+                    .lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect();
+                if history.prune() > 0 {
+                    let _ = history.rewrite(&path);
+                }
+            }
+        }
+        history
+    }
+
+    /// Load from disk on a background thread, keeping runs recorded meanwhile;
+    /// `then` runs on that thread with the loaded history
+    pub fn load_into(history: Arc<Mutex<History>>, then: impl FnOnce(&History) + Send + 'static) {
+        std::thread::spawn(move || {
+            let loaded = History::load();
+            let mut history = history.lock().unwrap();
+            let recorded = std::mem::replace(&mut history.entries, loaded.entries);
+            for entry in recorded {
+                if !history.entries.iter().any(|e| e.timestamp == entry.timestamp) {
+                    history.entries.push_back(entry);
+                }
+            }
+            then(&history);
+        });
+    }
+
+    /// Record a completed run and append it to disk
+    pub fn record(&mut self, report: &DiagnosticReport) {
+        let entry = HistoryEntry::from_report(report);
+        if let Some(path) = Self::history_path() {
+            let _ = Self::append(&path, &entry);
+        }
+        self.entries.push_back(entry);
+        self.prune();
+    }
+
+    /// Entries recorded within the last `hours` hours
+    pub fn since_hours(&self, hours: i64) -> impl Iterator<Item = &HistoryEntry> {
+        let cutoff = chrono::Local::now().naive_local() - chrono::Duration::hours(hours);
+        self.entries
+            .iter()
+            .filter(move |e| e.time().map(|t| t >= cutoff).unwrap_or(false))
+    }
+
+    /// Ids of checks that recorded latency at least once, in display order
+    pub fn latency_check_ids(&self) -> Vec<&'static str> {
+        ids::ALL
+            .iter()
+            .copied()
+            .filter(|id| {
+                self.entries
+                    .iter()
+                    .any(|e| e.check(id).and_then(|c| c.latency_ms).is_some())
+            })
+            .collect()
+    }
+
+    /// Average latency of a check bucketed by weekday and hour of day
+    pub fn latency_heatmap(&self, id: &str) -> Heatmap {
+        let mut sums = [[(0u64, 0u64); 24]; 7];
+        for entry in &self.entries {
+            let (Some(time), Some(ms)) = (entry.time(), entry.check(id).and_then(|c| c.latency_ms)) else {
+                continue;
+            };
+            let cell = &mut sums[time.weekday().num_days_from_monday() as usize][time.hour() as usize];
+            cell.0 += ms;
+            cell.1 += 1;
+        }
+
+        let mut heatmap = [[None; 24]; 7];
+        for (day, hours) in sums.iter().enumerate() {
+            for (hour, (sum, count)) in hours.iter().enumerate() {
+                if *count > 0 {
+                    heatmap[day][hour] = Some(sum / count);
+                }
+            }
+        }
+        heatmap
+    }
+
+    /// Runs of a check within the last `hours` hours, oldest first
+    pub fn latency_series(&self, id: &str, hours: i64) -> Vec<LatencySample> {
+        self.since_hours(hours)
+            .filter_map(|e| {
+                let check = e.check(id)?;
+                Some(LatencySample { time: e.time()?, status: check.status, latency_ms: check.latency_ms })
+            })
+            .collect()
+    }
+
+    /// Drop entries outside the retention window, returns how many were removed
+    fn prune(&mut self) -> usize {
+        let cutoff = chrono::Local::now().naive_local()
+            - chrono::Duration::days(HISTORY_RETENTION_DAYS);
+        let before = self.entries.len();
+        self.entries.retain(|e| e.time().map(|t| t >= cutoff).unwrap_or(false));
+        before - self.entries.len()
+    }
+
+    fn append(path: &PathBuf, entry: &HistoryEntry) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        let line = serde_json::to_string(entry)
+            .map_err(|e| format!("Failed to serialize history: {}", e))?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open history file: {}", e))?;
+        writeln!(file, "{}", line).map_err(|e| format!("Failed to write history: {}", e))
+    }
+
+    fn rewrite(&self, path: &PathBuf) -> Result<(), String> {
+        let mut out = String::new();
+        for entry in &self.entries {
+            if let Ok(line) = serde_json::to_string(entry) {
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+        std::fs::write(path, out).map_err(|e| format!("Failed to write history file: {}", e))
+    }
+}
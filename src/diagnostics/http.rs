@@ -0,0 +1,81 @@
+//! HTTP client setup shared by all outbound checks
+//!
+//! Some corporate proxies and providers treat unknown clients differently, so
+//! the User-Agent and extra request headers are configurable to reproduce the
+//! request profile of the real client.
+
+use crate::diagnostics::DiagnosticSettings;
+#[cfg(feature = "net-checks")]
+use crate::diagnostics::api::opencode;
+#[cfg(feature = "net-checks")]
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+#[cfg(feature = "net-checks")]
+use std::time::Duration;
+
+/// Sent when no custom User-Agent is configured
+pub const DEFAULT_USER_AGENT: &str = concat!("opencode-diag/", env!("CARGO_PKG_VERSION"));
+
+/// User-Agent and extra headers applied to every outbound request
+#[derive(Clone, Default)]
+pub struct HttpProfile {
+    pub user_agent: Option<String>,
+    pub headers: Vec<(String, String)>,
+    /// Send OpenCode's own requests (see `api::opencode`)
+    pub opencode_mode: bool,
+}
+
+impl HttpProfile {
+    pub fn from_settings(settings: &DiagnosticSettings) -> Self {
+        let user_agent = settings.user_agent.trim();
+        Self {
+            user_agent: (!user_agent.is_empty()).then(|| user_agent.to_string()),
+            headers: settings.extra_headers.iter().filter_map(|h| parse_header(h).ok()).collect(),
+            opencode_mode: settings.opencode_mode,
+        }
+    }
+
+    /// Client builder with the profile's headers and a timeout
+    #[cfg(feature = "net-checks")]
+    pub fn builder(&self, timeout: Duration) -> reqwest::blocking::ClientBuilder {
+        let mut headers = HeaderMap::new();
+        let default_agent = if self.opencode_mode { opencode::USER_AGENT } else { DEFAULT_USER_AGENT };
+        let user_agent = self.user_agent.as_deref().unwrap_or(default_agent);
+        if let Ok(value) = HeaderValue::from_str(user_agent) {
+            headers.insert(USER_AGENT, value);
+        }
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+                headers.append(name, value);
+            }
+        }
+        let builder = reqwest::blocking::Client::builder().timeout(timeout).default_headers(headers);
+        // OpenCode's runtime speaks HTTP/1.1 to the providers
+        if self.opencode_mode {
+            builder.http1_only()
+        } else {
+            builder
+        }
+    }
+
+    #[cfg(feature = "net-checks")]
+    pub fn client(&self, timeout: Duration) -> Result<reqwest::blocking::Client, String> {
+        self.builder(timeout)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))
+    }
+}
+
+/// Parse a `Name: value` header line
+pub fn parse_header(line: &str) -> Result<(String, String), String> {
+    let (name, value) = line.split_once(':').ok_or("Expected \"Name: value\"")?;
+    let (name, value) = (name.trim(), value.trim());
+    // RFC 9110 token characters
+    let token = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c);
+    if name.is_empty() || !name.chars().all(token) {
+        return Err(format!("Invalid header name \"{}\"", name));
+    }
+    if !value.chars().all(|c| c == '\t' || (' '..='~').contains(&c)) {
+        return Err(format!("Invalid value for {}", name));
+    }
+    Ok((name.to_string(), value.to_string()))
+}
@@ -0,0 +1,85 @@
+//! Status hysteresis: a check changes status only after N runs in a row agree
+//!
+//! A machine hovering around a load threshold turns LOCAL RESOURCES OK,
+//! WARN, OK, WARN on consecutive runs, and every flip lands in the error log.
+//! With hysteresis set for a check, a new OK/WARN/ERROR status is held back
+//! until it has been measured the configured number of times in a row.
+
+use crate::diagnostics::{CheckResult, CheckStatus, DiagnosticSettings};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Preset runs in a row before a status changes (1 = change at once)
+pub const HYSTERESIS_PRESETS: &[u8] = &[1, 2, 3, 5];
+
+/// Per check: the status shown, and a different one seen `count` times in a row
+struct Track {
+    shown: CheckStatus,
+    pending: Option<(CheckStatus, u8)>,
+}
+
+static TRACKS: Mutex<BTreeMap<String, Track>> = Mutex::new(BTreeMap::new());
+
+/// Whether a status takes part (not skipped, unknown or crashed-before-measuring)
+fn is_measured(status: CheckStatus) -> bool {
+    matches!(status, CheckStatus::Ok | CheckStatus::Warning | CheckStatus::Error)
+}
+
+/// Apply hysteresis to a fresh result of check `id`, one call per run
+pub fn settle(id: &str, result: &mut CheckResult, settings: &DiagnosticSettings) {
+    let required = settings.hysteresis_for(id);
+    let mut tracks = TRACKS.lock().unwrap_or_else(|e| e.into_inner());
+    let measured = result.status;
+
+    let track = match tracks.get_mut(id) {
+        Some(track) if required > 1 && is_measured(measured) && is_measured(track.shown) => track,
+        _ => {
+            tracks.insert(id.to_string(), Track { shown: measured, pending: None });
+            return;
+        }
+    };
+    if measured == track.shown {
+        track.pending = None;
+        return;
+    }
+
+    let count = match track.pending {
+        Some((status, count)) if status == measured => count + 1,
+        _ => 1,
+    };
+    if count >= required {
+        track.shown = measured;
+        track.pending = None;
+        return;
+    }
+    track.pending = Some((measured, count));
+
+    // Held: keep the shown status, say what was measured
+    result.status = track.shown;
+    result.details = format!("{} :: {} {}/{} (held {})", result.details, measured.label(), count, required, track.shown.label());
+    if track.shown == CheckStatus::Ok {
+        result.message = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_a_third_warning_in_a_row_shows() {
+        let id = "test.hysteresis";
+        let mut settings = DiagnosticSettings::default();
+        settings.set_hysteresis(id, 3);
+        let measured = [CheckStatus::Ok, CheckStatus::Warning, CheckStatus::Ok, CheckStatus::Warning, CheckStatus::Warning, CheckStatus::Warning];
+        let shown: Vec<CheckStatus> = measured
+            .iter()
+            .map(|status| {
+                let mut result = CheckResult::new("HYSTERESIS", *status, "");
+                settle(id, &mut result, &settings);
+                result.status
+            })
+            .collect();
+        assert_eq!(shown, [CheckStatus::Ok, CheckStatus::Ok, CheckStatus::Ok, CheckStatus::Ok, CheckStatus::Ok, CheckStatus::Warning]);
+    }
+}
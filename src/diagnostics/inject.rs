@@ -0,0 +1,184 @@
+//! Failure injection: a provider outage on demand
+//!
+//! A fallback provider in opencode.json is only trusted once it has taken
+//! over for real. An injection points a provider's API host at 0.0.0.0 in
+//! the hosts file for a few minutes, so neither OpenCode nor the checks here
+//! can reach it. Every line added carries a marker with its end time and is
+//! removed when the time is up, when the app closes, and on the next start
+//! if it never closed cleanly. Writing the hosts file needs administrator
+//! (root) rights.
+
+use crate::diagnostics::actions::log_action;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Providers that can be blocked: label and API host (the hosts the API checks call)
+pub const PROVIDERS: &[(&str, &str)] = &[
+    ("CLAUDE", "api.anthropic.com"),
+    ("OPENAI", "api.openai.com"),
+    ("GOOGLE", "generativelanguage.googleapis.com"),
+];
+
+/// How long an injection lasts, in minutes
+pub const DURATION_PRESETS: &[(u32, &str)] = &[(1, "1M"), (5, "5M"), (15, "15M")];
+
+/// Ends every line an injection adds, followed by its end time
+const MARKER: &str = "# opencode-diag failure injection until";
+
+const LOG_LABEL: &str = "FAILURE INJECTION";
+
+#[cfg(target_os = "windows")]
+pub fn hosts_path() -> PathBuf {
+    let root = std::env::var_os("SystemRoot").unwrap_or_else(|| "C:\\Windows".into());
+    PathBuf::from(root).join("System32").join("drivers").join("etc").join("hosts")
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn hosts_path() -> PathBuf {
+    PathBuf::from("/etc/hosts")
+}
+
+/// The lines added to block `host` until `until` ("12:05:00")
+pub fn hosts_lines(host: &str, until: &str) -> [String; 2] {
+    [format!("0.0.0.0 {} {} {}", host, MARKER, until), format!(":: {} {} {}", host, MARKER, until)]
+}
+
+/// Hosts blocked by an injection and when it ends, from the hosts file
+pub fn active() -> Vec<(String, String)> {
+    let text = std::fs::read_to_string(hosts_path()).unwrap_or_default();
+    let mut blocked: Vec<(String, String)> = text
+        .lines()
+        .filter_map(|line| {
+            let (entry, until) = line.split_once(MARKER)?;
+            Some((entry.split_whitespace().nth(1)?.to_string(), until.trim().to_string()))
+        })
+        .collect();
+    blocked.dedup();
+    blocked
+}
+
+/// `text` with `lines` appended, each after a line break of the file's kind
+/// and the last without one, so removing them restores `text` exactly
+fn with_injection(text: &str, lines: &[String]) -> String {
+    let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut text = text.to_string();
+    for line in lines {
+        text.push_str(newline);
+        text.push_str(line);
+    }
+    text
+}
+
+/// `text` without injected lines, and how many were removed
+pub fn without_injection(text: &str) -> (String, usize) {
+    let mut removed = 0;
+    let mut kept = String::with_capacity(text.len());
+    let mut last_removed = false;
+    for line in text.split_inclusive('\n') {
+        last_removed = line.contains(MARKER);
+        if last_removed {
+            removed += 1;
+        } else {
+            kept.push_str(line);
+        }
+    }
+    // The block ends the file without a line break: the one before it was ours
+    if last_removed && !text.ends_with('\n') {
+        kept.pop();
+        if kept.ends_with('\r') {
+            kept.pop();
+        }
+    }
+    (kept, removed)
+}
+
+/// Replace the hosts file through a temporary file next to it, so a crash or
+/// a full disk never leaves it cut short
+fn write_hosts(text: &str) -> Result<(), String> {
+    let path = hosts_path();
+    let temp = path.with_extension("opencode-diag.tmp");
+    let replace = || -> std::io::Result<()> {
+        let permissions = std::fs::metadata(&path)?.permissions();
+        let mut file = std::fs::File::create(&temp)?;
+        file.write_all(text.as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+        std::fs::set_permissions(&temp, permissions)?;
+        std::fs::rename(&temp, &path)
+    };
+    replace()
+        .inspect_err(|_| {
+            let _ = std::fs::remove_file(&temp);
+        })
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied if cfg!(target_os = "windows") => {
+                "Editing the hosts file needs administrator rights (SETTINGS > RESTART AS ADMINISTRATOR)".to_string()
+            }
+            std::io::ErrorKind::PermissionDenied => "Editing the hosts file needs root (start opencode-diag with sudo)".to_string(),
+            _ => format!("Failed to write {}: {}", path.display(), e),
+        })
+}
+
+/// Block `host` for `minutes`, replacing any injection still in place
+pub fn start(host: &str, minutes: u32) -> Result<String, String> {
+    let path = hosts_path();
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let until = (chrono::Local::now() + chrono::Duration::minutes(minutes as i64)).format("%H:%M:%S").to_string();
+    let lines = hosts_lines(host, &until);
+    let text = with_injection(&without_injection(&text).0, &lines);
+    let result = write_hosts(&text).map(|()| format!("{} blocked until {}", host, until));
+    log_action(LOG_LABEL, &format!("{} += {}", path.display(), lines.join(" / ")), &result);
+    result
+}
+
+/// Remove every injected line; how many hosts were unblocked (the file is
+/// left untouched when there are none)
+pub fn stop() -> Result<usize, String> {
+    let blocked = active().len();
+    if blocked == 0 {
+        return Ok(0);
+    }
+    let path = hosts_path();
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let (text, _) = without_injection(&text);
+    let result = write_hosts(&text);
+    log_action(
+        LOG_LABEL,
+        &format!("{} -= injected lines", path.display()),
+        &result.as_ref().map(|()| format!("{} hosts unblocked", blocked)).map_err(String::clone),
+    );
+    result.map(|()| blocked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removal_leaves_the_users_own_lines() {
+        let hosts = "127.0.0.1 localhost\r\n# 0.0.0.0 ads.example # the user's own comment\r\n";
+        let injected = with_injection(hosts, &hosts_lines("api.anthropic.com", "12:00:00"));
+        let (cleaned, removed) = without_injection(&injected);
+        assert_eq!(removed, 2);
+        assert_eq!(cleaned, hosts);
+    }
+
+    #[test]
+    fn cycles_keep_the_trailing_newline_state() {
+        let lines = hosts_lines("api.openai.com", "12:00:00");
+        for hosts in ["", "127.0.0.1 localhost", "127.0.0.1 localhost\n", "127.0.0.1 localhost\r\n::1 localhost"] {
+            let mut text = hosts.to_string();
+            for _ in 0..3 {
+                text = without_injection(&with_injection(&text, &lines)).0;
+            }
+            assert_eq!(text, hosts);
+        }
+    }
+
+    #[test]
+    fn lines_added_by_older_versions_come_out() {
+        let [v4, v6] = hosts_lines("api.openai.com", "12:00:00");
+        let (cleaned, removed) = without_injection(&format!("127.0.0.1 localhost\n{}\n{}\n", v4, v6));
+        assert_eq!((cleaned.as_str(), removed), ("127.0.0.1 localhost\n", 2));
+    }
+}
@@ -0,0 +1,80 @@
+//! Editor integration (IPC) check
+//!
+//! Editor plugins reach a running OpenCode through its local HTTP server
+//! (`opencode --port N`, a random port otherwise); it exposes no named pipe or
+//! Unix socket. The check finds the ports each instance listens on and makes
+//! sure one of them answers HTTP on loopback, which tells "the plugin can't
+//! reach OpenCode" apart from "OpenCode isn't running".
+
+use crate::diagnostics::{processes, CheckResult, CheckStatus, SystemSnapshot};
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Check that each OpenCode instance answers on its local server
+pub fn check_opencode_ipc(snapshot: &mut SystemSnapshot) -> CheckResult {
+    let instances = processes::opencode_ports(snapshot);
+    if instances.is_empty() {
+        return CheckResult::new("EDITOR IPC", CheckStatus::Inactive, "OpenCode not running");
+    }
+
+    let mut parts = Vec::new();
+    let mut no_server = Vec::new();
+    let mut not_answering = Vec::new();
+    let mut latency = None;
+    for (pid, ports) in &instances {
+        if ports.is_empty() {
+            parts.push(format!("PID {} no server", pid));
+            no_server.push(pid.to_string());
+            continue;
+        }
+        match ports.iter().find_map(|&port| probe(port).map(|ms| (port, ms))) {
+            Some((port, ms)) => {
+                parts.push(format!("PID {} :{} OK {}ms", pid, port, ms));
+                latency = latency.max(Some(ms));
+            }
+            None => {
+                let ports: Vec<String> = ports.iter().map(|p| format!(":{}", p)).collect();
+                parts.push(format!("PID {} {} no answer", pid, ports.join(",")));
+                not_answering.push(pid.to_string());
+            }
+        }
+    }
+    let details = parts.join(" :: ");
+
+    let result = if !not_answering.is_empty() {
+        CheckResult::new("EDITOR IPC", CheckStatus::Error, &details).with_message(&format!(
+            "OpenCode (PID {}) listens but does not answer; restart it",
+            not_answering.join(", ")
+        ))
+    } else if !no_server.is_empty() {
+        CheckResult::new("EDITOR IPC", CheckStatus::Warning, &details).with_message(&format!(
+            "OpenCode (PID {}) has no local server; editor plugins can't connect",
+            no_server.join(", ")
+        ))
+    } else {
+        CheckResult::new("EDITOR IPC", CheckStatus::Ok, &details)
+    };
+    match latency {
+        Some(ms) => result.with_latency(ms),
+        None => result,
+    }
+}
+
+/// Send a plain GET to 127.0.0.1:port; any HTTP status line counts as an answer
+fn probe(port: u16) -> Option<u128> {
+    let started = Instant::now();
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let mut stream = TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).ok()?;
+    stream.set_read_timeout(Some(PROBE_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(PROBE_TIMEOUT)).ok()?;
+    stream
+        .write_all(b"GET /config HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\n\r\n")
+        .ok()?;
+
+    let mut head = [0u8; 12];
+    stream.read_exact(&mut head).ok()?;
+    head.starts_with(b"HTTP/").then(|| started.elapsed().as_millis())
+}
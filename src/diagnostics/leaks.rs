@@ -0,0 +1,229 @@
+//! Open file handles of OpenCode and temp directory growth across runs
+//!
+//! A leaked handle per tool call or a temp file per request goes unnoticed
+//! for hours, then a long session dies with EMFILE ("too many open files")
+//! or a full disk. Each run samples the handle count of every OpenCode
+//! process tree and the size of the temp directory; the trend over the
+//! samples kept in memory is what gets reported.
+
+use crate::diagnostics::traffic::format_bytes;
+use crate::diagnostics::{processes, CheckResult, CheckStatus, SystemSnapshot};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use sysinfo::Pid;
+
+/// Samples older than this are dropped
+const WINDOW: Duration = Duration::from_secs(6 * 3600);
+
+/// Runs needed before a trend is reported
+const MIN_SAMPLES: usize = 3;
+
+/// Time the samples must span before a trend is reported
+const MIN_SPAN: Duration = Duration::from_secs(10 * 60);
+
+/// Handle growth over the samples that counts as a leak
+const HANDLE_GROWTH: usize = 500;
+
+/// Temp directory growth over the samples that counts as a leak
+const TEMP_GROWTH_BYTES: u64 = 1_000_000_000;
+
+/// Share of the open-file limit at which the check turns red
+const LIMIT_RATIO: f64 = 0.8;
+
+/// Temp entries looked at per sample
+const MAX_TEMP_FILES: usize = 20_000;
+
+/// How deep the temp walk goes
+const MAX_TEMP_DEPTH: usize = 4;
+
+/// One run's measurements
+struct Sample {
+    at: Instant,
+    /// Open handles per OpenCode instance (root pid), summed over its tree
+    handles: BTreeMap<Pid, usize>,
+    temp_bytes: u64,
+}
+
+static SAMPLES: Mutex<VecDeque<Sample>> = Mutex::new(VecDeque::new());
+
+/// Whether a series rises through most of its steps, not just end over start
+fn mostly_rising<T: PartialOrd>(values: &[T]) -> bool {
+    let rises = values.windows(2).filter(|w| w[1] > w[0]).count();
+    values.len() >= MIN_SAMPLES && rises * 3 >= (values.len() - 1) * 2
+}
+
+/// "42m" / "3h 10m"
+fn format_span(span: Duration) -> String {
+    let minutes = span.as_secs() / 60;
+    if minutes < 60 { format!("{}m", minutes) } else { format!("{}h {}m", minutes / 60, minutes % 60) }
+}
+
+pub fn check_leaks(snapshot: &mut SystemSnapshot) -> CheckResult {
+    let trees = processes::opencode_trees(snapshot);
+    if trees.is_empty() {
+        return CheckResult::new("LEAKS", CheckStatus::Inactive, "OpenCode not running");
+    }
+
+    let mut handles = BTreeMap::new();
+    let mut near_limit = Vec::new();
+    for (root, tree) in &trees {
+        let mut total = 0;
+        for pid in tree {
+            let Some(count) = open_handles(*pid) else {
+                continue;
+            };
+            total += count;
+            if let Some(limit) = open_file_limit(*pid) {
+                if count as f64 >= limit as f64 * LIMIT_RATIO {
+                    near_limit.push((*pid, count, limit));
+                }
+            }
+        }
+        handles.insert(*root, total);
+    }
+    let temp_bytes = temp_size();
+
+    let mut samples = SAMPLES.lock().unwrap_or_else(|e| e.into_inner());
+    samples.retain(|s| s.at.elapsed() < WINDOW);
+    samples.push_back(Sample { at: Instant::now(), handles: handles.clone(), temp_bytes });
+
+    let mut details = Vec::new();
+    let mut leaks = Vec::new();
+    for (root, count) in &handles {
+        // Samples of this instance only; a restart starts a new series
+        let series: Vec<(Instant, usize)> = samples.iter().filter_map(|s| Some((s.at, *s.handles.get(root)?))).collect();
+        let (first_at, first) = series[0];
+        let span = first_at.elapsed();
+        let growth = count.saturating_sub(first);
+        let values: Vec<usize> = series.iter().map(|(_, v)| *v).collect();
+        if series.len() >= MIN_SAMPLES && span >= MIN_SPAN {
+            details.push(format!("PID {} {} handles ({:+} in {})", root, count, *count as i64 - first as i64, format_span(span)));
+            if growth >= HANDLE_GROWTH && mostly_rising(&values) {
+                leaks.push(format!(
+                    "OpenCode (PID {}) open handles grew from {} to {} in {}; a leak ends in \"too many open files\". Restart OpenCode before long sessions.",
+                    root,
+                    first,
+                    count,
+                    format_span(span)
+                ));
+            }
+        } else {
+            details.push(format!("PID {} {} handles", root, count));
+        }
+    }
+
+    let temp_series: Vec<u64> = samples.iter().map(|s| s.temp_bytes).collect();
+    let temp_span = samples.front().map(|s| s.at.elapsed()).unwrap_or_default();
+    let temp_growth = temp_bytes.saturating_sub(temp_series[0]);
+    if samples.len() >= MIN_SAMPLES && temp_span >= MIN_SPAN {
+        details.push(format!("temp {} (+{} in {})", format_bytes(temp_bytes), format_bytes(temp_growth), format_span(temp_span)));
+        if temp_growth >= TEMP_GROWTH_BYTES && mostly_rising(&temp_series) {
+            leaks.push(format!(
+                "Temp directory {} grew by {} in {} while OpenCode ran; clear it and watch whether it grows again.",
+                std::env::temp_dir().display(),
+                format_bytes(temp_growth),
+                format_span(temp_span)
+            ));
+        }
+    } else {
+        details.push(format!("temp {}", format_bytes(temp_bytes)));
+        details.push(format!("trend after {} runs over {}m ({} so far)", MIN_SAMPLES, MIN_SPAN.as_secs() / 60, samples.len()));
+    }
+    let details = details.join(" :: ");
+
+    if let Some((pid, count, limit)) = near_limit.first() {
+        return CheckResult::new("LEAKS", CheckStatus::Error, &details).with_message(&format!(
+            "PID {} has {} of {} allowed open files; it will fail with EMFILE (too many open files). Restart OpenCode, or raise the limit with ulimit -n.",
+            pid, count, limit
+        ));
+    }
+    match leaks.first() {
+        Some(leak) => CheckResult::new("LEAKS", CheckStatus::Warning, &details).with_message(leak),
+        None => CheckResult::new("LEAKS", CheckStatus::Ok, &details),
+    }
+}
+
+/// Bytes in the temp directory, up to the walk limits
+fn temp_size() -> u64 {
+    let mut bytes = 0;
+    let mut scanned = 0;
+    let mut pending = vec![(std::env::temp_dir(), 0)];
+    while let Some((dir, depth)) = pending.pop() {
+        for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+            if scanned >= MAX_TEMP_FILES {
+                return bytes;
+            }
+            scanned += 1;
+            let Ok(metadata) = std::fs::symlink_metadata(entry.path()) else {
+                continue;
+            };
+            if metadata.is_dir() {
+                if depth < MAX_TEMP_DEPTH {
+                    pending.push((entry.path(), depth + 1));
+                }
+            } else {
+                bytes += metadata.len();
+            }
+        }
+    }
+    bytes
+}
+
+/// Entries in `/proc/<pid>/fd` (only readable for our own user's processes)
+#[cfg(target_os = "linux")]
+fn open_handles(pid: Pid) -> Option<usize> {
+    Some(std::fs::read_dir(format!("/proc/{}/fd", pid)).ok()?.count())
+}
+
+/// File descriptors listed by lsof
+#[cfg(target_os = "macos")]
+fn open_handles(pid: Pid) -> Option<usize> {
+    let output = crate::diagnostics::command("lsof")
+        .args(["-n", "-P", "-F", "f", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    // "f12" per numbered descriptor; cwd/txt/mem entries are not descriptors
+    let count = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.strip_prefix('f').is_some_and(|fd| fd.chars().all(|c| c.is_ascii_digit())))
+        .count();
+    Some(count)
+}
+
+/// Kernel handles of any type (files, events, sockets, ...)
+#[cfg(target_os = "windows")]
+fn open_handles(pid: Pid) -> Option<usize> {
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::{GetProcessHandleCount, OpenProcess};
+    use winapi::um::winnt::PROCESS_QUERY_LIMITED_INFORMATION;
+
+    let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid.as_u32()) };
+    if process.is_null() {
+        return None;
+    }
+    let mut count = 0;
+    let ok = unsafe { GetProcessHandleCount(process, &mut count) };
+    unsafe { CloseHandle(process) };
+    (ok != 0).then_some(count as usize)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn open_handles(_pid: Pid) -> Option<usize> {
+    None
+}
+
+/// Soft "Max open files" from `/proc/<pid>/limits`
+#[cfg(target_os = "linux")]
+fn open_file_limit(pid: Pid) -> Option<usize> {
+    let limits = std::fs::read_to_string(format!("/proc/{}/limits", pid)).ok()?;
+    // "Max open files            1024                 4096                 files"
+    let line = limits.lines().find(|line| line.starts_with("Max open files"))?;
+    line.split_whitespace().nth(3)?.parse().ok()
+}
+
+/// No per-process file limit to read (Windows handles run into the millions)
+#[cfg(not(target_os = "linux"))]
+fn open_file_limit(_pid: Pid) -> Option<usize> {
+    None
+}
@@ -0,0 +1,101 @@
+//! Live CPU, RAM and network throughput while a run is in progress
+//!
+//! A spike during a run (an antivirus scan, a sync client saturating the
+//! uplink) skews the latencies it measures, so it is shown next to the
+//! results instead of being lost between two auto-refreshes.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use sysinfo::{MemoryRefreshKind, Networks, System};
+
+pub const SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// One minute of samples
+const MAX_SAMPLES: usize = 240;
+
+/// One sample of the whole machine
+#[derive(Clone, Copy, Default)]
+pub struct Sample {
+    pub cpu: f32,
+    pub ram: f32,
+    /// Bytes per second over all interfaces
+    pub rx: u64,
+    pub tx: u64,
+}
+
+/// Sampler thread for one run, stopped with `stop`
+pub struct LiveMetrics {
+    samples: Mutex<Vec<Sample>>,
+    stopped: AtomicBool,
+}
+
+impl LiveMetrics {
+    /// Start sampling every `SAMPLE_INTERVAL`; `on_sample` runs after each sample
+    pub fn start(on_sample: impl Fn() + Send + 'static) -> Arc<Self> {
+        let live = Arc::new(Self {
+            samples: Mutex::new(Vec::new()),
+            stopped: AtomicBool::new(false),
+        });
+        let sampler = Arc::clone(&live);
+        std::thread::spawn(move || {
+            let mut sys = System::new();
+            let mut networks = Networks::new_with_refreshed_list();
+            sys.refresh_cpu_usage();
+            let mut last = Instant::now();
+            loop {
+                std::thread::sleep(SAMPLE_INTERVAL);
+                if sampler.stopped.load(Ordering::Relaxed) {
+                    break;
+                }
+                sys.refresh_cpu_usage();
+                sys.refresh_memory_specifics(MemoryRefreshKind::new().with_ram());
+                networks.refresh();
+                let secs = last.elapsed().as_secs_f64().max(0.001);
+                last = Instant::now();
+
+                let per_sec = |bytes: u64| (bytes as f64 / secs) as u64;
+                let sample = Sample {
+                    cpu: sys.global_cpu_usage(),
+                    ram: sys.used_memory() as f32 / sys.total_memory().max(1) as f32 * 100.0,
+                    rx: per_sec(networks.values().map(|n| n.received()).sum()),
+                    tx: per_sec(networks.values().map(|n| n.transmitted()).sum()),
+                };
+                {
+                    let mut samples = sampler.samples.lock().unwrap();
+                    if samples.len() == MAX_SAMPLES {
+                        samples.remove(0);
+                    }
+                    samples.push(sample);
+                }
+                on_sample();
+            }
+        });
+        live
+    }
+
+    /// End sampling (the samples stay readable)
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.stopped.load(Ordering::Relaxed)
+    }
+
+    pub fn samples(&self) -> Vec<Sample> {
+        self.samples.lock().unwrap().clone()
+    }
+
+    /// Highest value of each metric over the run
+    pub fn peak(&self) -> Option<Sample> {
+        let samples = self.samples.lock().unwrap();
+        let first = *samples.first()?;
+        Some(samples.iter().fold(first, |peak, s| Sample {
+            cpu: peak.cpu.max(s.cpu),
+            ram: peak.ram.max(s.ram),
+            rx: peak.rx.max(s.rx),
+            tx: peak.tx.max(s.tx),
+        }))
+    }
+}
@@ -0,0 +1,75 @@
+//! Windows long path support
+//!
+//! Deep `node_modules` trees easily pass the 260 character MAX_PATH limit.
+//! Without `LongPathsEnabled` and git's `core.longpaths`, agents then fail
+//! with "file not found" or "Filename too long" errors that don't mention the
+//! path length at all.
+
+use crate::diagnostics::{CheckResult, CheckStatus};
+
+/// Check the LongPathsEnabled policy and git's core.longpaths
+#[cfg(target_os = "windows")]
+pub fn check_long_paths() -> CheckResult {
+    let windows = long_paths_enabled();
+    let git = git_long_paths();
+
+    let mut details = format!("Windows {}", on_off(windows));
+    match git {
+        Some(git) => details.push_str(&format!(" :: git {}", on_off(git))),
+        None => details.push_str(" :: git not found"),
+    }
+
+    let mut fixes = Vec::new();
+    if !windows {
+        fixes.push(
+            "Enable long paths (admin): reg add HKLM\\SYSTEM\\CurrentControlSet\\Control\\FileSystem /v LongPathsEnabled /t REG_DWORD /d 1 /f",
+        );
+    }
+    if git == Some(false) {
+        fixes.push("Enable in git: git config --global core.longpaths true");
+    }
+
+    if fixes.is_empty() {
+        CheckResult::new("LONG PATHS", CheckStatus::Ok, &details)
+    } else {
+        CheckResult::new("LONG PATHS", CheckStatus::Warning, &details).with_message(&fixes.join("; "))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn check_long_paths() -> CheckResult {
+    CheckResult::new("LONG PATHS", CheckStatus::Inactive, "Path length limit only applies on Windows")
+}
+
+#[cfg(target_os = "windows")]
+fn on_off(enabled: bool) -> &'static str {
+    if enabled { "ON" } else { "OFF" }
+}
+
+/// HKLM\SYSTEM\CurrentControlSet\Control\FileSystem\LongPathsEnabled = 1
+#[cfg(target_os = "windows")]
+fn long_paths_enabled() -> bool {
+    let output = crate::diagnostics::command("reg")
+        .args([
+            "query",
+            "HKLM\\SYSTEM\\CurrentControlSet\\Control\\FileSystem",
+            "/v",
+            "LongPathsEnabled",
+        ])
+        .output();
+    match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).contains("0x1"),
+        Err(_) => false,
+    }
+}
+
+/// Effective core.longpaths (system, global or Git for Windows default), `None` without git
+#[cfg(target_os = "windows")]
+fn git_long_paths() -> Option<bool> {
+    let output = crate::diagnostics::command("git")
+        .args(["config", "--get", "--type=bool", "core.longpaths"])
+        .output()
+        .ok()?;
+    // Exit code 1 with no output means unset (off)
+    Some(String::from_utf8_lossy(&output.stdout).trim() == "true")
+}
@@ -2,19 +2,129 @@
 //!
 //! Checks the chain: [User PC] -> [Internet] -> [Claude API] -> [OpenCode]
 
+pub mod actions;
+#[cfg_attr(not(feature = "net-checks"), path = "api/disabled.rs")]
 pub mod api;
+pub mod bandwidth;
+pub mod bundle;
+pub mod capacity;
+pub mod certs;
+pub mod cloudsync;
+pub mod compare;
+pub mod crashes;
+pub mod datadirs;
+pub mod defender;
+pub mod digest;
+pub mod editors;
+pub mod elevation;
+pub mod fallback;
+pub mod feedback;
+pub mod firewall;
+pub mod flapping;
 pub mod gpu;
+pub mod hardware;
+pub mod history;
+pub mod hysteresis;
+pub mod inject;
+pub mod ipc;
+pub mod leaks;
+pub mod live;
+pub mod longpaths;
+pub mod monitor;
+pub mod http;
+pub mod netwatch;
+pub mod network;
+pub mod notify;
+pub mod palette;
+pub mod policy;
+pub mod power;
 pub mod processes;
+pub mod profiles;
+pub mod quic;
+pub mod readiness;
+pub mod relay;
+pub mod reportfile;
+pub mod session;
 pub mod settings;
+pub mod share;
+pub mod signing;
+pub mod simulate;
+pub mod smoke;
+pub mod snapshot;
+pub mod statuspage;
+pub mod support;
+pub mod tags;
+pub mod tail;
+pub mod trace;
+pub mod traffic;
+pub mod upload;
+pub mod verdict;
+pub mod wizard;
+pub mod wlan;
 
+#[cfg(feature = "net-checks")]
 use std::time::Instant;
 use sysinfo::System;
 use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
 
+pub use history::History;
+pub use http::HttpProfile;
+pub use profiles::ReportSections;
 pub use settings::DiagnosticSettings;
+pub use snapshot::SystemSnapshot;
+pub use tags::RunTags;
+
+/// Stable check identifiers used by history and the status server
+pub mod ids {
+    pub const RESOURCES: &str = "system.resources";
+    pub const GPU: &str = "system.gpu";
+    pub const LONG_PATHS: &str = "system.long_paths";
+    pub const DEFENDER: &str = "system.defender";
+    pub const DATA_DIRS: &str = "system.data_dirs";
+    pub const CLOUD_SYNC: &str = "system.cloud_sync";
+    pub const GATEWAY: &str = "net.gateway";
+    pub const INTERNET: &str = "net.internet";
+    pub const FIREWALL: &str = "net.firewall";
+    pub const QUIC: &str = "net.quic";
+    pub const BANDWIDTH: &str = "net.bandwidth";
+    pub const ROOT_STORE: &str = "net.root_store";
+    pub const CLAUDE: &str = "api.claude";
+    pub const OPENAI: &str = "api.openai";
+    pub const GOOGLE: &str = "api.google";
+    pub const RELAY: &str = "api.relay";
+    pub const ENDPOINT_CERTS: &str = "api.endpoint_certs";
+    pub const OPENCODE: &str = "process.opencode";
+    pub const CRASHES: &str = "process.crashes";
+    pub const LEAKS: &str = "process.leaks";
+    pub const TERMINALS: &str = "process.terminals";
+    pub const IPC: &str = "process.ipc";
+    pub const EDITORS: &str = "process.editors";
+    pub const SMOKE: &str = "process.smoke";
+
+    /// Every check, in display order
+    pub const ALL: &[&str] = &[RESOURCES, GPU, LONG_PATHS, DEFENDER, DATA_DIRS, CLOUD_SYNC, GATEWAY, INTERNET, FIREWALL, QUIC, BANDWIDTH, ROOT_STORE, CLAUDE, OPENAI, GOOGLE, RELAY, ENDPOINT_CERTS, OPENCODE, CRASHES, LEAKS, IPC, EDITORS, TERMINALS, SMOKE];
+
+    /// Card name of a check, for places that only keep ids (history, settings)
+    pub fn name(id: &str) -> String {
+        let name = match id {
+            RESOURCES => "LOCAL RESOURCES",
+            ROOT_STORE => "ROOT CA",
+            GATEWAY => "ROUTER",
+            CLAUDE => "CLAUDE API",
+            OPENAI => "OPENAI API",
+            GOOGLE => "GOOGLE AI",
+            IPC => "EDITOR IPC",
+            SMOKE => "SMOKE TEST",
+            _ => return id.rsplit('.').next().unwrap_or(id).replace('_', " ").to_uppercase(),
+        };
+        name.to_string()
+    }
+}
 
 /// Status of a single check
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub enum CheckStatus {
     Ok,
     Warning,
@@ -34,15 +144,39 @@ impl CheckStatus {
             CheckStatus::Inactive => "--",
         }
     }
+
+    /// Ordering from healthy to broken
+    pub fn severity(&self) -> u8 {
+        match self {
+            CheckStatus::Ok => 0,
+            CheckStatus::Inactive => 1,
+            CheckStatus::Unknown => 2,
+            CheckStatus::Warning => 3,
+            CheckStatus::Error => 4,
+        }
+    }
+}
+
+/// One hop of the network path diagram (`None` when not checked)
+pub struct PathHop {
+    pub label: &'static str,
+    pub status: Option<CheckStatus>,
 }
 
 /// Result of a diagnostic check
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CheckResult {
     pub name: String,
     pub status: CheckStatus,
     pub details: String,
     pub message: Option<String>,
+    pub latency_ms: Option<u64>,
+    /// When the check completed (unix seconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checked_at: Option<i64>,
+    /// Response facts of API checks, for the capacity analyzer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<capacity::Evidence>,
 }
 
 impl CheckResult {
@@ -52,6 +186,9 @@ impl CheckResult {
             status,
             details: details.to_string(),
             message: None,
+            latency_ms: None,
+            checked_at: None,
+            evidence: None,
         }
     }
 
@@ -59,21 +196,136 @@ impl CheckResult {
         self.message = Some(msg.to_string());
         self
     }
+
+    pub fn with_latency(mut self, ms: u128) -> Self {
+        self.latency_ms = Some(ms as u64);
+        self
+    }
+
+    pub fn with_evidence(mut self, evidence: capacity::Evidence) -> Self {
+        self.evidence = Some(evidence);
+        self
+    }
+
+    /// Seconds since the check completed
+    pub fn age_secs(&self) -> Option<u64> {
+        self.checked_at
+            .map(|at| (chrono::Utc::now().timestamp() - at).max(0) as u64)
+    }
+}
+
+/// One conclusion of the diagnosis, with how sure it is and what led to it
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Finding {
+    /// Rule that produced it ("capacity.provider"), for feedback
+    #[serde(default)]
+    pub rule: String,
+    pub message: String,
+    /// 0-100
+    pub confidence: u8,
+    /// Checks and values behind it ("CLAUDE API: api.anthropic.com :: 529 :: overloaded")
+    pub evidence: Vec<String>,
+}
+
+impl Finding {
+    fn new(rule: &str, message: &str, confidence: u8, evidence: Vec<String>) -> Self {
+        Self {
+            rule: rule.to_string(),
+            message: message.to_string(),
+            confidence,
+            evidence,
+        }
+    }
+
+    /// "HIGH" / "MEDIUM" / "LOW"
+    pub fn level(&self) -> &'static str {
+        match self.confidence {
+            80.. => "HIGH",
+            50..=79 => "MEDIUM",
+            _ => "LOW",
+        }
+    }
+}
+
+/// "NAME: first line of details" as evidence for a finding
+fn cite(check: &CheckResult) -> String {
+    format!("{}: {}", check.name, check.details.lines().next().unwrap_or_default())
 }
 
 /// All diagnostic results
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct DiagnosticReport {
     pub local_resources: Option<CheckResult>,
     pub gpu: Option<CheckResult>,
+    #[serde(default)]
+    pub long_paths: Option<CheckResult>,
+    #[serde(default)]
+    pub defender: Option<CheckResult>,
+    #[serde(default)]
+    pub data_dirs: Option<CheckResult>,
+    #[serde(default)]
+    pub cloud_sync: Option<CheckResult>,
+    pub gateway: Option<CheckResult>,
     pub internet: Option<CheckResult>,
+    pub firewall: Option<CheckResult>,
+    #[serde(default)]
+    pub quic: Option<CheckResult>,
+    #[serde(default)]
+    pub bandwidth: Option<CheckResult>,
+    pub root_store: Option<CheckResult>,
     pub claude_api: Option<CheckResult>,
     pub openai_api: Option<CheckResult>,
     pub google_api: Option<CheckResult>,
+    #[serde(default)]
+    pub relay: Option<CheckResult>,
+    pub endpoint_certs: Option<CheckResult>,
     pub opencode: Option<CheckResult>,
+    #[serde(default)]
+    pub crashes: Option<CheckResult>,
+    #[serde(default)]
+    pub leaks: Option<CheckResult>,
+    #[serde(default)]
+    pub ipc: Option<CheckResult>,
+    #[serde(default)]
+    pub editors: Option<CheckResult>,
     pub terminals: Option<CheckResult>,
+    #[serde(default)]
+    pub smoke: Option<CheckResult>,
+    /// What the providers' own status pages said during the run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub status_pages: Vec<statuspage::ProviderStatus>,
     pub diagnosis: Option<String>,
+    /// Everything behind the diagnosis, most fundamental first
+    #[serde(default)]
+    pub findings: Vec<Finding>,
     pub timestamp: Option<String>,
+    /// Project, model and network the run was made in
+    #[serde(default)]
+    pub tags: RunTags,
+    /// What started the run when it was not the user or the timer
+    #[serde(default)]
+    pub trigger: Option<String>,
+}
+
+/// Latest report shared between the run thread, the UI and the status server
+///
+/// Readers take an `Arc` snapshot, so drawing a frame or serving a request
+/// never deep-clones the report.
+#[derive(Clone, Default)]
+pub struct SharedReport {
+    current: Arc<Mutex<Arc<DiagnosticReport>>>,
+}
+
+impl SharedReport {
+    /// The current report (cheap: clones the `Arc`, not the report)
+    pub fn snapshot(&self) -> Arc<DiagnosticReport> {
+        Arc::clone(&self.current.lock().unwrap())
+    }
+
+    /// Publish a completed report
+    pub fn replace(&self, report: DiagnosticReport) {
+        *self.current.lock().unwrap() = Arc::new(report);
+    }
 }
 
 impl DiagnosticReport {
@@ -83,158 +335,524 @@ impl DiagnosticReport {
 
     /// Run diagnostics based on settings
     pub fn run_with_settings(&mut self, settings: &DiagnosticSettings) {
-        self.timestamp = Some(chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+        // Heavy checks pause for the rest of the month once the traffic cap is reached
+        let capped;
+        let settings = if traffic::over_cap(settings.monthly_cap_mb) {
+            capped = traffic::throttled(settings);
+            &capped
+        } else {
+            settings
+        };
+        // Checks snoozed from their card are skipped and shown as snoozed
+        let snoozed = snoozed_checks(settings, chrono::Local::now().naive_local());
+        let awake;
+        let settings = if snoozed.is_empty() {
+            settings
+        } else {
+            let mut without = settings.clone();
+            for (id, _) in &snoozed {
+                without.set_check_enabled(id, false);
+            }
+            awake = without;
+            &awake
+        };
+        self.timestamp = Some(chrono::Local::now().format(history::TIMESTAMP_FORMAT).to_string());
+        // Refreshed lazily, only for the checks below that need it
+        let mut snapshot = SystemSnapshot::new();
+        let http = HttpProfile::from_settings(settings);
         
         // System checks
         if settings.check_cpu_ram {
-            self.local_resources = Some(check_local_resources());
+            self.local_resources = Some(isolated("LOCAL RESOURCES", || check_local_resources(&mut snapshot)));
         }
         
         if settings.check_gpu {
-            self.gpu = Some(gpu::check_gpu());
+            self.gpu = Some(isolated("GPU", gpu::check_gpu));
         }
         
-        // Network
-        if settings.check_internet {
-            self.internet = Some(check_internet());
+        if settings.check_long_paths {
+            self.long_paths = Some(isolated("LONG PATHS", longpaths::check_long_paths));
         }
         
-        // API checks
-        if settings.check_claude {
-            self.claude_api = Some(api::check_claude_api());
+        if settings.check_defender {
+            self.defender = Some(isolated("DEFENDER", || defender::check_defender(&mut snapshot)));
         }
         
-        if settings.check_openai {
-            self.openai_api = Some(api::check_openai_api());
+        if settings.check_data_dirs {
+            self.data_dirs = Some(isolated("DATA DIRS", datadirs::check_data_dirs));
         }
         
+        if settings.check_cloud_sync {
+            self.cloud_sync = Some(isolated("CLOUD SYNC", || cloudsync::check_cloud_sync(&mut snapshot)));
+        }
+        
+        if settings.check_root_store {
+            self.root_store = Some(isolated("ROOT CA", certs::check_root_store));
+        }
+        
+        // Network and API checks run in parallel, at most `network_concurrency` at once
+        let mut jobs: Vec<(&'static str, NetworkJob)> = Vec::new();
+        if settings.check_internet {
+            jobs.push((ids::GATEWAY, Box::new(|| isolated("ROUTER", network::check_gateway))));
+            jobs.push((ids::INTERNET, Box::new(|| isolated("INTERNET", || check_internet(&http)))));
+        }
+        if settings.check_firewall {
+            jobs.push((ids::FIREWALL, Box::new(|| isolated("FIREWALL", firewall::check_firewall))));
+        }
+        if settings.check_quic {
+            jobs.push((ids::QUIC, Box::new(|| isolated("QUIC", quic::check_quic))));
+        }
+        if settings.check_claude {
+            jobs.push((ids::CLAUDE, Box::new(|| isolated("CLAUDE API", || api::check_claude_api(&http)))));
+        }
+        if settings.check_openai {
+            jobs.push((ids::OPENAI, Box::new(|| isolated("OPENAI API", || api::check_openai_api(&http)))));
+        }
         if settings.check_google_ai {
-            self.google_api = Some(api::check_google_api());
+            jobs.push((ids::GOOGLE, Box::new(|| isolated("GOOGLE AI", || api::check_google_api(&http)))));
+        }
+        if settings.check_endpoint_certs {
+            jobs.push((
+                ids::ENDPOINT_CERTS,
+                Box::new(|| {
+                    isolated("ENDPOINT CERTS", || {
+                        certs::check_endpoint_certs(&http, &settings.custom_endpoints, settings.cert_warn_days)
+                    })
+                }),
+            ));
+        }
+        for (id, result) in run_limited(jobs, settings.network_concurrency as usize) {
+            if let Some(slot) = self.slot_mut(id) {
+                *slot = Some(result);
+            }
+        }
+        
+        // The providers' own word, for the verdict
+        let providers: Vec<&str> = [ids::CLAUDE, ids::OPENAI].into_iter().filter(|id| settings.is_check_enabled(id)).collect();
+        if !providers.is_empty() {
+            self.status_pages = statuspage::fetch(&http, &providers, settings.network_concurrency as usize);
+        }
+
+        // Compared against the API results above
+        if settings.check_relay {
+            let local = [(ids::CLAUDE, &self.claude_api), (ids::OPENAI, &self.openai_api), (ids::GOOGLE, &self.google_api)];
+            self.relay = Some(isolated("RELAY", || relay::check_relay(&http, &settings.relay_url, &local)));
+        }
+        
+        // After the network checks, so their own traffic is not in the sample
+        if settings.check_bandwidth {
+            self.bandwidth = Some(isolated("BANDWIDTH", || bandwidth::check_bandwidth(&mut snapshot)));
         }
         
         // Process checks
         if settings.check_opencode {
-            self.opencode = Some(processes::check_opencode_process());
+            self.opencode = Some(isolated("OPENCODE", || processes::check_opencode_process(&mut snapshot)));
+        }
+        
+        if settings.check_crashes {
+            self.crashes = Some(isolated("CRASHES", crashes::check_crashes));
+        }
+        
+        if settings.check_leaks {
+            self.leaks = Some(isolated("LEAKS", || leaks::check_leaks(&mut snapshot)));
+        }
+        
+        if settings.check_ipc {
+            self.ipc = Some(isolated("EDITOR IPC", || ipc::check_opencode_ipc(&mut snapshot)));
+        }
+        
+        if settings.check_editors {
+            self.editors = Some(isolated("EDITORS", || editors::check_editors(&mut snapshot)));
         }
         
         if settings.check_terminals {
-            self.terminals = Some(processes::check_terminals());
+            self.terminals = Some(isolated("TERMINALS", || processes::check_terminals(&mut snapshot)));
+        }
+        
+        // Last: a whole OpenCode session, up to smoke::TIMEOUT
+        if settings.check_smoke {
+            self.smoke = Some(isolated("SMOKE TEST", smoke::check_smoke));
+        }
+        
+        traffic::save();
+        self.tags = RunTags::resolve(&settings.tags, &mut snapshot);
+        
+        for id in ids::ALL.iter().filter(|id| settings.is_check_enabled(id)) {
+            if let Some(Some(result)) = self.slot_mut(id) {
+                flapping::observe(id, result.status);
+                hysteresis::settle(id, result, settings);
+            }
         }
+        self.show_snoozed(&snoozed);
         
         // Generate diagnosis
-        self.diagnosis = Some(self.generate_diagnosis());
+        self.diagnose();
+    }
+
+    /// Ids of completed checks younger than `ttl_secs`
+    pub fn fresh_ids(&self, ttl_secs: u32) -> Vec<&'static str> {
+        self.checks()
+            .into_iter()
+            .filter(|(_, check)| check.age_secs().map(|age| age < ttl_secs as u64).unwrap_or(false))
+            .map(|(id, _)| id)
+            .collect()
     }
 
-    fn generate_diagnosis(&self) -> String {
-        // Check each component and find the issue
-        if let Some(ref check) = self.local_resources {
-            if check.status == CheckStatus::Error {
-                return "System resources critical. Close other applications.".to_string();
+    /// Take over results from an earlier report and rebuild the diagnosis
+    pub fn adopt(&mut self, previous: &DiagnosticReport, ids: &[&str]) {
+        for id in ids {
+            if let (Some(slot), Some(check)) = (self.slot_mut(id), previous.checks().into_iter().find(|(i, _)| i == id)) {
+                *slot = Some(check.1.clone());
             }
         }
+        if self.status_pages.is_empty() {
+            self.status_pages = previous.status_pages.clone();
+        }
+        self.diagnose();
+    }
 
-        if let Some(ref check) = self.gpu {
-            if check.status == CheckStatus::Error {
-                return "GPU overloaded. Close GPU-heavy applications.".to_string();
-            }
-            if check.status == CheckStatus::Warning {
-                return "High GPU usage detected. May affect performance.".to_string();
+    /// Fill the cards of snoozed checks, which did not run
+    fn show_snoozed(&mut self, snoozed: &[(&str, chrono::NaiveDateTime)]) {
+        for (id, until) in snoozed {
+            if let Some(slot) = self.slot_mut(id) {
+                let details = format!("Snoozed until {}", until.format("%H:%M"));
+                *slot = Some(CheckResult::new(&ids::name(id), CheckStatus::Inactive, &details));
             }
         }
+    }
+
+    /// Result field for a check id
+    fn slot_mut(&mut self, id: &str) -> Option<&mut Option<CheckResult>> {
+        match id {
+            ids::RESOURCES => Some(&mut self.local_resources),
+            ids::GPU => Some(&mut self.gpu),
+            ids::LONG_PATHS => Some(&mut self.long_paths),
+            ids::DEFENDER => Some(&mut self.defender),
+            ids::DATA_DIRS => Some(&mut self.data_dirs),
+            ids::CLOUD_SYNC => Some(&mut self.cloud_sync),
+            ids::GATEWAY => Some(&mut self.gateway),
+            ids::INTERNET => Some(&mut self.internet),
+            ids::FIREWALL => Some(&mut self.firewall),
+            ids::QUIC => Some(&mut self.quic),
+            ids::BANDWIDTH => Some(&mut self.bandwidth),
+            ids::ROOT_STORE => Some(&mut self.root_store),
+            ids::CLAUDE => Some(&mut self.claude_api),
+            ids::OPENAI => Some(&mut self.openai_api),
+            ids::GOOGLE => Some(&mut self.google_api),
+            ids::RELAY => Some(&mut self.relay),
+            ids::ENDPOINT_CERTS => Some(&mut self.endpoint_certs),
+            ids::OPENCODE => Some(&mut self.opencode),
+            ids::CRASHES => Some(&mut self.crashes),
+            ids::LEAKS => Some(&mut self.leaks),
+            ids::IPC => Some(&mut self.ipc),
+            ids::EDITORS => Some(&mut self.editors),
+            ids::TERMINALS => Some(&mut self.terminals),
+            ids::SMOKE => Some(&mut self.smoke),
+            _ => None,
+        }
+    }
+
+    /// All completed checks with their stable ids, in display order
+    pub fn checks(&self) -> Vec<(&'static str, &CheckResult)> {
+        [
+            (ids::RESOURCES, &self.local_resources),
+            (ids::GPU, &self.gpu),
+            (ids::LONG_PATHS, &self.long_paths),
+            (ids::DEFENDER, &self.defender),
+            (ids::DATA_DIRS, &self.data_dirs),
+            (ids::CLOUD_SYNC, &self.cloud_sync),
+            (ids::GATEWAY, &self.gateway),
+            (ids::INTERNET, &self.internet),
+            (ids::FIREWALL, &self.firewall),
+            (ids::QUIC, &self.quic),
+            (ids::BANDWIDTH, &self.bandwidth),
+            (ids::ROOT_STORE, &self.root_store),
+            (ids::CLAUDE, &self.claude_api),
+            (ids::OPENAI, &self.openai_api),
+            (ids::GOOGLE, &self.google_api),
+            (ids::RELAY, &self.relay),
+            (ids::ENDPOINT_CERTS, &self.endpoint_certs),
+            (ids::OPENCODE, &self.opencode),
+            (ids::CRASHES, &self.crashes),
+            (ids::LEAKS, &self.leaks),
+            (ids::IPC, &self.ipc),
+            (ids::EDITORS, &self.editors),
+            (ids::TERMINALS, &self.terminals),
+            (ids::SMOKE, &self.smoke),
+        ]
+        .into_iter()
+        .filter_map(|(id, check)| check.as_ref().map(|c| (id, c)))
+        .collect()
+    }
+
+    /// Status of each hop in the chain [PC] -> [Router] -> [Internet] -> [API] -> [OpenCode]
+    pub fn path(&self) -> Vec<PathHop> {
+        let status_of = |check: &Option<CheckResult>| check.as_ref().map(|c| c.status);
 
-        if let Some(ref check) = self.internet {
-            if check.status == CheckStatus::Error {
-                return "No internet connection. Check your network.".to_string();
+        // Routers often drop probes; if the internet works, the router does too
+        let internet = status_of(&self.internet);
+        let router = match (status_of(&self.gateway), internet) {
+            (Some(CheckStatus::Warning), Some(CheckStatus::Ok)) => Some(CheckStatus::Ok),
+            (router, _) => router,
+        };
+
+        // Provider hop is the worst of the APIs that were checked
+        let api = [&self.claude_api, &self.openai_api, &self.google_api]
+            .into_iter()
+            .filter_map(status_of)
+            .max_by_key(|s| s.severity());
+
+        vec![
+            PathHop { label: "PC", status: status_of(&self.local_resources) },
+            PathHop { label: "ROUTER", status: router },
+            PathHop { label: "INTERNET", status: internet },
+            PathHop { label: "API", status: api },
+            PathHop { label: "OPENCODE", status: status_of(&self.opencode) },
+        ]
+    }
+
+    /// Set `findings` and the headline `diagnosis`, with confidences adjusted by user feedback
+    fn diagnose(&mut self) {
+        self.findings = self.generate_findings();
+        feedback::adjust(&mut self.findings);
+        self.diagnosis = Some(match self.headline() {
+            Some(finding) => finding.message.clone(),
+            None => "All systems operational.".to_string(),
+        });
+    }
+
+    /// Finding shown as the diagnosis: the most fundamental one that feedback hasn't discredited
+    pub fn headline(&self) -> Option<&Finding> {
+        self.findings
+            .iter()
+            .find(|f| f.confidence >= feedback::HEADLINE_MIN_CONFIDENCE)
+            .or(self.findings.first())
+    }
+
+    /// Every issue found, most fundamental first
+    fn generate_findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let with_status = |check: &Option<CheckResult>, status: CheckStatus| check.clone().filter(|c| c.status == status);
+
+        if let Some(check) = with_status(&self.local_resources, CheckStatus::Error) {
+            findings.push(Finding::new("resources.critical", "System resources critical. Close other applications.", 90, vec![cite(&check)]));
+        }
+
+        if let Some(check) = with_status(&self.gpu, CheckStatus::Error) {
+            findings.push(Finding::new("gpu.overloaded", "GPU overloaded. Close GPU-heavy applications.", 85, vec![cite(&check)]));
+        }
+        if let Some(check) = with_status(&self.gpu, CheckStatus::Warning) {
+            findings.push(Finding::new("gpu.high", "High GPU usage detected. May affect performance.", 60, vec![cite(&check)]));
+        }
+
+        if let Some(check) = with_status(&self.gateway, CheckStatus::Error) {
+            findings.push(Finding::new("gateway.no_route", "No default route. Check Wi-Fi/Ethernet connection.", 90, vec![cite(&check)]));
+        }
+
+        if let Some(check) = with_status(&self.internet, CheckStatus::Error) {
+            // A working router narrows it down to the line or the ISP
+            let router = with_status(&self.gateway, CheckStatus::Ok);
+            let mut evidence = vec![cite(&check)];
+            evidence.extend(router.as_ref().map(cite));
+            let confidence = if router.is_some() { 90 } else { 80 };
+            findings.push(Finding::new("internet.down", "No internet connection. Check your network.", confidence, evidence));
+        }
+
+        if let Some(check) = with_status(&self.firewall, CheckStatus::Error) {
+            findings.push(Finding::new(
+                "firewall.blocked",
+                "Firewall is blocking opencode-diag. Allow it in your security software.",
+                80,
+                vec![cite(&check)],
+            ));
+        }
+
+        if let Some(check) = with_status(&self.root_store, CheckStatus::Error) {
+            findings.push(Finding::new(
+                "root_store.outdated",
+                "Certificate root store is outdated. Enable root updates and run Windows Update.",
+                85,
+                vec![cite(&check)],
+            ));
+        }
+
+        // The relay knows whether a failing provider is down for everyone
+        if let Some(check) = with_status(&self.relay, CheckStatus::Warning) {
+            if let Some(ref message) = check.message {
+                findings.push(Finding::new("relay.blocked_here", message, 80, vec![cite(&check)]));
             }
         }
 
+        // Tell a real provider overload from a proxy 503, socket exhaustion or clock skew
+        let verdict = capacity::analyze(self);
+        if let Some(ref verdict) = verdict {
+            findings.push(Finding::new(verdict.cause.rule(), &verdict.summary(), verdict.confidence as u8, verdict.evidence.clone()));
+        }
+
         if let Some(ref check) = self.claude_api {
+            let evidence = vec![cite(check)];
+            let status_code = check.evidence.as_ref().and_then(|e| e.status_code);
             match check.status {
+                // 5xx, exhausted sockets and clock skew: explained by the capacity verdict
+                CheckStatus::Error if verdict.is_some() => {}
                 CheckStatus::Error => {
-                    if check.details.contains("503") || check.details.contains("capacity") {
-                        return "Claude API is overloaded. Try again later.".to_string();
-                    } else if check.details.contains("529") {
-                        return "Claude API overloaded (529). Try again in a few minutes.".to_string();
-                    }
-                    return format!("Claude API issue: {}", check.details);
+                    findings.push(Finding::new("claude.error", &format!("Claude API issue: {}", check.details), 70, evidence));
                 }
                 CheckStatus::Warning => {
-                    if check.details.contains("429") {
-                        return "Claude API rate limited. Wait a few minutes.".to_string();
-                    }
-                    return "Claude API is slow. May experience delays.".to_string();
+                    // Say who is using the line, if anyone
+                    let busy = with_status(&self.bandwidth, CheckStatus::Warning);
+                    let finding = if status_code == Some(429) {
+                        Finding::new("claude.rate_limited", "Claude API rate limited. Wait a few minutes.", 90, evidence)
+                    } else if let Some((bandwidth, message)) = busy.as_ref().and_then(|b| Some((b, b.message.as_ref()?))) {
+                        Finding::new("claude.slow_bandwidth", &format!("Claude API is slow. {}", message), 70, vec![cite(check), cite(bandwidth)])
+                    } else {
+                        Finding::new("claude.slow", "Claude API is slow. May experience delays.", 50, evidence)
+                    };
+                    findings.push(finding);
                 }
                 _ => {}
             }
         }
 
-        if let Some(ref check) = self.openai_api {
-            if check.status == CheckStatus::Error {
-                return format!("OpenAI API issue: {}", check.details);
-            }
+        if let Some(check) = with_status(&self.openai_api, CheckStatus::Error).filter(|_| verdict.is_none()) {
+            findings.push(Finding::new("openai.error", &format!("OpenAI API issue: {}", check.details), 70, vec![cite(&check)]));
         }
 
-        if let Some(ref check) = self.opencode {
-            if check.status == CheckStatus::Error {
-                return "OpenCode process not running.".to_string();
-            }
+        if let Some(check) = with_status(&self.endpoint_certs, CheckStatus::Error) {
+            findings.push(Finding::new(
+                "endpoint_certs.error",
+                &format!("Custom endpoint certificate problem: {}", check.details),
+                90,
+                vec![cite(&check)],
+            ));
+        }
+
+        if let Some(check) = with_status(&self.opencode, CheckStatus::Error) {
+            findings.push(Finding::new("opencode.not_running", "OpenCode process not running.", 95, vec![cite(&check)]));
+        }
+
+        if let Some(check) = with_status(&self.crashes, CheckStatus::Error) {
+            let message = check.message.clone().unwrap_or_else(|| "OpenCode keeps crashing.".to_string());
+            findings.push(Finding::new("crashes.repeated", &message, 75, vec![cite(&check)]));
+        }
+
+        if let Some(check) = self.leaks.as_ref().filter(|c| matches!(c.status, CheckStatus::Error | CheckStatus::Warning)) {
+            let (rule, confidence) = match check.status {
+                CheckStatus::Error => ("leaks.fd_limit", 80),
+                _ => ("leaks.growth", 50),
+            };
+            let message = check.message.clone().unwrap_or_else(|| "OpenCode is leaking file handles or temp files.".to_string());
+            findings.push(Finding::new(rule, &message, confidence, vec![cite(check)]));
+        }
+
+        if let Some(check) = self.data_dirs.as_ref().filter(|c| matches!(c.status, CheckStatus::Error | CheckStatus::Warning)) {
+            let (rule, confidence) = match check.status {
+                CheckStatus::Error => ("data_dirs.unwritable", 80),
+                _ => ("data_dirs.permissions", 55),
+            };
+            let message = check.message.clone().unwrap_or_else(|| "Problem in OpenCode's data directories.".to_string());
+            findings.push(Finding::new(rule, &message, confidence, vec![cite(check)]));
+        }
+
+        if let Some(check) = with_status(&self.cloud_sync, CheckStatus::Warning) {
+            let message = check.message.clone().unwrap_or_else(|| "Project or config is in a cloud-synced folder.".to_string());
+            findings.push(Finding::new("cloud_sync.interference", &message, 50, vec![cite(&check)]));
+        }
+
+        if let Some(check) = with_status(&self.ipc, CheckStatus::Error) {
+            findings.push(Finding::new(
+                "ipc.no_answer",
+                "OpenCode is running but its local server does not answer. Restart OpenCode.",
+                85,
+                vec![cite(&check)],
+            ));
+        }
+
+        // Slows every tool call, but rarely the whole story
+        if let Some(check) = with_status(&self.defender, CheckStatus::Warning) {
+            let message = check.message.clone().unwrap_or_else(|| "Defender scanning slows file access.".to_string());
+            findings.push(Finding::new("defender.scan_overhead", &message, 50, vec![cite(&check)]));
+        }
+
+        // Alternating OK/failing: a different cause than a steady failure
+        for (id, transitions) in flapping::flapping() {
+            let Some((_, check)) = self.checks().into_iter().find(|(check_id, _)| *check_id == id) else {
+                continue;
+            };
+            let message = format!(
+                "{} is flapping: {} transitions in {} min. {}",
+                check.name,
+                transitions,
+                flapping::FLAP_WINDOW.as_secs() / 60,
+                flapping::likely_cause(id)
+            );
+            findings.push(Finding::new(&format!("flapping.{}", id), &message, 70, vec![cite(check)]));
         }
 
-        "All systems operational.".to_string()
+        findings
     }
 
-    /// Generate a text report for clipboard
-    pub fn to_text_report(&self) -> String {
+    /// Generate a text report for clipboard with the given sections (see `ViewRole`)
+    pub fn to_text_report(&self, sections: ReportSections) -> String {
         let mut report = String::new();
         
         report.push_str("=== OpenCode Diagnostics Report ===\n");
         if let Some(ref ts) = self.timestamp {
             report.push_str(&format!("Time: {}\n", ts));
         }
-        report.push('\n');
-
-        if let Some(ref check) = self.local_resources {
-            report.push_str(&format_check_for_report(check));
-        }
-        
-        if let Some(ref check) = self.gpu {
-            report.push_str(&format_check_for_report(check));
-        }
-        
-        if let Some(ref check) = self.internet {
-            report.push_str(&format_check_for_report(check));
+        if !self.tags.is_empty() {
+            report.push_str(&format!("Tags: {}\n", self.tags.summary()));
         }
-        
-        if let Some(ref check) = self.claude_api {
-            report.push_str(&format_check_for_report(check));
+        if let Some(ref trigger) = self.trigger {
+            report.push_str(&format!("Trigger: {}\n", trigger));
         }
-        
-        if let Some(ref check) = self.openai_api {
-            report.push_str(&format_check_for_report(check));
-        }
-        
-        if let Some(ref check) = self.google_api {
-            report.push_str(&format_check_for_report(check));
+        report.push('\n');
+
+        if sections.system_info {
+            report.push_str(&system_summary());
+            report.push('\n');
         }
-        
-        if let Some(ref check) = self.opencode {
-            report.push_str(&format_check_for_report(check));
+
+        if sections.latency_summary {
+            let latencies: Vec<String> = self.checks()
+                .into_iter()
+                .filter_map(|(_, c)| c.latency_ms.map(|ms| format!("{} {}ms", c.name, ms)))
+                .collect();
+            if !latencies.is_empty() {
+                report.push_str(&format!("LATENCY: {}\n\n", latencies.join(" :: ")));
+            }
         }
-        
-        if let Some(ref check) = self.terminals {
-            report.push_str(&format_check_for_report(check));
+
+        if sections.check_details {
+            for (_, check) in self.checks() {
+                report.push_str(&format_check_for_report(check, sections.messages));
+            }
         }
 
         if let Some(ref diag) = self.diagnosis {
             report.push_str(&format!("\nDIAGNOSIS: {}\n", diag));
         }
 
+        if let Some(fallback) = fallback::recommend(self) {
+            report.push_str(&format!("FALLBACK: {} :: opencode.json {}\n", fallback.advice, fallback.config));
+        }
+
+        if sections.messages && !self.findings.is_empty() {
+            report.push_str("WHY:\n");
+            for finding in &self.findings {
+                report.push_str(&format!("  [{} {}%] {}\n", finding.level(), finding.confidence, finding.message));
+                for evidence in &finding.evidence {
+                    report.push_str(&format!("    - {}\n", evidence));
+                }
+            }
+        }
+
         report
     }
 }
 
 /// Single error type with timestamps when it occurred
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ErrorEntry {
     pub name: String,           // "GPU", "CLAUDE API", etc.
     pub times: VecDeque<String>, // Up to 5 timestamps (HH:MM)
@@ -265,6 +883,7 @@ impl ErrorEntry {
 }
 
 /// Log of errors grouped by type
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ErrorLog {
     pub entries: Vec<ErrorEntry>,
 }
@@ -291,26 +910,15 @@ impl ErrorLog {
             .unwrap_or_else(|| "--:--".to_string());
 
         // Check each result for errors/warnings
-        let checks: Vec<Option<&CheckResult>> = vec![
-            report.local_resources.as_ref(),
-            report.gpu.as_ref(),
-            report.internet.as_ref(),
-            report.claude_api.as_ref(),
-            report.openai_api.as_ref(),
-            report.google_api.as_ref(),
-            report.opencode.as_ref(),
-            report.terminals.as_ref(),
-        ];
-
-        for check in checks.into_iter().flatten() {
+        for (_, check) in report.checks() {
             if check.status == CheckStatus::Error || check.status == CheckStatus::Warning {
                 self.add_error(&check.name, &time);
             }
         }
     }
 
-    /// Add an error occurrence
-    fn add_error(&mut self, name: &str, time: &str) {
+    /// Add an error occurrence (also for app-level problems, e.g. a slow startup)
+    pub fn add_error(&mut self, name: &str, time: &str) {
         // Find existing entry or create new
         if let Some(entry) = self.entries.iter_mut().find(|e| e.name == name) {
             entry.add_time(time);
@@ -335,9 +943,21 @@ impl ErrorLog {
     pub fn clear(&mut self) {
         self.entries.clear();
     }
+
+    /// Format the log as a report section
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("ERROR LOG:\n");
+        if self.entries.is_empty() {
+            text.push_str("     No issues recorded.\n");
+        }
+        for entry in &self.entries {
+            text.push_str(&format!("     {} :: {}\n", entry.name, entry.format_times()));
+        }
+        text
+    }
 }
 
-fn format_check_for_report(check: &CheckResult) -> String {
+fn format_check_for_report(check: &CheckResult, with_message: bool) -> String {
     let icon = match check.status {
         CheckStatus::Ok => "[OK]",
         CheckStatus::Warning => "[!!]",
@@ -347,22 +967,119 @@ fn format_check_for_report(check: &CheckResult) -> String {
     };
     
     let mut result = format!("{} {}\n", icon, check.name);
-    result.push_str(&format!("     {}\n", check.details));
-    if let Some(ref msg) = check.message {
+    result.push_str(&format!("     {}\n", check.details.replace('\n', "\n     ")));
+    if let (true, Some(msg)) = (with_message, &check.message) {
         result.push_str(&format!("     Message: \"{}\"\n", msg));
     }
     result.push('\n');
     result
 }
 
+/// A network check (or status page fetch) waiting for a free slot
+type NetworkJob<'a, T = CheckResult> = Box<dyn FnOnce() -> T + Send + 'a>;
+
+/// Run `jobs` on at most `limit` threads at once, results in job order
+///
+/// Keeps constrained routers from facing a burst of simultaneous TLS
+/// handshakes; a limit of 1 runs the checks one after another.
+fn run_limited<'a, T: Send>(jobs: Vec<(&'static str, NetworkJob<'a, T>)>, limit: usize) -> Vec<(&'static str, T)> {
+    let count = jobs.len();
+    let queue = Mutex::new(jobs.into_iter().enumerate());
+    let results = Mutex::new(Vec::with_capacity(count));
+    std::thread::scope(|scope| {
+        for _ in 0..limit.clamp(1, count.max(1)) {
+            scope.spawn(|| loop {
+                let Some((index, (id, job))) = queue.lock().unwrap().next() else {
+                    break;
+                };
+                let result = job();
+                results.lock().unwrap().push((index, id, result));
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _, _)| *index);
+    results.into_iter().map(|(_, id, result)| (id, result)).collect()
+}
+
+/// Run one check on its own thread so a panic (e.g. in a platform backend)
+/// becomes an Error result instead of aborting the whole run
+fn isolated(name: &str, check: impl FnOnce() -> CheckResult + Send) -> CheckResult {
+    let mut result = caught(name, check).unwrap_or_else(|e| {
+        CheckResult::new(name, CheckStatus::Error, "Check crashed").with_message(&format!("check crashed: {}", e))
+    });
+    result.checked_at = Some(chrono::Utc::now().timestamp());
+    result
+}
+
+/// Run `job` on its own thread; a panic comes back as its message
+fn caught<T: Send>(name: &str, job: impl FnOnce() -> T + Send) -> Result<T, String> {
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .name(format!("check {}", name))
+            .spawn_scoped(scope, job)
+            .map_err(|e| format!("could not start: {}", e))?
+            .join()
+            .map_err(|panic| {
+                panic
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string())
+            })
+    })
+}
+
+/// Build a command that does not flash a console window on Windows
+pub fn command(program: &str) -> std::process::Command {
+    #[allow(unused_mut)]
+    let mut cmd = std::process::Command::new(program);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    cmd
+}
+
+/// OS, host and hardware totals for support reports
+pub fn system_summary() -> String {
+    let mut sys = System::new();
+    sys.refresh_memory();
+
+    let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(0);
+    format!(
+        "SYSTEM: {} :: host {} :: {} cores :: {}MB RAM :: opencode-diag v{}{}\n",
+        System::long_os_version().unwrap_or_else(|| std::env::consts::OS.to_string()),
+        System::host_name().unwrap_or_else(|| "unknown".to_string()),
+        cores,
+        sys.total_memory() / (1024 * 1024),
+        env!("CARGO_PKG_VERSION"),
+        if running_emulated() { " :: x64 build emulated on ARM64" } else { "" },
+    )
+}
+
+/// True when this x64 build runs under emulation on a Windows ARM64 device
+#[cfg(all(target_os = "windows", not(target_arch = "aarch64")))]
+fn running_emulated() -> bool {
+    use winapi::um::processthreadsapi::GetCurrentProcess;
+    use winapi::um::wow64apiset::IsWow64Process2;
+
+    const IMAGE_FILE_MACHINE_ARM64: u16 = 0xAA64;
+    let (mut process_machine, mut native_machine) = (0u16, 0u16);
+    let ok = unsafe { IsWow64Process2(GetCurrentProcess(), &mut process_machine, &mut native_machine) };
+    ok != 0 && native_machine == IMAGE_FILE_MACHINE_ARM64
+}
+
+#[cfg(not(all(target_os = "windows", not(target_arch = "aarch64"))))]
+fn running_emulated() -> bool {
+    false
+}
+
 /// Check local system resources (CPU, RAM)
-pub fn check_local_resources() -> CheckResult {
-    let mut sys = System::new_all();
-    sys.refresh_all();
-    
-    // Need to wait a bit and refresh again for accurate CPU reading
-    std::thread::sleep(std::time::Duration::from_millis(200));
-    sys.refresh_cpu_all();
+pub fn check_local_resources(snapshot: &mut SystemSnapshot) -> CheckResult {
+    let sys = snapshot.cpu_memory();
 
     let cpu_usage = sys.global_cpu_usage();
     let total_mem = sys.total_memory();
@@ -391,22 +1108,32 @@ pub fn check_local_resources() -> CheckResult {
 }
 
 /// Check internet connectivity by making HTTP requests
-pub fn check_internet() -> CheckResult {
-    let client = match reqwest::blocking::Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-    {
+#[cfg(feature = "net-checks")]
+pub fn check_internet(http: &HttpProfile) -> CheckResult {
+    check_internet_at(http, "https://www.google.com", "https://1.1.1.1")
+}
+
+#[cfg(not(feature = "net-checks"))]
+pub fn check_internet(_http: &HttpProfile) -> CheckResult {
+    CheckResult::new("INTERNET", CheckStatus::Inactive, "Network checks not included in this build")
+}
+
+/// Internet check against other URLs (used by `--self-test` mock endpoints)
+#[cfg(feature = "net-checks")]
+pub fn check_internet_at(http: &HttpProfile, primary: &str, fallback: &str) -> CheckResult {
+    let client = match http.client(std::time::Duration::from_secs(5)) {
         Ok(c) => c,
-        Err(_) => {
-            return CheckResult::new("INTERNET", CheckStatus::Error, "Failed to create HTTP client");
+        Err(e) => {
+            return CheckResult::new("INTERNET", CheckStatus::Error, "Failed to create HTTP client").with_message(&e);
         }
     };
 
     let start = Instant::now();
     
     // Try Google
-    let google_ok = client.get("https://www.google.com")
-        .send()
+    let response = client.get(primary).send();
+    traffic::record_http(primary, &response);
+    let google_ok = response
         .map(|r| r.status().is_success())
         .unwrap_or(false);
 
@@ -424,17 +1151,90 @@ pub fn check_internet() -> CheckResult {
             status,
             &format!("PING: {}ms :: google.com reachable", elapsed),
         )
+        .with_latency(elapsed)
     } else {
         // Try Cloudflare as backup
-        let cf_ok = client.get("https://1.1.1.1")
-            .send()
+        let response = client.get(fallback).send();
+        traffic::record_http(fallback, &response);
+        let cf_ok = response
             .map(|r| r.status().is_success())
             .unwrap_or(false);
 
         if cf_ok {
             CheckResult::new("INTERNET", CheckStatus::Warning, "google.com unreachable, cloudflare OK")
+                .with_evidence(capacity::Evidence { unreachable_by_name: true, ..Default::default() })
         } else {
             CheckResult::new("INTERNET", CheckStatus::Error, "No internet connection")
         }
     }
 }
+
+/// Enabled checks snoozed at `now`, with when each snooze ends
+fn snoozed_checks(settings: &DiagnosticSettings, now: chrono::NaiveDateTime) -> Vec<(&'static str, chrono::NaiveDateTime)> {
+    ids::ALL
+        .iter()
+        .filter(|id| settings.is_check_enabled(id))
+        .filter_map(|id| Some((*id, settings.snoozed_until(id, now)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panicking_check_is_an_error_result() {
+        let result = isolated("GPU", || panic!("driver returned garbage"));
+        assert_eq!(result.status, CheckStatus::Error);
+        assert_eq!(result.details, "Check crashed");
+        assert!(result.message.as_deref().is_some_and(|m| m.contains("driver returned garbage")));
+    }
+
+    fn claude(status: CheckStatus, details: &str, status_code: Option<u16>) -> DiagnosticReport {
+        let mut check = CheckResult::new("CLAUDE API", status, details);
+        if let Some(code) = status_code {
+            check = check.with_evidence(capacity::Evidence { status_code: Some(code), from_provider: true, ..Default::default() });
+        }
+        let mut report = DiagnosticReport { claude_api: Some(check), ..Default::default() };
+        report.diagnose();
+        report
+    }
+
+    fn rules(report: &DiagnosticReport) -> Vec<&str> {
+        report.findings.iter().map(|f| f.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn provider_529_is_one_capacity_finding_with_confidence() {
+        let report = claude(CheckStatus::Error, "api.anthropic.com :: 529 :: overloaded", Some(529));
+        assert_eq!(rules(&report), ["capacity.provider"]);
+        assert!(report.findings[0].confidence >= 50);
+    }
+
+    #[test]
+    fn rate_limit_comes_from_the_status_code() {
+        assert_eq!(rules(&claude(CheckStatus::Warning, "api.anthropic.com :: slow", Some(429))), ["claude.rate_limited"]);
+        // The wording alone decides nothing
+        assert_eq!(rules(&claude(CheckStatus::Warning, "429 requests queued", None)), ["claude.slow"]);
+    }
+
+    #[test]
+    fn snoozed_router_shows_on_both_network_cards() {
+        let now = chrono::NaiveDateTime::parse_from_str("2026-03-02 10:00:00", history::TIMESTAMP_FORMAT).unwrap();
+        let mut settings = DiagnosticSettings::default();
+        for id in ids::ALL {
+            settings.set_check_enabled(id, false);
+        }
+        settings.set_check_enabled(ids::GATEWAY, true);
+        settings.snooze(ids::GATEWAY, 60, now);
+
+        let mut report = DiagnosticReport::new();
+        report.show_snoozed(&snoozed_checks(&settings, now));
+        for check in [&report.gateway, &report.internet] {
+            let check = check.as_ref().unwrap();
+            assert_eq!(check.status, CheckStatus::Inactive);
+            assert_eq!(check.details, "Snoozed until 11:00");
+        }
+        assert!(report.claude_api.is_none());
+    }
+}
@@ -0,0 +1,67 @@
+//! Continuous CPU/RAM/GPU sampling between runs
+//!
+//! The LOCAL RESOURCES check reads a single 200ms snapshot, which says little
+//! about a machine that is busy in bursts. The monitor keeps a rolling buffer
+//! of cheap samples taken every few seconds by the scheduler, independent of
+//! diagnostic runs.
+
+use crate::diagnostics::gpu::UsageSampler;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sysinfo::{MemoryRefreshKind, System};
+
+pub const MONITOR_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Five minutes of samples
+pub const MONITOR_WINDOW: usize = 100;
+
+/// One sample of the machine; CPU usage is averaged since the previous sample
+#[derive(Clone, Copy)]
+pub struct ResourceSample {
+    pub cpu: f32,
+    pub ram: f32,
+    pub gpu: Option<f32>,
+}
+
+/// Rolling buffer of resource samples, shared with the sampler
+#[derive(Clone, Default)]
+pub struct ResourceMonitor {
+    samples: Arc<Mutex<VecDeque<ResourceSample>>>,
+}
+
+impl ResourceMonitor {
+    /// Sampling closure for a scheduler watch; returns true when a sample was added
+    pub fn sampler(&self) -> impl FnMut() -> bool + Send + 'static {
+        let samples = Arc::clone(&self.samples);
+        let mut sys = System::new();
+        let mut gpu = UsageSampler::default();
+        let mut primed = false;
+        move || {
+            sys.refresh_cpu_usage();
+            sys.refresh_memory_specifics(MemoryRefreshKind::new().with_ram());
+            let gpu_usage = gpu.sample();
+            // The first CPU reading has no previous one to compare against
+            if !primed {
+                primed = true;
+                return false;
+            }
+
+            let sample = ResourceSample {
+                cpu: sys.global_cpu_usage(),
+                ram: sys.used_memory() as f32 / sys.total_memory().max(1) as f32 * 100.0,
+                gpu: gpu_usage,
+            };
+            let mut samples = samples.lock().unwrap();
+            if samples.len() == MONITOR_WINDOW {
+                samples.pop_front();
+            }
+            samples.push_back(sample);
+            true
+        }
+    }
+
+    pub fn samples(&self) -> Vec<ResourceSample> {
+        self.samples.lock().unwrap().iter().copied().collect()
+    }
+}
@@ -0,0 +1,150 @@
+//! Network change detection
+//!
+//! Most "it broke just now" reports follow a network change: docking, a VPN
+//! connecting, roaming to another Wi-Fi. The watcher polls the adapters with
+//! an address, the default route and the Wi-Fi name, and reports a change
+//! once it has settled, so a run starts on the new network and the report
+//! says what changed.
+
+use crate::diagnostics::{network, tags, wlan};
+use std::collections::BTreeSet;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sysinfo::Networks;
+
+/// How often the network state is polled
+pub const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// What identifies the current network
+#[derive(Clone, PartialEq)]
+struct NetState {
+    /// Adapters with a routable address
+    adapters: BTreeSet<String>,
+    gateway: Option<Ipv4Addr>,
+    ssid: Option<String>,
+}
+
+fn routable(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => !ip.is_loopback() && !ip.is_link_local() && !ip.is_unspecified(),
+        IpAddr::V6(ip) => !ip.is_loopback() && !ip.is_unspecified() && (ip.segments()[0] & 0xffc0) != 0xfe80,
+    }
+}
+
+impl NetState {
+    fn read() -> Self {
+        let networks = Networks::new_with_refreshed_list();
+        let adapters = networks
+            .list()
+            .iter()
+            .filter(|(_, data)| data.ip_networks().iter().any(|net| routable(net.addr)))
+            .map(|(name, _)| name.clone())
+            .collect();
+        Self { adapters, gateway: network::default_gateway(), ssid: wlan::ssid() }
+    }
+
+    /// Wi-Fi name or gateway (see `tags::network_name`)
+    fn name(&self) -> Option<String> {
+        tags::network_name(self.ssid.as_deref(), self.gateway)
+    }
+
+    /// "Wi-Fi up, default route 192.168.1.1 -> 10.8.0.1"
+    fn changes_from(&self, before: &NetState) -> Vec<String> {
+        let mut changes = Vec::new();
+        for name in self.adapters.difference(&before.adapters) {
+            changes.push(format!("{} up", name));
+        }
+        for name in before.adapters.difference(&self.adapters) {
+            changes.push(format!("{} down", name));
+        }
+        let show = |value: Option<String>| value.unwrap_or_else(|| "none".to_string());
+        if self.gateway != before.gateway {
+            let (old, new) = (show(before.gateway.map(|g| g.to_string())), show(self.gateway.map(|g| g.to_string())));
+            changes.push(format!("default route {} -> {}", old, new));
+        }
+        if self.ssid != before.ssid {
+            changes.push(format!("Wi-Fi {} -> {}", show(before.ssid.clone()), show(self.ssid.clone())));
+        }
+        changes
+    }
+}
+
+/// Polls the network state; a change is reported one quiet poll after it
+#[derive(Default)]
+pub struct NetWatcher {
+    last: Option<NetState>,
+    /// Changes seen while the network was still settling
+    pending: Vec<String>,
+}
+
+impl NetWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the state; returns the settled change, if any
+    pub fn poll(&mut self) -> Option<String> {
+        let current = NetState::read();
+        let last = self.last.replace(current.clone())?;
+        let changes = current.changes_from(&last);
+        if !changes.is_empty() {
+            self.pending.extend(changes);
+            return None;
+        }
+        if self.pending.is_empty() {
+            return None;
+        }
+        Some(std::mem::take(&mut self.pending).join(", "))
+    }
+
+    /// Poll and publish a settled change to `state`; true when one was published
+    /// (nothing is read while `state` is disabled)
+    pub fn poll_into(&mut self, state: &NetChange) -> bool {
+        if !state.enabled.load(Ordering::Relaxed) {
+            self.last = None;
+            self.pending.clear();
+            *state.network.lock().unwrap() = None;
+            return false;
+        }
+        let change = self.poll();
+        let network = self.last.as_ref().and_then(NetState::name);
+        let mut published = state.network.lock().unwrap();
+        let renamed = *published != network;
+        *published = network;
+        if let Some(change) = change {
+            *state.change.lock().unwrap() = Some(change);
+            return true;
+        }
+        renamed
+    }
+}
+
+/// Latest settled change published by a background `NetWatcher` for the UI
+#[derive(Clone, Default)]
+pub struct NetChange {
+    change: Arc<Mutex<Option<String>>>,
+    /// Current network name, for network profiles
+    network: Arc<Mutex<Option<String>>>,
+    enabled: Arc<AtomicBool>,
+}
+
+impl NetChange {
+    /// The change since the last call, if any
+    pub fn take(&self) -> Option<String> {
+        self.change.lock().unwrap().take()
+    }
+
+    /// Name of the current network (None until read, or while disabled)
+    pub fn network(&self) -> Option<String> {
+        self.network.lock().unwrap().clone()
+    }
+
+    /// Start or stop watching (a change seen before stopping is dropped)
+    pub fn set_enabled(&self, enabled: bool) {
+        if !self.enabled.swap(enabled, Ordering::Relaxed) && enabled {
+            self.change.lock().unwrap().take();
+        }
+    }
+}
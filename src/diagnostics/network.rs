@@ -0,0 +1,152 @@
+//! Local network checks (default gateway / router)
+
+use crate::diagnostics::{CheckResult, CheckStatus};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream, UdpSocket};
+use std::time::{Duration, Instant};
+
+/// Ports tried when probing the router; a refused connection still proves it answered
+const GATEWAY_PROBE_PORTS: &[u16] = &[80, 53, 443];
+
+/// Check that the default gateway answers
+pub fn check_gateway() -> CheckResult {
+    let Some(gateway) = default_gateway() else {
+        return CheckResult::new("ROUTER", CheckStatus::Error, "No default route");
+    };
+
+    let start = Instant::now();
+    let answered = GATEWAY_PROBE_PORTS.iter().any(|port| {
+        let addr = SocketAddr::new(IpAddr::V4(gateway), *port);
+        match TcpStream::connect_timeout(&addr, Duration::from_millis(500)) {
+            Ok(_) => true,
+            Err(e) => e.kind() == std::io::ErrorKind::ConnectionRefused,
+        }
+    });
+    let elapsed = start.elapsed().as_millis();
+
+    if answered {
+        CheckResult::new("ROUTER", CheckStatus::Ok, &format!("GW {} :: {}ms", gateway, elapsed))
+            .with_latency(elapsed)
+    } else {
+        CheckResult::new(
+            "ROUTER",
+            CheckStatus::Warning,
+            &format!("GW {} :: no response (may filter probes)", gateway),
+        )
+    }
+}
+
+/// Best-effort LAN address of this machine (no packets are sent)
+pub fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(8, 8, 8, 8), 80)).ok()?;
+    socket.local_addr().ok().map(|a| a.ip())
+}
+
+/// Find the IPv4 default gateway
+#[cfg(target_os = "windows")]
+pub fn default_gateway() -> Option<Ipv4Addr> {
+    // "0.0.0.0  0.0.0.0  192.168.1.1  192.168.1.50  25"
+    let output = crate::diagnostics::command("route").args(["print", "-4", "0.0.0.0"]).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().find_map(|line| {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        if cols.len() >= 3 && cols[0] == "0.0.0.0" && cols[1] == "0.0.0.0" {
+            cols[2].parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+/// Find the IPv4 default gateway
+#[cfg(target_os = "linux")]
+pub fn default_gateway() -> Option<Ipv4Addr> {
+    // Columns: Iface Destination Gateway ... (hex, little-endian)
+    let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+    routes.lines().skip(1).find_map(|line| {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        if cols.len() >= 3 && cols[1] == "00000000" {
+            let raw = u32::from_str_radix(cols[2], 16).ok()?;
+            Some(Ipv4Addr::from(raw.to_le_bytes()))
+        } else {
+            None
+        }
+    })
+}
+
+/// Find the IPv4 default gateway
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn default_gateway() -> Option<Ipv4Addr> {
+    // "    gateway: 192.168.1.1"
+    let output = crate::diagnostics::command("route").args(["-n", "get", "default"]).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("gateway:").and_then(|g| g.trim().parse().ok()))
+}
+
+/// Listening TCP ports as (pid, port)
+#[cfg(target_os = "windows")]
+pub fn listening_ports() -> Vec<(u32, u16)> {
+    // "  TCP    127.0.0.1:4096    0.0.0.0:0    LISTENING    1234" (state text is localized)
+    let Ok(output) = crate::diagnostics::command("netstat").args(["-ano", "-p", "TCP"]).output() else {
+        return Vec::new();
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            if cols.len() != 5 || cols[0] != "TCP" || !matches!(cols[2], "0.0.0.0:0" | "[::]:0") {
+                return None;
+            }
+            let port = cols[1].rsplit_once(':')?.1.parse().ok()?;
+            Some((cols[4].parse().ok()?, port))
+        })
+        .collect()
+}
+
+/// Listening TCP ports as (pid, port)
+#[cfg(target_os = "linux")]
+pub fn listening_ports() -> Vec<(u32, u16)> {
+    // "LISTEN 0 511 127.0.0.1:4096 0.0.0.0:* users:(("opencode",pid=1234,fd=22))"
+    let Ok(output) = crate::diagnostics::command("ss").args(["-ltnpH"]).output() else {
+        return Vec::new();
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut ports = Vec::new();
+    for line in stdout.lines() {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        let Some(port) = cols.get(3).and_then(|a| a.rsplit_once(':')).and_then(|(_, p)| p.parse().ok()) else {
+            continue;
+        };
+        for part in line.split("pid=").skip(1) {
+            if let Ok(pid) = part.split(|c: char| !c.is_ascii_digit()).next().unwrap_or("").parse() {
+                ports.push((pid, port));
+            }
+        }
+    }
+    ports
+}
+
+/// Listening TCP ports as (pid, port)
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn listening_ports() -> Vec<(u32, u16)> {
+    // Field output: "p1234" starts a process, "n*:4096" is one of its sockets
+    let Ok(output) = crate::diagnostics::command("lsof").args(["-nP", "-iTCP", "-sTCP:LISTEN", "-Fpn"]).output() else {
+        return Vec::new();
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut ports = Vec::new();
+    let mut pid = None;
+    for line in stdout.lines() {
+        if let Some(p) = line.strip_prefix('p') {
+            pid = p.parse().ok();
+        } else if let (Some(pid), Some(name)) = (pid, line.strip_prefix('n')) {
+            if let Some(port) = name.rsplit_once(':').and_then(|(_, p)| p.parse().ok()) {
+                ports.push((pid, port));
+            }
+        }
+    }
+    ports
+}
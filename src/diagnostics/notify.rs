@@ -0,0 +1,48 @@
+//! Desktop notifications through the platform's own tools
+//!
+//! No extra dependency: a PowerShell balloon on Windows, `osascript` on
+//! macOS and `notify-send` elsewhere. The notifier is started and left
+//! running, so the caller is never blocked.
+
+/// Wait for the notifier in the background (no zombie left behind)
+fn reap(mut child: std::process::Child) {
+    std::thread::spawn(move || child.wait());
+}
+
+/// Tray balloon (shown for 10s; the PowerShell process then exits)
+#[cfg(target_os = "windows")]
+pub fn notify(title: &str, body: &str) -> Result<(), String> {
+    let quote = |s: &str| s.replace('\'', "''");
+    let script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms; $n = New-Object System.Windows.Forms.NotifyIcon; \
+         $n.Icon = [System.Drawing.SystemIcons]::Warning; $n.Visible = $true; \
+         $n.ShowBalloonTip(10000, '{}', '{}', 'Warning'); Start-Sleep -Seconds 10; $n.Dispose()",
+        quote(title),
+        quote(body)
+    );
+    crate::diagnostics::command("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .spawn()
+        .map(reap)
+        .map_err(|e| format!("powershell failed: {}", e))
+}
+
+#[cfg(target_os = "macos")]
+pub fn notify(title: &str, body: &str) -> Result<(), String> {
+    let quote = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let script = format!("display notification \"{}\" with title \"{}\"", quote(body), quote(title));
+    crate::diagnostics::command("osascript")
+        .args(["-e", &script])
+        .spawn()
+        .map(reap)
+        .map_err(|e| format!("osascript failed: {}", e))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn notify(title: &str, body: &str) -> Result<(), String> {
+    crate::diagnostics::command("notify-send")
+        .args(["--urgency=critical", "--app-name=OpenCode Diagnostics", title, body])
+        .spawn()
+        .map(reap)
+        .map_err(|e| format!("notify-send failed: {}", e))
+}
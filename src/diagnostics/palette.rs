@@ -0,0 +1,93 @@
+//! Status colors picked by the user
+//!
+//! Green / orange / red is hard to tell apart with the most common kinds of
+//! color blindness. OK, WARNING and ERROR can be remapped to a preset or any
+//! `#rrggbb`; the cards, badges and charts of the window and the status page
+//! all draw with them. An empty color keeps the theme's own.
+
+use crate::diagnostics::CheckStatus;
+use serde::{Deserialize, Serialize};
+
+/// Preset palettes: name and the ok / warning / error colors ("" = theme's)
+pub const PRESETS: &[(&str, [&str; 3])] = &[
+    ("THEME", ["", "", ""]),
+    // Okabe-Ito blue, yellow and vermillion
+    ("COLORBLIND", ["#0072b2", "#f0e442", "#d55e00"]),
+    ("HIGH CONTRAST", ["#00e5ff", "#ffea00", "#ff1744"]),
+];
+
+/// `#rrggbb` per status; empty = the theme's color
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusColors {
+    pub ok: String,
+    pub warning: String,
+    pub error: String,
+}
+
+impl StatusColors {
+    pub fn preset(colors: [&str; 3]) -> Self {
+        let [ok, warning, error] = colors.map(str::to_string);
+        Self { ok, warning, error }
+    }
+
+    /// Name of the preset these colors are, if any
+    pub fn preset_name(&self) -> Option<&'static str> {
+        PRESETS.iter().find(|(_, colors)| *self == Self::preset(*colors)).map(|(name, _)| *name)
+    }
+
+    /// The color set for `status` (Unknown and Inactive are never remapped)
+    pub fn rgb(&self, status: CheckStatus) -> Option<[u8; 3]> {
+        match status {
+            CheckStatus::Ok => parse_hex(&self.ok),
+            CheckStatus::Warning => parse_hex(&self.warning),
+            CheckStatus::Error => parse_hex(&self.error),
+            CheckStatus::Unknown | CheckStatus::Inactive => None,
+        }
+    }
+}
+
+/// "#4caf50" or "4CAF50" -> [0x4c, 0xaf, 0x50]
+pub fn parse_hex(text: &str) -> Option<[u8; 3]> {
+    let hex = text.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([byte(0)?, byte(2)?, byte(4)?])
+}
+
+pub fn to_hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Black or white, whichever reads better on `rgb`
+pub fn text_on([r, g, b]: [u8; 3]) -> [u8; 3] {
+    let luma = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+    if luma > 140.0 { [0, 0, 0] } else { [0xff, 0xff, 0xff] }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::{CheckResult, DiagnosticReport, History};
+
+    #[test]
+    fn colorblind_preset_reaches_the_status_page() {
+        let (_, colors) = PRESETS.iter().find(|(name, _)| *name == "COLORBLIND").unwrap();
+        let colorblind = StatusColors::preset(*colors);
+        let mut report = DiagnosticReport::new();
+        report.claude_api = Some(CheckResult::new("CLAUDE API", CheckStatus::Error, "HTTP 500"));
+        let html = crate::server::page::render(&report, &History::default(), &colorblind);
+        assert!(html.contains(&colorblind.error));
+        assert!(!html.contains("#f44336"));
+    }
+
+    #[test]
+    fn bad_hex_keeps_the_theme_color() {
+        let bad = StatusColors { ok: "#12345".to_string(), warning: "green".to_string(), ..Default::default() };
+        assert_eq!(bad.rgb(CheckStatus::Ok), None);
+        assert_eq!(bad.rgb(CheckStatus::Warning), None);
+        assert_eq!(parse_hex("4CAF50"), Some([0x4c, 0xaf, 0x50]));
+    }
+}
@@ -0,0 +1,138 @@
+//! Managed policy: settings locked by IT
+//!
+//! A managed deployment ships `policy.json` in a machine-wide folder users
+//! cannot write to. It holds settings the same way `settings.json` does
+//! (`{"check_claude": true, "upload_url": "https://..."}`); every setting in
+//! it is locked: applied over the user's settings on load and whenever
+//! settings change, and shown disabled in the settings panel. Settings not
+//! in it stay the user's.
+
+use crate::diagnostics::DiagnosticSettings;
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Policy file of this machine, if any (read once)
+static CURRENT: OnceLock<Option<Policy>> = OnceLock::new();
+
+pub struct Policy {
+    pub path: PathBuf,
+    /// Locked settings and their values (empty when the file is invalid)
+    values: Map<String, Value>,
+    /// Why the file was not applied
+    pub error: Option<String>,
+}
+
+/// `%ProgramData%\opencode-diag\policy.json`
+#[cfg(target_os = "windows")]
+pub fn policy_path() -> Option<PathBuf> {
+    let data = std::env::var_os("ProgramData").unwrap_or_else(|| "C:\\ProgramData".into());
+    Some(PathBuf::from(data).join("opencode-diag").join("policy.json"))
+}
+
+#[cfg(target_os = "macos")]
+pub fn policy_path() -> Option<PathBuf> {
+    Some(PathBuf::from("/Library/Application Support/opencode-diag/policy.json"))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn policy_path() -> Option<PathBuf> {
+    Some(PathBuf::from("/etc/opencode-diag/policy.json"))
+}
+
+/// The machine's policy; None when there is no policy file
+pub fn current() -> Option<&'static Policy> {
+    CURRENT
+        .get_or_init(|| {
+            let path = policy_path()?;
+            let text = std::fs::read_to_string(&path).ok()?;
+            Some(match Policy::parse(&text) {
+                Ok(values) => Policy { path, values, error: None },
+                Err(e) => Policy { path, values: Map::new(), error: Some(e) },
+            })
+        })
+        .as_ref()
+}
+
+/// Whether the machine's policy locks `key`
+pub fn is_locked(key: &str) -> bool {
+    current().is_some_and(|policy| policy.values.contains_key(key))
+}
+
+impl Policy {
+    /// Locked settings of a policy file; every key must be a setting with a valid value
+    pub fn parse(text: &str) -> Result<Map<String, Value>, String> {
+        let values = match serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))? {
+            Value::Object(values) => values,
+            _ => return Err("Expected an object of settings".to_string()),
+        };
+        let Value::Object(known) = serde_json::to_value(DiagnosticSettings::default()).map_err(|e| e.to_string())? else {
+            return Err("Settings are not an object".to_string());
+        };
+        if let Some(unknown) = values.keys().find(|key| !known.contains_key(*key)) {
+            return Err(format!("Unknown setting \"{}\"", unknown));
+        }
+        // Each value must deserialize as its setting; kept as it serializes back
+        // (1.1 as an f32 is 1.100000023841858), so `enforce` compares like with like
+        let mut merged = known;
+        merged.extend(values.clone());
+        let settings: DiagnosticSettings = serde_json::from_value(Value::Object(merged)).map_err(|e| format!("Invalid value: {}", e))?;
+        let Value::Object(mut normalized) = serde_json::to_value(settings).map_err(|e| e.to_string())? else {
+            return Err("Settings are not an object".to_string());
+        };
+        normalized.retain(|key, _| values.contains_key(key));
+        Ok(normalized)
+    }
+
+    /// Names of the locked settings
+    pub fn locked(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    /// Put the locked values back into `settings`; true when any had changed
+    pub fn enforce(&self, settings: &mut DiagnosticSettings) -> bool {
+        if self.values.is_empty() {
+            return false;
+        }
+        let Ok(Value::Object(mut current)) = serde_json::to_value(&*settings) else {
+            return false;
+        };
+        if self.values.iter().all(|(key, value)| current.get(key) == Some(value)) {
+            return false;
+        }
+        current.extend(self.values.clone());
+        match serde_json::from_value(Value::Object(current)) {
+            Ok(enforced) => {
+                *settings = enforced;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locked_settings_come_back_after_a_change() {
+        let values = Policy::parse(r#"{"check_claude": false, "upload_url": "https://collect.example/runs", "ui_scale": 1.1}"#).unwrap();
+        let policy = Policy { path: PathBuf::new(), values, error: None };
+        let mut settings = DiagnosticSettings::default();
+        assert!(policy.enforce(&mut settings));
+        settings.check_claude = true;
+        settings.check_openai = false;
+        assert!(policy.enforce(&mut settings));
+        assert!(!settings.check_claude);
+        assert!(!settings.check_openai);
+        assert_eq!(settings.upload_url, "https://collect.example/runs");
+        assert!(!policy.enforce(&mut settings));
+    }
+
+    #[test]
+    fn unknown_or_mistyped_settings_reject_the_file() {
+        assert!(Policy::parse(r#"{"no_such_setting": 1}"#).is_err());
+        assert!(Policy::parse(r#"{"check_claude": "yes"}"#).is_err());
+    }
+}
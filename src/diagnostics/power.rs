@@ -0,0 +1,99 @@
+//! Battery state for throttling checks on low battery
+//!
+//! Below the configured threshold on battery, heavy checks are skipped and
+//! the auto-refresh interval is stretched.
+
+use crate::diagnostics::{ids, DiagnosticSettings};
+
+/// Checks skipped on low battery (WMI, PowerShell, several probes)
+pub const HEAVY_CHECKS: &[&str] = &[ids::GPU, ids::FIREWALL, ids::ROOT_STORE];
+
+/// Auto-refresh interval multiplier on low battery
+pub const BATTERY_INTERVAL_FACTOR: u32 = 3;
+
+/// Preset battery thresholds (percent)
+pub const BATTERY_PRESETS: &[(u8, &str)] = &[
+    (20, "20%"),
+    (30, "30%"),
+    (50, "50%"),
+];
+
+/// Current power source
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PowerStatus {
+    pub on_battery: bool,
+    pub percent: Option<u8>,
+}
+
+impl PowerStatus {
+    /// On battery and below `threshold` percent
+    pub fn is_low(&self, threshold: u8) -> bool {
+        self.on_battery && self.percent.map(|p| p < threshold).unwrap_or(false)
+    }
+}
+
+/// Settings with the heavy checks turned off
+pub fn throttled(settings: &DiagnosticSettings) -> DiagnosticSettings {
+    let mut settings = settings.clone();
+    for id in HEAVY_CHECKS {
+        settings.set_check_enabled(id, false);
+    }
+    settings
+}
+
+/// Battery state, `None` on machines without a battery
+#[cfg(target_os = "windows")]
+pub fn power_status() -> Option<PowerStatus> {
+    use winapi::um::winbase::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return None;
+    }
+    // BatteryFlag 128 = no system battery
+    if status.BatteryFlag == 128 {
+        return None;
+    }
+    Some(PowerStatus {
+        on_battery: status.ACLineStatus == 0,
+        percent: (status.BatteryLifePercent <= 100).then_some(status.BatteryLifePercent),
+    })
+}
+
+/// Battery state, `None` on machines without a battery
+#[cfg(target_os = "linux")]
+pub fn power_status() -> Option<PowerStatus> {
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let read = |path: &std::path::Path, name: &str| {
+        std::fs::read_to_string(path.join(name)).map(|s| s.trim().to_string()).unwrap_or_default()
+    };
+
+    let mut found = None;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if read(&path, "type") == "Battery" {
+            found = Some(PowerStatus {
+                on_battery: read(&path, "status") == "Discharging",
+                percent: read(&path, "capacity").parse().ok(),
+            });
+            break;
+        }
+    }
+    found
+}
+
+/// Battery state, `None` on machines without a battery
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn power_status() -> Option<PowerStatus> {
+    // "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1234)\t85%; discharging; ..."
+    let output = crate::diagnostics::command("pmset").args(["-g", "batt"]).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout.lines().find(|l| l.contains("InternalBattery"))?;
+    let percent = line
+        .split_whitespace()
+        .find_map(|word| word.strip_suffix("%;").and_then(|p| p.parse().ok()));
+    Some(PowerStatus {
+        on_battery: stdout.contains("'Battery Power'"),
+        percent,
+    })
+}
@@ -1,54 +1,323 @@
 //! Process monitoring (OpenCode, terminals, etc.)
 
-use crate::diagnostics::{CheckResult, CheckStatus};
-use sysinfo::System;
+use crate::diagnostics::{network, CheckResult, CheckStatus, SystemSnapshot};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+use sysinfo::{Pid, Process, System};
 
-/// Check if OpenCode process is running
-pub fn check_opencode_process() -> CheckResult {
-    let sys = System::new_all();
-    
-    // Look for opencode process
-    let opencode_processes: Vec<_> = sys.processes()
+/// One running OpenCode (its own child processes folded in)
+struct Instance {
+    pid: Pid,
+    version: Option<String>,
+    cwd: Option<PathBuf>,
+    ports: Vec<u16>,
+    memory: u64,
+}
+
+impl Instance {
+    /// "PID 1234 v0.15.2 :4096 C:\dev\app"
+    fn describe(&self) -> String {
+        let mut parts = vec![format!("PID {}", self.pid)];
+        if let Some(version) = &self.version {
+            parts.push(format!("v{}", version.trim_start_matches('v')));
+        }
+        if !self.ports.is_empty() {
+            parts.push(self.ports.iter().map(|p| format!(":{}", p)).collect::<Vec<_>>().join(","));
+        }
+        if let Some(cwd) = &self.cwd {
+            parts.push(cwd.display().to_string());
+        }
+        parts.join(" ")
+    }
+}
+
+fn is_opencode(process: &Process) -> bool {
+    let name = process.name().to_string_lossy().to_lowercase();
+    // Not this tool, nor threads (listed as tasks on Linux, named after our checks)
+    name.contains("opencode")
+        && !name.contains("opencode-diag")
+        && process.pid().as_u32() != std::process::id()
+        && process.thread_kind().is_none()
+}
+
+/// Pids of every OpenCode process
+fn opencode_pids(snapshot: &mut SystemSnapshot) -> Vec<Pid> {
+    snapshot.processes()
+        .processes()
         .values()
-        .filter(|p| {
-            let name = p.name().to_string_lossy().to_lowercase();
-            name.contains("opencode")
-        })
-        .collect();
+        .filter(|p| is_opencode(p))
+        .map(|p| p.pid())
+        .collect()
+}
+
+/// Check if OpenCode process is running
+pub fn check_opencode_process(snapshot: &mut SystemSnapshot) -> CheckResult {
+    let pids = opencode_pids(snapshot);
+
+    if pids.is_empty() {
+        return CheckResult::new("OPENCODE", CheckStatus::Inactive, "Process not detected");
+    }
 
-    if opencode_processes.is_empty() {
-        CheckResult::new("OPENCODE", CheckStatus::Inactive, "Process not detected")
+    let sys = snapshot.process_details(&pids);
+    let instances = opencode_instances(sys, &pids);
+
+    let mem_mb = instances.iter().map(|i| i.memory).sum::<u64>() / (1024 * 1024);
+    let details = if instances.len() == 1 {
+        format!("{} :: {}MB", instances[0].describe(), mem_mb)
     } else {
-        let total_mem: u64 = opencode_processes.iter()
-            .map(|p| p.memory())
-            .sum();
-        let mem_mb = total_mem / (1024 * 1024);
-        
-        let proc = &opencode_processes[0];
-        let pid = proc.pid();
-        
-        let count_str = if opencode_processes.len() > 1 {
-            format!(" ({} instances)", opencode_processes.len())
-        } else {
-            String::new()
+        let lines: Vec<String> = instances.iter().map(Instance::describe).collect();
+        format!("{} instances :: {}MB\n{}", instances.len(), mem_mb, lines.join("\n"))
+    };
+
+    let conflicts = conflicts(&instances);
+
+    // Warn if using too much memory or instances step on each other
+    let status = if mem_mb > 2000 || !conflicts.is_empty() {
+        CheckStatus::Warning
+    } else {
+        CheckStatus::Ok
+    };
+
+    let result = CheckResult::new("OPENCODE", status, &details);
+    if conflicts.is_empty() {
+        result
+    } else {
+        result.with_message(&conflicts.join("; "))
+    }
+}
+
+/// Group OpenCode processes into instances: a process whose parent is also
+/// OpenCode belongs to that parent's instance
+fn opencode_instances(sys: &System, pids: &[Pid]) -> Vec<Instance> {
+    let listening = network::listening_ports();
+    let mut instances: Vec<Instance> = Vec::new();
+    for &pid in pids {
+        let Some(process) = sys.process(pid) else {
+            continue;
         };
-        
-        let details = format!("PID {} :: {}MB{}", pid, mem_mb, count_str);
-        
-        // Warn if using too much memory
-        let status = if mem_mb > 2000 {
-            CheckStatus::Warning
-        } else {
-            CheckStatus::Ok
+        let root = root_of(sys, pids, pid);
+        let index = match instances.iter().position(|i| i.pid == root) {
+            Some(index) => index,
+            None => {
+                let root_process = sys.process(root).unwrap_or(process);
+                instances.push(Instance {
+                    pid: root,
+                    version: root_process.exe().and_then(version_of),
+                    cwd: root_process.cwd().map(Path::to_path_buf),
+                    ports: requested_port(root_process.cmd()).into_iter().collect(),
+                    memory: 0,
+                });
+                instances.len() - 1
+            }
         };
-        
-        CheckResult::new("OPENCODE", status, &details)
+        let instance = &mut instances[index];
+        instance.memory += process.memory();
+        for &(_, port) in listening.iter().filter(|(p, _)| *p == pid.as_u32()) {
+            if !instance.ports.contains(&port) {
+                instance.ports.push(port);
+            }
+        }
+    }
+    instances.sort_by_key(|i| i.pid);
+    instances
+}
+
+/// Topmost ancestor of `pid` that is still one of `pids`
+fn root_of(sys: &System, pids: &[Pid], mut pid: Pid) -> Pid {
+    while let Some(parent) = sys.process(pid).and_then(|p| p.parent()).filter(|p| pids.contains(p)) {
+        pid = parent;
     }
+    pid
+}
+
+/// Running OpenCode instances as (root pid, working directory)
+pub fn opencode_roots(snapshot: &mut SystemSnapshot) -> Vec<(Pid, Option<PathBuf>)> {
+    let pids = opencode_pids(snapshot);
+    let sys = snapshot.process_details(&pids);
+
+    let mut roots: Vec<Pid> = pids.iter().map(|&pid| root_of(sys, &pids, pid)).collect();
+    roots.sort();
+    roots.dedup();
+    roots
+        .into_iter()
+        .map(|pid| (pid, sys.process(pid).and_then(|p| p.cwd()).map(Path::to_path_buf)))
+        .collect()
+}
+
+/// Each OpenCode instance as (root pid, every process below it), so helpers
+/// it spawns (language servers, node, shells) count as part of it
+pub fn opencode_trees(snapshot: &mut SystemSnapshot) -> Vec<(Pid, Vec<Pid>)> {
+    let roots: Vec<Pid> = opencode_roots(snapshot).into_iter().map(|(pid, _)| pid).collect();
+    let sys = snapshot.processes();
+    roots
+        .iter()
+        .map(|&root| {
+            let tree = sys
+                .processes()
+                .keys()
+                .copied()
+                .filter(|&pid| {
+                    let mut current = Some(pid);
+                    // Bounded walk; parent links can loop after pid reuse
+                    for _ in 0..64 {
+                        match current {
+                            Some(p) if p == root => return true,
+                            Some(p) => current = sys.process(p).and_then(|p| p.parent()),
+                            None => return false,
+                        }
+                    }
+                    false
+                })
+                .collect();
+            (root, tree)
+        })
+        .collect()
+}
+
+/// Executables of the running OpenCode instances
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub fn opencode_exes(snapshot: &mut SystemSnapshot) -> Vec<PathBuf> {
+    let roots: Vec<Pid> = opencode_roots(snapshot).into_iter().map(|(pid, _)| pid).collect();
+    let sys = snapshot.process_details(&roots);
+    let mut exes: Vec<PathBuf> = roots
+        .iter()
+        .filter_map(|pid| sys.process(*pid)?.exe().map(Path::to_path_buf))
+        .collect();
+    exes.sort();
+    exes.dedup();
+    exes
+}
+
+/// Running OpenCode instances as (root pid, TCP ports its processes listen on)
+pub fn opencode_ports(snapshot: &mut SystemSnapshot) -> Vec<(Pid, Vec<u16>)> {
+    let pids = opencode_pids(snapshot);
+    if pids.is_empty() {
+        return Vec::new();
+    }
+    let sys = snapshot.processes();
+    let listening = network::listening_ports();
+
+    let mut instances: Vec<(Pid, Vec<u16>)> = Vec::new();
+    for &pid in &pids {
+        let root = root_of(sys, &pids, pid);
+        let index = match instances.iter().position(|(p, _)| *p == root) {
+            Some(index) => index,
+            None => {
+                instances.push((root, Vec::new()));
+                instances.len() - 1
+            }
+        };
+        let ports = &mut instances[index].1;
+        for &(_, port) in listening.iter().filter(|(p, _)| *p == pid.as_u32()) {
+            if !ports.contains(&port) {
+                ports.push(port);
+            }
+        }
+    }
+    instances.sort_by_key(|(pid, _)| *pid);
+    instances
+}
+
+/// Port passed as `--port 4096` / `--port=4096`
+fn requested_port(cmd: &[OsString]) -> Option<u16> {
+    let args: Vec<String> = cmd.iter().map(|a| a.to_string_lossy().to_string()).collect();
+    args.iter().enumerate().find_map(|(i, arg)| match arg.strip_prefix("--port") {
+        Some("") => args.get(i + 1)?.parse().ok(),
+        Some(rest) => rest.strip_prefix('=')?.parse().ok(),
+        None => None,
+    })
+    .filter(|&port| port != 0)
+}
+
+/// Known conflicts between instances
+fn conflicts(instances: &[Instance]) -> Vec<String> {
+    let mut found = Vec::new();
+    for (i, a) in instances.iter().enumerate() {
+        for b in &instances[i + 1..] {
+            if let Some(port) = a.ports.iter().find(|p| b.ports.contains(p)) {
+                found.push(format!("PID {} and {} both use port {}", a.pid, b.pid, port));
+            }
+            if let (Some(cwd), Some(other)) = (&a.cwd, &b.cwd) {
+                if cwd == other {
+                    found.push(format!(
+                        "PID {} and {} share project {} (same config and session state)",
+                        a.pid,
+                        b.pid,
+                        cwd.display()
+                    ));
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Versions already read, keyed by executable and its modification time
+type VersionCache = Mutex<Vec<((PathBuf, Option<SystemTime>), Option<String>)>>;
+
+/// OpenCode version of an executable: the npm package.json next to it, or `--version`
+pub fn version_of(exe: &Path) -> Option<String> {
+    static CACHE: OnceLock<VersionCache> = OnceLock::new();
+    let key = (exe.to_path_buf(), std::fs::metadata(exe).and_then(|m| m.modified()).ok());
+    let cache = CACHE.get_or_init(Default::default);
+    if let Some((_, version)) = cache.lock().unwrap().iter().find(|(k, _)| *k == key) {
+        return version.clone();
+    }
+
+    let version = package_version(exe).or_else(|| run_version(exe));
+    cache.lock().unwrap().push((key, version.clone()));
+    version
+}
+
+/// npm installs ship the binary in `node_modules/opencode-<platform>/bin/`
+fn package_version(exe: &Path) -> Option<String> {
+    exe.ancestors().skip(1).take(3).find_map(|dir| {
+        let text = std::fs::read_to_string(dir.join("package.json")).ok()?;
+        let package: serde_json::Value = serde_json::from_str(&text).ok()?;
+        if !package["name"].as_str()?.contains("opencode") {
+            return None;
+        }
+        package["version"].as_str().map(str::to_string)
+    })
+}
+
+/// How long `opencode --version` may take
+const VERSION_TIMEOUT: Duration = Duration::from_secs(3);
+
+fn run_version(exe: &Path) -> Option<String> {
+    let mut child = crate::diagnostics::command(&exe.to_string_lossy())
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    let started = Instant::now();
+    while child.try_wait().ok()?.is_none() {
+        if started.elapsed() > VERSION_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    let output = child.wait_with_output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // "0.15.2" or "opencode 0.15.2"
+    stdout
+        .split_whitespace()
+        .find(|word| word.trim_start_matches('v').starts_with(|c: char| c.is_ascii_digit()))
+        .map(str::to_string)
 }
 
 /// Check terminal processes (cmd, powershell, Windows Terminal)
-pub fn check_terminals() -> CheckResult {
-    let sys = System::new_all();
+pub fn check_terminals(snapshot: &mut SystemSnapshot) -> CheckResult {
+    let sys = snapshot.processes();
     
     let mut cmd_count = 0;
     let mut powershell_count = 0;
@@ -102,8 +371,8 @@ pub fn check_terminals() -> CheckResult {
 
 /// Get top processes by memory usage
 #[allow(dead_code)]
-pub fn get_top_processes(limit: usize) -> Vec<(String, u64)> {
-    let sys = System::new_all();
+pub fn get_top_processes(snapshot: &mut SystemSnapshot, limit: usize) -> Vec<(String, u64)> {
+    let sys = snapshot.processes();
     
     let mut processes: Vec<_> = sys.processes()
         .values()
@@ -112,7 +381,7 @@ pub fn get_top_processes(limit: usize) -> Vec<(String, u64)> {
         })
         .collect();
     
-    processes.sort_by(|a, b| b.1.cmp(&a.1));
+    processes.sort_by_key(|p| std::cmp::Reverse(p.1));
     processes.truncate(limit);
     
     processes
@@ -120,8 +389,8 @@ pub fn get_top_processes(limit: usize) -> Vec<(String, u64)> {
 
 /// Get processes by name pattern
 #[allow(dead_code)]
-pub fn find_processes(pattern: &str) -> Vec<(String, u32, u64)> {
-    let sys = System::new_all();
+pub fn find_processes(snapshot: &mut SystemSnapshot, pattern: &str) -> Vec<(String, u32, u64)> {
+    let sys = snapshot.processes();
     let pattern_lower = pattern.to_lowercase();
     
     sys.processes()
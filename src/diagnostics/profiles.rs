@@ -0,0 +1,152 @@
+//! View profiles: which checks and report sections each role cares about
+//!
+//! Network profiles: check toggles that apply while on a given network
+//! (office Wi-Fi: relay and firewall on, bandwidth off), switched when the
+//! network changes and undone when leaving it.
+
+use crate::diagnostics::ids;
+use serde::{Deserialize, Serialize};
+
+/// Who is looking at the results
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum ViewRole {
+    /// User-picked checks, standard report
+    #[default]
+    Custom,
+    /// API latency and status only
+    Developer,
+    /// Everything, with maximum detail for bug reports
+    Support,
+}
+
+/// Role presets shown in settings
+pub const ROLE_PRESETS: &[(ViewRole, &str)] = &[
+    (ViewRole::Custom, "CUSTOM"),
+    (ViewRole::Developer, "DEV"),
+    (ViewRole::Support, "SUPPORT"),
+];
+
+/// Which parts of the text report are included
+#[derive(Clone, Copy)]
+pub struct ReportSections {
+    /// OS, host, CPU and memory totals
+    pub system_info: bool,
+    /// One-line latency summary of network checks
+    pub latency_summary: bool,
+    /// Full per-check details
+    pub check_details: bool,
+    /// Provider error messages
+    pub messages: bool,
+    /// Grouped error log
+    pub error_log: bool,
+}
+
+impl Default for ReportSections {
+    fn default() -> Self {
+        Self {
+            system_info: false,
+            latency_summary: false,
+            check_details: true,
+            messages: true,
+            error_log: false,
+        }
+    }
+}
+
+/// Checks and report sections for a role
+pub struct RoleProfile {
+    pub checks: &'static [&'static str],
+    pub sections: ReportSections,
+}
+
+impl ViewRole {
+    /// Profile for this role, `None` for Custom (keep the user's choices)
+    pub fn profile(&self) -> Option<RoleProfile> {
+        match self {
+            ViewRole::Custom => None,
+            ViewRole::Developer => Some(RoleProfile {
+                checks: &[ids::INTERNET, ids::CLAUDE, ids::OPENAI, ids::GOOGLE],
+                sections: ReportSections {
+                    system_info: false,
+                    latency_summary: true,
+                    check_details: true,
+                    messages: false,
+                    error_log: false,
+                },
+            }),
+            ViewRole::Support => Some(RoleProfile {
+                checks: ids::ALL,
+                sections: ReportSections {
+                    system_info: true,
+                    latency_summary: true,
+                    check_details: true,
+                    messages: true,
+                    error_log: true,
+                },
+            }),
+        }
+    }
+
+    /// Report sections for this role
+    pub fn sections(&self) -> ReportSections {
+        self.profile().map(|p| p.sections).unwrap_or_default()
+    }
+
+    pub fn label(&self) -> &'static str {
+        ROLE_PRESETS
+            .iter()
+            .find(|(role, _)| role == self)
+            .map(|(_, label)| *label)
+            .unwrap_or("CUSTOM")
+    }
+}
+
+/// Check toggles applied while on a network
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkProfile {
+    pub name: String,
+    /// Wi-Fi name or "gw <gateway>" (the network run tag), any case
+    pub network: String,
+    /// Check ids turned on / off while on the network; others are left alone
+    pub enable: Vec<String>,
+    pub disable: Vec<String>,
+}
+
+impl NetworkProfile {
+    pub fn matches(&self, network: &str) -> bool {
+        !self.network.trim().is_empty() && self.network.trim().eq_ignore_ascii_case(network)
+    }
+
+    /// Name shown, the network when unnamed
+    pub fn label(&self) -> &str {
+        if self.name.trim().is_empty() { self.network.trim() } else { self.name.trim() }
+    }
+
+    /// What the profile does to check `id` (None: left alone)
+    pub fn toggle(&self, id: &str) -> Option<bool> {
+        if self.enable.iter().any(|e| e == id) {
+            Some(true)
+        } else if self.disable.iter().any(|d| d == id) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Left alone -> on -> off -> left alone
+    pub fn cycle(&mut self, id: &str) {
+        let next = match self.toggle(id) {
+            None => Some(true),
+            Some(true) => Some(false),
+            Some(false) => None,
+        };
+        self.enable.retain(|e| e != id);
+        self.disable.retain(|d| d != id);
+        match next {
+            Some(true) => self.enable.push(id.to_string()),
+            Some(false) => self.disable.push(id.to_string()),
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! UDP 443 / QUIC (HTTP/3) reachability
+//!
+//! Sends each host a padded QUIC long-header packet with a reserved version.
+//! A QUIC server must answer it with a Version Negotiation packet (RFC 9000
+//! §6), which proves UDP 443 gets through without any TLS. Networks that drop
+//! UDP 443 make HTTP/3-capable clients stall before they fall back to TCP.
+
+use crate::diagnostics::{traffic, CheckResult, CheckStatus};
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+const PROBE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Hosts probed; Google always runs QUIC, so it answers even when no provider does
+const QUIC_HOSTS: &[(&str, &str)] = &[
+    ("google", "www.google.com"),
+    ("claude", "api.anthropic.com"),
+    ("openai", "api.openai.com"),
+    ("gemini", "generativelanguage.googleapis.com"),
+];
+
+/// Reserved version (0x?a?a?a?a pattern) that forces version negotiation
+const GREASE_VERSION: [u8; 4] = [0x1a, 0x2a, 0x3a, 0x4a];
+
+/// Clients must pad Initial packets to at least this size
+const MIN_DATAGRAM: usize = 1200;
+
+/// Check whether QUIC handshakes can reach the provider edges
+pub fn check_quic() -> CheckResult {
+    let results: Vec<(&str, Result<u128, String>)> = std::thread::scope(|scope| {
+        let probes: Vec<_> = QUIC_HOSTS
+            .iter()
+            .map(|(label, host)| (*label, scope.spawn(move || probe(host))))
+            .collect();
+        probes
+            .into_iter()
+            .map(|(label, handle)| (label, handle.join().unwrap_or_else(|_| Err("probe crashed".to_string()))))
+            .collect()
+    });
+
+    // Each sent probe is a padded datagram, answers are a few dozen bytes
+    let sent = results.iter().filter(|(_, r)| !matches!(r, Err(e) if e == "no DNS")).count();
+    let answered_count = results.iter().filter(|(_, r)| r.is_ok()).count();
+    traffic::record((sent * (MIN_DATAGRAM + 28) + answered_count * 100) as u64);
+
+    let parts: Vec<String> = results
+        .iter()
+        .map(|(label, result)| match result {
+            Ok(ms) => format!("{} {}ms", label, ms),
+            Err(e) => format!("{} {}", label, e),
+        })
+        .collect();
+    let details = format!("UDP 443 :: {}", parts.join(" :: "));
+
+    let answered: Vec<u128> = results.iter().filter_map(|(_, r)| r.as_ref().ok().copied()).collect();
+    let resolved = results.iter().any(|(_, r)| !matches!(r, Err(e) if e == "no DNS"));
+    if !resolved {
+        return CheckResult::new("QUIC", CheckStatus::Unknown, &details);
+    }
+    match answered.iter().min() {
+        Some(&fastest) => CheckResult::new("QUIC", CheckStatus::Ok, &details).with_latency(fastest),
+        None => CheckResult::new("QUIC", CheckStatus::Warning, &details).with_message(
+            "UDP 443 (QUIC/HTTP3) looks blocked. HTTP/3-capable clients may stall before falling back to TCP.",
+        ),
+    }
+}
+
+/// Round trip of a version negotiation exchange with host:443
+fn probe(host: &str) -> Result<u128, String> {
+    let addr = (host, 443)
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.find(|a| a.is_ipv4()))
+        .ok_or("no DNS")?;
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).map_err(|_| "no socket")?;
+    socket.set_read_timeout(Some(PROBE_TIMEOUT)).map_err(|_| "no socket")?;
+    socket.connect(addr).map_err(|_| "unreachable")?;
+
+    let (packet, source_id) = initial_packet();
+    let started = Instant::now();
+    socket.send(&packet).map_err(|_| "send failed")?;
+
+    let mut buf = [0u8; 1500];
+    while started.elapsed() < PROBE_TIMEOUT {
+        let Ok(len) = socket.recv(&mut buf) else {
+            break;
+        };
+        // Version Negotiation: long header, version 0, echoing our source id as destination id
+        let reply = &buf[..len];
+        if len > 6 && reply[0] & 0x80 != 0 && reply[1..5] == [0, 0, 0, 0] {
+            let dcid_len = reply[5] as usize;
+            if reply.get(6..6 + dcid_len) == Some(&source_id[..]) {
+                return Ok(started.elapsed().as_millis());
+            }
+        }
+    }
+    Err("no answer".to_string())
+}
+
+/// Padded long-header packet with random connection ids
+fn initial_packet() -> (Vec<u8>, [u8; 8]) {
+    let seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+        ^ ((std::process::id() as u64) << 32);
+    let destination_id = seed.to_be_bytes();
+    let source_id = seed.rotate_left(29).wrapping_mul(0x9e37_79b9_7f4a_7c15).to_be_bytes();
+
+    let mut packet = Vec::with_capacity(MIN_DATAGRAM);
+    packet.push(0xc0); // long header, fixed bit, Initial
+    packet.extend_from_slice(&GREASE_VERSION);
+    packet.push(destination_id.len() as u8);
+    packet.extend_from_slice(&destination_id);
+    packet.push(source_id.len() as u8);
+    packet.extend_from_slice(&source_id);
+    packet.resize(MIN_DATAGRAM, 0);
+    (packet, source_id)
+}
@@ -0,0 +1,73 @@
+//! Morning readiness: one run at a set time each day
+//!
+//! With a time set just before the workday (08:55), the enabled checks run
+//! once a day at that time and a desktop notification is raised only when
+//! something is wrong, so a quiet morning means a working setup. A machine
+//! that was off or asleep then runs it on the first chance within two hours.
+
+use crate::diagnostics::{CheckStatus, DiagnosticReport};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+/// How late a missed run is still made
+pub const CATCH_UP_MINUTES: i64 = 120;
+
+pub const DEFAULT_TIME: &str = "08:55";
+
+/// Day a run was made, as stored in settings
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// "08:55" / "8:55"
+pub fn parse_time(text: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(text.trim(), "%H:%M").ok()
+}
+
+/// Whether the run is due at `now`: the time has passed today, by less than
+/// the catch-up window, and it has not run today (`last_run` is a date)
+pub fn is_due(time: &str, last_run: &str, now: NaiveDateTime) -> bool {
+    let Some(time) = parse_time(time) else {
+        return false;
+    };
+    let today = now.date();
+    let since = now - today.and_time(time);
+    let ran_today = NaiveDate::parse_from_str(last_run, DATE_FORMAT).is_ok_and(|date| date == today);
+    !ran_today && since >= chrono::Duration::zero() && since < chrono::Duration::minutes(CATCH_UP_MINUTES)
+}
+
+/// The next time the run is due after `now` (today's if still ahead, else tomorrow's)
+pub fn next_due(time: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let today = now.date().and_time(parse_time(time)?);
+    Some(if today > now { today } else { today + chrono::Duration::days(1) })
+}
+
+/// "CLAUDE API ERROR, GATEWAY WARN" for what is wrong; None when nothing is
+pub fn problems(report: &DiagnosticReport) -> Option<String> {
+    let wrong: Vec<String> = report
+        .checks()
+        .into_iter()
+        .filter(|(_, check)| matches!(check.status, CheckStatus::Warning | CheckStatus::Error))
+        .map(|(_, check)| format!("{} {}", check.name, check.status.label()))
+        .collect();
+    (!wrong.is_empty()).then(|| wrong.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("2026-03-02 {}", time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn due_once_from_its_time_until_the_window_closes() {
+        assert!(!is_due("08:55", "", at("08:54")));
+        assert!(is_due("08:55", "", at("08:56")));
+        assert!(!is_due("08:55", "2026-03-02", at("08:56")));
+        assert!(!is_due("08:55", "2026-03-01", at("11:00")));
+    }
+
+    #[test]
+    fn next_run_is_tomorrow_once_today_passed() {
+        assert_eq!(next_due("08:55", at("08:56")).map(|t| t.to_string()).as_deref(), Some("2026-03-03 08:55:00"));
+    }
+}
@@ -0,0 +1,116 @@
+//! Ping relay comparison
+//!
+//! A relay is a small HTTP service that runs the same provider checks from
+//! its own network and returns the results. Comparing them with the local
+//! results tells "provider down" apart from "provider reachable from the
+//! internet but not from this machine". No relay is configured by default;
+//! the protocol is described in the README so anyone can host one.
+
+use crate::diagnostics::{CheckResult, CheckStatus, HttpProfile};
+#[cfg(feature = "net-checks")]
+use crate::diagnostics::traffic;
+#[cfg(feature = "net-checks")]
+use serde::Deserialize;
+
+/// Providers asked for, as (check id, short label)
+#[cfg_attr(not(feature = "net-checks"), allow(dead_code))]
+/// Marks a provider failing from both ends (read by the capacity analyzer)
+pub const OUTAGE_NOTE: &str = "provider-side outage";
+
+const PROVIDERS: &[(&str, &str)] = &[
+    (crate::diagnostics::ids::CLAUDE, "claude"),
+    (crate::diagnostics::ids::OPENAI, "openai"),
+    (crate::diagnostics::ids::GOOGLE, "google"),
+];
+
+/// `GET <relay>/check?ids=...` response
+#[cfg(feature = "net-checks")]
+#[derive(Deserialize)]
+struct RelayResponse {
+    /// Where the relay runs, e.g. "eu-west"
+    #[serde(default)]
+    region: Option<String>,
+    results: Vec<RelayResult>,
+}
+
+#[cfg(feature = "net-checks")]
+#[derive(Deserialize)]
+struct RelayResult {
+    id: String,
+    status: CheckStatus,
+    #[serde(default)]
+    latency_ms: Option<u64>,
+}
+
+/// Ask the relay for its provider results and compare them with `local`
+#[cfg(feature = "net-checks")]
+pub fn check_relay(http: &HttpProfile, relay_url: &str, local: &[(&str, &Option<CheckResult>)]) -> CheckResult {
+    let relay_url = relay_url.trim().trim_end_matches('/');
+    if relay_url.is_empty() {
+        return CheckResult::new("RELAY", CheckStatus::Inactive, "No relay URL configured");
+    }
+    let client = match http.client(std::time::Duration::from_secs(15)) {
+        Ok(c) => c,
+        Err(e) => return CheckResult::new("RELAY", CheckStatus::Error, "Failed to create HTTP client").with_message(&e),
+    };
+    let ids: Vec<&str> = PROVIDERS.iter().map(|(id, _)| *id).collect();
+    let response = client.get(format!("{}/check", relay_url)).query(&[("ids", ids.join(","))]).send();
+    traffic::record_http(relay_url, &response);
+    let response = response
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json::<RelayResponse>());
+    let response = match response {
+        Ok(response) => response,
+        Err(e) if e.is_decode() => {
+            return CheckResult::new("RELAY", CheckStatus::Unknown, "Relay sent an unexpected response");
+        }
+        Err(e) => {
+            let reason = if e.is_timeout() { "timeout".to_string() } else { e.without_url().to_string() };
+            return CheckResult::new("RELAY", CheckStatus::Unknown, &format!("Relay unreachable :: {}", reason));
+        }
+    };
+
+    let mut parts = vec![format!("from {}", response.region.as_deref().unwrap_or("relay"))];
+    let mut blocked_here = Vec::new();
+    let mut down_everywhere = Vec::new();
+    for (id, label) in PROVIDERS {
+        let Some(remote) = response.results.iter().find(|r| r.id == *id) else {
+            continue;
+        };
+        let remote_cell = match remote.latency_ms {
+            Some(ms) => format!("{} {}ms", remote.status.label(), ms),
+            None => remote.status.label().to_string(),
+        };
+        let here = local.iter().find(|(i, _)| i == id).and_then(|(_, c)| c.as_ref());
+        match here {
+            Some(here) => {
+                parts.push(format!("{} here {} / relay {}", label, here.status.label(), remote_cell));
+                let remote_up = matches!(remote.status, CheckStatus::Ok | CheckStatus::Warning);
+                match (here.status, remote_up) {
+                    (CheckStatus::Error, true) => blocked_here.push(*label),
+                    (CheckStatus::Error, false) => down_everywhere.push(*label),
+                    _ => {}
+                }
+            }
+            None => parts.push(format!("{} relay {}", label, remote_cell)),
+        }
+    }
+    let details = parts.join(" :: ");
+
+    if !blocked_here.is_empty() {
+        CheckResult::new("RELAY", CheckStatus::Warning, &details).with_message(&format!(
+            "{} reachable from the internet but not from your machine. Check proxy, VPN and firewall.",
+            blocked_here.join(", ")
+        ))
+    } else if !down_everywhere.is_empty() {
+        CheckResult::new("RELAY", CheckStatus::Ok, &details)
+            .with_message(&format!("{} also failing from the relay: {}", down_everywhere.join(", "), OUTAGE_NOTE))
+    } else {
+        CheckResult::new("RELAY", CheckStatus::Ok, &details)
+    }
+}
+
+#[cfg(not(feature = "net-checks"))]
+pub fn check_relay(_http: &HttpProfile, _relay_url: &str, _local: &[(&str, &Option<CheckResult>)]) -> CheckResult {
+    CheckResult::new("RELAY", CheckStatus::Inactive, "Network checks not included in this build")
+}
@@ -0,0 +1,154 @@
+//! Report files attached to issues
+//!
+//! Maintainers get reports as the `.txt` COPY REPORT produces or the `.json`
+//! of a support bundle or `/api/report`. Dropped on the window, either opens
+//! read-only like a shared session. A text report is read back check by
+//! check; the WHY findings are not, the diagnosis line is.
+
+use crate::diagnostics::signing;
+use crate::diagnostics::tags::RunTags;
+use crate::diagnostics::{ids, CheckResult, CheckStatus, DiagnosticReport};
+use std::path::Path;
+
+/// Extensions opened as report files
+pub const EXTENSIONS: &[&str] = &["json", "txt"];
+
+/// A report read from a file, and the SYSTEM line of a text report
+pub struct ReportFile {
+    pub report: DiagnosticReport,
+    pub system: Option<String>,
+}
+
+/// Whether `path` looks like a report file by its extension
+pub fn is_report_file(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()).is_some_and(|e| EXTENSIONS.iter().any(|x| e.eq_ignore_ascii_case(x)))
+}
+
+/// Read a `.json` or `.txt` report
+pub fn load(path: &Path) -> Result<ReportFile, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let is_json = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json"));
+    if is_json {
+        from_json(&text)
+    } else {
+        from_text(&text)
+    }
+}
+
+pub fn from_json(text: &str) -> Result<ReportFile, String> {
+    let report: DiagnosticReport = serde_json::from_str(text).map_err(|e| format!("Not a report: {}", e))?;
+    if report.timestamp.is_none() && report.checks().is_empty() {
+        return Err("Not a report: no time and no checks".to_string());
+    }
+    Ok(ReportFile { report, system: None })
+}
+
+/// "[OK] CLAUDE API" -> (status, "CLAUDE API")
+fn check_header(line: &str) -> Option<(CheckStatus, &str)> {
+    let (icon, name) = line.split_once(' ')?;
+    let status = match icon {
+        "[OK]" => CheckStatus::Ok,
+        "[!!]" => CheckStatus::Warning,
+        "[XX]" => CheckStatus::Error,
+        "[??]" => CheckStatus::Unknown,
+        "[--]" => CheckStatus::Inactive,
+        _ => return None,
+    };
+    Some((status, name.trim()))
+}
+
+/// Id of the check shown as `name`
+fn id_for_name(name: &str) -> Option<&'static str> {
+    ids::ALL.iter().copied().find(|id| ids::name(id) == name)
+}
+
+/// Parse the text of COPY REPORT (the format of `to_text_report`)
+pub fn from_text(text: &str) -> Result<ReportFile, String> {
+    // Nothing after a signature block is report
+    let text = text.split(signing::BEGIN).next().unwrap_or_default();
+    let mut lines = text.lines().map(|l| l.trim_end_matches('\r'));
+    if !lines.any(|l| l.trim() == "=== OpenCode Diagnostics Report ===") {
+        return Err("Not an OpenCode Diagnostics report".to_string());
+    }
+
+    let mut report = DiagnosticReport::new();
+    let mut system = None;
+    let mut latencies = Vec::new();
+    // Check being read: its id and result
+    let mut current: Option<(&'static str, CheckResult)> = None;
+    let finish = |report: &mut DiagnosticReport, current: &mut Option<(&'static str, CheckResult)>| {
+        if let Some((id, check)) = current.take() {
+            if let Some(slot) = report.slot_mut(id) {
+                *slot = Some(check);
+            }
+        }
+    };
+    for line in lines {
+        if let Some(detail) = line.strip_prefix("     ") {
+            if let Some((_, ref mut check)) = current {
+                match detail.strip_prefix("Message: \"").and_then(|m| m.strip_suffix('"')) {
+                    Some(message) => check.message = Some(message.to_string()),
+                    None if check.details.is_empty() => check.details = detail.to_string(),
+                    None => {
+                        check.details.push('\n');
+                        check.details.push_str(detail);
+                    }
+                }
+            }
+            continue;
+        }
+        finish(&mut report, &mut current);
+        if let Some((status, name)) = check_header(line) {
+            current = id_for_name(name).map(|id| (id, CheckResult::new(name, status, "")));
+        } else if let Some(time) = line.strip_prefix("Time: ") {
+            report.timestamp = Some(time.trim().to_string());
+        } else if let Some(trigger) = line.strip_prefix("Trigger: ") {
+            report.trigger = Some(trigger.trim().to_string());
+        } else if let Some(tags) = line.strip_prefix("Tags: ") {
+            report.tags = parse_tags(tags);
+        } else if line.starts_with("SYSTEM: ") {
+            system = Some(line.trim().to_string());
+        } else if let Some(list) = line.strip_prefix("LATENCY: ") {
+            latencies.extend(list.split(" :: ").filter_map(|item| {
+                let (name, ms) = item.trim().rsplit_once(' ')?;
+                Some((id_for_name(name)?, ms.strip_suffix("ms")?.parse::<u64>().ok()?))
+            }));
+        } else if let Some(diagnosis) = line.strip_prefix("DIAGNOSIS: ") {
+            report.diagnosis = Some(diagnosis.trim().to_string());
+        }
+    }
+    finish(&mut report, &mut current);
+
+    for (id, ms) in latencies {
+        if let Some(Some(check)) = report.slot_mut(id) {
+            check.latency_ms = Some(ms);
+        }
+    }
+    if report.checks().is_empty() {
+        return Err("No checks found in the report".to_string());
+    }
+    Ok(ReportFile { report, system })
+}
+
+/// "project=api model=x network=Home Wifi" (a value may contain spaces)
+fn parse_tags(text: &str) -> RunTags {
+    const KEYS: [&str; 3] = ["project", "model", "network"];
+    let mut values: [String; 3] = Default::default();
+    let mut current = None;
+    for word in text.split(' ') {
+        let started = word.split_once('=').and_then(|(key, start)| Some((KEYS.iter().position(|k| *k == key)?, start)));
+        match (started, current) {
+            (Some((index, start)), _) => {
+                values[index] = start.to_string();
+                current = Some(index);
+            }
+            (None, Some(index)) => {
+                values[index].push(' ');
+                values[index].push_str(word);
+            }
+            (None, None) => {}
+        }
+    }
+    let [project, model, network] = values;
+    RunTags { project, model, network }
+}
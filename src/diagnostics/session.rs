@@ -0,0 +1,125 @@
+//! Session lock / suspend detection
+//!
+//! Auto-refresh pauses while the session is locked, so a closed laptop lid
+//! does not fill the error log with failures. On unlock or wake the watcher
+//! reports `Resumed` and the app runs immediately.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// How often the lock state is polled
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A wall-clock gap this much longer than the poll interval means the machine slept
+const SUSPEND_GAP: Duration = Duration::from_secs(30);
+
+/// Lock state change seen by `SessionWatcher::poll`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SessionEvent {
+    Locked,
+    /// Unlocked or woke from sleep
+    Resumed,
+}
+
+/// Lock state published by a background `SessionWatcher` for the UI
+#[derive(Clone, Default)]
+pub struct SessionState {
+    locked: Arc<AtomicBool>,
+    resumed: Arc<AtomicBool>,
+}
+
+impl SessionState {
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Relaxed)
+    }
+
+    /// Whether the session resumed since the last call
+    pub fn take_resumed(&self) -> bool {
+        self.resumed.swap(false, Ordering::Relaxed)
+    }
+
+    fn apply(&self, event: SessionEvent) {
+        match event {
+            SessionEvent::Locked => self.locked.store(true, Ordering::Relaxed),
+            SessionEvent::Resumed => {
+                self.locked.store(false, Ordering::Relaxed);
+                self.resumed.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Tracks lock state and sleep gaps between polls
+pub struct SessionWatcher {
+    locked: bool,
+    last_wall: SystemTime,
+}
+
+impl SessionWatcher {
+    pub fn new() -> Self {
+        Self {
+            locked: false,
+            last_wall: SystemTime::now(),
+        }
+    }
+
+    /// Check for lock/unlock and wake-from-sleep; call every `POLL_INTERVAL`
+    pub fn poll(&mut self) -> Option<SessionEvent> {
+        let now = SystemTime::now();
+        let gap = now.duration_since(self.last_wall).unwrap_or_default();
+        self.last_wall = now;
+        let woke = gap > POLL_INTERVAL + SUSPEND_GAP;
+
+        let was_locked = self.locked;
+        self.locked = is_session_locked();
+        match (was_locked, self.locked) {
+            (false, true) => Some(SessionEvent::Locked),
+            (true, false) => Some(SessionEvent::Resumed),
+            (false, false) if woke => Some(SessionEvent::Resumed),
+            _ => None,
+        }
+    }
+
+    /// Poll and publish changes to `state`, returns true when something changed
+    pub fn poll_into(&mut self, state: &SessionState) -> bool {
+        match self.poll() {
+            Some(event) => {
+                state.apply(event);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// The secure desktop (lock screen) refuses to be switched to from a user process
+#[cfg(target_os = "windows")]
+pub fn is_session_locked() -> bool {
+    use winapi::um::winuser::{CloseDesktop, OpenInputDesktop, SwitchDesktop, DESKTOP_SWITCHDESKTOP};
+    unsafe {
+        let desktop = OpenInputDesktop(0, 0, DESKTOP_SWITCHDESKTOP);
+        if desktop.is_null() {
+            return true;
+        }
+        let switched = SwitchDesktop(desktop) != 0;
+        CloseDesktop(desktop);
+        !switched
+    }
+}
+
+/// systemd-logind LockedHint of the current session
+#[cfg(target_os = "linux")]
+pub fn is_session_locked() -> bool {
+    let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "auto".to_string());
+    crate::diagnostics::command("loginctl")
+        .args(["show-session", &session, "-p", "LockedHint"])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim() == "LockedHint=yes")
+        .unwrap_or(false)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn is_session_locked() -> bool {
+    false
+}
@@ -1,7 +1,14 @@
 //! Diagnostic settings with serialization support
 
+use crate::diagnostics::{ids, policy, readiness};
+use crate::diagnostics::palette::StatusColors;
+use crate::diagnostics::profiles::{NetworkProfile, ViewRole};
+use crate::diagnostics::tags::RunTags;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use crate::diagnostics::history::TIMESTAMP_FORMAT;
+use chrono::NaiveDateTime;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 /// Preset intervals for auto-refresh (in seconds)
 pub const REFRESH_PRESETS: &[(u32, &str)] = &[
@@ -19,86 +26,294 @@ pub const SCALE_PRESETS: &[(f32, &str)] = &[
     (2.0, "200%"),
 ];
 
+/// Preset pre-warning windows for endpoint certificate expiry (in days)
+pub const CERT_WARN_PRESETS: &[(u32, &str)] = &[
+    (7, "7D"),
+    (14, "14D"),
+    (30, "30D"),
+];
+
+/// Preset freshness windows for skipping recent results (in seconds)
+pub const FRESH_TTL_PRESETS: &[(u32, &str)] = &[
+    (30, "30s"),
+    (60, "1m"),
+    (300, "5m"),
+    (900, "15m"),
+];
+
+/// Preset intervals for the hardware checks' own worker (in seconds)
+pub const HARDWARE_INTERVAL_PRESETS: &[(u32, &str)] = &[
+    (30, "30s"),
+    (60, "1m"),
+    (300, "5m"),
+    (900, "15m"),
+];
+
+/// Preset limits for network checks running at once
+pub const CONCURRENCY_PRESETS: &[(u8, &str)] = &[
+    (1, "1"),
+    (2, "2"),
+    (4, "4"),
+    (8, "8"),
+];
+
+/// Default port for the local status page
+pub const DEFAULT_STATUS_PORT: u16 = 7878;
+
 /// Settings for which checks to perform
 #[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DiagnosticSettings {
     // System
     pub check_cpu_ram: bool,
     pub check_gpu: bool,
+    // CPU/RAM and GPU re-run on their own worker at most this often (runs in between reuse their results)
+    pub hardware_interval_secs: u32,
+    pub check_long_paths: bool,
+    pub check_defender: bool,
+    pub check_data_dirs: bool,
+    pub check_cloud_sync: bool,
     
     // Network
     pub check_internet: bool,
+    pub check_firewall: bool,
+    pub check_quic: bool,
+    pub check_bandwidth: bool,
+    pub check_root_store: bool,
     
     // APIs
     pub check_claude: bool,
     pub check_openai: bool,
     pub check_google_ai: bool,
+    // Ping relay to compare provider results with (empty = none)
+    pub check_relay: bool,
+    pub relay_url: String,
+    
+    // Custom / self-hosted endpoints (proxies, gateways)
+    pub custom_endpoints: Vec<String>,
+    pub check_endpoint_certs: bool,
+    pub cert_warn_days: u32,
+    
+    // Outbound requests (empty user agent = opencode-diag/<version>)
+    pub user_agent: String,
+    pub extra_headers: Vec<String>,
+    pub opencode_mode: bool,
+    // Network checks running at once (1 = one after another)
+    pub network_concurrency: u8,
     
     // Processes
     pub check_opencode: bool,
+    pub check_crashes: bool,
+    pub check_leaks: bool,
+    pub check_ipc: bool,
+    pub check_editors: bool,
     pub check_terminals: bool,
+    // `opencode run "say hi"` end to end (spends tokens)
+    pub check_smoke: bool,
+    // RESTART OPENCODE launch command (empty = platform default)
+    pub restart_command: String,
+    // OUTPUT > LAUNCH command, run with its output piped to the panel (empty = default)
+    pub tail_command: String,
     
     // Auto-refresh
     pub auto_refresh: bool,
     pub refresh_interval_secs: u32,
+    // Run as soon as the network changes (adapter, default route, Wi-Fi)
+    pub run_on_network_change: bool,
+    // Daily run at a set time ("08:55"), notifying only when something is wrong; the day it last ran
+    pub readiness_enabled: bool,
+    pub readiness_time: String,
+    pub readiness_last: String,
+    
+    // RUN DIAGNOSTICS reuses results younger than the TTL
+    pub skip_fresh: bool,
+    pub fresh_ttl_secs: u32,
+    
+    // Runs in a row a new status must be measured before it is shown, per check id (missing = 1)
+    pub hysteresis: BTreeMap<String, u8>,
+    // Checks muted from their card until a time ("%Y-%m-%d %H:%M:%S"), per check id
+    pub snoozed: BTreeMap<String, String>,
+    
+    // Battery saver (skip heavy checks, longer interval on low battery)
+    pub battery_saver: bool,
+    pub battery_threshold: u8,
+    
+    // Monthly traffic cap in MB for the checks' own requests (0 = no cap)
+    pub monthly_cap_mb: u32,
     
     // UI Scale
     pub ui_scale: f32,
     
     // History (unused now, kept for compatibility)
     pub max_history_entries: usize,
+    // Open the weekly digest on launch; when it last opened by itself ("" = never)
+    pub weekly_digest: bool,
+    pub digest_shown: String,
+    // Run tags set by hand; an empty one is detected on each run
+    pub tags: RunTags,
+    
+    // Status page (local web server)
+    pub status_page_enabled: bool,
+    pub status_page_port: u16,
+    pub status_page_lan: bool,
+    pub api_token: String,
+    pub discover_agents: bool,
+    // History upload to the user's own endpoint (Authorization header value optional)
+    pub upload_enabled: bool,
+    pub upload_url: String,
+    pub upload_auth: String,
+    pub upload_interval_mins: u32,
+    // Append an Ed25519 signature to copied and printed reports
+    pub sign_reports: bool,
+    // OK / WARNING / ERROR colors remapped by the user (empty = theme's)
+    pub status_colors: StatusColors,
+    
+    // View role (developer / support presets)
+    pub view_role: ViewRole,
+    
+    // Check toggles per network, the profile in effect and the toggles it replaced
+    pub network_profiles: Vec<NetworkProfile>,
+    pub active_network_profile: String,
+    pub network_profile_restore: BTreeMap<String, bool>,
 }
 
 impl Default for DiagnosticSettings {
     fn default() -> Self {
         Self {
-            // System - CPU/RAM enabled, GPU disabled (experimental)
+            // System
             check_cpu_ram: true,
-            check_gpu: false,  // Disabled by default - WMI issues on some systems
+            check_gpu: true,
+            hardware_interval_secs: 60,
+            check_long_paths: true,
+            check_defender: false,  // Spawns PowerShell and writes probe files; opt-in
+            check_data_dirs: true,
+            check_cloud_sync: true,
             
             // Network - enabled by default
             check_internet: true,
+            check_firewall: false,  // Opens a local listener; opt-in
+            check_quic: false,
+            check_bandwidth: false,  // Samples for a second; opt-in
+            check_root_store: false,  // Spawns PowerShell; opt-in
             
             // APIs - only Claude by default
             check_claude: true,
             check_openai: false,
             check_google_ai: false,
+            check_relay: false,
+            relay_url: String::new(),
+            
+            // Custom endpoints - none configured, warn 14 days ahead
+            custom_endpoints: Vec::new(),
+            check_endpoint_certs: false,
+            cert_warn_days: 14,
+            
+            // Outbound requests - default user agent, no extra headers
+            user_agent: String::new(),
+            extra_headers: Vec::new(),
+            opencode_mode: false,
+            // Up to 4 network checks at once
+            network_concurrency: 4,
             
             // Processes - opencode by default
             check_opencode: true,
+            check_crashes: true,
+            check_leaks: true,
+            check_ipc: false,
+            check_editors: false,
             check_terminals: false,
+            check_smoke: false,  // A real session with the model; opt-in
+            restart_command: String::new(),
+            tail_command: String::new(),
             
             // Auto-refresh - disabled by default, 60s interval
             auto_refresh: false,
             refresh_interval_secs: 60,
+            run_on_network_change: true,
+            readiness_enabled: false,
+            readiness_time: readiness::DEFAULT_TIME.to_string(),
+            readiness_last: String::new(),
+            
+            // Skip fresh - off, 1m
+            skip_fresh: false,
+            fresh_ttl_secs: 60,
+            
+            // Hysteresis - 3 runs for the load-based checks, none for the rest
+            hysteresis: [(ids::RESOURCES, 3), (ids::GPU, 3)].into_iter().map(|(id, runs)| (id.to_string(), runs)).collect(),
+            snoozed: BTreeMap::new(),
+            
+            // Battery saver - on, below 30%
+            battery_saver: true,
+            battery_threshold: 30,
+            
+            // Traffic cap - off
+            monthly_cap_mb: 0,
             
             // UI Scale - 100%
             ui_scale: 1.0,
             
             // History - keep last 10 reports
             max_history_entries: 10,
+            weekly_digest: true,
+            digest_shown: String::new(),
+            tags: RunTags::default(),
+            
+            // Status page - off by default, localhost only
+            status_page_enabled: false,
+            status_page_port: DEFAULT_STATUS_PORT,
+            status_page_lan: false,
+            api_token: String::new(),
+            discover_agents: false,
+            upload_enabled: false,
+            upload_url: String::new(),
+            upload_auth: String::new(),
+            upload_interval_mins: 60,
+            sign_reports: false,
+            status_colors: StatusColors::default(),
+            
+            // View role - user-picked checks
+            view_role: ViewRole::Custom,
+            
+            // Network profiles - none
+            network_profiles: Vec::new(),
+            active_network_profile: String::new(),
+            network_profile_restore: BTreeMap::new(),
         }
     }
 }
 
 impl DiagnosticSettings {
-    /// Get the settings file path
+    /// Get the settings file path (the `--config` file when given)
     fn settings_path() -> Option<PathBuf> {
-        dirs::config_dir().map(|p| p.join("opencode-diag").join("settings.json"))
+        crate::cli::args()
+            .config
+            .clone()
+            .or_else(|| dirs::config_dir().map(|p| p.join("opencode-diag").join("settings.json")))
     }
 
-    /// Load settings from file or return defaults
-    pub fn load() -> Self {
-        if let Some(path) = Self::settings_path() {
-            if path.exists() {
-                if let Ok(contents) = std::fs::read_to_string(&path) {
-                    if let Ok(settings) = serde_json::from_str(&contents) {
-                        return settings;
-                    }
-                }
-            }
+    /// Settings in `path`, `None` when the file does not exist
+    pub fn read(path: &Path) -> Result<Option<Self>, String> {
+        if !path.exists() {
+            return Ok(None);
         }
-        Self::default()
+        let contents = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|e| format!("{} is not a valid settings file: {}", path.display(), e))
+    }
+
+    /// Load settings from file or return defaults, with the managed policy applied
+    pub fn load() -> Self {
+        let mut settings: Self = Self::settings_path()
+            .and_then(|path| Self::read(&path).ok().flatten())
+            .unwrap_or_default();
+        settings.enforce_policy();
+        settings
+    }
+
+    /// Put back settings locked by the managed policy; true when any had changed
+    pub fn enforce_policy(&mut self) -> bool {
+        policy::current().is_some_and(|policy| policy.enforce(self))
     }
 
     /// Save settings to file
@@ -120,17 +335,189 @@ impl DiagnosticSettings {
         Ok(())
     }
 
+    /// Make sure a REST API token exists, returns true if one was generated
+    pub fn ensure_api_token(&mut self) -> bool {
+        if self.api_token.is_empty() {
+            self.api_token = generate_token();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Mutable flag for a check id
+    fn check_flag_mut(&mut self, id: &str) -> Option<&mut bool> {
+        match id {
+            ids::RESOURCES => Some(&mut self.check_cpu_ram),
+            ids::GPU => Some(&mut self.check_gpu),
+            ids::LONG_PATHS => Some(&mut self.check_long_paths),
+            ids::DEFENDER => Some(&mut self.check_defender),
+            ids::DATA_DIRS => Some(&mut self.check_data_dirs),
+            ids::CLOUD_SYNC => Some(&mut self.check_cloud_sync),
+            ids::GATEWAY | ids::INTERNET => Some(&mut self.check_internet),
+            ids::FIREWALL => Some(&mut self.check_firewall),
+            ids::QUIC => Some(&mut self.check_quic),
+            ids::BANDWIDTH => Some(&mut self.check_bandwidth),
+            ids::ROOT_STORE => Some(&mut self.check_root_store),
+            ids::CLAUDE => Some(&mut self.check_claude),
+            ids::OPENAI => Some(&mut self.check_openai),
+            ids::GOOGLE => Some(&mut self.check_google_ai),
+            ids::RELAY => Some(&mut self.check_relay),
+            ids::ENDPOINT_CERTS => Some(&mut self.check_endpoint_certs),
+            ids::OPENCODE => Some(&mut self.check_opencode),
+            ids::CRASHES => Some(&mut self.check_crashes),
+            ids::LEAKS => Some(&mut self.check_leaks),
+            ids::IPC => Some(&mut self.check_ipc),
+            ids::EDITORS => Some(&mut self.check_editors),
+            ids::TERMINALS => Some(&mut self.check_terminals),
+            ids::SMOKE => Some(&mut self.check_smoke),
+            _ => None,
+        }
+    }
+
+    /// Runs in a row a new status of `id` must be measured before it is shown
+    pub fn hysteresis_for(&self, id: &str) -> u8 {
+        self.hysteresis.get(id).copied().unwrap_or(1).max(1)
+    }
+
+    /// Set the hysteresis of `id` (1 removes it)
+    pub fn set_hysteresis(&mut self, id: &str, runs: u8) {
+        if runs <= 1 {
+            self.hysteresis.remove(id);
+        } else {
+            self.hysteresis.insert(id.to_string(), runs);
+        }
+    }
+
+    /// Whether a check id runs with these settings (the router runs with the internet check)
+    pub fn is_check_enabled(&self, id: &str) -> bool {
+        match id {
+            ids::RESOURCES => self.check_cpu_ram,
+            ids::GPU => self.check_gpu,
+            ids::LONG_PATHS => self.check_long_paths,
+            ids::DEFENDER => self.check_defender,
+            ids::DATA_DIRS => self.check_data_dirs,
+            ids::CLOUD_SYNC => self.check_cloud_sync,
+            ids::GATEWAY | ids::INTERNET => self.check_internet,
+            ids::FIREWALL => self.check_firewall,
+            ids::QUIC => self.check_quic,
+            ids::BANDWIDTH => self.check_bandwidth,
+            ids::ROOT_STORE => self.check_root_store,
+            ids::CLAUDE => self.check_claude,
+            ids::OPENAI => self.check_openai,
+            ids::GOOGLE => self.check_google_ai,
+            ids::RELAY => self.check_relay,
+            ids::ENDPOINT_CERTS => self.check_endpoint_certs,
+            ids::OPENCODE => self.check_opencode,
+            ids::CRASHES => self.check_crashes,
+            ids::LEAKS => self.check_leaks,
+            ids::IPC => self.check_ipc,
+            ids::EDITORS => self.check_editors,
+            ids::TERMINALS => self.check_terminals,
+            ids::SMOKE => self.check_smoke,
+            _ => false,
+        }
+    }
+
+    /// Enable or disable a check by id
+    pub fn set_check_enabled(&mut self, id: &str, enabled: bool) {
+        if let Some(flag) = self.check_flag_mut(id) {
+            *flag = enabled;
+        }
+    }
+
+    /// Whether the managed policy keeps check `id` turned on
+    pub fn check_locked_on(&self, id: &str) -> bool {
+        let mut probe = self.clone();
+        probe.set_check_enabled(id, false);
+        probe.enforce_policy()
+    }
+
+    /// When the snooze of `id` ends, if it is snoozed at `now`
+    pub fn snoozed_until(&self, id: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        // The router and internet cards share one check
+        let id = if id == ids::GATEWAY { ids::INTERNET } else { id };
+        let until = NaiveDateTime::parse_from_str(self.snoozed.get(id)?, TIMESTAMP_FORMAT).ok()?;
+        (until > now).then_some(until)
+    }
+
+    /// Skip `id` for `minutes`, dropping snoozes that have ended
+    pub fn snooze(&mut self, id: &str, minutes: i64, now: NaiveDateTime) {
+        let id = if id == ids::GATEWAY { ids::INTERNET } else { id };
+        self.snoozed.retain(|_, until| NaiveDateTime::parse_from_str(until, TIMESTAMP_FORMAT).is_ok_and(|until| until > now));
+        self.snoozed.insert(id.to_string(), (now + chrono::Duration::minutes(minutes)).format(TIMESTAMP_FORMAT).to_string());
+    }
+
+    pub fn unsnooze(&mut self, id: &str) {
+        let id = if id == ids::GATEWAY { ids::INTERNET } else { id };
+        self.snoozed.remove(id);
+    }
+
+    /// Switch role, enabling exactly the checks its profile lists
+    pub fn apply_role(&mut self, role: ViewRole) {
+        self.view_role = role;
+        if let Some(profile) = role.profile() {
+            for id in ids::ALL {
+                self.set_check_enabled(id, profile.checks.contains(id));
+            }
+        }
+    }
+
+    /// Apply the first network profile matching `network` (none matching: the
+    /// user's own toggles); true when the profile in effect changed
+    pub fn apply_network(&mut self, network: &str) -> bool {
+        let profile = self.network_profiles.iter().find(|p| p.matches(network)).cloned();
+        let label = profile.as_ref().map(|p| p.label().to_string()).unwrap_or_default();
+        if label == self.active_network_profile {
+            return false;
+        }
+        self.leave_network_profile();
+        if let Some(profile) = profile {
+            for id in ids::ALL {
+                if let Some(enabled) = profile.toggle(id) {
+                    self.network_profile_restore.insert(id.to_string(), self.is_check_enabled(id));
+                    self.set_check_enabled(id, enabled);
+                }
+            }
+            self.active_network_profile = label;
+        }
+        true
+    }
+
+    /// Undo the network profile in effect (the next `apply_network` re-applies it)
+    pub fn leave_network_profile(&mut self) {
+        for (id, enabled) in std::mem::take(&mut self.network_profile_restore) {
+            self.set_check_enabled(&id, enabled);
+        }
+        self.active_network_profile.clear();
+    }
+
     /// Count how many checks are enabled
     pub fn enabled_count(&self) -> usize {
         let mut count = 0;
         if self.check_cpu_ram { count += 1; }
         if self.check_gpu { count += 1; }
+        if self.check_long_paths { count += 1; }
+        if self.check_defender { count += 1; }
+        if self.check_data_dirs { count += 1; }
+        if self.check_cloud_sync { count += 1; }
         if self.check_internet { count += 1; }
+        if self.check_firewall { count += 1; }
+        if self.check_quic { count += 1; }
+        if self.check_bandwidth { count += 1; }
+        if self.check_root_store { count += 1; }
         if self.check_claude { count += 1; }
         if self.check_openai { count += 1; }
         if self.check_google_ai { count += 1; }
+        if self.check_relay { count += 1; }
+        if self.check_endpoint_certs { count += 1; }
         if self.check_opencode { count += 1; }
+        if self.check_crashes { count += 1; }
+        if self.check_leaks { count += 1; }
+        if self.check_ipc { count += 1; }
+        if self.check_editors { count += 1; }
         if self.check_terminals { count += 1; }
+        if self.check_smoke { count += 1; }
         count
     }
     
@@ -149,6 +536,7 @@ impl DiagnosticSettings {
     }
     
     /// Format the current interval for display
+    #[allow(dead_code)]
     pub fn format_interval(&self) -> String {
         if self.refresh_interval_secs >= 60 {
             format!("{}m", self.refresh_interval_secs / 60)
@@ -180,3 +568,76 @@ impl DiagnosticSettings {
         format!("{}%", (self.ui_scale * 100.0) as u32)
     }
 }
+
+/// Random 128-bit hex token (seeded from the OS via `RandomState`)
+fn generate_token() -> String {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let half = || {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(nanos);
+        hasher.finish()
+    };
+    format!("{:016x}{:016x}", half(), half())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_profile_applies_and_restores() {
+        let mut settings = DiagnosticSettings::default();
+        settings.set_check_enabled(ids::RELAY, false);
+        settings.set_check_enabled(ids::BANDWIDTH, true);
+        settings.network_profiles.push(NetworkProfile {
+            name: "Office".to_string(),
+            network: "Corp-Net".to_string(),
+            enable: vec![ids::RELAY.to_string()],
+            disable: vec![ids::BANDWIDTH.to_string()],
+        });
+
+        assert!(settings.apply_network("corp-net"));
+        assert!(settings.is_check_enabled(ids::RELAY));
+        assert!(!settings.is_check_enabled(ids::BANDWIDTH));
+        assert_eq!(settings.active_network_profile, "Office");
+
+        assert!(settings.apply_network("HomeWifi"));
+        assert!(!settings.is_check_enabled(ids::RELAY));
+        assert!(settings.is_check_enabled(ids::BANDWIDTH));
+        assert_eq!(settings.active_network_profile, "");
+    }
+
+    #[test]
+    fn snoozing_the_router_snoozes_the_internet_check() {
+        let now = NaiveDateTime::parse_from_str("2026-03-02 10:00:00", TIMESTAMP_FORMAT).unwrap();
+        let mut settings = DiagnosticSettings::default();
+        settings.snooze(ids::GATEWAY, 60, now);
+        let until = NaiveDateTime::parse_from_str("2026-03-02 11:00:00", TIMESTAMP_FORMAT).ok();
+        assert_eq!(settings.snoozed_until(ids::INTERNET, now), until);
+        assert_eq!(settings.snoozed_until(ids::INTERNET, now + chrono::Duration::minutes(61)), None);
+        settings.unsnooze(ids::GATEWAY);
+        assert_eq!(settings.snoozed_until(ids::GATEWAY, now), None);
+    }
+
+    #[test]
+    fn config_file_missing_partial_and_invalid() {
+        let path = std::env::temp_dir().join(format!("opencode-diag-test-config-{}.json", std::process::id()));
+        assert!(matches!(DiagnosticSettings::read(&path), Ok(None)));
+
+        std::fs::write(&path, r#"{"refresh_interval_secs": 5}"#).unwrap();
+        let partial = DiagnosticSettings::read(&path).unwrap().unwrap();
+        assert_eq!(partial.refresh_interval_secs, 5);
+        assert!(partial.check_internet);
+
+        std::fs::write(&path, "{refresh").unwrap();
+        let invalid = DiagnosticSettings::read(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(invalid.is_err());
+    }
+}
@@ -0,0 +1,165 @@
+//! Encrypted session export for remote assistance
+//!
+//! EXPORT SESSION writes the latest report, recent history and the error log
+//! to one `.ocdsession` file encrypted with a generated access code. Someone
+//! helping over chat opens the file in their own copy (drop it on the window
+//! or start with `--view-session FILE`), enters the code and browses the
+//! results read-only. The code is shown once and never written to disk.
+
+use crate::diagnostics::history::HistoryEntry;
+use crate::diagnostics::{system_summary, DiagnosticReport, ErrorLog, History};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const EXTENSION: &str = "ocdsession";
+
+/// History included in the export; older runs are left out
+const SHARE_HISTORY_HOURS: i64 = 48;
+
+/// File header: magic and format version, also authenticated with the payload
+const MAGIC: &[u8; 5] = b"OCDS\x01";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KDF_ITERATIONS: u32 = 100_000;
+
+/// Access code alphabet, without look-alikes (0/O, 1/I/L, U/V)
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTWXYZ23456789";
+const CODE_GROUPS: usize = 5;
+const CODE_GROUP_LEN: usize = 4;
+
+/// What the other side sees
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SharedSession {
+    pub app_version: String,
+    pub created: String,
+    pub system: String,
+    pub report: DiagnosticReport,
+    pub history: Vec<HistoryEntry>,
+    pub error_log: ErrorLog,
+}
+
+impl SharedSession {
+    pub fn capture(report: &DiagnosticReport, history: &History, error_log: &ErrorLog) -> Self {
+        Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            created: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            system: system_summary(),
+            report: report.clone(),
+            history: history.since_hours(SHARE_HISTORY_HOURS).cloned().collect(),
+            error_log: error_log.clone(),
+        }
+    }
+
+    /// History rebuilt for the heatmap and history views
+    pub fn history(&self) -> History {
+        History {
+            entries: self.history.iter().cloned().collect(),
+        }
+    }
+}
+
+fn random_bytes(buf: &mut [u8]) -> Result<(), String> {
+    getrandom::getrandom(buf).map_err(|e| format!("No secure random source: {}", e))
+}
+
+/// Random access code, e.g. "K7QM-2XPA-..."
+fn generate_code() -> Result<String, String> {
+    let mut code = String::new();
+    while code.len() < CODE_GROUPS * (CODE_GROUP_LEN + 1) - 1 {
+        let mut byte = [0u8; 1];
+        random_bytes(&mut byte)?;
+        // Reject the top of the range so every character is equally likely
+        let limit = 256 - 256 % CODE_ALPHABET.len();
+        if (byte[0] as usize) >= limit {
+            continue;
+        }
+        if code.len() % (CODE_GROUP_LEN + 1) == CODE_GROUP_LEN {
+            code.push('-');
+        }
+        code.push(CODE_ALPHABET[byte[0] as usize % CODE_ALPHABET.len()] as char);
+    }
+    Ok(code)
+}
+
+/// Upper case without separators, so "k7qm 2xpa" matches "K7QM-2XPA"
+fn normalize_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+fn cipher(code: &str, salt: &[u8]) -> ChaCha20Poly1305 {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(normalize_code(code).as_bytes(), salt, KDF_ITERATIONS, &mut key);
+    ChaCha20Poly1305::new(&key.into())
+}
+
+/// Encrypt `session` with a new access code, returns (file contents, code)
+fn seal(session: &SharedSession) -> Result<(Vec<u8>, String), String> {
+    let code = generate_code()?;
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    random_bytes(&mut salt)?;
+    random_bytes(&mut nonce)?;
+
+    let json = serde_json::to_vec(session).map_err(|e| format!("Failed to serialize session: {}", e))?;
+    let payload = cipher(&code, &salt)
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: &json, aad: MAGIC })
+        .map_err(|_| "Failed to encrypt session".to_string())?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + payload.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&payload);
+    Ok((out, code))
+}
+
+/// Decrypt file contents with the access code
+fn open(data: &[u8], code: &str) -> Result<SharedSession, String> {
+    let header = MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if data.len() < header || &data[..MAGIC.len()] != MAGIC {
+        return Err("Not an opencode-diag session file".to_string());
+    }
+    let salt = &data[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let nonce = Nonce::from_slice(&data[MAGIC.len() + SALT_LEN..header]);
+    let plain = cipher(code, salt)
+        .decrypt(nonce, Payload { msg: &data[header..], aad: MAGIC })
+        .map_err(|_| "Wrong access code or damaged file".to_string())?;
+    serde_json::from_slice(&plain).map_err(|e| format!("Unreadable session: {}", e))
+}
+
+/// Write the session to the Downloads folder, returns (path, access code)
+pub fn export_session(session: &SharedSession) -> Result<(PathBuf, String), String> {
+    let (data, code) = seal(session)?;
+    let dir = dirs::download_dir()
+        .or_else(dirs::desktop_dir)
+        .or_else(dirs::home_dir)
+        .ok_or("Could not determine Downloads folder")?;
+    let name = format!("opencode-diag-session-{}.{}", chrono::Local::now().format("%Y%m%d-%H%M%S"), EXTENSION);
+    let path = dir.join(name);
+    std::fs::write(&path, data).map_err(|e| format!("Failed to write session: {}", e))?;
+    Ok((path, code))
+}
+
+/// Read and decrypt a session file
+pub fn import_session(path: &Path, code: &str) -> Result<SharedSession, String> {
+    let data = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    open(&data, code)
+}
+
+/// Session file given with `--view-session FILE`
+pub fn path_from_args() -> Option<PathBuf> {
+    crate::cli::args().view_session.clone()
+}
+
+/// Encrypt and decrypt in memory; true when the right code opens it and a wrong one does not
+pub fn round_trip(session: &SharedSession) -> Result<bool, String> {
+    let (data, code) = seal(session)?;
+    let opened = open(&data, &code.to_lowercase().replace('-', " "))?;
+    let same = serde_json::to_string(&opened.report).ok() == serde_json::to_string(&session.report).ok();
+    Ok(same && open(&data, "WRONG-CODE").is_err())
+}
@@ -0,0 +1,147 @@
+//! Signed reports
+//!
+//! With signing on, a copied report ends in a signature block made with an
+//! Ed25519 key generated on this machine the first time it is needed. A
+//! team's triage bot that knows the public key (SETTINGS shows it) checks
+//! that a pasted report is unmodified and came from the tool, the same way
+//! `--verify FILE` does. The signature covers the report with trailing
+//! whitespace and line endings normalized, so chat clients do not break it.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// First line of the signature block
+pub const BEGIN: &str = "-----BEGIN OPENCODE-DIAG SIGNATURE-----";
+const END: &str = "-----END OPENCODE-DIAG SIGNATURE-----";
+
+/// Key file contents (the secret never leaves this machine)
+#[derive(Serialize, Deserialize)]
+struct KeyFile {
+    secret_key: String,
+}
+
+fn key_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("opencode-diag").join("signing_key.json"))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex<const N: usize>(text: &str) -> Option<[u8; N]> {
+    let text = text.trim();
+    if text.len() != N * 2 || !text.is_ascii() {
+        return None;
+    }
+    let mut bytes = [0u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+/// A new random key (not saved)
+pub fn generate_key() -> Result<SigningKey, String> {
+    let mut secret = [0u8; 32];
+    getrandom::getrandom(&mut secret).map_err(|e| format!("No secure random source: {}", e))?;
+    Ok(SigningKey::from_bytes(&secret))
+}
+
+/// This machine's key, generated and saved on first use
+fn signing_key() -> Result<SigningKey, String> {
+    let path = key_path().ok_or("Could not determine config directory")?;
+    if let Ok(contents) = std::fs::read_to_string(&path) {
+        let file: KeyFile = serde_json::from_str(&contents).map_err(|e| format!("Invalid signing key file: {}", e))?;
+        let secret = from_hex::<32>(&file.secret_key).ok_or("Invalid signing key")?;
+        return Ok(SigningKey::from_bytes(&secret));
+    }
+
+    let key = generate_key()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&KeyFile { secret_key: to_hex(key.as_bytes()) })
+        .map_err(|e| format!("Failed to serialize signing key: {}", e))?;
+    write_private(&path, &json).map_err(|e| format!("Failed to write signing key: {}", e))?;
+    Ok(key)
+}
+
+/// Readable by the owner only
+#[cfg(unix)]
+fn write_private(path: &std::path::Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut file = std::fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)?;
+    file.write_all(contents.as_bytes())
+}
+
+/// The config directory is per user already
+#[cfg(not(unix))]
+fn write_private(path: &std::path::Path, contents: &str) -> std::io::Result<()> {
+    std::fs::write(path, contents)
+}
+
+/// Public key to give to whoever verifies the reports (hex)
+pub fn public_key() -> Result<String, String> {
+    Ok(to_hex(signing_key()?.verifying_key().as_bytes()))
+}
+
+/// What is signed: lines without trailing whitespace, joined with "\n"
+fn normalize(text: &str) -> String {
+    text.lines().map(str::trim_end).collect::<Vec<_>>().join("\n").trim_matches('\n').to_string()
+}
+
+/// `text` followed by its signature block, signed with `key`
+pub fn sign_with(key: &SigningKey, text: &str) -> String {
+    let signature = key.sign(normalize(text).as_bytes());
+    format!(
+        "{}\n\n{}\nkey: {}\nsig: {}\n{}\n",
+        text.trim_end(),
+        BEGIN,
+        to_hex(key.verifying_key().as_bytes()),
+        to_hex(&signature.to_bytes()),
+        END
+    )
+}
+
+/// `text` followed by its signature block, signed with this machine's key
+pub fn sign(text: &str) -> Result<String, String> {
+    Ok(sign_with(&signing_key()?, text))
+}
+
+/// Check a signed report; the public key (hex) that signed it when valid
+pub fn verify(signed: &str) -> Result<String, String> {
+    let start = signed.rfind(BEGIN).ok_or("No signature block")?;
+    let (report, block) = signed.split_at(start);
+    let block = block.split(END).next().unwrap_or_default();
+    let field = |name: &str| {
+        block.lines().find_map(|line| line.trim().strip_prefix(name)?.strip_prefix(':').map(str::trim))
+    };
+    let key = field("key").and_then(from_hex::<32>).ok_or("Missing or invalid key")?;
+    let signature = field("sig").and_then(from_hex::<64>).ok_or("Missing or invalid signature")?;
+    let key = VerifyingKey::from_bytes(&key).map_err(|_| "Invalid key".to_string())?;
+    key.verify(normalize(report).as_bytes(), &Signature::from_bytes(&signature))
+        .map_err(|_| "Signature does not match: the report was modified or signed by another tool".to_string())?;
+    Ok(to_hex(key.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = "=== OpenCode Diagnostics Report ===\nCLAUDE API: OK  \n";
+
+    #[test]
+    fn survives_a_chat_client_rewrapping_lines() {
+        let signed = sign_with(&generate_key().unwrap(), REPORT);
+        let key = verify(&signed.replace('\n', "\r\n")).unwrap();
+        assert_eq!(key.len(), 64);
+    }
+
+    #[test]
+    fn edited_report_fails() {
+        let signed = sign_with(&generate_key().unwrap(), REPORT);
+        assert!(verify(&signed.replace("OK", "ERROR")).is_err());
+    }
+}
@@ -0,0 +1,371 @@
+//! Simulation mode with generated results
+//!
+//! `--simulate` replaces the real checks with mock results, for UI work and
+//! screenshots without a broken network at hand. With `--seed N` every run
+//! produces the same report, timestamps included, so a screenshot can be
+//! retaken identically.
+
+use crate::diagnostics::capacity::Evidence;
+use crate::diagnostics::{history, ids, CheckResult, CheckStatus, DiagnosticReport, DiagnosticSettings};
+
+/// Fixed time of seeded runs (2025-01-01 12:00 UTC)
+const SEEDED_TIME: i64 = 1_735_732_800;
+
+/// Mock data source, seeded or random per run
+#[derive(Clone, Copy)]
+pub struct Simulation {
+    pub seed: Option<u64>,
+}
+
+impl Simulation {
+    /// `--simulate [--seed N]`, `None` without `--simulate`
+    pub fn from_args() -> Option<Self> {
+        let args = crate::cli::args();
+        args.simulate.then_some(Self { seed: args.seed })
+    }
+
+    /// "SIMULATION :: SEED 42" / "SIMULATION"
+    pub fn label(&self) -> String {
+        match self.seed {
+            Some(seed) => format!("SIMULATION :: SEED {}", seed),
+            None => "SIMULATION".to_string(),
+        }
+    }
+
+    /// Mock report for the checks enabled in `settings`
+    pub fn report(&self, settings: &DiagnosticSettings) -> DiagnosticReport {
+        let (seed, time) = match self.seed {
+            Some(seed) => (seed, chrono::DateTime::from_timestamp(SEEDED_TIME, 0).unwrap_or_default()),
+            None => {
+                let now = chrono::Utc::now();
+                (now.timestamp_nanos_opt().unwrap_or_default() as u64, now)
+            }
+        };
+
+        let mut report = DiagnosticReport::new();
+        report.timestamp = Some(time.format(history::TIMESTAMP_FORMAT).to_string());
+        for id in ids::ALL.iter().filter(|id| settings.is_check_enabled(id)) {
+            // One stream per check, so toggling a check doesn't change the others
+            let mut rng = Rng(seed ^ fnv1a(id));
+            let mut result = mock_check(id, &mut rng);
+            result.checked_at = Some(time.timestamp());
+            if let Some(slot) = report.slot_mut(id) {
+                *slot = Some(result);
+            }
+        }
+        report.diagnose();
+        report
+    }
+}
+
+/// SplitMix64: tiny, fast and identical on every platform
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `low..=high`
+    fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next() % (high - low + 1)
+    }
+
+    /// Mostly healthy: 75% OK, 17% warning, 8% error
+    fn status(&mut self) -> CheckStatus {
+        match self.next() % 100 {
+            0..=74 => CheckStatus::Ok,
+            75..=91 => CheckStatus::Warning,
+            _ => CheckStatus::Error,
+        }
+    }
+}
+
+fn fnv1a(text: &str) -> u64 {
+    text.bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, b| (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// A plausible result for `id`, shaped like the real check's output
+fn mock_check(id: &str, rng: &mut Rng) -> CheckResult {
+    use CheckStatus::{Error, Ok, Warning};
+
+    let status = rng.status();
+    match id {
+        ids::RESOURCES => {
+            let cpu = match status {
+                Ok => rng.range(4, 60),
+                Warning => rng.range(71, 90),
+                _ => rng.range(91, 100),
+            };
+            let ram = rng.range(35, 84);
+            CheckResult::new("LOCAL RESOURCES", status, &format!("CPU: {}% :: RAM: {}%", cpu, ram))
+        }
+        ids::GPU => {
+            let usage = match status {
+                Ok => rng.range(1, 70),
+                Warning => rng.range(81, 95),
+                _ => rng.range(96, 100),
+            };
+            CheckResult::new("GPU", status, &format!("RTX 4070: {}%", usage))
+        }
+        ids::LONG_PATHS => match status {
+            Ok => CheckResult::new("LONG PATHS", Ok, "Windows ON :: git ON"),
+            _ => CheckResult::new("LONG PATHS", Warning, "Windows ON :: git OFF")
+                .with_message("Enable in git: git config --global core.longpaths true"),
+        },
+        ids::DEFENDER => match status {
+            Ok => CheckResult::new(
+                "DEFENDER",
+                Ok,
+                "real-time ON :: install C:\\Users\\dev\\AppData\\Roaming\\npm 0.9ms/file (scanned) :: project C:\\dev\\app 0.4ms/file (excluded)",
+            ),
+            _ => {
+                let ms = rng.range(6, 30);
+                CheckResult::new(
+                    "DEFENDER",
+                    Warning,
+                    &format!("real-time ON :: install C:\\Users\\dev\\AppData\\Roaming\\npm 1.1ms/file (scanned) :: project C:\\dev\\app {}.0ms/file (scanned)", ms),
+                )
+                .with_message(&format!(
+                    "Real-time scanning adds ~{}ms per file in C:\\dev\\app. If you trust it, exclude it (admin PowerShell): Add-MpPreference -ExclusionPath 'C:\\dev\\app'",
+                    ms
+                ))
+            }
+        },
+        ids::DATA_DIRS => match status {
+            Ok => CheckResult::new(
+                "DATA DIRS",
+                Ok,
+                "config C:\\Users\\dev\\.config\\opencode OK (12 files) :: data C:\\Users\\dev\\.local\\share\\opencode OK (348 files) :: cache C:\\Users\\dev\\.cache\\opencode OK (1920 files) :: state not created yet",
+            ),
+            Warning => CheckResult::new(
+                "DATA DIRS",
+                Warning,
+                "config C:\\Users\\dev\\OneDrive\\.config\\opencode in OneDrive, 4 online-only :: data C:\\Users\\dev\\.local\\share\\opencode OK (348 files) :: cache C:\\Users\\dev\\.cache\\opencode OK (1920 files) :: state not created yet",
+            )
+            .with_message("4 files in C:\\Users\\dev\\OneDrive\\.config\\opencode are OneDrive online-only; right-click the folder > Always keep on this device"),
+            _ => CheckResult::new(
+                "DATA DIRS",
+                Error,
+                "config C:\\Users\\dev\\.config\\opencode OK (12 files) :: data C:\\Users\\dev\\.local\\share\\opencode NOT WRITABLE :: cache C:\\Users\\dev\\.cache\\opencode OK (1920 files) :: state not created yet",
+            )
+            .with_message("Cannot write to C:\\Users\\dev\\.local\\share\\opencode: Access is denied. (os error 5)"),
+        },
+        ids::CLOUD_SYNC => match status {
+            Ok => CheckResult::new(
+                "CLOUD SYNC",
+                Ok,
+                "project C:\\dev\\webapp not synced :: config C:\\Users\\dev\\.config\\opencode not synced",
+            ),
+            Warning => CheckResult::new(
+                "CLOUD SYNC",
+                Warning,
+                "project C:\\Users\\dev\\OneDrive\\Projects\\webapp OneDrive :: config C:\\Users\\dev\\.config\\opencode not synced",
+            )
+            .with_message("C:\\Users\\dev\\OneDrive\\Projects\\webapp is synced by OneDrive, which locks files while uploading (EBUSY/EPERM on agent edits) and can revert them with sync conflicts. Move the project out or pause syncing while working."),
+            _ => CheckResult::new(
+                "CLOUD SYNC",
+                Warning,
+                &format!(
+                    "project C:\\Users\\dev\\OneDrive\\Projects\\webapp OneDrive, free up space, {} online-only :: Storage Sense frees OneDrive files after 30 days",
+                    rng.range(20, 400)
+                ),
+            )
+            .with_message("C:\\Users\\dev\\OneDrive\\Projects\\webapp in OneDrive has online-only files; agent reads stall and edits fail while they download. Right-click the folder > Always keep on this device, or move the project out of OneDrive."),
+        },
+        ids::GATEWAY => match status {
+            Error => CheckResult::new("ROUTER", Error, "No default route"),
+            _ => {
+                let ms = rng.range(1, 9);
+                CheckResult::new("ROUTER", Ok, &format!("GW 192.168.1.1 :: {}ms", ms)).with_latency(ms as u128)
+            }
+        },
+        ids::INTERNET => match status {
+            Ok => {
+                let ms = rng.range(18, 140);
+                CheckResult::new("INTERNET", Ok, &format!("PING: {}ms :: google.com reachable", ms))
+                    .with_latency(ms as u128)
+            }
+            Warning => CheckResult::new("INTERNET", Warning, "google.com unreachable, cloudflare OK")
+                .with_evidence(Evidence { unreachable_by_name: true, ..Default::default() }),
+            _ => CheckResult::new("INTERNET", Error, "No internet connection"),
+        },
+        ids::FIREWALL => match status {
+            Ok => CheckResult::new("FIREWALL", Ok, "loopback OK :: LAN inbound OK :: outbound OK :: curl OK"),
+            Warning => CheckResult::new("FIREWALL", Warning, "loopback OK :: LAN inbound BLOCKED :: outbound OK :: curl OK")
+                .with_message("Inbound connections to this app are filtered (status page won't be reachable on LAN)"),
+            _ => CheckResult::new("FIREWALL", Error, "loopback OK :: LAN inbound OK :: outbound BLOCKED :: curl OK")
+                .with_message("Outbound traffic from opencode-diag is blocked while other apps get through"),
+        },
+        ids::QUIC => match status {
+            Ok => {
+                let ms = rng.range(12, 90);
+                let details = format!(
+                    "UDP 443 :: google {}ms :: claude {}ms :: openai {}ms :: gemini {}ms",
+                    ms,
+                    ms + rng.range(2, 40),
+                    ms + rng.range(2, 40),
+                    ms + rng.range(0, 10)
+                );
+                CheckResult::new("QUIC", Ok, &details).with_latency(ms as u128)
+            }
+            _ => CheckResult::new(
+                "QUIC",
+                Warning,
+                "UDP 443 :: google no answer :: claude no answer :: openai no answer :: gemini no answer",
+            )
+            .with_message("UDP 443 (QUIC/HTTP3) looks blocked. HTTP/3-capable clients may stall before falling back to TCP."),
+        },
+        ids::BANDWIDTH => match status {
+            Ok => {
+                let kb = rng.range(5, 400);
+                CheckResult::new("BANDWIDTH", Ok, &format!("chrome {} KB/s (23 conn) :: opencode 12.4 KB/s (3 conn) :: Code 1.2 KB/s (4 conn)", kb))
+            }
+            _ => {
+                let mb = rng.range(12, 95) as f64 / 10.0;
+                CheckResult::new(
+                    "BANDWIDTH",
+                    Warning,
+                    &format!("OneDrive {:.1} MB/s (6 conn) :: chrome 84.0 KB/s (23 conn) :: opencode 12.4 KB/s (3 conn)", mb),
+                )
+                .with_message(&format!("OneDrive (sync client) is using {:.1} MB/s. Pause it if API latency is poor.", mb))
+            }
+        },
+        ids::ROOT_STORE => match status {
+            Ok => CheckResult::new("ROOT CA", Ok, "4/4 provider roots :: auto-update on"),
+            Warning => CheckResult::new("ROOT CA", Warning, "4/4 provider roots :: auto-update OFF")
+                .with_message("Automatic root updates are disabled. Future root changes will break API TLS."),
+            _ => CheckResult::new("ROOT CA", Error, "3/4 provider roots :: missing ISRG Root X1 :: auto-update OFF")
+                .with_message("Root store is stale and automatic root updates are disabled by policy. API TLS handshakes will fail."),
+        },
+        ids::CLAUDE => mock_api(rng, status, "CLAUDE API", "api.anthropic.com", (529, "overloaded")),
+        ids::OPENAI => mock_api(rng, status, "OPENAI API", "api.openai.com", (500, "server error")),
+        ids::GOOGLE => mock_api(rng, status, "GOOGLE AI", "googleapis.com", (503, "server error")),
+        ids::RELAY => match status {
+            Ok => CheckResult::new("RELAY", Ok, &format!("from eu-west :: claude here OK / relay OK {}ms", rng.range(60, 200))),
+            _ => CheckResult::new("RELAY", Warning, "from eu-west :: claude here ERROR / relay OK 92ms")
+                .with_message("claude reachable from the internet but not from your machine. Check proxy, VPN and firewall."),
+        },
+        ids::ENDPOINT_CERTS => match status {
+            Ok => CheckResult::new("ENDPOINT CERTS", Ok, &format!("gateway.example.com: {}d", rng.range(31, 89))),
+            _ => {
+                let days = rng.range(2, 13);
+                CheckResult::new("ENDPOINT CERTS", Warning, &format!("gateway.example.com: {}d", days))
+                    .with_message(&format!("gateway.example.com expires in {} days", days))
+            }
+        },
+        ids::CRASHES => match status {
+            Ok => CheckResult::new("CRASHES", Ok, "None in the last 7 days"),
+            Warning => CheckResult::new("CRASHES", Warning, "1 in 7 days :: opencode.exe 2025-01-14 16:42 (AppCrash)")
+                .with_message("opencode.exe crashed 20h ago."),
+            _ => {
+                let count = rng.range(3, 9);
+                CheckResult::new(
+                    "CRASHES",
+                    Error,
+                    &format!("{} in 7 days :: opencode.exe 2025-01-15 09:12 (AppCrash) :: opencode.exe 2025-01-15 08:47 (dump) :: node.exe 2025-01-14 18:03 (AppCrash)", count),
+                )
+                .with_message(&format!(
+                    "opencode.exe crashed {} times in 7 days (last 2h ago). Dropped sessions may be crashes, not the API; check OpenCode's log.",
+                    count
+                ))
+            }
+        },
+        ids::LEAKS => match status {
+            Ok => CheckResult::new(
+                "LEAKS",
+                Ok,
+                &format!("PID 4812 {} handles (+12 in 1h 5m) :: temp 412.6 MB (+3.1 MB in 1h 5m)", rng.range(300, 600)),
+            ),
+            Warning => {
+                let handles = rng.range(2400, 6000);
+                CheckResult::new(
+                    "LEAKS",
+                    Warning,
+                    &format!("PID 4812 {} handles (+{} in 2h 40m) :: temp 1.8 GB (+40.2 MB in 2h 40m)", handles, handles - 380),
+                )
+                .with_message(&format!(
+                    "OpenCode (PID 4812) open handles grew from 380 to {} in 2h 40m; a leak ends in \"too many open files\". Restart OpenCode before long sessions.",
+                    handles
+                ))
+            }
+            _ => {
+                let open = rng.range(830, 1020);
+                CheckResult::new("LEAKS", Error, &format!("PID 4812 {} handles (+{} in 3h 2m) :: temp 2.3 GB (+1.2 GB in 3h 2m)", open, open - 96))
+                    .with_message(&format!(
+                        "PID 4830 has {} of 1024 allowed open files; it will fail with EMFILE (too many open files). Restart OpenCode, or raise the limit with ulimit -n.",
+                        open
+                    ))
+            }
+        },
+        ids::OPENCODE => {
+            let memory = rng.range(180, 900);
+            match status {
+                Ok => CheckResult::new("OPENCODE", Ok, &format!("PID 4812 v0.15.2 :4096 C:\\dev\\app :: {}MB", memory)),
+                _ => CheckResult::new(
+                    "OPENCODE",
+                    Warning,
+                    &format!(
+                        "2 instances :: {}MB\nPID 4812 v0.15.2 :4096 C:\\dev\\app\nPID 5120 v0.15.2 :4096 C:\\dev\\app",
+                        memory * 2
+                    ),
+                )
+                .with_message("PID 4812 and 5120 both use port 4096"),
+            }
+        }
+        ids::IPC => match status {
+            Ok => {
+                let ms = rng.range(1, 6);
+                CheckResult::new("EDITOR IPC", Ok, &format!("PID 4812 :4096 OK {}ms", ms)).with_latency(ms as u128)
+            }
+            Warning => CheckResult::new("EDITOR IPC", Warning, "PID 4812 no server")
+                .with_message("OpenCode (PID 4812) has no local server; editor plugins can't connect"),
+            _ => CheckResult::new("EDITOR IPC", Error, "PID 4812 :4096 no answer")
+                .with_message("OpenCode (PID 4812) listens but does not answer; restart it"),
+        },
+        ids::EDITORS => match status {
+            Ok => CheckResult::new("EDITORS", Ok, "VS Code 0.3.2 (running) :: Cursor 0.3.2 (closed) :: CLI 0.15.2"),
+            _ => CheckResult::new("EDITORS", Warning, "VS Code 0.3.3 (running) :: CLI 0.15.2")
+                .with_message("Reload VS Code to load the updated OpenCode plugin"),
+        },
+        ids::TERMINALS => {
+            let count = match status {
+                Ok => rng.range(1, 6),
+                _ => rng.range(11, 16),
+            };
+            let status = if count > 10 { Warning } else { Ok };
+            CheckResult::new("TERMINALS", status, &format!("ps:{} wt:1 :: {}MB", count, count * 38))
+        }
+        ids::SMOKE => {
+            let ms = rng.range(2500, 9000);
+            match status {
+                Ok => CheckResult::new("SMOKE TEST", Ok, &format!("Answered in {:.1}s :: \"Hi! How can I help you today?\"", ms as f64 / 1000.0)),
+                Warning => CheckResult::new("SMOKE TEST", Warning, &format!("Finished in {:.1}s without an answer", ms as f64 / 1000.0))
+                    .with_message("Check the model and the provider's credentials"),
+                _ => CheckResult::new("SMOKE TEST", Error, &format!("opencode run failed after {:.1}s (exit status: 1)", ms as f64 / 1000.0))
+                    .with_message("Error: API key is missing or invalid"),
+            }
+            .with_latency(ms as u128)
+        }
+        _ => CheckResult::new(id, CheckStatus::Unknown, "No mock data"),
+    }
+}
+
+/// Provider check: reachable (auth required), rate limited or failing, answered
+/// by the provider itself
+fn mock_api(rng: &mut Rng, status: CheckStatus, name: &str, host: &str, (code, failure): (u16, &str)) -> CheckResult {
+    let ms = rng.range(70, 450);
+    let (code, result) = match status {
+        CheckStatus::Ok => (401, CheckResult::new(name, CheckStatus::Ok, &format!("{} :: reachable :: {}ms (auth required)", host, ms))),
+        CheckStatus::Warning => (429, CheckResult::new(name, CheckStatus::Warning, &format!("{} :: 429 :: rate limited", host))),
+        _ => (code, CheckResult::new(name, CheckStatus::Error, &format!("{} :: {} :: {}", host, code, failure))),
+    };
+    result
+        .with_latency(ms as u128)
+        .with_evidence(Evidence { status_code: Some(code), from_provider: true, ..Default::default() })
+}
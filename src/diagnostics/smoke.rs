@@ -0,0 +1,187 @@
+//! End-to-end smoke test: a real OpenCode session
+//!
+//! Every other check looks at one link (network, provider, process). This
+//! one runs `opencode run "say hi"` the way a user would and only passes when
+//! an answer comes back, so config, credentials, model and API are tested
+//! together. It spends a few tokens and can take a while, so it is opt-in.
+//! It runs in an empty folder so only the global config applies and no
+//! project is touched.
+
+use crate::diagnostics::{actions, command, CheckResult, CheckStatus};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+pub const PROMPT: &str = "say hi";
+
+/// How long the session may take before it is stopped
+pub const TIMEOUT: Duration = Duration::from_secs(90);
+
+/// How long output is still awaited after opencode exited or was stopped
+/// (a leftover grandchild can hold the pipes open)
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Longer answers are cut in the card
+const MAX_ANSWER_CHARS: usize = 60;
+
+/// The opencode executable on PATH (npm installs a `.cmd` shim on Windows)
+fn find_opencode() -> Option<PathBuf> {
+    let names: &[&str] = if cfg!(target_os = "windows") { &["opencode.exe", "opencode.cmd"] } else { &["opencode"] };
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| names.iter().map(|name| dir.join(name)).find(|exe| exe.is_file()))
+}
+
+/// Read a child's stream on its own thread, so a full pipe never blocks the
+/// session; output comes in chunks as it is read, the channel closes at the end
+fn drain(stream: Option<impl Read + Send + 'static>) -> mpsc::Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let Some(mut stream) = stream else {
+            return;
+        };
+        let mut buf = [0u8; 4096];
+        loop {
+            match stream.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if sender.send(buf[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+    receiver
+}
+
+/// Output read until the stream closed or `deadline` passed, whichever comes first
+/// (a leftover grandchild holding the pipe open does not lose what was written)
+fn collect(receiver: mpsc::Receiver<Vec<u8>>, deadline: Instant) -> String {
+    let mut bytes = Vec::new();
+    while let Ok(chunk) = receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        bytes.extend(chunk);
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Run `opencode run "say hi"` and report whether it answered and how fast
+pub fn check_smoke() -> CheckResult {
+    match find_opencode() {
+        Some(exe) => check_smoke_with(&exe),
+        None => CheckResult::new("SMOKE TEST", CheckStatus::Inactive, "opencode not found on PATH"),
+    }
+}
+
+/// Smoke test with the given opencode executable
+fn check_smoke_with(exe: &Path) -> CheckResult {
+    let dir = std::env::temp_dir().join("opencode-diag-smoke");
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        return CheckResult::new("SMOKE TEST", CheckStatus::Error, "Could not create a work folder").with_message(&e.to_string());
+    }
+
+    let started = Instant::now();
+    let mut cmd = command(&exe.to_string_lossy());
+    cmd.args(["run", PROMPT]).current_dir(&dir).stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    // Its own process group, so a timeout stops everything it started
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            return CheckResult::new("SMOKE TEST", CheckStatus::Error, "Could not start opencode").with_message(&e.to_string());
+        }
+    };
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if started.elapsed() > TIMEOUT => {
+                actions::force_stop(child.id());
+                let _ = child.wait();
+                break None;
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(100)),
+            Err(_) => break None,
+        }
+    };
+    let elapsed = started.elapsed();
+    let seconds = format!("{:.1}s", elapsed.as_secs_f64());
+    let deadline = Instant::now() + DRAIN_TIMEOUT;
+    let (stdout, stderr) = (collect(stdout, deadline), collect(stderr, deadline));
+
+    let Some(status) = status else {
+        return CheckResult::new("SMOKE TEST", CheckStatus::Error, &format!("No answer within {}s", TIMEOUT.as_secs()))
+            .with_message("OpenCode hung; run `opencode run \"say hi\"` in a terminal to see where")
+            .with_latency(elapsed.as_millis());
+    };
+    let last_error = stderr.lines().rev().map(str::trim).find(|line| !line.is_empty());
+    if !status.success() {
+        let result = CheckResult::new("SMOKE TEST", CheckStatus::Error, &format!("opencode run failed after {} ({})", seconds, status));
+        return match last_error {
+            Some(line) => result.with_message(line),
+            None => result,
+        }
+        .with_latency(elapsed.as_millis());
+    }
+    let answer = stdout.split_whitespace().collect::<Vec<_>>().join(" ");
+    if answer.is_empty() {
+        return CheckResult::new("SMOKE TEST", CheckStatus::Warning, &format!("Finished in {} without an answer", seconds))
+            .with_message(last_error.unwrap_or("Check the model and the provider's credentials"))
+            .with_latency(elapsed.as_millis());
+    }
+    let answer: String = if answer.chars().count() > MAX_ANSWER_CHARS {
+        answer.chars().take(MAX_ANSWER_CHARS).chain("...".chars()).collect()
+    } else {
+        answer
+    };
+    CheckResult::new("SMOKE TEST", CheckStatus::Ok, &format!("Answered in {} :: \"{}\"", seconds, answer)).with_latency(elapsed.as_millis())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// A stand-in for opencode running `script`, in its own folder
+    fn stand_in(name: &str, script: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("opencode-diag-test-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let exe = dir.join("opencode");
+        std::fs::write(&exe, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).unwrap();
+        (dir, exe)
+    }
+
+    #[test]
+    fn answer_is_shown() {
+        let (dir, exe) = stand_in("answer", "echo \"Hi there\"");
+        let result = check_smoke_with(&exe);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(result.status, CheckStatus::Ok);
+        assert!(result.details.ends_with(":: \"Hi there\""), "{}", result.details);
+    }
+
+    #[test]
+    fn grandchild_holding_the_pipes_does_not_hang_the_check() {
+        let (dir, exe) = stand_in("grandchild", "sleep 30 &\necho \"Hi there\"");
+        let started = Instant::now();
+        let result = check_smoke_with(&exe);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(started.elapsed() < DRAIN_TIMEOUT * 3, "took {:?}", started.elapsed());
+        assert_eq!(result.status, CheckStatus::Ok, "{}", result.details);
+        assert!(result.details.ends_with(":: \"Hi there\""), "{}", result.details);
+    }
+
+    #[test]
+    fn failure_shows_the_last_error_line() {
+        let (dir, exe) = stand_in("failure", "echo \"Error: no provider configured\" >&2\nexit 1");
+        let result = check_smoke_with(&exe);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(result.status, CheckStatus::Error);
+        assert_eq!(result.message.as_deref(), Some("Error: no provider configured"));
+    }
+}
@@ -0,0 +1,63 @@
+//! Per-run sysinfo snapshot shared by the local checks
+//!
+//! Building `System::new_all()` loads every process, disk and network
+//! interface. The snapshot starts empty and refreshes only what a check asks
+//! for, once per run.
+
+use sysinfo::{MemoryRefreshKind, Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+pub struct SystemSnapshot {
+    sys: System,
+    cpu_memory: bool,
+    processes: bool,
+}
+
+impl SystemSnapshot {
+    pub fn new() -> Self {
+        Self {
+            sys: System::new(),
+            cpu_memory: false,
+            processes: false,
+        }
+    }
+
+    /// CPU usage and RAM (first call samples CPU twice, `MINIMUM_CPU_UPDATE_INTERVAL` apart)
+    pub fn cpu_memory(&mut self) -> &System {
+        if !self.cpu_memory {
+            self.sys.refresh_memory_specifics(MemoryRefreshKind::new().with_ram());
+            self.sys.refresh_cpu_usage();
+            std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+            self.sys.refresh_cpu_usage();
+            self.cpu_memory = true;
+        }
+        &self.sys
+    }
+
+    /// Process list with names and memory only
+    pub fn processes(&mut self) -> &System {
+        if !self.processes {
+            self.sys.refresh_processes_specifics(
+                ProcessesToUpdate::All,
+                true,
+                ProcessRefreshKind::new().with_memory(),
+            );
+            self.processes = true;
+        }
+        &self.sys
+    }
+
+    /// Also load command line, executable and working directory of `pids`
+    pub fn process_details(&mut self, pids: &[Pid]) -> &System {
+        self.processes();
+        self.sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(pids),
+            false,
+            ProcessRefreshKind::new()
+                .with_memory()
+                .with_cmd(UpdateKind::OnlyIfNotSet)
+                .with_exe(UpdateKind::OnlyIfNotSet)
+                .with_cwd(UpdateKind::OnlyIfNotSet),
+        );
+        &self.sys
+    }
+}
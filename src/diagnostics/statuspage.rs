@@ -0,0 +1,110 @@
+//! Provider status pages
+//!
+//! Anthropic and OpenAI publish their incidents on Statuspage-style pages
+//! with a small JSON summary at `/api/v2/status.json`. The summary is read
+//! alongside the API checks so the verdict can say "it's them" from the
+//! provider's own word, not only from failed requests. Google has no such
+//! page for the Gemini API and is left out.
+
+use crate::diagnostics::{ids, HttpProfile};
+#[cfg(feature = "net-checks")]
+use crate::diagnostics::{caught, run_limited, traffic, NetworkJob};
+use serde::{Deserialize, Serialize};
+
+/// Check id, provider name and summary URL
+pub const PAGES: &[(&str, &str, &str)] = &[
+    (ids::CLAUDE, "Anthropic", "https://status.anthropic.com/api/v2/status.json"),
+    (ids::OPENAI, "OpenAI", "https://status.openai.com/api/v2/status.json"),
+];
+
+/// What a provider's status page said
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProviderStatus {
+    /// Check id of the provider's API check
+    pub id: String,
+    pub provider: String,
+    /// "none", "minor", "major", "critical" or "maintenance"; "unknown" when
+    /// the page could not be read
+    pub indicator: String,
+    /// "All Systems Operational" / "Partial System Outage"
+    pub description: String,
+}
+
+impl ProviderStatus {
+    /// A page that could not be read
+    fn unknown(id: &str, provider: &str, description: String) -> Self {
+        Self { id: id.to_string(), provider: provider.to_string(), indicator: "unknown".to_string(), description }
+    }
+
+    /// The provider reports a problem of its own
+    pub fn has_incident(&self) -> bool {
+        matches!(self.indicator.as_str(), "minor" | "major" | "critical")
+    }
+
+    /// Bad enough to explain failing requests on its own
+    pub fn is_major(&self) -> bool {
+        matches!(self.indicator.as_str(), "major" | "critical")
+    }
+}
+
+/// `GET /api/v2/status.json` response
+#[cfg(feature = "net-checks")]
+#[derive(Deserialize)]
+struct Summary {
+    status: SummaryStatus,
+}
+
+#[cfg(feature = "net-checks")]
+#[derive(Deserialize)]
+struct SummaryStatus {
+    indicator: String,
+    description: String,
+}
+
+/// Status pages of the providers in `ids`, fetched alongside each other within
+/// the network concurrency limit; a page whose fetch crashed is "unknown"
+#[cfg(feature = "net-checks")]
+pub fn fetch(http: &HttpProfile, ids: &[&str], limit: usize) -> Vec<ProviderStatus> {
+    let jobs: Vec<(&'static str, NetworkJob<ProviderStatus>)> = PAGES
+        .iter()
+        .filter(|(id, _, _)| ids.contains(id))
+        .map(|&(id, provider, url)| {
+            let job = move || {
+                caught(provider, || fetch_page(http, id, provider, url))
+                    .unwrap_or_else(|e| ProviderStatus::unknown(id, provider, format!("Status page check crashed: {}", e)))
+            };
+            (id, Box::new(job) as NetworkJob<ProviderStatus>)
+        })
+        .collect();
+    run_limited(jobs, limit).into_iter().map(|(_, status)| status).collect()
+}
+
+/// Read one summary; an unreadable page is reported as "unknown"
+#[cfg(feature = "net-checks")]
+pub fn fetch_page(http: &HttpProfile, id: &str, provider: &str, url: &str) -> ProviderStatus {
+    let unknown = |description: String| ProviderStatus::unknown(id, provider, description);
+    let client = match http.client(std::time::Duration::from_secs(5)) {
+        Ok(c) => c,
+        Err(e) => return unknown(e),
+    };
+    let response = client.get(url).send();
+    traffic::record_http(url, &response);
+    match response.and_then(|r| r.error_for_status()).and_then(|r| r.json::<Summary>()) {
+        Ok(summary) => ProviderStatus {
+            id: id.to_string(),
+            provider: provider.to_string(),
+            indicator: summary.status.indicator,
+            description: summary.status.description,
+        },
+        Err(e) if e.is_decode() => unknown("Status page sent an unexpected response".to_string()),
+        Err(e) => {
+            let reason = if e.is_timeout() { "timeout".to_string() } else { e.without_url().to_string() };
+            unknown(format!("Status page unreachable :: {}", reason))
+        }
+    }
+}
+
+#[cfg(not(feature = "net-checks"))]
+pub fn fetch(_http: &HttpProfile, _ids: &[&str], _limit: usize) -> Vec<ProviderStatus> {
+    Vec::new()
+}
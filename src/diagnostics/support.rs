@@ -0,0 +1,64 @@
+//! Asking for help after a failed run
+//!
+//! Between seeing an error and getting help sit a few chores: copying the
+//! report, finding where OpenCode takes issues, formatting the paste. After a
+//! run with errors the app offers all of it in one click; the report goes to
+//! the clipboard as Markdown (a headline with what failed, the report in a
+//! code block, which both GitHub and Discord keep aligned) and the issue form
+//! or the Discord server opens.
+
+use crate::diagnostics::{CheckStatus, DiagnosticReport};
+
+/// New issue on the OpenCode repository
+pub const ISSUE_URL: &str = "https://github.com/sst/opencode/issues/new";
+
+/// Invite to the OpenCode Discord server
+pub const DISCORD_URL: &str = "https://opencode.ai/discord";
+
+/// Names of the checks that ended in an error
+pub fn failed_checks(report: &DiagnosticReport) -> Vec<String> {
+    report
+        .checks()
+        .into_iter()
+        .filter(|(_, check)| check.status == CheckStatus::Error)
+        .map(|(_, check)| check.name.clone())
+        .collect()
+}
+
+/// `text` (a COPY REPORT text) as Markdown under a headline naming the failed
+/// checks and, when there is one, the verdict
+pub fn to_markdown(report: &DiagnosticReport, text: &str, verdict: Option<&str>) -> String {
+    let failed = failed_checks(report);
+    let mut markdown = if failed.is_empty() {
+        "### OpenCode Diagnostics\n\n".to_string()
+    } else {
+        format!("### OpenCode Diagnostics: {} failing\n\n", failed.join(", "))
+    };
+    if let Some(verdict) = verdict {
+        markdown.push_str(&format!("**{}**\n\n", verdict));
+    }
+    markdown.push_str("```text\n");
+    markdown.push_str(text.trim_end());
+    markdown.push_str("\n```\n");
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::{reportfile, CheckResult, ReportSections};
+
+    #[test]
+    fn markdown_names_the_failure_and_reads_back() {
+        let mut report = DiagnosticReport::new();
+        report.timestamp = Some("2026-01-05 10:00:00".to_string());
+        report.claude_api = Some(CheckResult::new("CLAUDE API", CheckStatus::Error, "HTTP 529 overloaded"));
+        report.internet = Some(CheckResult::new("INTERNET", CheckStatus::Ok, "Connected"));
+        let text = report.to_text_report(ReportSections::default());
+        let markdown = to_markdown(&report, &text, Some("It's them: Anthropic incident ongoing"));
+        assert!(markdown.starts_with("### OpenCode Diagnostics: CLAUDE API failing"));
+        let read = reportfile::from_text(&markdown).unwrap().report;
+        assert_eq!(read.checks().len(), 2);
+        assert_eq!(read.claude_api.map(|c| c.status), Some(CheckStatus::Error));
+    }
+}
@@ -0,0 +1,101 @@
+//! Context tags of a run: project, model and network
+//!
+//! "Fails at the office but not at home" or "only with one model" shows up
+//! once failures can be sliced by where they happened. Every run is tagged
+//! and the tags are kept in history. A tag set in settings is used as is;
+//! an empty one is detected on each run.
+
+use crate::diagnostics::datadirs::opencode_dirs;
+use crate::diagnostics::{network, processes, wlan, SystemSnapshot};
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+use std::path::Path;
+
+/// Context a run was made in (empty = unknown)
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunTags {
+    pub project: String,
+    pub model: String,
+    pub network: String,
+}
+
+impl RunTags {
+    pub fn is_empty(&self) -> bool {
+        self.project.is_empty() && self.model.is_empty() && self.network.is_empty()
+    }
+
+    /// (kind, value) of each tag, set or not
+    pub fn pairs(&self) -> [(&'static str, &str); 3] {
+        [("project", &self.project), ("model", &self.model), ("network", &self.network)]
+    }
+
+    /// "project=api model=anthropic/claude-sonnet-4-5 network=HomeWifi"
+    pub fn summary(&self) -> String {
+        self.pairs()
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(kind, value)| format!("{}={}", kind, value))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The tags set in settings, with the empty ones detected
+    pub fn resolve(set: &RunTags, snapshot: &mut SystemSnapshot) -> RunTags {
+        let roots = if set.project.is_empty() || set.model.is_empty() { processes::opencode_roots(snapshot) } else { Vec::new() };
+        let cwds: Vec<&Path> = roots.iter().filter_map(|(_, cwd)| cwd.as_deref()).collect();
+        let or_detect = |value: &str, detect: &dyn Fn() -> Option<String>| {
+            if value.is_empty() { detect().unwrap_or_default() } else { value.to_string() }
+        };
+        RunTags {
+            project: or_detect(&set.project, &|| detect_project(&cwds)),
+            model: or_detect(&set.model, &|| detect_model(&cwds)),
+            network: or_detect(&set.network, &detect_network),
+        }
+    }
+}
+
+/// Folder names OpenCode runs in, e.g. "api" or "api,web"
+fn detect_project(cwds: &[&Path]) -> Option<String> {
+    let mut names: Vec<String> = cwds.iter().filter_map(|cwd| Some(cwd.file_name()?.to_string_lossy().into_owned())).collect();
+    names.dedup();
+    (!names.is_empty()).then(|| names.join(","))
+}
+
+/// Top-level "model" of opencode.json(c): the project's first, then the global one
+fn detect_model(cwds: &[&Path]) -> Option<String> {
+    let global = opencode_dirs().into_iter().find(|(kind, _)| *kind == "config").map(|(_, dir)| dir);
+    let dirs = cwds.iter().map(|cwd| cwd.to_path_buf()).chain(global);
+    for dir in dirs {
+        for name in ["opencode.json", "opencode.jsonc"] {
+            let Ok(text) = std::fs::read_to_string(dir.join(name)) else {
+                continue;
+            };
+            if let Some(model) = config_model(&text) {
+                return Some(model);
+            }
+        }
+    }
+    None
+}
+
+/// "model" from a config that may carry `//` comment lines (JSONC)
+fn config_model(text: &str) -> Option<String> {
+    let config: serde_json::Value = serde_json::from_str(text).ok().or_else(|| {
+        let stripped: Vec<&str> = text.lines().filter(|line| !line.trim_start().starts_with("//")).collect();
+        serde_json::from_str(&stripped.join("\n")).ok()
+    })?;
+    Some(config.get("model")?.as_str()?.to_string())
+}
+
+/// Wi-Fi name, else the default gateway (wired or VPN)
+fn detect_network() -> Option<String> {
+    let ssid = wlan::ssid();
+    network_name(ssid.as_deref(), if ssid.is_none() { network::default_gateway() } else { None })
+}
+
+/// "HomeWifi", or "gw 10.0.0.1" off Wi-Fi (also what network profiles match)
+pub fn network_name(ssid: Option<&str>, gateway: Option<Ipv4Addr>) -> Option<String> {
+    ssid.map(str::to_string).or_else(|| gateway.map(|gateway| format!("gw {}", gateway)))
+}
+
@@ -0,0 +1,322 @@
+//! Live tail of OpenCode's output
+//!
+//! Either launches OpenCode headless with its stdout/stderr piped here, or
+//! follows the newest file in OpenCode's log directory. Lines matching known
+//! failure patterns are tagged with the check that covers them, so a 529 in
+//! the output is one click away from re-running the CLAUDE API check.
+
+use crate::diagnostics::{actions, datadirs, ids};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Used when no tail command is configured; `serve` has no TUI to break
+pub const DEFAULT_TAIL_COMMAND: &str = "opencode serve --print-logs";
+
+/// Lines kept in the panel
+const MAX_LINES: usize = 2000;
+
+/// How often a followed log file and a launched process are polled
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Tail of an existing log file shown when following starts
+const LOG_BACKLOG_BYTES: u64 = 16 * 1024;
+
+/// Where a line came from
+#[derive(Clone, Copy, PartialEq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+    Log,
+    /// Started / exited / switched file
+    Status,
+}
+
+/// A recognized failure and the check that covers it
+#[derive(Clone, Copy, PartialEq)]
+pub struct Tag {
+    pub label: &'static str,
+    pub check: &'static str,
+}
+
+#[derive(Clone)]
+pub struct TailLine {
+    pub time: String,
+    pub stream: Stream,
+    pub text: String,
+    pub tag: Option<Tag>,
+}
+
+impl TailLine {
+    /// Shown with ERRORS ONLY
+    pub fn is_error(&self) -> bool {
+        self.tag.is_some() || self.text.to_lowercase().contains("error")
+    }
+}
+
+/// Provider patterns: (substrings, HTTP status, label); the check follows the provider named on the line
+const PROVIDER_PATTERNS: &[(&[&str], &str, &str)] = &[
+    (&["overloaded"], "529", "OVERLOADED"),
+    (&["rate_limit", "rate limit", "too many requests"], "429", "RATE LIMITED"),
+    (&["authentication_error", "invalid x-api-key", "invalid_api_key", "incorrect api key"], "401", "AUTH"),
+];
+
+/// Local patterns: (substrings, label, check)
+const LOCAL_PATTERNS: &[(&[&str], &str, &str)] = &[
+    (
+        &["unable_to_verify_leaf_signature", "self_signed_cert", "self-signed certificate", "unable to get local issuer", "certificate has expired"],
+        "CERTIFICATE",
+        ids::ROOT_STORE,
+    ),
+    (&["enotfound", "getaddrinfo", "eai_again"], "DNS", ids::INTERNET),
+    (&["econnrefused", "econnreset", "etimedout", "socket hang up", "fetch failed"], "CONNECTION", ids::INTERNET),
+    (&["eaddrinuse", "address already in use"], "PORT IN USE", ids::OPENCODE),
+    (&["enametoolong", "path too long"], "LONG PATH", ids::LONG_PATHS),
+    (&["ebusy", "resource busy or locked"], "FILE LOCKED", ids::CLOUD_SYNC),
+    (&["enomem", "out of memory"], "MEMORY", ids::RESOURCES),
+    (&["emfile", "too many open files"], "TOO MANY FILES", ids::LEAKS),
+];
+
+fn provider_check(lower: &str) -> &'static str {
+    if lower.contains("openai") {
+        ids::OPENAI
+    } else if ["google", "gemini", "vertex"].iter().any(|p| lower.contains(p)) {
+        ids::GOOGLE
+    } else {
+        ids::CLAUDE
+    }
+}
+
+/// Tag a line when it matches a known failure
+pub fn recognize(text: &str) -> Option<Tag> {
+    let lower = text.to_lowercase();
+    let matches = |patterns: &[&str]| patterns.iter().any(|p| lower.contains(p));
+    // Status codes as whole words, so "529ms" does not count
+    let has_status = |code: &str| lower.split(|c: char| !c.is_ascii_alphanumeric()).any(|word| word == code);
+    let provider = PROVIDER_PATTERNS.iter().find(|(patterns, code, _)| matches(patterns) || has_status(code));
+    if let Some((_, _, label)) = provider {
+        return Some(Tag { label, check: provider_check(&lower) });
+    }
+    LOCAL_PATTERNS
+        .iter()
+        .find(|(patterns, _, _)| matches(patterns))
+        .map(|(_, label, check)| Tag { label, check })
+}
+
+/// Shared line buffer, appended to by the reader threads
+#[derive(Clone, Default)]
+struct Lines(Arc<Mutex<VecDeque<TailLine>>>);
+
+impl Lines {
+    fn push(&self, stream: Stream, text: &str) {
+        let text = text.trim_end();
+        if text.is_empty() {
+            return;
+        }
+        let line = TailLine {
+            time: chrono::Local::now().format("%H:%M:%S").to_string(),
+            stream,
+            text: text.to_string(),
+            tag: if stream == Stream::Status { None } else { recognize(text) },
+        };
+        let mut lines = self.0.lock().unwrap();
+        if lines.len() == MAX_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+}
+
+/// A running tail, stopped when dropped
+pub struct OutputTail {
+    /// Command line or log file being tailed
+    pub source: String,
+    lines: Lines,
+    stopped: Arc<AtomicBool>,
+}
+
+impl OutputTail {
+    /// Launch `command` (empty = default) with stdout/stderr piped; `on_line` runs after new output
+    pub fn launch(command: &str, on_line: impl Fn() + Clone + Send + 'static) -> Result<Self, String> {
+        let command = match command.trim() {
+            "" => DEFAULT_TAIL_COMMAND,
+            command => command,
+        };
+        let mut child = actions::shell(command)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to launch \"{}\": {}", command, e))?;
+
+        let tail = Self {
+            source: command.to_string(),
+            lines: Lines::default(),
+            stopped: Arc::new(AtomicBool::new(false)),
+        };
+        tail.lines.push(Stream::Status, &format!("launched `{}` (pid {})", command, child.id()));
+
+        if let Some(stdout) = child.stdout.take() {
+            tail.read_lines(stdout, Stream::Stdout, on_line.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            tail.read_lines(stderr, Stream::Stderr, on_line.clone());
+        }
+
+        // Reap the child, or kill its tree once the tail is stopped
+        let lines = tail.lines.clone();
+        let stopped = Arc::clone(&tail.stopped);
+        std::thread::spawn(move || loop {
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    lines.push(Stream::Status, &format!("exited ({})", status));
+                    on_line();
+                    break;
+                }
+                Ok(None) if stopped.load(Ordering::Relaxed) => {
+                    actions::force_stop(child.id());
+                    let _ = child.wait();
+                    break;
+                }
+                Ok(None) => std::thread::sleep(POLL_INTERVAL),
+                Err(_) => break,
+            }
+        });
+        Ok(tail)
+    }
+
+    /// Follow the newest OpenCode log file, switching when a newer one appears
+    pub fn follow_log(on_line: impl Fn() + Send + 'static) -> Result<Self, String> {
+        let dir = log_dir().ok_or("Could not determine OpenCode's log directory")?;
+        let first = newest_log(&dir).ok_or_else(|| format!("No OpenCode logs in {}", dir.display()))?;
+
+        let tail = Self {
+            source: dir.display().to_string(),
+            lines: Lines::default(),
+            stopped: Arc::new(AtomicBool::new(false)),
+        };
+        let lines = tail.lines.clone();
+        let stopped = Arc::clone(&tail.stopped);
+        std::thread::spawn(move || {
+            let mut current: Option<(PathBuf, u64)> = None;
+            let mut partial = String::new();
+            let mut candidate = Some(first);
+            while !stopped.load(Ordering::Relaxed) {
+                // OpenCode starts a new file per launch
+                if let Some(path) = candidate.take() {
+                    if current.as_ref().map(|(p, _)| p != &path).unwrap_or(true) {
+                        let len = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                        let start = if current.is_none() { len.saturating_sub(LOG_BACKLOG_BYTES) } else { 0 };
+                        lines.push(Stream::Status, &format!("following {}", path.display()));
+                        current = Some((path, start));
+                        partial.clear();
+                    }
+                }
+
+                if let Some((ref path, ref mut offset)) = current {
+                    let appended = read_from(path, offset).unwrap_or_default();
+                    if !appended.is_empty() {
+                        partial.push_str(&appended);
+                        // Keep an unfinished last line for the next poll
+                        let complete = partial.rfind('\n').map(|i| i + 1).unwrap_or(0);
+                        for line in partial[..complete].lines() {
+                            lines.push(Stream::Log, line);
+                        }
+                        partial.drain(..complete);
+                        on_line();
+                    }
+                }
+
+                std::thread::sleep(POLL_INTERVAL);
+                candidate = newest_log(&dir);
+            }
+        });
+        Ok(tail)
+    }
+
+    fn read_lines(&self, source: impl Read + Send + 'static, stream: Stream, on_line: impl Fn() + Send + 'static) {
+        let lines = self.lines.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(source).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                lines.push(stream, &line);
+                on_line();
+            }
+        });
+    }
+
+    pub fn lines(&self) -> Vec<TailLine> {
+        self.lines.0.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.lines.0.lock().unwrap().clear();
+    }
+}
+
+impl Drop for OutputTail {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+/// `log` in OpenCode's data directory
+fn log_dir() -> Option<PathBuf> {
+    datadirs::data_dir().map(|dir| dir.join("log"))
+}
+
+/// Most recently modified `.log` file
+fn newest_log(dir: &std::path::Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|e| e == "log"))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+/// Text appended to `path` since `offset`, advancing it
+fn read_from(path: &std::path::Path, offset: &mut u64) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    // Truncated or rotated in place
+    if len < *offset {
+        *offset = 0;
+    }
+    file.seek(SeekFrom::Start(*offset))?;
+    let mut bytes = Vec::new();
+    file.take(len - *offset).read_to_end(&mut bytes)?;
+    *offset = len;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_status_codes_not_durations() {
+        assert_eq!(recognize("API error 529: overloaded_error").map(|t| t.check), Some(ids::CLAUDE));
+        assert!(recognize("request took 529ms").is_none());
+    }
+
+    #[test]
+    fn launched_output_is_tagged() {
+        let output = OutputTail::launch("echo connect ECONNREFUSED 127.0.0.1:4096", || {}).unwrap();
+        let mut tag = None;
+        for _ in 0..50 {
+            tag = output.lines().into_iter().find_map(|l| l.tag).map(|t| t.check);
+            if tag.is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        assert_eq!(tag, Some(ids::INTERNET));
+    }
+}
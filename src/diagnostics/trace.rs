@@ -0,0 +1,233 @@
+//! Trace mode: the network checks every few seconds for five minutes
+//!
+//! Intermittent failures rarely land on a run a minute apart. While the
+//! problem is reproduced in OpenCode, a trace repeats the enabled network
+//! checks every 5s on its own thread, keeps every result and condenses them
+//! into a summary: how often each check failed, its latency spread and when
+//! the failures happened. Traced results stay out of the cards and history.
+
+use crate::diagnostics::{api, check_internet, ids, isolated, network, quic, run_limited, traffic, NetworkJob};
+use crate::diagnostics::{CheckResult, CheckStatus, DiagnosticSettings, HttpProfile};
+use chrono::NaiveDateTime;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const TRACE_DURATION: Duration = Duration::from_secs(5 * 60);
+
+/// Time between the starts of two rounds
+pub const TRACE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Checks a trace repeats, when enabled
+pub const NETWORK_CHECKS: &[&str] = &[ids::GATEWAY, ids::INTERNET, ids::QUIC, ids::CLAUDE, ids::OPENAI, ids::GOOGLE];
+
+/// Failure windows listed per check in the summary
+const MAX_WINDOWS: usize = 5;
+
+/// One traced result
+#[derive(Clone)]
+pub struct TraceSample {
+    pub time: NaiveDateTime,
+    pub id: &'static str,
+    pub status: CheckStatus,
+    pub latency_ms: Option<u64>,
+    pub details: String,
+}
+
+/// A running (or finished) trace
+pub struct Trace {
+    started: Instant,
+    started_at: NaiveDateTime,
+    samples: Arc<Mutex<Vec<TraceSample>>>,
+    stop: Arc<AtomicBool>,
+    done: Arc<AtomicBool>,
+}
+
+impl Trace {
+    /// Start tracing the network checks enabled in `settings`; `on_round`
+    /// runs after every round (e.g. to repaint)
+    pub fn start(settings: &DiagnosticSettings, on_round: impl Fn() + Send + 'static) -> Result<Self, String> {
+        // API checks stay paused once the traffic cap is reached
+        let settings = if traffic::over_cap(settings.monthly_cap_mb) { traffic::throttled(settings) } else { settings.clone() };
+        let checks: Vec<&'static str> = NETWORK_CHECKS.iter().copied().filter(|id| settings.is_check_enabled(id)).collect();
+        if checks.is_empty() {
+            return Err("No network checks enabled to trace".to_string());
+        }
+        let http = HttpProfile::from_settings(&settings);
+        let limit = settings.network_concurrency as usize;
+        let trace = Self {
+            started: Instant::now(),
+            started_at: chrono::Local::now().naive_local(),
+            samples: Arc::new(Mutex::new(Vec::new())),
+            stop: Arc::new(AtomicBool::new(false)),
+            done: Arc::new(AtomicBool::new(false)),
+        };
+        let (samples, stop, done) = (Arc::clone(&trace.samples), Arc::clone(&trace.stop), Arc::clone(&trace.done));
+        let deadline = trace.started + TRACE_DURATION;
+        std::thread::spawn(move || {
+            while Instant::now() < deadline && !stop.load(Ordering::Relaxed) {
+                let round = Instant::now();
+                let results = round_of(&checks, &http, limit);
+                samples.lock().unwrap().extend(results);
+                on_round();
+                // Sleep in short steps so STOP is quick
+                while round.elapsed() < TRACE_INTERVAL && Instant::now() < deadline && !stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(Duration::from_millis(200));
+                }
+            }
+            traffic::save();
+            done.store(true, Ordering::Relaxed);
+            on_round();
+        });
+        Ok(trace)
+    }
+
+    pub fn samples(&self) -> Vec<TraceSample> {
+        self.samples.lock().unwrap().clone()
+    }
+
+    /// Time left until the trace ends on its own
+    pub fn remaining(&self) -> Duration {
+        TRACE_DURATION.saturating_sub(self.started.elapsed())
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    /// End early (the round in progress still completes)
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn summary(&self) -> String {
+        summarize(&self.samples(), self.started_at)
+    }
+}
+
+/// Run `checks` once, at most `limit` at a time like a regular run
+fn round_of(checks: &[&'static str], http: &HttpProfile, limit: usize) -> Vec<TraceSample> {
+    let time = chrono::Local::now().naive_local();
+    let jobs: Vec<(&'static str, NetworkJob)> = checks.iter().map(|id| (*id, Box::new(move || probe(id, http)) as NetworkJob)).collect();
+    run_limited(jobs, limit)
+        .into_iter()
+        .map(|(id, result)| TraceSample { time, id, status: result.status, latency_ms: result.latency_ms, details: result.details })
+        .collect()
+}
+
+fn probe(id: &str, http: &HttpProfile) -> CheckResult {
+    let name = ids::name(id);
+    match id {
+        ids::GATEWAY => isolated(&name, network::check_gateway),
+        ids::INTERNET => isolated(&name, || check_internet(http)),
+        ids::QUIC => isolated(&name, quic::check_quic),
+        ids::CLAUDE => isolated(&name, || api::check_claude_api(http)),
+        ids::OPENAI => isolated(&name, || api::check_openai_api(http)),
+        ids::GOOGLE => isolated(&name, || api::check_google_api(http)),
+        _ => CheckResult::new(&name, CheckStatus::Inactive, "Not traced"),
+    }
+}
+
+/// Condensed trace: one block per check, then a one-line conclusion
+pub fn summarize(samples: &[TraceSample], started: NaiveDateTime) -> String {
+    let rounds = {
+        let mut times: Vec<NaiveDateTime> = samples.iter().map(|s| s.time).collect();
+        times.dedup();
+        times.len()
+    };
+    let end = samples.last().map(|s| s.time).unwrap_or(started);
+    let mut lines = vec![format!(
+        "TRACE {} - {} :: {} rounds, {}s apart",
+        started.format("%H:%M:%S"),
+        end.format("%H:%M:%S"),
+        rounds,
+        TRACE_INTERVAL.as_secs()
+    )];
+    let mut failing = Vec::new();
+    for id in NETWORK_CHECKS {
+        let traced: Vec<&TraceSample> = samples.iter().filter(|s| s.id == *id).collect();
+        if traced.is_empty() {
+            continue;
+        }
+        let count = |status: CheckStatus| traced.iter().filter(|s| s.status == status).count();
+        let failed = count(CheckStatus::Warning) + count(CheckStatus::Error);
+        let mut line = format!(
+            "{} :: {} OK / {} WARN / {} ERROR",
+            ids::name(id),
+            count(CheckStatus::Ok),
+            count(CheckStatus::Warning),
+            count(CheckStatus::Error)
+        );
+        let mut latencies: Vec<u64> = traced.iter().filter_map(|s| s.latency_ms).collect();
+        latencies.sort_unstable();
+        if let (Some(min), Some(max)) = (latencies.first(), latencies.last()) {
+            let at = |percent: usize| latencies[(latencies.len() * percent / 100).min(latencies.len() - 1)];
+            line.push_str(&format!(" :: {}ms min / {}ms median / {}ms p95 / {}ms max", min, at(50), at(95), max));
+        }
+        lines.push(line);
+        let windows = failure_windows(&traced);
+        for (from, to, sample) in windows.iter().take(MAX_WINDOWS) {
+            let span = if from == to { from.format("%H:%M:%S").to_string() } else { format!("{}-{}", from.format("%H:%M:%S"), to.format("%H:%M:%S")) };
+            lines.push(format!("  {} {} {}", span, sample.status.label(), sample.details));
+        }
+        if windows.len() > MAX_WINDOWS {
+            lines.push(format!("  ... {} more", windows.len() - MAX_WINDOWS));
+        }
+        if failed > 0 {
+            failing.push(format!("{} {} of {}", ids::name(id), failed, traced.len()));
+        }
+    }
+    lines.push(if failing.is_empty() {
+        "No failures while tracing: the network and providers held up".to_string()
+    } else {
+        format!("Failed: {}", failing.join(", "))
+    });
+    lines.join("\n")
+}
+
+/// Runs of consecutive failed samples: first time, last time and the first failure
+fn failure_windows<'a>(traced: &[&'a TraceSample]) -> Vec<(NaiveDateTime, NaiveDateTime, &'a TraceSample)> {
+    let mut windows: Vec<(NaiveDateTime, NaiveDateTime, &TraceSample)> = Vec::new();
+    let mut open = false;
+    for sample in traced {
+        let failed = matches!(sample.status, CheckStatus::Warning | CheckStatus::Error);
+        match windows.last_mut() {
+            Some(window) if failed && open => window.1 = sample.time,
+            _ if failed => windows.push((sample.time, sample.time, sample)),
+            _ => {}
+        }
+        open = failed;
+    }
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_merges_consecutive_failures() {
+        let start = chrono::NaiveDate::from_ymd_opt(2026, 3, 2).and_then(|d| d.and_hms_opt(10, 0, 0)).unwrap();
+        let statuses = [CheckStatus::Ok, CheckStatus::Error, CheckStatus::Error, CheckStatus::Ok, CheckStatus::Warning];
+        let samples: Vec<TraceSample> = statuses
+            .iter()
+            .enumerate()
+            .map(|(i, status)| TraceSample {
+                time: start + chrono::Duration::seconds(5 * i as i64),
+                id: ids::CLAUDE,
+                status: *status,
+                latency_ms: Some(100 * (i as u64 + 1)),
+                details: format!("round {}", i),
+            })
+            .collect();
+        let summary = summarize(&samples, start);
+        for line in [
+            "CLAUDE API :: 2 OK / 1 WARN / 2 ERROR :: 100ms min / 300ms median / 500ms p95 / 500ms max",
+            "  10:00:05-10:00:10 ERROR round 1",
+            "  10:00:20 WARN round 4",
+            "Failed: CLAUDE API 3 of 5",
+        ] {
+            assert!(summary.lines().any(|l| l == line), "missing {:?} in\n{}", line, summary);
+        }
+    }
+}
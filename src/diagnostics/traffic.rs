@@ -0,0 +1,163 @@
+//! Network traffic generated by the checks themselves
+//!
+//! reqwest does not expose wire byte counts, so each request is estimated
+//! from its response headers, body length and a TLS handshake allowance.
+//! Usage is kept per check for the current day and summed for the month, so
+//! a monthly cap can pause the heavy checks on metered connections.
+
+use crate::diagnostics::{ids, DiagnosticSettings};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Checks paused once the monthly cap is reached (full HTTPS requests)
+pub const HEAVY_CHECKS: &[&str] = &[ids::CLAUDE, ids::OPENAI, ids::GOOGLE, ids::RELAY, ids::ENDPOINT_CERTS, ids::SMOKE];
+
+/// Monthly cap presets in MB (0 = no cap)
+pub const CAP_PRESETS: &[(u32, &str)] = &[
+    (0, "OFF"),
+    (50, "50MB"),
+    (200, "200MB"),
+    (1000, "1GB"),
+];
+
+/// Certificate chain and key exchange of a new TLS connection
+#[cfg_attr(not(feature = "net-checks"), allow(dead_code))]
+const TLS_HANDSHAKE_BYTES: u64 = 6_000;
+
+/// Request line, headers and TCP/IP framing of one request
+#[cfg_attr(not(feature = "net-checks"), allow(dead_code))]
+const REQUEST_BYTES: u64 = 500;
+
+/// Assumed for responses without Content-Length (dropped unread, partly received)
+#[cfg_attr(not(feature = "net-checks"), allow(dead_code))]
+const UNKNOWN_BODY_BYTES: u64 = 16_000;
+
+/// Traffic of today and this month
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Usage {
+    /// Local date, "2026-10-15"
+    pub day: String,
+    /// Bytes per check name today
+    pub today: BTreeMap<String, u64>,
+    /// Local month, "2026-10"
+    pub month: String,
+    pub month_bytes: u64,
+}
+
+impl Usage {
+    fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|p| p.join("opencode-diag").join("traffic.json"))
+    }
+
+    fn load() -> Self {
+        Self::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = Self::path().ok_or("No config directory")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize traffic: {}", e))?;
+        std::fs::write(&path, json).map_err(|e| format!("Failed to write traffic: {}", e))
+    }
+
+    /// Start a new day / month when the date changed
+    fn roll(&mut self) {
+        let now = chrono::Local::now();
+        let day = now.format("%Y-%m-%d").to_string();
+        let month = now.format("%Y-%m").to_string();
+        if self.month != month {
+            self.month = month;
+            self.month_bytes = 0;
+        }
+        if self.day != day {
+            self.day = day;
+            self.today.clear();
+        }
+    }
+
+    pub fn today_bytes(&self) -> u64 {
+        self.today.values().sum()
+    }
+}
+
+static USAGE: Mutex<Option<Usage>> = Mutex::new(None);
+
+fn with_usage<T>(f: impl FnOnce(&mut Usage) -> T) -> T {
+    let mut usage = USAGE.lock().unwrap_or_else(|e| e.into_inner());
+    let usage = usage.get_or_insert_with(Usage::load);
+    usage.roll();
+    f(usage)
+}
+
+/// Current usage (loaded from disk on first use)
+pub fn usage() -> Usage {
+    with_usage(|usage| usage.clone())
+}
+
+/// Write usage to disk (after each run)
+pub fn save() {
+    let _ = with_usage(|usage| usage.save());
+}
+
+/// Add `bytes` to the check running on this thread (see `isolated`)
+pub fn record(bytes: u64) {
+    let name = std::thread::current()
+        .name()
+        .and_then(|name| name.strip_prefix("check "))
+        .unwrap_or("OTHER")
+        .to_string();
+    with_usage(|usage| {
+        *usage.today.entry(name).or_default() += bytes;
+        usage.month_bytes += bytes;
+    });
+}
+
+/// Record the estimated size of one HTTP exchange with `url`
+#[cfg(feature = "net-checks")]
+pub fn record_http(url: &str, result: &reqwest::Result<reqwest::blocking::Response>) {
+    let tls = if url.starts_with("https://") { TLS_HANDSHAKE_BYTES } else { 0 };
+    let bytes = match result {
+        Ok(response) => {
+            let headers: u64 = response
+                .headers()
+                .iter()
+                .map(|(name, value)| (name.as_str().len() + value.len() + 4) as u64)
+                .sum();
+            REQUEST_BYTES + tls + headers + response.content_length().unwrap_or(UNKNOWN_BODY_BYTES)
+        }
+        // Nothing beyond a few SYNs
+        Err(e) if e.is_connect() => 0,
+        Err(_) => REQUEST_BYTES + tls,
+    };
+    record(bytes);
+}
+
+/// Monthly cap set and reached
+pub fn over_cap(cap_mb: u32) -> bool {
+    cap_mb > 0 && usage().month_bytes >= cap_mb as u64 * 1_000_000
+}
+
+/// Settings with the heavy checks turned off
+pub fn throttled(settings: &DiagnosticSettings) -> DiagnosticSettings {
+    let mut settings = settings.clone();
+    for id in HEAVY_CHECKS {
+        settings.set_check_enabled(id, false);
+    }
+    settings
+}
+
+/// "512 B" / "48.2 KB" / "12.3 MB"
+pub fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..=999 => format!("{} B", bytes),
+        1_000..=999_999 => format!("{:.1} KB", bytes as f64 / 1e3),
+        _ => format!("{:.1} MB", bytes as f64 / 1e6),
+    }
+}
@@ -0,0 +1,153 @@
+//! Upload of run history to the user's own endpoint
+//!
+//! For teams collecting results centrally without a vendor service: once per
+//! interval, the runs recorded since the last upload are sent as one JSON
+//! batch to a URL the user configured (their own server, or a presigned S3
+//! URL, which gets a PUT). The newest uploaded run is remembered on disk, so
+//! nothing is sent twice and a failed batch is retried next time.
+
+use crate::diagnostics::history::{HistoryEntry, TIMESTAMP_FORMAT};
+use crate::diagnostics::{DiagnosticSettings, History};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Preset upload intervals in minutes
+pub const UPLOAD_INTERVAL_PRESETS: &[(u32, &str)] = &[
+    (15, "15M"),
+    (60, "1H"),
+    (360, "6H"),
+    (1440, "1D"),
+];
+
+/// Runs per request; a longer backlog goes out over the next intervals
+const MAX_BATCH: usize = 500;
+
+/// What was uploaded and when the last attempt was made
+#[derive(Default, Serialize, Deserialize)]
+struct Cursor {
+    /// Timestamp of the newest run uploaded
+    uploaded: String,
+    last_attempt: String,
+}
+
+impl Cursor {
+    fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|p| p.join("opencode-diag").join("upload.json"))
+    }
+
+    fn load() -> Self {
+        Self::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = Self::path().ok_or("No config directory")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize upload state: {}", e))?;
+        std::fs::write(&path, json).map_err(|e| format!("Failed to write upload state: {}", e))
+    }
+}
+
+/// Request body
+#[derive(Serialize)]
+#[cfg_attr(not(feature = "net-checks"), allow(dead_code))]
+struct Batch<'a> {
+    host: String,
+    app_version: &'static str,
+    entries: &'a [HistoryEntry],
+}
+
+/// Held while an upload is in flight (runs can finish back to back)
+static BUSY: Mutex<()> = Mutex::new(());
+
+/// Outcome of the last upload, for settings
+static STATUS: Mutex<Option<String>> = Mutex::new(None);
+
+/// "Uploaded 42 runs at 12:00:00" / "Upload failed at 12:00:00: HTTP 403"
+pub fn status() -> Option<String> {
+    STATUS.lock().unwrap().clone()
+}
+
+/// Upload the runs recorded since the last upload once the interval has
+/// passed (blocks on the request; call it off the UI thread)
+pub fn upload_if_due(history: &Mutex<History>, settings: &DiagnosticSettings) {
+    let url = settings.upload_url.trim();
+    if !settings.upload_enabled || url.is_empty() {
+        return;
+    }
+    let Ok(_busy) = BUSY.try_lock() else {
+        return;
+    };
+    let mut cursor = Cursor::load();
+    let now = chrono::Local::now().naive_local();
+    let interval = chrono::Duration::minutes(settings.upload_interval_mins.max(1) as i64);
+    let last_attempt = chrono::NaiveDateTime::parse_from_str(&cursor.last_attempt, TIMESTAMP_FORMAT).ok();
+    if last_attempt.is_some_and(|at| now - at < interval) {
+        return;
+    }
+
+    // Timestamps sort as text
+    let entries: Vec<HistoryEntry> = history
+        .lock()
+        .unwrap()
+        .entries
+        .iter()
+        .filter(|e| e.timestamp > cursor.uploaded)
+        .take(MAX_BATCH)
+        .cloned()
+        .collect();
+    cursor.last_attempt = now.format(TIMESTAMP_FORMAT).to_string();
+    if let Some(last) = entries.last() {
+        let time = now.format("%H:%M:%S");
+        *STATUS.lock().unwrap() = Some(match send(url, settings.upload_auth.trim(), &entries) {
+            Ok(()) => {
+                cursor.uploaded = last.timestamp.clone();
+                format!("Uploaded {} runs at {}", entries.len(), time)
+            }
+            Err(e) => format!("Upload failed at {}: {}", time, e),
+        });
+    }
+    let _ = cursor.save();
+}
+
+/// S3 (and compatible) presigned URLs only accept the method they were signed for: PUT
+#[cfg_attr(not(feature = "net-checks"), allow(dead_code))]
+fn is_presigned(url: &str) -> bool {
+    url.contains("X-Amz-Signature=") || url.contains("x-amz-signature=")
+}
+
+/// Send one batch; `auth` is sent as the Authorization header when set
+#[cfg(feature = "net-checks")]
+pub fn send(url: &str, auth: &str, entries: &[HistoryEntry]) -> Result<(), String> {
+    use crate::diagnostics::http::DEFAULT_USER_AGENT;
+    use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+
+    let batch = Batch { host: sysinfo::System::host_name().unwrap_or_default(), app_version: env!("CARGO_PKG_VERSION"), entries };
+    let body = serde_json::to_vec(&batch).map_err(|e| format!("Failed to serialize history: {}", e))?;
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .user_agent(DEFAULT_USER_AGENT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut request = if is_presigned(url) { client.put(url) } else { client.post(url) };
+    request = request.header(CONTENT_TYPE, "application/json").body(body);
+    if !auth.is_empty() {
+        request = request.header(AUTHORIZATION, auth);
+    }
+    let response = request.send().map_err(|e| e.without_url().to_string())?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", response.status().as_u16()))
+    }
+}
+
+#[cfg(not(feature = "net-checks"))]
+pub fn send(_url: &str, _auth: &str, _entries: &[HistoryEntry]) -> Result<(), String> {
+    Err("Built without network checks (net-checks feature)".to_string())
+}
@@ -0,0 +1,215 @@
+//! "Is it me or them?"
+//!
+//! The question behind nearly every run. The local network checks, the
+//! providers' status pages, the relay comparison and the capacity analyzer
+//! each answer part of it; the verdict weighs them in that order and says
+//! which side the problem is on in one plain sentence. History adds how
+//! long the deciding check has been failing.
+
+use crate::diagnostics::capacity::{self, Cause};
+use crate::diagnostics::{ids, relay, statuspage, CheckResult, CheckStatus, DiagnosticReport, History};
+
+/// Which side of the connection is at fault
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Side {
+    /// This machine or its network
+    You,
+    /// The provider
+    Them,
+    /// Nothing is wrong
+    Neither,
+    /// Something fails but the evidence points nowhere
+    Unclear,
+}
+
+impl Side {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Side::You => "It's you",
+            Side::Them => "It's them",
+            Side::Neither => "All clear",
+            Side::Unclear => "Unclear",
+        }
+    }
+
+    /// Status the verdict is drawn in
+    pub fn status(&self) -> CheckStatus {
+        match self {
+            Side::You => CheckStatus::Error,
+            Side::Them | Side::Unclear => CheckStatus::Warning,
+            Side::Neither => CheckStatus::Ok,
+        }
+    }
+}
+
+pub struct Verdict {
+    pub side: Side,
+    /// "Anthropic incident ongoing" / "DNS failing locally"
+    pub reason: String,
+    /// What else supports it, one line each
+    pub evidence: Vec<String>,
+    /// Check that decided it, for the history streak
+    pub check_id: Option<&'static str>,
+}
+
+impl Verdict {
+    fn new(side: Side, reason: &str, check_id: Option<&'static str>) -> Self {
+        Self { side, reason: reason.to_string(), evidence: Vec::new(), check_id }
+    }
+
+    /// "It's them: Anthropic incident ongoing"
+    pub fn headline(&self) -> String {
+        format!("{}: {}", self.side.label(), self.reason)
+    }
+}
+
+/// Provider API checks with their provider names
+fn providers(report: &DiagnosticReport) -> [(&'static str, &'static str, &Option<CheckResult>); 3] {
+    [
+        (ids::CLAUDE, "Anthropic", &report.claude_api),
+        (ids::OPENAI, "OpenAI", &report.openai_api),
+        (ids::GOOGLE, "Google", &report.google_api),
+    ]
+}
+
+fn has_status(check: &Option<CheckResult>, status: CheckStatus) -> bool {
+    check.as_ref().is_some_and(|c| c.status == status)
+}
+
+/// The verdict for `report`, `None` before any network or API check has run
+pub fn decide(report: &DiagnosticReport, history: &History) -> Option<Verdict> {
+    let mut verdict = decide_report(report)?;
+    if let Some(streak) = verdict.check_id.and_then(|id| failing_streak(history, id)) {
+        verdict.evidence.push(streak);
+    }
+    Some(verdict)
+}
+
+fn decide_report(report: &DiagnosticReport) -> Option<Verdict> {
+    let apis = providers(report);
+    if report.gateway.is_none() && report.internet.is_none() && apis.iter().all(|(_, _, c)| c.is_none()) {
+        return None;
+    }
+    let failing: Vec<(&'static str, &'static str)> = apis
+        .iter()
+        .filter(|(_, _, check)| has_status(check, CheckStatus::Error))
+        .map(|(id, name, _)| (*id, *name))
+        .collect();
+
+    // No network at all: nothing else can be judged
+    if has_status(&report.gateway, CheckStatus::Error) {
+        return Some(Verdict::new(Side::You, "no network connection", Some(ids::GATEWAY)));
+    }
+    if has_status(&report.internet, CheckStatus::Error) {
+        let mut verdict = Verdict::new(Side::You, "no internet connection", Some(ids::INTERNET));
+        if has_status(&report.gateway, CheckStatus::Ok) {
+            verdict.evidence.push("Router answers; the line or the ISP is down".to_string());
+        }
+        return Some(verdict);
+    }
+    // Reaching an address but not a name is DNS
+    let dns_failing = report.internet.as_ref().is_some_and(|c| {
+        c.status == CheckStatus::Warning && c.evidence.as_ref().is_some_and(|e| e.unreachable_by_name)
+    });
+    if dns_failing {
+        let mut verdict = Verdict::new(Side::You, "DNS failing locally", Some(ids::INTERNET));
+        verdict.evidence.push("google.com unreachable, 1.1.1.1 reachable by address".to_string());
+        return Some(verdict);
+    }
+    if has_status(&report.firewall, CheckStatus::Error) {
+        return Some(Verdict::new(Side::You, "firewall blocking connections", Some(ids::FIREWALL)));
+    }
+
+    // The provider's own word
+    let incidents: Vec<_> = report.status_pages.iter().filter(|s| s.has_incident()).collect();
+    if let Some(page) = incidents
+        .iter()
+        .find(|s| s.is_major() || failing.iter().any(|(id, _)| *id == s.id))
+    {
+        let check_id = statuspage::PAGES.iter().map(|(id, _, _)| *id).find(|id| *id == page.id);
+        let mut verdict = Verdict::new(Side::Them, &format!("{} incident ongoing", page.provider), check_id);
+        verdict.evidence.push(format!("{} status page: {}", page.provider, page.description));
+        return Some(verdict);
+    }
+
+    let capacity = capacity::analyze(report);
+    let relay_outage = report
+        .relay
+        .as_ref()
+        .is_some_and(|r| r.message.as_deref().is_some_and(|m| m.contains(relay::OUTAGE_NOTE)));
+    if let Some((id, name)) = failing.first() {
+        if relay_outage {
+            let mut verdict = Verdict::new(Side::Them, &format!("{} failing for everyone", name), Some(*id));
+            verdict.evidence.push("The relay sees the same failure from another network".to_string());
+            return Some(verdict);
+        }
+        if has_status(&report.relay, CheckStatus::Warning) {
+            let mut verdict = Verdict::new(Side::You, &format!("{} blocked on this network", name), Some(*id));
+            verdict.evidence.push("The relay reaches the provider from another network; check proxy, VPN and firewall".to_string());
+            return Some(verdict);
+        }
+        if let Some(capacity) = capacity.filter(|v| v.confidence >= 50) {
+            let side = if capacity.cause == Cause::Provider { Side::Them } else { Side::You };
+            let mut verdict = Verdict::new(side, capacity.cause.label(), Some(*id));
+            verdict.evidence.push(format!("{}% confidence: {}", capacity.confidence, capacity.evidence.join(", ")));
+            return Some(verdict);
+        }
+        let mut verdict = Verdict::new(Side::Unclear, &format!("{} API failing, local network fine", name), Some(*id));
+        if failing.len() == 1 && apis.iter().any(|(_, _, c)| has_status(c, CheckStatus::Ok)) {
+            verdict.evidence.push("Other providers answer, which points at the provider".to_string());
+        }
+        if report.relay.is_none() {
+            verdict.evidence.push("Enable the relay comparison to tell for sure".to_string());
+        }
+        return Some(verdict);
+    }
+
+    let mut verdict = Verdict::new(Side::Neither, "network and providers answering", None);
+    verdict.evidence.extend(incidents.iter().map(|s| format!("{} status page: {}", s.provider, s.description)));
+    Some(verdict)
+}
+
+/// "Failing since 09:42 (5 runs in a row)" when the check failed in more than
+/// the latest run
+fn failing_streak(history: &History, id: &str) -> Option<String> {
+    let streak: Vec<_> = history
+        .entries
+        .iter()
+        .rev()
+        .take_while(|entry| entry.check(id).is_some_and(|c| matches!(c.status, CheckStatus::Error | CheckStatus::Warning)))
+        .collect();
+    let first = streak.last()?.time()?;
+    let format = if first.date() == chrono::Local::now().date_naive() { "%H:%M" } else { "%a %H:%M" };
+    (streak.len() > 1).then(|| format!("{} failing since {} ({} runs in a row)", ids::name(id), first.format(format), streak.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headline(report: &DiagnosticReport) -> String {
+        decide(report, &History::default()).map(|v| v.headline()).unwrap_or_default()
+    }
+
+    #[test]
+    fn unreachable_by_name_is_local_dns() {
+        let evidence = capacity::Evidence { unreachable_by_name: true, ..Default::default() };
+        let internet = CheckResult::new("INTERNET", CheckStatus::Warning, "google.com unreachable, cloudflare OK").with_evidence(evidence);
+        let report = DiagnosticReport { internet: Some(internet), ..Default::default() };
+        assert_eq!(headline(&report), "It's you: DNS failing locally");
+    }
+
+    #[test]
+    fn details_wording_alone_is_not_dns() {
+        let internet = CheckResult::new("INTERNET", CheckStatus::Warning, "google.com unreachable, cloudflare OK");
+        let report = DiagnosticReport { internet: Some(internet), ..Default::default() };
+        assert_ne!(headline(&report), "It's you: DNS failing locally");
+    }
+
+    #[test]
+    fn reachable_network_is_all_clear() {
+        let internet = CheckResult::new("INTERNET", CheckStatus::Ok, "PING: 20ms :: google.com reachable");
+        let report = DiagnosticReport { internet: Some(internet), ..Default::default() };
+        assert_eq!(headline(&report), "All clear: network and providers answering");
+    }
+}
@@ -0,0 +1,229 @@
+//! Guided troubleshooting wizard
+//!
+//! Each symptom runs a targeted set of checks, then walks through remediation
+//! steps. Every step names the checks that prove it worked, so "VERIFY"
+//! re-runs just those and reports whether the fix took.
+
+use crate::diagnostics::{ids, CheckStatus, DiagnosticReport, DiagnosticSettings};
+
+/// What the user is experiencing
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Symptom {
+    ServerAtCapacity,
+    WontStart,
+    SlowResponses,
+}
+
+/// Symptoms offered on the first wizard page
+pub const SYMPTOMS: &[(Symptom, &str)] = &[
+    (Symptom::ServerAtCapacity, "I get \"server at capacity\""),
+    (Symptom::WontStart, "OpenCode won't start"),
+    (Symptom::SlowResponses, "Responses are slow"),
+];
+
+/// One remediation step
+pub struct WizardStep {
+    pub title: &'static str,
+    pub instruction: &'static str,
+    /// Checks that must pass after the step
+    pub verify: &'static [&'static str],
+    /// Whether an Inactive result counts as passing (false when the step is "start it")
+    pub pass_on_inactive: bool,
+}
+
+const CAPACITY_STEPS: &[WizardStep] = &[
+    WizardStep {
+        title: "Confirm internet",
+        instruction: "Make sure you are online. If a VPN or proxy is active, disable it temporarily.",
+        verify: &[ids::INTERNET],
+        pass_on_inactive: true,
+    },
+    WizardStep {
+        title: "Check provider status",
+        instruction: "503/529 from CLAUDE API means the provider is overloaded. Check status.anthropic.com and wait a few minutes.",
+        verify: &[ids::CLAUDE],
+        pass_on_inactive: true,
+    },
+    WizardStep {
+        title: "Reduce parallel agents",
+        instruction: "429 means rate limited. Close extra OpenCode sessions sharing the same API key, then verify.",
+        verify: &[ids::CLAUDE, ids::TERMINALS],
+        pass_on_inactive: true,
+    },
+    WizardStep {
+        title: "Switch provider",
+        instruction: "If Claude stays overloaded while other providers are OK, switch the OpenCode model to another provider for now.",
+        verify: &[ids::OPENAI, ids::GOOGLE],
+        pass_on_inactive: true,
+    },
+];
+
+const WONT_START_STEPS: &[WizardStep] = &[
+    WizardStep {
+        title: "Free system resources",
+        instruction: "CPU or RAM is near its limit. Close heavy applications before starting OpenCode.",
+        verify: &[ids::RESOURCES],
+        pass_on_inactive: true,
+    },
+    WizardStep {
+        title: "Close stale sessions",
+        instruction: "Many open terminals can mean stuck agents. Close leftover terminals and OpenCode processes (Task Manager).",
+        verify: &[ids::TERMINALS],
+        pass_on_inactive: true,
+    },
+    WizardStep {
+        title: "Check network",
+        instruction: "OpenCode needs the network at startup. Reconnect or disable a misbehaving VPN.",
+        verify: &[ids::INTERNET],
+        pass_on_inactive: true,
+    },
+    WizardStep {
+        title: "Launch OpenCode",
+        instruction: "Start `opencode` from a fresh terminal. If it exits immediately, the terminal shows the error to report.",
+        verify: &[ids::OPENCODE],
+        pass_on_inactive: false,
+    },
+];
+
+const SLOW_STEPS: &[WizardStep] = &[
+    WizardStep {
+        title: "Check local load",
+        instruction: "High CPU/RAM slows everything, including OpenCode's UI. Close heavy applications.",
+        verify: &[ids::RESOURCES],
+        pass_on_inactive: true,
+    },
+    WizardStep {
+        title: "Check connection",
+        instruction: "PING over 2000ms means a slow connection. Pause large downloads or cloud sync, or switch networks.",
+        verify: &[ids::INTERNET],
+        pass_on_inactive: true,
+    },
+    WizardStep {
+        title: "Check API latency",
+        instruction: "A slow CLAUDE API usually means provider load. Try again off-peak or switch models.",
+        verify: &[ids::CLAUDE],
+        pass_on_inactive: true,
+    },
+    WizardStep {
+        title: "Restart long sessions",
+        instruction: "OpenCode above 2GB memory gets sluggish. Restart it to clear long sessions.",
+        verify: &[ids::OPENCODE],
+        pass_on_inactive: true,
+    },
+];
+
+impl Symptom {
+    pub fn label(&self) -> &'static str {
+        SYMPTOMS
+            .iter()
+            .find(|(s, _)| s == self)
+            .map(|(_, label)| *label)
+            .unwrap_or("")
+    }
+
+    pub fn steps(&self) -> &'static [WizardStep] {
+        match self {
+            Symptom::ServerAtCapacity => CAPACITY_STEPS,
+            Symptom::WontStart => WONT_START_STEPS,
+            Symptom::SlowResponses => SLOW_STEPS,
+        }
+    }
+}
+
+/// Outcome of verifying a step
+#[derive(Clone)]
+pub struct StepResult {
+    pub passed: bool,
+    /// "NAME: details" for each failing check
+    pub failing: Vec<String>,
+}
+
+/// Wizard progress for one symptom
+pub struct Wizard {
+    pub symptom: Symptom,
+    pub step: usize,
+    pub results: Vec<Option<StepResult>>,
+    /// Waiting for the initial scan (all steps) or a single step verification
+    pub awaiting: Option<Awaiting>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Awaiting {
+    InitialScan,
+    Step(usize),
+}
+
+impl Wizard {
+    pub fn new(symptom: Symptom) -> Self {
+        Self {
+            symptom,
+            step: 0,
+            results: vec![None; symptom.steps().len()],
+            awaiting: Some(Awaiting::InitialScan),
+        }
+    }
+
+    pub fn current(&self) -> &'static WizardStep {
+        &self.symptom.steps()[self.step]
+    }
+
+    /// Settings that run only the checks needed for the pending verification
+    pub fn settings_for(&self, base: &DiagnosticSettings) -> DiagnosticSettings {
+        let steps = self.symptom.steps();
+        let wanted: Vec<&str> = match self.awaiting {
+            Some(Awaiting::Step(i)) => steps[i].verify.to_vec(),
+            _ => steps.iter().flat_map(|s| s.verify.iter().copied()).collect(),
+        };
+
+        let mut settings = base.clone();
+        for id in ids::ALL {
+            settings.set_check_enabled(id, wanted.contains(id));
+        }
+        settings
+    }
+
+    /// Consume a completed report for the pending verification
+    pub fn apply_report(&mut self, report: &DiagnosticReport) {
+        match self.awaiting.take() {
+            Some(Awaiting::InitialScan) => {
+                for (i, step) in self.symptom.steps().iter().enumerate() {
+                    self.results[i] = Some(evaluate(step, report));
+                }
+                // Start at the first step that needs attention
+                self.step = self.results
+                    .iter()
+                    .position(|r| r.as_ref().map(|r| !r.passed).unwrap_or(false))
+                    .unwrap_or(0);
+            }
+            Some(Awaiting::Step(i)) => {
+                self.results[i] = Some(evaluate(&self.symptom.steps()[i], report));
+            }
+            None => {}
+        }
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.step + 1 >= self.symptom.steps().len()
+    }
+}
+
+/// Check a step's verification checks against a report
+fn evaluate(step: &WizardStep, report: &DiagnosticReport) -> StepResult {
+    let checks = report.checks();
+    let failing: Vec<String> = step
+        .verify
+        .iter()
+        .filter_map(|id| checks.iter().find(|(cid, _)| cid == id))
+        .filter(|(_, check)| match check.status {
+            CheckStatus::Ok => false,
+            CheckStatus::Inactive => !step.pass_on_inactive,
+            _ => true,
+        })
+        .map(|(_, check)| format!("{}: {}", check.name, check.details))
+        .collect();
+
+    StepResult {
+        passed: failing.is_empty(),
+        failing,
+    }
+}
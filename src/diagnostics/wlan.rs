@@ -0,0 +1,168 @@
+//! Wi-Fi adapter and network name
+//!
+//! `netsh wlan show interfaces` labels its lines in the system language
+//! ("Name" and "SSID" only on English Windows), so Windows asks the WLAN API
+//! instead; macOS and Linux tools run with the C locale. Runs, the network
+//! watcher and the Wi-Fi restart all ask, so a read is reused for a moment.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a read is reused
+const CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Last read and when it was taken
+static CACHE: Mutex<Option<(Instant, Wifi)>> = Mutex::new(None);
+
+#[derive(Clone, Default)]
+struct Wifi {
+    /// Adapter name as `netsh interface` knows it ("Wi-Fi", "WLAN", ...)
+    interface: Option<String>,
+    /// Network it is connected to
+    ssid: Option<String>,
+}
+
+/// Name of the Wi-Fi adapter, the connected one first (Windows only)
+pub fn interface() -> Option<String> {
+    current().interface
+}
+
+/// Name of the Wi-Fi network this machine is on
+pub fn ssid() -> Option<String> {
+    current().ssid
+}
+
+fn current() -> Wifi {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((_, wifi)) = cache.as_ref().filter(|(read_at, _)| read_at.elapsed() < CACHE_TTL) {
+        return wifi.clone();
+    }
+    let wifi = read();
+    *cache = Some((Instant::now(), wifi.clone()));
+    wifi
+}
+
+/// An SSID is up to 32 bytes, usually UTF-8
+fn ssid_text(bytes: &[u8]) -> Option<String> {
+    Some(String::from_utf8_lossy(bytes).trim().to_string()).filter(|ssid| !ssid.is_empty())
+}
+
+#[cfg(target_os = "windows")]
+fn read() -> Wifi {
+    use std::ptr::null_mut;
+    use winapi::shared::winerror::ERROR_SUCCESS;
+    use winapi::um::wlanapi::{
+        wlan_interface_state_connected, WlanCloseHandle, WlanEnumInterfaces, WlanFreeMemory, WlanOpenHandle,
+        PWLAN_INTERFACE_INFO_LIST,
+    };
+
+    let mut wifi = Wifi::default();
+    let (mut version, mut handle) = (0, null_mut());
+    if unsafe { WlanOpenHandle(2, null_mut(), &mut version, &mut handle) } != ERROR_SUCCESS {
+        return wifi;
+    }
+    let mut list: PWLAN_INTERFACE_INFO_LIST = null_mut();
+    if unsafe { WlanEnumInterfaces(handle, null_mut(), &mut list) } == ERROR_SUCCESS {
+        let interfaces = unsafe { std::slice::from_raw_parts((*list).InterfaceInfo.as_ptr(), (*list).dwNumberOfItems as usize) };
+        let connected = interfaces.iter().find(|info| info.isState == wlan_interface_state_connected);
+        if let Some(info) = connected.or(interfaces.first()) {
+            wifi.interface = interface_alias(&info.InterfaceGuid);
+        }
+        wifi.ssid = connected.and_then(|info| connected_ssid(handle, &info.InterfaceGuid));
+        unsafe { WlanFreeMemory(list.cast()) };
+    }
+    unsafe { WlanCloseHandle(handle, null_mut()) };
+    wifi
+}
+
+/// "Wi-Fi" for the adapter's GUID (the alias `netsh interface` takes)
+#[cfg(target_os = "windows")]
+fn interface_alias(guid: &winapi::shared::guiddef::GUID) -> Option<String> {
+    use winapi::shared::ifdef::{IF_MAX_STRING_SIZE, NET_LUID};
+    use winapi::shared::netioapi::{ConvertInterfaceGuidToLuid, ConvertInterfaceLuidToAlias};
+    use winapi::shared::winerror::NO_ERROR;
+
+    let mut luid: NET_LUID = unsafe { std::mem::zeroed() };
+    if unsafe { ConvertInterfaceGuidToLuid(guid, &mut luid) } != NO_ERROR {
+        return None;
+    }
+    let mut alias = [0u16; IF_MAX_STRING_SIZE + 1];
+    if unsafe { ConvertInterfaceLuidToAlias(&luid, alias.as_mut_ptr(), alias.len()) } != NO_ERROR {
+        return None;
+    }
+    let len = alias.iter().position(|&c| c == 0).unwrap_or(alias.len());
+    Some(String::from_utf16_lossy(&alias[..len])).filter(|alias| !alias.is_empty())
+}
+
+/// SSID of the connection on interface `guid`
+#[cfg(target_os = "windows")]
+fn connected_ssid(handle: winapi::um::winnt::HANDLE, guid: &winapi::shared::guiddef::GUID) -> Option<String> {
+    use std::ptr::null_mut;
+    use winapi::shared::winerror::ERROR_SUCCESS;
+    use winapi::um::wlanapi::{wlan_intf_opcode_current_connection, WlanFreeMemory, WlanQueryInterface, WLAN_CONNECTION_ATTRIBUTES};
+
+    let (mut size, mut data) = (0, null_mut());
+    let status = unsafe {
+        WlanQueryInterface(handle, guid, wlan_intf_opcode_current_connection, null_mut(), &mut size, &mut data, null_mut())
+    };
+    if status != ERROR_SUCCESS || data.is_null() {
+        return None;
+    }
+    let ssid = unsafe { &(*(data as *const WLAN_CONNECTION_ATTRIBUTES)).wlanAssociationAttributes.dot11Ssid };
+    let len = (ssid.uSSIDLength as usize).min(ssid.ucSSID.len());
+    let text = ssid_text(&ssid.ucSSID[..len]);
+    unsafe { WlanFreeMemory(data) };
+    text
+}
+
+/// "Current Wi-Fi Network: HomeWifi"
+#[cfg(target_os = "macos")]
+fn read() -> Wifi {
+    let output = crate::diagnostics::command("networksetup").args(["-getairportnetwork", "en0"]).env("LC_ALL", "C").output();
+    let ssid = output.ok().and_then(|output| {
+        let text = String::from_utf8_lossy(&output.stdout).into_owned();
+        ssid_text(text.split_once("Network:")?.1.as_bytes())
+    });
+    Wifi { interface: None, ssid }
+}
+
+/// From the cached scan, no rescan
+#[cfg(target_os = "linux")]
+fn read() -> Wifi {
+    let output = crate::diagnostics::command("nmcli")
+        .args(["-t", "-f", "active,ssid", "dev", "wifi", "list", "--rescan", "no"])
+        .env("LC_ALL", "C")
+        .output();
+    let ssid = output.ok().and_then(|output| active_ssid(&String::from_utf8_lossy(&output.stdout)));
+    Wifi { interface: None, ssid }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn read() -> Wifi {
+    Wifi::default()
+}
+
+/// "yes:HomeWifi" is the active connection in `nmcli -t -f active,ssid`
+#[cfg(any(target_os = "linux", test))]
+fn active_ssid(nmcli: &str) -> Option<String> {
+    nmcli.lines().find_map(|line| ssid_text(line.strip_prefix("yes:")?.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ssid_bytes_become_text() {
+        assert_eq!(ssid_text(b"HomeWifi").as_deref(), Some("HomeWifi"));
+        assert_eq!(ssid_text("Café".as_bytes()).as_deref(), Some("Café"));
+        assert_eq!(ssid_text(b"Lab\xff").as_deref(), Some("Lab\u{fffd}"));
+        assert_eq!(ssid_text(b""), None);
+    }
+
+    #[test]
+    fn only_the_active_network_counts() {
+        assert_eq!(active_ssid("no:Neighbor\nyes:HomeWifi\nno:\n").as_deref(), Some("HomeWifi"));
+        assert_eq!(active_ssid("no:Neighbor\nyes:\n"), None);
+    }
+}
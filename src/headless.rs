@@ -0,0 +1,85 @@
+//! Headless mode for builds without the `gui` feature
+//!
+//! Runs the enabled checks once and prints the text report. With the status
+//! page enabled in settings it keeps serving it, re-running the checks every
+//! auto-refresh interval and as soon as the network changes.
+
+use crate::diagnostics::netwatch::{self, NetWatcher};
+use crate::diagnostics::simulate::Simulation;
+use crate::diagnostics::{network, signing, tags, upload, wlan};
+use crate::diagnostics::{DiagnosticReport, DiagnosticSettings, History, SharedReport};
+use crate::server::{ServerState, StatusServer};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub fn run() {
+    let mut settings = DiagnosticSettings::load();
+    let report = SharedReport::default();
+    let history = Arc::new(Mutex::new(History::load()));
+
+    let server = if settings.status_page_enabled {
+        if settings.ensure_api_token() {
+            let _ = settings.save();
+        }
+        let state = ServerState {
+            report: report.clone(),
+            history: Arc::clone(&history),
+            api_token: settings.api_token.clone(),
+            status_colors: settings.status_colors.clone(),
+        };
+        match StatusServer::start(settings.status_page_port, settings.status_page_lan, state) {
+            Ok(server) => {
+                println!("Status page: {}", server.url());
+                Some(server)
+            }
+            Err(e) => {
+                eprintln!("Status page: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let simulation = Simulation::from_args();
+    let mut watcher = settings.run_on_network_change.then(NetWatcher::new);
+    let mut trigger = None;
+    loop {
+        let new_report = match simulation {
+            Some(simulation) => simulation.report(&settings),
+            None => {
+                if !settings.network_profiles.is_empty() {
+                    let network = tags::network_name(wlan::ssid().as_deref(), network::default_gateway());
+                    settings.apply_network(network.as_deref().unwrap_or_default());
+                    settings.enforce_policy();
+                }
+                let mut new_report = DiagnosticReport::new();
+                new_report.run_with_settings(&settings);
+                new_report.trigger = trigger.take();
+                history.lock().unwrap().record(&new_report);
+                upload::upload_if_due(&history, &settings);
+                new_report
+            }
+        };
+        let text = new_report.to_text_report(settings.view_role.sections());
+        match settings.sign_reports.then(|| signing::sign(&text)) {
+            Some(Ok(signed)) => println!("{}", signed),
+            Some(Err(e)) => println!("{}\nNot signed: {}", text, e),
+            None => println!("{}", text),
+        }
+        report.replace(new_report);
+
+        if server.is_none() {
+            return;
+        }
+        // Until the next run is due, or the network changes
+        let due = Instant::now() + Duration::from_secs(settings.refresh_interval_secs.max(1) as u64);
+        while let Some(left) = due.checked_duration_since(Instant::now()).filter(|left| !left.is_zero()) {
+            std::thread::sleep(left.min(netwatch::POLL_INTERVAL));
+            if let Some(change) = watcher.as_mut().and_then(NetWatcher::poll) {
+                trigger = Some(format!("network change ({})", change));
+                break;
+            }
+        }
+    }
+}
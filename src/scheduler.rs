@@ -0,0 +1,128 @@
+//! Event-driven repaint scheduling
+//!
+//! Instead of repainting every second, the UI asks for a repaint at the exact
+//! moment something on screen changes (the next countdown tick, the feedback
+//! timeout). Background watches (session lock, battery, resource monitor) run
+//! on the scheduler thread and wake the UI only when their state changes.
+
+use eframe::egui;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// A periodic background check; `check` returns true when the UI should repaint
+pub struct Watch {
+    interval: Duration,
+    next: Instant,
+    check: Box<dyn FnMut() -> bool + Send>,
+}
+
+impl Watch {
+    pub fn new(interval: Duration, check: impl FnMut() -> bool + Send + 'static) -> Self {
+        Self {
+            interval,
+            next: Instant::now() + interval,
+            check: Box::new(check),
+        }
+    }
+
+    /// Run the first check right away on the scheduler thread instead of after one interval
+    pub fn immediately(mut self) -> Self {
+        self.next = Instant::now();
+        self
+    }
+}
+
+#[derive(Default)]
+struct State {
+    /// Earliest requested repaint
+    deadline: Option<Instant>,
+    stop: bool,
+}
+
+/// Scheduler thread, stopped on drop
+pub struct Scheduler {
+    shared: Arc<(Mutex<State>, Condvar)>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Scheduler {
+    pub fn start(ctx: egui::Context, mut watches: Vec<Watch>) -> Self {
+        let shared = Arc::new((Mutex::new(State::default()), Condvar::new()));
+        let thread_shared = Arc::clone(&shared);
+
+        let handle = thread::spawn(move || {
+            let (lock, cond) = &*thread_shared;
+            loop {
+                let now = Instant::now();
+                {
+                    let mut state = lock.lock().unwrap();
+                    if state.stop {
+                        return;
+                    }
+                    if state.deadline.map(|d| d <= now).unwrap_or(false) {
+                        state.deadline = None;
+                        ctx.request_repaint();
+                    }
+                }
+
+                for watch in watches.iter_mut().filter(|w| w.next <= now) {
+                    if (watch.check)() {
+                        ctx.request_repaint();
+                    }
+                    watch.next = Instant::now() + watch.interval;
+                }
+
+                let state = lock.lock().unwrap();
+                if state.stop {
+                    return;
+                }
+                let wake = watches
+                    .iter()
+                    .map(|w| w.next)
+                    .chain(state.deadline)
+                    .min();
+                let timeout = wake
+                    .map(|w| w.saturating_duration_since(Instant::now()))
+                    .unwrap_or(Duration::from_secs(3600));
+                let _ = cond.wait_timeout(state, timeout);
+            }
+        });
+
+        Self {
+            shared,
+            handle: Some(handle),
+        }
+    }
+
+    /// Repaint at `at` (keeps the earlier time if one is already pending)
+    pub fn repaint_at(&self, at: Instant) {
+        let (lock, cond) = &*self.shared;
+        let mut state = lock.lock().unwrap();
+        if state.deadline.map(|d| at < d).unwrap_or(true) {
+            state.deadline = Some(at);
+            cond.notify_one();
+        }
+    }
+
+    /// Handle for other threads to request an immediate repaint
+    pub fn waker(&self) -> impl Fn() + Send + Sync + 'static {
+        let shared = Arc::clone(&self.shared);
+        move || {
+            let (lock, cond) = &*shared;
+            lock.lock().unwrap().deadline = Some(Instant::now());
+            cond.notify_one();
+        }
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        let (lock, cond) = &*self.shared;
+        lock.lock().unwrap().stop = true;
+        cond.notify_one();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,392 @@
+//! `--self-test`: verify the tool itself works on this machine
+//!
+//! API and internet checks run against a local mock server that answers with
+//! fixed status codes, so their classification is tested without depending on
+//! the real providers. Local checks run for real against known-safe targets
+//! and pass when they complete. Prints a pass/fail matrix and returns whether
+//! everything passed.
+
+use crate::diagnostics::share::{self, SharedSession};
+use crate::diagnostics::simulate::Simulation;
+use crate::diagnostics::{self, editors, firewall, ipc, network, processes, CheckResult, CheckStatus, DiagnosticReport, DiagnosticSettings, ErrorLog, History, ReportSections, SharedReport, SystemSnapshot};
+#[cfg(feature = "net-checks")]
+use crate::diagnostics::{api, upload, verdict, HttpProfile};
+use crate::diagnostics::palette::StatusColors;
+use crate::server::{ServerState, StatusServer};
+use std::io::{Read, Write};
+#[cfg(feature = "net-checks")]
+use std::net::TcpListener;
+use std::net::{Ipv4Addr, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// What a case expects
+enum Expect {
+    Status(CheckStatus),
+    /// Live check: any result is fine as long as it completes
+    Completes,
+    /// Plain condition (server responses, probes)
+    True,
+}
+
+/// One row of the matrix
+struct Case {
+    check: &'static str,
+    scenario: String,
+    expect: Expect,
+    got: String,
+    pass: bool,
+}
+
+impl Case {
+    fn status(check: &'static str, scenario: &str, expected: CheckStatus, result: CheckResult) -> Self {
+        Self {
+            check,
+            scenario: scenario.to_string(),
+            pass: result.status == expected,
+            expect: Expect::Status(expected),
+            got: result.status.label().to_string(),
+        }
+    }
+
+    fn live(check: &'static str, scenario: &str, result: CheckResult) -> Self {
+        Self {
+            check,
+            scenario: scenario.to_string(),
+            expect: Expect::Completes,
+            got: result.status.label().to_string(),
+            pass: true,
+        }
+    }
+
+    fn condition(check: &'static str, scenario: &str, ok: bool, got: String) -> Self {
+        Self {
+            check,
+            scenario: scenario.to_string(),
+            expect: Expect::True,
+            got,
+            pass: ok,
+        }
+    }
+}
+
+/// Run every case and print the matrix, returns true when all passed
+pub fn run() -> bool {
+    println!("opencode-diag v{} self-test\n", env!("CARGO_PKG_VERSION"));
+
+    let mut cases = Vec::new();
+    #[cfg(feature = "net-checks")]
+    cases.extend(net_cases());
+
+    // Firewall: our own loopback listener must be reachable
+    let loopback = firewall::probe_listener(Ipv4Addr::LOCALHOST, None);
+    cases.push(Case::condition(
+        "FIREWALL",
+        "loopback probe",
+        loopback,
+        if loopback { "reachable" } else { "blocked" }.to_string(),
+    ));
+
+    cases.extend(status_server_cases());
+
+    // Simulation: a fixed seed gives the same report every run
+    let mut settings = DiagnosticSettings::default();
+    for id in diagnostics::ids::ALL {
+        settings.set_check_enabled(id, true);
+    }
+    let seeded = Simulation { seed: Some(42) };
+    let first = seeded.report(&settings).to_text_report(ReportSections::default());
+    let same = first == seeded.report(&settings).to_text_report(ReportSections::default());
+    cases.push(Case::condition("SIMULATION", "seed 42 twice", same, if same { "identical" } else { "differs" }.to_string()));
+
+    // Shared session: the access code opens the file, a wrong one does not
+    let session = SharedSession::capture(&seeded.report(&settings), &History::default(), &ErrorLog::new());
+    let (ok, got) = match share::round_trip(&session) {
+        Ok(true) => (true, "sealed".to_string()),
+        Ok(false) => (false, "mismatch".to_string()),
+        Err(e) => (false, e),
+    };
+    cases.push(Case::condition("SHARE", "encrypt/decrypt", ok, got));
+
+    // Report files: COPY REPORT text and the JSON read back into the same checks
+    let report = seeded.report(&settings);
+    let checks = |report: &DiagnosticReport| {
+        report.checks().into_iter().map(|(id, c)| (id, c.status, c.latency_ms, c.details.clone(), c.message.clone())).collect::<Vec<_>>()
+    };
+    let sections = ReportSections { system_info: true, latency_summary: true, ..ReportSections::default() };
+    let text = diagnostics::reportfile::from_text(&report.to_text_report(sections));
+    let json = serde_json::to_string(&report).map_err(|e| e.to_string()).and_then(|json| diagnostics::reportfile::from_json(&json));
+    let read = |file: &Result<diagnostics::reportfile::ReportFile, String>| match file {
+        Ok(file) if checks(&file.report) == checks(&report) => "same".to_string(),
+        Ok(file) => format!("{} of {} checks differ", checks(&file.report).iter().filter(|c| !checks(&report).contains(c)).count(), report.checks().len()),
+        Err(e) => e.clone(),
+    };
+    let (text, json) = (read(&text), read(&json));
+    cases.push(Case::condition("REPORT FILE", "txt / json", text == "same" && json == "same", format!("{} / {}", text, json)));
+
+    // Local checks against the real machine
+    let mut snapshot = SystemSnapshot::new();
+    cases.push(Case::live("RESOURCES", "this machine", diagnostics::check_local_resources(&mut snapshot)));
+    cases.push(Case::live("GPU", "this machine", diagnostics::gpu::check_gpu()));
+    cases.push(Case::live("LONG PATHS", "this machine", diagnostics::longpaths::check_long_paths()));
+    cases.push(Case::live("DEFENDER", "scan overhead", diagnostics::defender::check_defender(&mut snapshot)));
+    cases.push(Case::live("DATA DIRS", "OpenCode directories", diagnostics::datadirs::check_data_dirs()));
+    cases.push(Case::live("CLOUD SYNC", "sync folders", diagnostics::cloudsync::check_cloud_sync(&mut snapshot)));
+    cases.push(Case::live("ROUTER", "default gateway", network::check_gateway()));
+    cases.push(Case::live("QUIC", "provider edges", diagnostics::quic::check_quic()));
+    cases.push(Case::live("BANDWIDTH", "open connections", diagnostics::bandwidth::check_bandwidth(&mut snapshot)));
+    cases.push(Case::live("OPENCODE", "process list", processes::check_opencode_process(&mut snapshot)));
+    cases.push(Case::live("CRASHES", "crash reports", diagnostics::crashes::check_crashes()));
+    cases.push(Case::live("LEAKS", "handles and temp", diagnostics::leaks::check_leaks(&mut snapshot)));
+    cases.push(Case::live("EDITOR IPC", "process list", ipc::check_opencode_ipc(&mut snapshot)));
+    cases.push(Case::live("EDITORS", "installed plugins", editors::check_editors(&mut snapshot)));
+    cases.push(Case::live("TERMINALS", "process list", processes::check_terminals(&mut snapshot)));
+
+    print_matrix(&cases);
+    cases.iter().all(|c| c.pass)
+}
+
+/// API and internet classification against the mock server
+#[cfg(feature = "net-checks")]
+fn net_cases() -> Vec<Case> {
+    let mock = match start_mock_server() {
+        Ok(port) => port,
+        Err(e) => return vec![Case::condition("MOCK SERVER", "start", false, e)],
+    };
+    let url = |path: &str| format!("http://127.0.0.1:{}{}", mock, path);
+    // A port nothing listens on: bind, read the port, close
+    let dead = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .and_then(|l| l.local_addr())
+        .map(|a| format!("http://127.0.0.1:{}/", a.port()))
+        .unwrap_or_else(|_| "http://127.0.0.1:1/".to_string());
+
+    let http = HttpProfile::default();
+    vec![
+        // Claude classification
+        Case::status("CLAUDE API", "mock 200", CheckStatus::Ok, api::check_claude_api_at(&http, &url("/status/200"))),
+        Case::status("CLAUDE API", "mock 401", CheckStatus::Ok, api::check_claude_api_at(&http, &url("/status/401"))),
+        Case::status("CLAUDE API", "mock 429", CheckStatus::Warning, api::check_claude_api_at(&http, &url("/status/429"))),
+        Case::status("CLAUDE API", "mock 503", CheckStatus::Error, api::check_claude_api_at(&http, &url("/status/503"))),
+        Case::status("CLAUDE API", "mock 529", CheckStatus::Error, api::check_claude_api_at(&http, &url("/status/529"))),
+        Case::status("CLAUDE API", "refused", CheckStatus::Error, api::check_claude_api_at(&http, &dead)),
+        // OpenAI classification and error message extraction
+        Case::status("OPENAI API", "mock 401", CheckStatus::Ok, api::check_openai_api_at(&http, &url("/status/401"))),
+        Case::status("OPENAI API", "mock 429", CheckStatus::Warning, api::check_openai_api_at(&http, &url("/status/429"))),
+        Case::status("OPENAI API", "mock 500", CheckStatus::Error, api::check_openai_api_at(&http, &url("/status/500"))),
+        {
+            let result = api::check_openai_api_at(&http, &url("/status/500"));
+            let ok = result.message.as_deref() == Some("mock error 500");
+            Case::condition("OPENAI API", "error message", ok, if ok { "parsed" } else { "missing" }.to_string())
+        },
+        // Google classification
+        Case::status("GOOGLE AI", "mock 403", CheckStatus::Ok, api::check_google_api_at(&http, &url("/status/403"))),
+        Case::status("GOOGLE AI", "mock 429", CheckStatus::Warning, api::check_google_api_at(&http, &url("/status/429"))),
+        Case::status("GOOGLE AI", "mock 502", CheckStatus::Error, api::check_google_api_at(&http, &url("/status/502"))),
+        // Internet primary / fallback logic
+        Case::status(
+            "INTERNET",
+            "primary up",
+            CheckStatus::Ok,
+            diagnostics::check_internet_at(&http, &url("/status/200"), &dead),
+        ),
+        Case::status(
+            "INTERNET",
+            "fallback only",
+            CheckStatus::Warning,
+            diagnostics::check_internet_at(&http, &url("/status/500"), &url("/status/200")),
+        ),
+        Case::status("INTERNET", "both down", CheckStatus::Error, diagnostics::check_internet_at(&http, &dead, &dead)),
+        // Relay comparison: the mock relay reports every provider up
+        {
+            let local = Some(api::check_claude_api_at(&http, &dead));
+            let result = diagnostics::relay::check_relay(&http, &url("/relay"), &[(diagnostics::ids::CLAUDE, &local)]);
+            Case::status("RELAY", "blocked here", CheckStatus::Warning, result)
+        },
+        {
+            let local = Some(api::check_claude_api_at(&http, &url("/status/401")));
+            let result = diagnostics::relay::check_relay(&http, &url("/relay"), &[(diagnostics::ids::CLAUDE, &local)]);
+            Case::status("RELAY", "both up", CheckStatus::Ok, result)
+        },
+        // History upload: a 2xx is accepted, anything else kept for the next attempt
+        {
+            let (accepted, rejected) = (upload::send(&url("/status/200"), "", &[]), upload::send(&url("/status/500"), "Bearer x", &[]));
+            let ok = accepted.is_ok() && rejected.as_ref().is_err_and(|e| e == "HTTP 500");
+            let outcome = |sent: Result<(), String>| sent.err().unwrap_or_else(|| "sent".to_string());
+            Case::condition("UPLOAD", "mock 200 / 500", ok, format!("{} / {}", outcome(accepted), outcome(rejected)))
+        },
+        // Verdict: a failing API plus the provider's status page in an incident
+        {
+            let report = DiagnosticReport {
+                claude_api: Some(api::check_claude_api_at(&http, &dead)),
+                status_pages: vec![diagnostics::statuspage::fetch_page(&http, diagnostics::ids::CLAUDE, "Anthropic", &url("/statuspage"))],
+                ..Default::default()
+            };
+            let headline = verdict::decide(&report, &History::default()).map(|v| v.headline()).unwrap_or_default();
+            Case::condition("VERDICT", "status page", headline == "It's them: Anthropic incident ongoing", headline)
+        },
+        // Capture proxy: what it records of plain HTTP and tunneled traffic
+        capture_proxy_case(mock),
+    ]
+}
+
+/// Status page and REST API on an ephemeral localhost port
+fn status_server_cases() -> Vec<Case> {
+    let token = "self-test-token";
+    let state = ServerState {
+        report: SharedReport::default(),
+        history: Arc::new(Mutex::new(History::default())),
+        api_token: token.to_string(),
+        status_colors: StatusColors::default(),
+    };
+    let server = match StatusServer::start(0, false, state) {
+        Ok(server) => server,
+        Err(e) => return vec![Case::condition("STATUS PAGE", "start", false, e)],
+    };
+    let port = server.port();
+
+    // Plain HTTP/1.1 GET, returns the status code
+    let code = |path: &str, auth: bool| -> String {
+        let request = format!(
+            "GET {} HTTP/1.1\r\nHost: localhost\r\n{}Connection: close\r\n\r\n",
+            path,
+            if auth { format!("Authorization: Bearer {}\r\n", token) } else { String::new() }
+        );
+        let response = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).and_then(|mut stream| {
+            stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+            stream.write_all(request.as_bytes())?;
+            let mut response = String::new();
+            stream.read_to_string(&mut response)?;
+            Ok(response)
+        });
+        response
+            .ok()
+            .and_then(|r| r.split_whitespace().nth(1).map(|c| c.to_string()))
+            .unwrap_or_else(|| "failed".to_string())
+    };
+
+    let page = code("/", false);
+    let no_token = code("/api/report", false);
+    let with_token = code("/api/report", true);
+    vec![
+        Case::condition("STATUS PAGE", "GET /", page == "200", page),
+        Case::condition("REST API", "no token", no_token == "401", no_token),
+        Case::condition("REST API", "bearer token", with_token == "200", with_token),
+    ]
+}
+
+/// Plain HTTP and a CONNECT tunnel through the capture proxy to the mock, and a
+/// tunnel to a port nothing listens on
+#[cfg(feature = "net-checks")]
+fn capture_proxy_case(mock: u16) -> Case {
+    use crate::server::proxy::CaptureProxy;
+    let proxy = match CaptureProxy::start(0, || {}) {
+        Ok(proxy) => proxy,
+        Err(e) => return Case::condition("CAPTURE PROXY", "http, tunnel, dead", false, e),
+    };
+    let addr = proxy.url().trim_start_matches("http://").to_string();
+    let dead = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).and_then(|l| l.local_addr()).map(|a| a.port()).unwrap_or(1);
+    let exchange = |request: String, then: Option<&str>| -> String {
+        let Ok(mut stream) = TcpStream::connect(&addr) else {
+            return String::new();
+        };
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+        let _ = stream.write_all(request.as_bytes());
+        if let Some(then) = then {
+            let mut established = [0u8; 39];
+            if stream.read_exact(&mut established).is_err() || !established.starts_with(b"HTTP/1.1 200") {
+                return String::from_utf8_lossy(&established).into_owned();
+            }
+            let _ = stream.write_all(then.as_bytes());
+        }
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response);
+        response
+    };
+    let plain = exchange(format!("GET http://127.0.0.1:{}/status/204 HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n", mock), None);
+    let tunneled = exchange(format!("CONNECT 127.0.0.1:{} HTTP/1.1\r\n\r\n", mock), Some("GET /status/200 HTTP/1.1\r\n\r\n"));
+    let refused = exchange(format!("CONNECT 127.0.0.1:{} HTTP/1.1\r\n\r\n", dead), None);
+
+    // Entries are recorded once each connection has closed
+    let deadline = std::time::Instant::now() + Duration::from_secs(3);
+    while proxy.entries().len() < 3 && std::time::Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    let mut statuses: Vec<u16> = proxy.entries().iter().map(|e| e.status).collect();
+    statuses.sort();
+    let ok = statuses == [200, 204, 502] && plain.starts_with("HTTP/1.1 204") && tunneled.starts_with("HTTP/1.1 200") && refused.starts_with("HTTP/1.1 502");
+    Case::condition("CAPTURE PROXY", "http, tunnel, dead", ok, statuses.iter().map(u16::to_string).collect::<Vec<_>>().join(","))
+}
+
+/// Serve `/status/NNN` with that status and a JSON error body, `/relay/check`
+/// as a relay and `/statuspage` as a provider status page in an incident, returns the port
+#[cfg(feature = "net-checks")]
+fn start_mock_server() -> Result<u16, String> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).map_err(|e| e.to_string())?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    // Lives until the process exits
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = serve_mock(stream);
+        }
+    });
+    Ok(port)
+}
+
+#[cfg(feature = "net-checks")]
+fn serve_mock(mut stream: TcpStream) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    let mut buf = [0u8; 2048];
+    let n = stream.read(&mut buf)?;
+    let head = String::from_utf8_lossy(&buf[..n]);
+    let path = head.split_whitespace().nth(1).unwrap_or("/");
+    let code: u16 = if path.starts_with("/relay/check") || path.starts_with("/statuspage") {
+        200
+    } else {
+        path.strip_prefix("/status/")
+            .and_then(|c| c.parse().ok())
+            .unwrap_or(404)
+    };
+    let body = if code == 200 && path.starts_with("/relay/check") {
+        r#"{"region":"mock","results":[{"id":"api.claude","status":"Ok","latency_ms":42},{"id":"api.openai","status":"Ok"},{"id":"api.google","status":"Ok"}]}"#.to_string()
+    } else if path.starts_with("/statuspage") {
+        r#"{"status":{"indicator":"major","description":"Partial System Outage"}}"#.to_string()
+    } else {
+        format!("{{\"error\":{{\"message\":\"mock error {}\"}}}}", code)
+    };
+    let response = format!(
+        "HTTP/1.1 {} MOCK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes())
+}
+
+fn print_matrix(cases: &[Case]) {
+    println!("{:<12} {:<16} {:<10} {:<10} RESULT", "CHECK", "SCENARIO", "EXPECT", "GOT");
+    println!("{}", "-".repeat(60));
+    for case in cases {
+        let expect = match case.expect {
+            Expect::Status(status) => status.label(),
+            Expect::Completes => "ANY",
+            Expect::True => "OK",
+        };
+        println!(
+            "{:<12} {:<16} {:<10} {:<10} {}",
+            case.check,
+            case.scenario,
+            expect,
+            case.got,
+            if case.pass { "PASS" } else { "FAIL" }
+        );
+    }
+
+    let failed = cases.iter().filter(|c| !c.pass).count();
+    println!("{}", "-".repeat(60));
+    if failed == 0 {
+        println!("{} passed", cases.len());
+    } else {
+        println!("{} passed, {} FAILED", cases.len() - failed, failed);
+    }
+}
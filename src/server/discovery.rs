@@ -0,0 +1,132 @@
+//! mDNS advertisement and discovery of other opencode-diag agents
+//!
+//! Agents whose status page is visible on the LAN announce themselves as
+//! `_opencode-diag._tcp.local.`; the GUI browses for the same type and lists
+//! what it finds.
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// mDNS service type for status page agents
+pub const SERVICE_TYPE: &str = "_opencode-diag._tcp.local.";
+
+/// A discovered agent on the LAN
+#[derive(Clone)]
+pub struct Agent {
+    pub fullname: String,
+    pub name: String,
+    pub address: String,
+    pub port: u16,
+    pub version: Option<String>,
+}
+
+impl Agent {
+    /// Status page URL of the agent
+    pub fn url(&self) -> String {
+        format!("http://{}:{}/", self.address, self.port)
+    }
+}
+
+/// mDNS daemon handle with the list of currently visible agents
+pub struct Discovery {
+    daemon: ServiceDaemon,
+    agents: Arc<Mutex<Vec<Agent>>>,
+    advertised: Option<(String, u16)>,
+}
+
+impl Discovery {
+    /// Start the daemon and begin browsing for agents; `on_change` runs when the list changes
+    pub fn start(on_change: impl Fn() + Send + 'static) -> Result<Self, String> {
+        let daemon = ServiceDaemon::new().map_err(|e| format!("mDNS init failed: {}", e))?;
+        let receiver = daemon.browse(SERVICE_TYPE)
+            .map_err(|e| format!("mDNS browse failed: {}", e))?;
+
+        let agents: Arc<Mutex<Vec<Agent>>> = Arc::new(Mutex::new(Vec::new()));
+        let list = Arc::clone(&agents);
+
+        // Ends when the daemon shuts down and the channel closes
+        thread::spawn(move || {
+            while let Ok(event) = receiver.recv() {
+                match event {
+                    ServiceEvent::ServiceResolved(info) => {
+                        let Some(address) = info.get_addresses_v4().into_iter().next() else {
+                            continue;
+                        };
+                        let fullname = info.get_fullname().to_string();
+                        let agent = Agent {
+                            name: fullname
+                                .strip_suffix(&format!(".{}", SERVICE_TYPE))
+                                .unwrap_or(&fullname)
+                                .to_string(),
+                            fullname,
+                            address: address.to_string(),
+                            port: info.get_port(),
+                            version: info.get_property_val_str("version").map(|v| v.to_string()),
+                        };
+                        let mut agents = list.lock().unwrap();
+                        agents.retain(|a| a.fullname != agent.fullname);
+                        agents.push(agent);
+                        agents.sort_by(|a, b| a.name.cmp(&b.name));
+                        drop(agents);
+                        on_change();
+                    }
+                    ServiceEvent::ServiceRemoved(_, fullname) => {
+                        list.lock().unwrap().retain(|a| a.fullname != fullname);
+                        on_change();
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        Ok(Self {
+            daemon,
+            agents,
+            advertised: None,
+        })
+    }
+
+    /// Announce this machine's status page, or withdraw it with `None`
+    pub fn set_advertised_port(&mut self, port: Option<u16>) {
+        if self.advertised.as_ref().map(|(_, p)| *p) == port {
+            return;
+        }
+        if let Some((fullname, _)) = self.advertised.take() {
+            let _ = self.daemon.unregister(&fullname);
+        }
+        let Some(port) = port else {
+            return;
+        };
+
+        let name = sysinfo::System::host_name().unwrap_or_else(|| "opencode-diag".to_string());
+        let host = format!("{}.local.", name);
+        let properties = [("version", env!("CARGO_PKG_VERSION"))];
+        let info = ServiceInfo::new(SERVICE_TYPE, &name, &host, "", port, &properties[..])
+            .map(|i| i.enable_addr_auto());
+        if let Ok(info) = info {
+            let fullname = info.get_fullname().to_string();
+            if self.daemon.register(info).is_ok() {
+                self.advertised = Some((fullname, port));
+            }
+        }
+    }
+
+    /// Agents currently visible, excluding this machine
+    pub fn agents(&self) -> Vec<Agent> {
+        let own = self.advertised.as_ref().map(|(name, _)| name.as_str());
+        self.agents
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|a| Some(a.fullname.as_str()) != own)
+            .cloned()
+            .collect()
+    }
+}
+
+impl Drop for Discovery {
+    fn drop(&mut self) {
+        let _ = self.daemon.shutdown();
+    }
+}
@@ -0,0 +1,32 @@
+//! Agent discovery in builds without the `lan-discovery` feature
+
+/// A discovered agent on the LAN
+#[derive(Clone)]
+pub struct Agent {
+    pub name: String,
+    pub address: String,
+    pub port: u16,
+    pub version: Option<String>,
+}
+
+impl Agent {
+    /// Status page URL of the agent
+    pub fn url(&self) -> String {
+        format!("http://{}:{}/", self.address, self.port)
+    }
+}
+
+/// Never started: there is no mDNS daemon in this build
+pub struct Discovery;
+
+impl Discovery {
+    pub fn start(_on_change: impl Fn() + Send + 'static) -> Result<Self, String> {
+        Err("LAN discovery not included in this build".to_string())
+    }
+
+    pub fn set_advertised_port(&mut self, _port: Option<u16>) {}
+
+    pub fn agents(&self) -> Vec<Agent> {
+        Vec::new()
+    }
+}
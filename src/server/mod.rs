@@ -0,0 +1,321 @@
+//! Lightweight local status server
+//!
+//! Serves the current report and run history as a minimal HTML page, so a
+//! machine whose GUI is unusable can still be checked from another device.
+//! Read-only JSON endpoints under `/api/` require the configured token, and
+//! so does the page itself once the server is reachable from the LAN.
+
+#[cfg_attr(not(feature = "lan-discovery"), path = "discovery_disabled.rs")]
+pub mod discovery;
+pub mod page;
+pub mod proxy;
+pub mod rest;
+
+use crate::diagnostics::network::local_ip;
+use crate::diagnostics::palette::StatusColors;
+use crate::diagnostics::{History, SharedReport};
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Connections handled at once; more are closed right away, so idle clients
+/// cannot pile up threads
+const MAX_CONNECTIONS: usize = 16;
+
+/// Shared data the server reads from
+#[derive(Clone)]
+pub struct ServerState {
+    pub report: SharedReport,
+    pub history: Arc<Mutex<History>>,
+    pub api_token: String,
+    /// Status colors of the page
+    pub status_colors: StatusColors,
+}
+
+/// Parsed HTTP request head
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+}
+
+impl Request {
+    /// First query parameter with this name
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    /// Header value (case-insensitive name)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Running status server, stopped on drop
+pub struct StatusServer {
+    addr: SocketAddr,
+    lan: bool,
+    api_token: String,
+    status_colors: StatusColors,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl StatusServer {
+    /// Bind and start serving. `lan` binds all interfaces instead of localhost.
+    pub fn start(port: u16, lan: bool, state: ServerState) -> Result<Self, String> {
+        let ip = if lan { Ipv4Addr::UNSPECIFIED } else { Ipv4Addr::LOCALHOST };
+        let listener = TcpListener::bind((ip, port))
+            .map_err(|e| format!("Failed to bind port {}: {}", port, e))?;
+        listener.set_nonblocking(true)
+            .map_err(|e| format!("Failed to configure listener: {}", e))?;
+        let addr = listener.local_addr()
+            .map_err(|e| format!("Failed to read listener address: {}", e))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&stop);
+        let api_token = state.api_token.clone();
+        let status_colors = state.status_colors.clone();
+
+        let handle = thread::spawn(move || {
+            let active = Arc::new(AtomicUsize::new(0));
+            while !stop_flag.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    // Each on its own thread: a slow or idle client must not stall the others
+                    Ok((stream, _)) if active.load(Ordering::Relaxed) < MAX_CONNECTIONS => {
+                        active.fetch_add(1, Ordering::Relaxed);
+                        let (state, active) = (state.clone(), Arc::clone(&active));
+                        thread::spawn(move || {
+                            let _ = handle_connection(stream, &state, lan);
+                            active.fetch_sub(1, Ordering::Relaxed);
+                        });
+                    }
+                    Ok(_) => {}
+                    Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        thread::sleep(Duration::from_millis(50));
+                    }
+                    Err(_) => thread::sleep(Duration::from_millis(200)),
+                }
+            }
+        });
+
+        Ok(Self {
+            addr,
+            lan,
+            api_token,
+            status_colors,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    /// Whether the server is already running with this configuration
+    pub fn matches(&self, port: u16, lan: bool, api_token: &str, status_colors: &StatusColors) -> bool {
+        self.addr.port() == port && self.lan == lan && self.api_token == api_token && self.status_colors == *status_colors
+    }
+
+    /// Port the server is listening on
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    /// Port the server is listening on, if reachable from the LAN
+    pub fn lan_port(&self) -> Option<u16> {
+        self.lan.then_some(self.addr.port())
+    }
+
+    /// URL other devices can use to reach the page (with the token when on the LAN)
+    pub fn url(&self) -> String {
+        let host = if self.addr.ip().is_unspecified() {
+            local_ip().unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        } else {
+            self.addr.ip()
+        };
+        if self.lan {
+            format!("http://{}:{}/?token={}", host, self.addr.port(), self.api_token)
+        } else {
+            format!("http://{}:{}/", host, self.addr.port())
+        }
+    }
+}
+
+impl Drop for StatusServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, state: &ServerState, lan: bool) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    stream.set_write_timeout(Some(Duration::from_secs(2)))?;
+
+    let request = match read_request(&mut stream) {
+        Some(r) => r,
+        None => return respond(&mut stream, "400 Bad Request", "text/plain", "Bad request"),
+    };
+
+    if request.method != "GET" {
+        return respond(&mut stream, "405 Method Not Allowed", "text/plain", "Method not allowed");
+    }
+
+    match request.path.as_str() {
+        // Anyone on the network could read the report, so the page needs the token there
+        "/" | "/index.html" if lan && !rest::authorized(&request, &state.api_token) => {
+            respond(&mut stream, "401 Unauthorized", "text/plain", "Token required: open the URL shown in the app (/?token=...)")
+        }
+        "/" | "/index.html" => {
+            let report = state.report.snapshot();
+            let html = {
+                let history = state.history.lock().unwrap();
+                page::render(&report, &history, &state.status_colors)
+            };
+            respond(&mut stream, "200 OK", "text/html; charset=utf-8", &html)
+        }
+        path if path.starts_with("/api/") => {
+            let (status, body) = rest::handle(&request, state);
+            respond(&mut stream, status, "application/json", &body)
+        }
+        _ => respond(&mut stream, "404 Not Found", "text/plain", "Not found"),
+    }
+}
+
+/// Read the request head and parse the request line
+fn read_request(stream: &mut TcpStream) -> Option<Request> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < 8192 {
+        let n = stream.read(&mut chunk).ok()?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let head = String::from_utf8_lossy(&buf);
+    let mut lines = head.lines();
+    let mut parts = lines.next()?.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?;
+
+    let (path, query) = match target.split_once('?') {
+        Some((p, q)) => (p.to_string(), parse_query(q)),
+        None => (target.to_string(), Vec::new()),
+    };
+
+    let headers = lines
+        .take_while(|l| !l.is_empty())
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+
+    Some(Request { method, path, query, headers })
+}
+
+/// Split `a=1&b=2` into decoded pairs
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(k), percent_decode(v))
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(b) => {
+                        out.push(b);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> std::io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(body.as_bytes())?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start(lan: bool) -> StatusServer {
+        let state = ServerState {
+            report: SharedReport::default(),
+            history: Arc::new(Mutex::new(History::default())),
+            api_token: "test-token".to_string(),
+            status_colors: StatusColors::default(),
+        };
+        StatusServer::start(0, lan, state).unwrap()
+    }
+
+    /// Status code of a plain GET
+    fn get(port: u16, path: &str) -> String {
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response);
+        response.split_whitespace().nth(1).unwrap_or("failed").to_string()
+    }
+
+    #[test]
+    fn lan_page_needs_the_token() {
+        let server = start(true);
+        assert_eq!(get(server.port(), "/"), "401");
+        assert_eq!(get(server.port(), "/?token=wrong"), "401");
+        assert_eq!(get(server.port(), "/?token=test-token"), "200");
+        assert!(server.url().ends_with("/?token=test-token"));
+    }
+
+    #[test]
+    fn local_page_is_open() {
+        let server = start(false);
+        assert_eq!(get(server.port(), "/"), "200");
+        assert_eq!(get(server.port(), "/api/report"), "401");
+    }
+
+    #[test]
+    fn idle_client_does_not_stall_others() {
+        let server = start(false);
+        let _idle = TcpStream::connect((Ipv4Addr::LOCALHOST, server.port())).unwrap();
+        let started = std::time::Instant::now();
+        assert_eq!(get(server.port(), "/"), "200");
+        assert!(started.elapsed() < Duration::from_secs(1), "took {:?}", started.elapsed());
+    }
+}
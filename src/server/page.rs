@@ -0,0 +1,174 @@
+//! HTML rendering for the status page
+//!
+//! Plain HTML + inline SVG, no scripts. Follows the Y2K clinical look of the GUI.
+
+use crate::diagnostics::palette::{self, StatusColors};
+use crate::diagnostics::{CheckStatus, DiagnosticReport, History};
+
+/// Hours of history shown in the charts
+const CHART_HOURS: i64 = 24;
+
+const CHART_WIDTH: f32 = 320.0;
+const CHART_HEIGHT: f32 = 40.0;
+
+const STYLE: &str = "\
+body{background:#0f0f0f;color:#e0e0e0;font-family:Consolas,monospace;margin:0;padding:20px}\
+h1{font-size:14px;letter-spacing:1px;margin:0 0 4px}\
+.dim{color:#5c5c5c;font-size:11px}\
+.section{color:#5c5c5c;font-size:11px;margin:20px 0 8px}\
+.card{background:#222;border-left:3px solid #5c5c5c;display:flex;align-items:center;padding:8px 12px;margin-bottom:5px}\
+.card .body{flex:1}\
+.card .name{font-size:12px;font-weight:bold}\
+.card .details{font-size:11px;color:#888;white-space:pre-line}\
+.badge{font-size:11px;font-weight:bold;padding:4px 10px;min-width:40px;text-align:center}\
+.chart{background:#222;padding:8px 12px;margin-bottom:5px}\
+.chart svg{display:block}";
+
+/// Render the full status page in the user's status colors
+pub fn render(report: &DiagnosticReport, history: &History, colors: &StatusColors) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html><html><head><meta charset=\"utf-8\">");
+    html.push_str("<meta http-equiv=\"refresh\" content=\"10\">");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width,initial-scale=1\">");
+    html.push_str("<title>OpenCode Diagnostics</title>");
+    html.push_str(&format!("<style>{}</style></head><body>", STYLE));
+
+    html.push_str("<h1>&#9632; OPENCODE DIAGNOSTICS</h1>");
+    html.push_str(&format!(
+        "<div class=\"dim\">{} :: v{}</div>",
+        escape(report.timestamp.as_deref().unwrap_or("no run yet")),
+        env!("CARGO_PKG_VERSION")
+    ));
+
+    // Current cards
+    html.push_str("<div class=\"section\">// SYSTEM CHECK</div>");
+    let checks = report.checks();
+    if checks.is_empty() {
+        html.push_str("<div class=\"dim\">No diagnostics run yet.</div>");
+    }
+    for (_, check) in &checks {
+        let color = status_color(check.status, colors);
+        html.push_str(&format!(
+            "<div class=\"card\" style=\"border-left-color:{c}\"><div class=\"body\">\
+             <div class=\"name\">{name}</div><div class=\"details\">{details}</div></div>\
+             <div class=\"badge\" style=\"background:{c};color:{fg}\">{label}</div></div>",
+            c = color,
+            fg = badge_text_color(check.status, colors),
+            name = escape(&check.name),
+            details = escape(&check.details),
+            label = check.status.label(),
+        ));
+    }
+
+    if let Some(ref diagnosis) = report.diagnosis {
+        html.push_str(&format!(
+            "<div class=\"card\"><div class=\"body\"><span class=\"name\">DIAGNOSIS:</span> \
+             <span class=\"details\">{}</span></div></div>",
+            escape(diagnosis)
+        ));
+    }
+
+    // History charts
+    html.push_str(&format!("<div class=\"section\">// HISTORY ({}H)</div>", CHART_HOURS));
+    let recent: Vec<_> = history.since_hours(CHART_HOURS).collect();
+    if recent.is_empty() {
+        html.push_str("<div class=\"dim\">No history recorded.</div>");
+    }
+    for (id, check) in &checks {
+        let points: Vec<(CheckStatus, Option<u64>)> = recent
+            .iter()
+            .filter_map(|e| e.check(id).map(|c| (c.status, c.latency_ms)))
+            .collect();
+        if points.is_empty() {
+            continue;
+        }
+        html.push_str(&format!(
+            "<div class=\"chart\"><div class=\"dim\">{} :: {} runs</div>{}</div>",
+            escape(&check.name),
+            points.len(),
+            render_chart(&points, colors)
+        ));
+    }
+
+    html.push_str("</body></html>");
+    html
+}
+
+/// Status strip along the bottom plus a latency line when latency is recorded
+fn render_chart(points: &[(CheckStatus, Option<u64>)], colors: &StatusColors) -> String {
+    let mut svg = format!(
+        "<svg width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT
+    );
+
+    let step = CHART_WIDTH / points.len() as f32;
+    for (i, (status, _)) in points.iter().enumerate() {
+        svg.push_str(&format!(
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"4\" fill=\"{}\"/>",
+            i as f32 * step,
+            CHART_HEIGHT - 4.0,
+            step.max(1.0),
+            status_color(*status, colors)
+        ));
+    }
+
+    let max_latency = points.iter().filter_map(|(_, l)| *l).max().unwrap_or(0);
+    if max_latency > 0 {
+        let line: Vec<String> = points
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, l))| {
+                l.map(|ms| {
+                    let x = i as f32 * step + step / 2.0;
+                    let y = (CHART_HEIGHT - 8.0) * (1.0 - ms as f32 / max_latency as f32) + 2.0;
+                    format!("{:.1},{:.1}", x, y)
+                })
+            })
+            .collect();
+        svg.push_str(&format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"#00bcd4\" stroke-width=\"1\"/>",
+            line.join(" ")
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"10\" fill=\"#5c5c5c\" font-size=\"9\" text-anchor=\"end\">max {}ms</text>",
+            CHART_WIDTH,
+            max_latency
+        ));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+fn status_color(status: CheckStatus, colors: &StatusColors) -> String {
+    if let Some(rgb) = colors.rgb(status) {
+        return palette::to_hex(rgb);
+    }
+    match status {
+        CheckStatus::Ok => "#4caf50",
+        CheckStatus::Warning => "#ff9800",
+        CheckStatus::Error => "#f44336",
+        CheckStatus::Unknown | CheckStatus::Inactive => "#5c5c5c",
+    }
+    .to_string()
+}
+
+fn badge_text_color(status: CheckStatus, colors: &StatusColors) -> String {
+    if let Some(rgb) = colors.rgb(status) {
+        return palette::to_hex(palette::text_on(rgb));
+    }
+    match status {
+        CheckStatus::Ok | CheckStatus::Inactive => "#ffffff",
+        _ => "#000000",
+    }
+    .to_string()
+}
+
+/// Escape text for safe inclusion in HTML
+pub fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,372 @@
+//! Capture proxy: OpenCode's real traffic next to the check results
+//!
+//! A forward proxy on localhost that OpenCode can be pointed at for a while
+//! (`HTTPS_PROXY=http://127.0.0.1:8899`). HTTPS goes through a CONNECT tunnel
+//! and is never decrypted, so what is recorded is the metadata: the host,
+//! whether the tunnel opened, connect time, duration and bytes each way.
+//! Plain HTTP requests also get the response status. No bodies are kept.
+
+use crate::diagnostics::{ids, DiagnosticReport};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Port tried first (any free port when it is taken)
+pub const DEFAULT_CAPTURE_PORT: u16 = 8899;
+
+/// Connections kept, newest last
+const MAX_ENTRIES: usize = 500;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// API hosts and the check that covers each
+const CHECK_HOSTS: &[(&str, &str)] = &[
+    ("api.anthropic.com", ids::CLAUDE),
+    ("api.openai.com", ids::OPENAI),
+    ("generativelanguage.googleapis.com", ids::GOOGLE),
+];
+
+/// One proxied connection
+#[derive(Clone, Serialize)]
+pub struct CaptureEntry {
+    /// When it started ("12:00:00")
+    pub time: String,
+    /// CONNECT, or the method of a plain HTTP request
+    pub method: String,
+    pub host: String,
+    pub port: u16,
+    /// Tunnel opened (200) or not (502); the response status for plain HTTP
+    pub status: u16,
+    pub connect_ms: Option<u64>,
+    pub duration_ms: u64,
+    pub bytes_up: u64,
+    pub bytes_down: u64,
+    /// Why the upstream could not be reached
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl CaptureEntry {
+    pub fn failed(&self) -> bool {
+        self.status >= 400 || self.error.is_some()
+    }
+
+    /// Id of the check that covers this host
+    pub fn check_id(&self) -> Option<&'static str> {
+        CHECK_HOSTS.iter().find(|(host, _)| *host == self.host).map(|(_, id)| *id)
+    }
+
+    /// "12:00:00 CONNECT api.anthropic.com:443 200 :: 85ms connect, 12.3s, 4 KB up, 180 KB down"
+    pub fn summary(&self) -> String {
+        let connect = self.connect_ms.map(|ms| format!("{}ms connect, ", ms)).unwrap_or_default();
+        let mut text = format!(
+            "{} {} {}:{} {} :: {}{:.1}s, {} KB up, {} KB down",
+            self.time,
+            self.method,
+            self.host,
+            self.port,
+            self.status,
+            connect,
+            self.duration_ms as f64 / 1000.0,
+            self.bytes_up.div_ceil(1024),
+            self.bytes_down.div_ceil(1024)
+        );
+        if let Some(ref error) = self.error {
+            text.push_str(&format!(" :: {}", error));
+        }
+        text
+    }
+}
+
+/// Command line that starts OpenCode through the proxy at `url`
+#[cfg(target_os = "windows")]
+pub fn launch_command(url: &str) -> String {
+    format!("set HTTPS_PROXY={0}&& set HTTP_PROXY={0}&& opencode", url)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn launch_command(url: &str) -> String {
+    format!("HTTPS_PROXY={0} HTTP_PROXY={0} opencode", url)
+}
+
+/// Connections to one host
+pub struct HostSummary {
+    pub host: String,
+    pub connections: usize,
+    pub failed: usize,
+    pub avg_connect_ms: Option<u64>,
+    /// Check covering the host, if any
+    pub check_id: Option<&'static str>,
+}
+
+/// Per host, most connections first
+pub fn by_host(entries: &[CaptureEntry]) -> Vec<HostSummary> {
+    let mut hosts: Vec<HostSummary> = Vec::new();
+    for entry in entries {
+        let index = match hosts.iter().position(|h| h.host == entry.host) {
+            Some(index) => index,
+            None => {
+                hosts.push(HostSummary { host: entry.host.clone(), connections: 0, failed: 0, avg_connect_ms: None, check_id: entry.check_id() });
+                hosts.len() - 1
+            }
+        };
+        let summary = &mut hosts[index];
+        summary.connections += 1;
+        summary.failed += entry.failed() as usize;
+    }
+    for summary in &mut hosts {
+        let connects: Vec<u64> = entries.iter().filter(|e| e.host == summary.host).filter_map(|e| e.connect_ms).collect();
+        summary.avg_connect_ms = (!connects.is_empty()).then(|| connects.iter().sum::<u64>() / connects.len() as u64);
+    }
+    hosts.sort_by_key(|h| std::cmp::Reverse(h.connections));
+    hosts
+}
+
+/// Plain-text capture for the clipboard, each host next to its check's result
+pub fn to_text(entries: &[CaptureEntry], report: &DiagnosticReport) -> String {
+    let checks = report.checks();
+    let mut text = format!("=== OpenCode Traffic Capture ({} connections) ===\n", entries.len());
+    for summary in by_host(entries) {
+        let check = summary
+            .check_id
+            .and_then(|id| checks.iter().find(|(i, _)| *i == id))
+            .map(|(_, check)| format!(" :: {} {}", check.name, check.status.label()))
+            .unwrap_or_default();
+        let connect = summary.avg_connect_ms.map(|ms| format!(", avg connect {}ms", ms)).unwrap_or_default();
+        text.push_str(&format!("{}: {} connections, {} failed{}{}\n", summary.host, summary.connections, summary.failed, connect, check));
+    }
+    text.push('\n');
+    for entry in entries {
+        text.push_str(&entry.summary());
+        text.push('\n');
+    }
+    text
+}
+
+type Entries = Arc<Mutex<VecDeque<CaptureEntry>>>;
+
+/// Running capture proxy, stopped on drop (open tunnels run to their end)
+pub struct CaptureProxy {
+    addr: SocketAddr,
+    entries: Entries,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl CaptureProxy {
+    /// Listen on localhost: `port`, or any free port when it is taken;
+    /// `on_entry` runs after each recorded connection (e.g. to repaint)
+    pub fn start(port: u16, on_entry: impl Fn() + Send + Sync + 'static) -> Result<Self, String> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .or_else(|_| TcpListener::bind((Ipv4Addr::LOCALHOST, 0)))
+            .map_err(|e| format!("Failed to bind the capture proxy: {}", e))?;
+        listener.set_nonblocking(true).map_err(|e| format!("Failed to configure listener: {}", e))?;
+        let addr = listener.local_addr().map_err(|e| format!("Failed to read listener address: {}", e))?;
+
+        let entries: Entries = Arc::default();
+        let stop = Arc::new(AtomicBool::new(false));
+        let on_entry = Arc::new(on_entry);
+        let handle = thread::spawn({
+            let (entries, stop) = (Arc::clone(&entries), Arc::clone(&stop));
+            move || {
+                while !stop.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((stream, _)) => {
+                            let (entries, on_entry) = (Arc::clone(&entries), Arc::clone(&on_entry));
+                            thread::spawn(move || {
+                                if let Some(entry) = handle_connection(stream) {
+                                    let mut entries = entries.lock().unwrap();
+                                    if entries.len() >= MAX_ENTRIES {
+                                        entries.pop_front();
+                                    }
+                                    entries.push_back(entry);
+                                    drop(entries);
+                                    on_entry();
+                                }
+                            });
+                        }
+                        Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                            thread::sleep(Duration::from_millis(50));
+                        }
+                        Err(_) => thread::sleep(Duration::from_millis(200)),
+                    }
+                }
+            }
+        });
+
+        Ok(Self { addr, entries, stop, handle: Some(handle) })
+    }
+
+    /// "http://127.0.0.1:8899", for HTTPS_PROXY / HTTP_PROXY
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Connections recorded so far, oldest first
+    pub fn entries(&self) -> Vec<CaptureEntry> {
+        self.entries.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+impl Drop for CaptureProxy {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Read up to the end of the request head; returns the head and any bytes after it
+fn read_head(stream: &mut TcpStream) -> Option<(String, Vec<u8>)> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let rest = buf.split_off(end + 4);
+            return Some((String::from_utf8_lossy(&buf).into_owned(), rest));
+        }
+        if buf.len() > 16 * 1024 {
+            return None;
+        }
+        let n = stream.read(&mut chunk).ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+}
+
+/// "host:443" / "[::1]:443" -> (host, port)
+fn split_host_port(authority: &str, default_port: u16) -> (String, u16) {
+    match authority.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() && (!host.contains(':') || host.ends_with(']')) => {
+            (host.trim_matches(['[', ']']).to_string(), port.parse().unwrap_or(default_port))
+        }
+        _ => (authority.trim_matches(['[', ']']).to_string(), default_port),
+    }
+}
+
+fn connect(host: &str, port: u16) -> Result<TcpStream, String> {
+    let addrs: Vec<SocketAddr> = (host, port).to_socket_addrs().map_err(|e| format!("DNS: {}", e))?.collect();
+    let mut last = "no address".to_string();
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last = e.to_string(),
+        }
+    }
+    Err(last)
+}
+
+/// Copy both ways until both sides are done; (bytes up, bytes down)
+fn pump(client: &TcpStream, upstream: &TcpStream, first_down: &[u8]) -> (u64, u64) {
+    let (Ok(mut client_read), Ok(mut upstream_write)) = (client.try_clone(), upstream.try_clone()) else {
+        return (0, 0);
+    };
+    let up = thread::spawn(move || {
+        let n = std::io::copy(&mut client_read, &mut upstream_write).unwrap_or(0);
+        let _ = upstream_write.shutdown(Shutdown::Write);
+        n
+    });
+    let (mut upstream_read, mut client_write) = (upstream, client);
+    let mut down = first_down.len() as u64;
+    if client_write.write_all(first_down).is_ok() {
+        down += std::io::copy(&mut upstream_read, &mut client_write).unwrap_or(0);
+    }
+    // The upstream is done: end the client side too, or a keep-alive client holds the copy up
+    let _ = client_write.shutdown(Shutdown::Both);
+    (up.join().unwrap_or(0), down)
+}
+
+/// Proxy one connection and describe it (None for a request that is not one)
+fn handle_connection(mut client: TcpStream) -> Option<CaptureEntry> {
+    let _ = client.set_nonblocking(false);
+    let started = Instant::now();
+    let time = chrono::Local::now().format("%H:%M:%S").to_string();
+    let (head, rest) = read_head(&mut client)?;
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let (method, target, version) = (request_line.next()?.to_string(), request_line.next()?, request_line.next().unwrap_or("HTTP/1.1"));
+
+    let tunnel = method.eq_ignore_ascii_case("CONNECT");
+    let (host, port, path) = if tunnel {
+        let (host, port) = split_host_port(target, 443);
+        (host, port, String::new())
+    } else {
+        let without_scheme = target.strip_prefix("http://")?;
+        let (authority, path) = without_scheme.split_once('/').map_or((without_scheme, "/".to_string()), |(a, p)| (a, format!("/{}", p)));
+        let (host, port) = split_host_port(authority, 80);
+        (host, port, path)
+    };
+    let mut entry = CaptureEntry {
+        time,
+        method,
+        host,
+        port,
+        status: 502,
+        connect_ms: None,
+        duration_ms: 0,
+        bytes_up: 0,
+        bytes_down: 0,
+        error: None,
+    };
+
+    let upstream = match connect(&entry.host, entry.port) {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            let _ = client.write_all(b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            entry.error = Some(e);
+            entry.duration_ms = started.elapsed().as_millis() as u64;
+            return Some(entry);
+        }
+    };
+    entry.connect_ms = Some(started.elapsed().as_millis() as u64);
+
+    let (up, down) = if tunnel {
+        if client.write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n").is_err() {
+            return Some(entry);
+        }
+        entry.status = 200;
+        let mut upstream_write = &upstream;
+        let _ = upstream_write.write_all(&rest);
+        let (up, down) = pump(&client, &upstream, &[]);
+        (up + rest.len() as u64, down)
+    } else {
+        // Origin-form request line, one request per connection
+        let mut forward = format!("{} {} {}\r\n", entry.method, path, version);
+        for line in lines.filter(|l| !l.is_empty()) {
+            let name = line.split(':').next().unwrap_or_default().trim();
+            if !name.eq_ignore_ascii_case("proxy-connection") && !name.eq_ignore_ascii_case("connection") {
+                forward.push_str(line);
+                forward.push_str("\r\n");
+            }
+        }
+        forward.push_str("Connection: close\r\n\r\n");
+        let mut upstream_io = &upstream;
+        if upstream_io.write_all(forward.as_bytes()).and_then(|()| upstream_io.write_all(&rest)).is_err() {
+            entry.error = Some("Upstream closed the connection".to_string());
+            return Some(entry);
+        }
+        // Status from the first bytes of the response
+        let mut first = vec![0u8; 4096];
+        let n = upstream_io.read(&mut first).unwrap_or(0);
+        first.truncate(n);
+        entry.status = String::from_utf8_lossy(&first).split_whitespace().nth(1).and_then(|s| s.parse().ok()).unwrap_or(502);
+        let (up, down) = pump(&client, &upstream, &first);
+        (up + (forward.len() + rest.len()) as u64, down)
+    };
+    entry.bytes_up = up;
+    entry.bytes_down = down;
+    entry.duration_ms = started.elapsed().as_millis() as u64;
+    Some(entry)
+}
@@ -0,0 +1,92 @@
+//! Read-only REST endpoints for scripts and custom dashboards
+//!
+//! - `GET /api/report` - latest report
+//! - `GET /api/history?check=api.claude&hours=24` - recorded runs
+//!
+//! Every endpoint requires `Authorization: Bearer <token>` (or `?token=`).
+
+use crate::diagnostics::history::HISTORY_RETENTION_DAYS;
+use crate::server::{Request, ServerState};
+use serde_json::json;
+
+/// Default window for `/api/history`
+const DEFAULT_HISTORY_HOURS: i64 = 24;
+
+/// Route an `/api/` request, returns HTTP status line and JSON body
+pub fn handle(request: &Request, state: &ServerState) -> (&'static str, String) {
+    if !authorized(request, &state.api_token) {
+        return ("401 Unauthorized", error_body("missing or invalid token"));
+    }
+
+    match request.path.as_str() {
+        "/api/report" => {
+            let report = state.report.snapshot();
+            match serde_json::to_string(&*report) {
+                Ok(body) => ("200 OK", body),
+                Err(e) => ("500 Internal Server Error", error_body(&e.to_string())),
+            }
+        }
+        "/api/history" => history(request, state),
+        _ => ("404 Not Found", error_body("unknown endpoint")),
+    }
+}
+
+fn history(request: &Request, state: &ServerState) -> (&'static str, String) {
+    let hours = match request.param("hours") {
+        Some(h) => match h.parse::<i64>() {
+            Ok(h) if h > 0 => h.min(HISTORY_RETENTION_DAYS * 24),
+            _ => return ("400 Bad Request", error_body("hours must be a positive integer")),
+        },
+        None => DEFAULT_HISTORY_HOURS,
+    };
+    let check = request.param("check");
+
+    let history = state.history.lock().unwrap();
+    let entries: Vec<serde_json::Value> = history
+        .since_hours(hours)
+        .filter_map(|entry| match check {
+            // Single check: flatten to one sample per run
+            Some(id) => entry.check(id).map(|c| {
+                json!({
+                    "timestamp": entry.timestamp,
+                    "status": c.status,
+                    "latency_ms": c.latency_ms,
+                })
+            }),
+            None => serde_json::to_value(entry).ok(),
+        })
+        .collect();
+
+    let body = json!({
+        "check": check,
+        "hours": hours,
+        "entries": entries,
+    });
+    ("200 OK", body.to_string())
+}
+
+/// Check the bearer token (header or query), empty tokens never match
+pub fn authorized(request: &Request, token: &str) -> bool {
+    if token.is_empty() {
+        return false;
+    }
+    let provided = request
+        .header("Authorization")
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .or_else(|| request.param("token"));
+    match provided {
+        Some(p) => constant_time_eq(p.trim().as_bytes(), token.as_bytes()),
+        None => false,
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn error_body(message: &str) -> String {
+    json!({ "error": message }).to_string()
+}
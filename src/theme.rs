@@ -2,6 +2,8 @@
 //! 
 //! Light/Dark theme with technical aesthetic
 
+use crate::diagnostics::palette::StatusColors;
+use crate::diagnostics::CheckStatus;
 use egui::Color32;
 
 #[derive(Clone, Copy, PartialEq)]
@@ -21,6 +23,10 @@ pub struct Theme {
     pub border: Color32,
     pub accent_on: Color32,
     pub accent_off: Color32,
+    // Status colors (OK, WARNING, ERROR)
+    pub ok: Color32,
+    pub warning: Color32,
+    pub error: Color32,
 }
 
 impl Theme {
@@ -34,6 +40,9 @@ impl Theme {
         border: Color32::from_rgb(0xa0, 0xa0, 0xa0),
         accent_on: Color32::from_rgb(0x2a, 0x2a, 0x2a),
         accent_off: Color32::from_rgb(0xd0, 0xd0, 0xd0),
+        ok: Color32::from_rgb(0x2a, 0x2a, 0x2a),
+        warning: Color32::from_rgb(0xff, 0x98, 0x00),
+        error: Color32::from_rgb(0xf4, 0x43, 0x36),
     };
 
     pub const DARK: Self = Self {
@@ -46,6 +55,9 @@ impl Theme {
         border: Color32::from_rgb(0x33, 0x33, 0x33),
         accent_on: Color32::from_rgb(0x00, 0xbc, 0xd4), // Cyan
         accent_off: Color32::from_rgb(0x33, 0x33, 0x33),
+        ok: Color32::from_rgb(0x4c, 0xaf, 0x50),      // Green
+        warning: Color32::from_rgb(0xff, 0x98, 0x00), // Orange
+        error: Color32::from_rgb(0xf4, 0x43, 0x36),   // Red
     };
 
     pub fn from_mode(mode: ThemeMode) -> Self {
@@ -54,6 +66,24 @@ impl Theme {
             ThemeMode::Dark => Self::DARK,
         }
     }
+
+    /// The theme with the user's status colors over its own
+    pub fn with_status_colors(mut self, colors: &StatusColors) -> Self {
+        let pick = |status, own: Color32| colors.rgb(status).map_or(own, |[r, g, b]| Color32::from_rgb(r, g, b));
+        self.ok = pick(CheckStatus::Ok, self.ok);
+        self.warning = pick(CheckStatus::Warning, self.warning);
+        self.error = pick(CheckStatus::Error, self.error);
+        self
+    }
+
+    pub fn status_color(&self, status: CheckStatus) -> Color32 {
+        match status {
+            CheckStatus::Ok => self.ok,
+            CheckStatus::Warning => self.warning,
+            CheckStatus::Error => self.error,
+            CheckStatus::Unknown | CheckStatus::Inactive => self.text_dim,
+        }
+    }
 }
 
 /// Apply theme to egui visuals